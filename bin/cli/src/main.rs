@@ -1,7 +1,10 @@
 use clap::clap_derive::ArgEnum;
 use clap::{Args, Parser, Subcommand};
 use mango_v4::accounts_ix::{Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side};
-use mango_v4::state::{PlaceOrderType, SelfTradeBehavior, Side};
+use mango_v4::state::{
+    PerpMarketIndex, PlaceOrderType, SelfTradeBehavior, Side,
+    TokenConditionalSwapDisplayPriceStyle, TokenConditionalSwapIntention,
+};
 use mango_v4_client::{
     keypair_from_cli, pubkey_from_cli, Client, MangoClient, TransactionBuilderConfig,
 };
@@ -67,6 +70,106 @@ struct Deposit {
     rpc: Rpc,
 }
 
+#[derive(Args, Debug, Clone)]
+struct Withdraw {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(short, long)]
+    mint: String,
+
+    #[clap(short, long)]
+    amount: u64,
+
+    #[clap(long)]
+    allow_borrow: bool,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct AccountEdit {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(short, long)]
+    name: Option<String>,
+
+    /// use the default pubkey 11111111111111111111111111111111 to remove the delegate
+    #[clap(long)]
+    delegate: Option<String>,
+
+    #[clap(long)]
+    temporary_delegate: Option<String>,
+
+    /// in seconds from now
+    #[clap(long)]
+    temporary_delegate_expiry: Option<u64>,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+/// Counts that are not provided stay at their current value
+#[derive(Args, Debug, Clone)]
+struct AccountExpand {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    token_count: Option<u8>,
+
+    #[clap(long)]
+    serum3_count: Option<u8>,
+
+    #[clap(long)]
+    perp_count: Option<u8>,
+
+    #[clap(long)]
+    perp_oo_count: Option<u8>,
+
+    #[clap(long)]
+    token_conditional_swap_count: Option<u8>,
+
+    #[clap(long)]
+    openbook_v2_count: Option<u8>,
+
+    #[clap(long)]
+    perp_trigger_order_count: Option<u8>,
+
+    #[clap(long)]
+    fixed_term_loan_count: Option<u8>,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct AccountClose {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything, receives the rent
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
 #[derive(Args, Debug, Clone)]
 struct JupiterSwap {
     #[clap(long)]
@@ -127,6 +230,44 @@ struct PerpPlaceOrder {
     rpc: Rpc,
 }
 
+#[derive(Args, Debug, Clone)]
+struct PerpCancelOrder {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    market_name: String,
+
+    #[clap(long)]
+    order_id: u128,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct PerpCancelAllOrders {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    market_name: String,
+
+    #[clap(long, default_value = "10")]
+    limit: u8,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
 #[derive(Args, Debug, Clone)]
 struct Serum3CreateOpenOrders {
     #[clap(long)]
@@ -184,10 +325,133 @@ struct Serum3PlaceOrder {
     rpc: Rpc,
 }
 
+#[derive(Args, Debug, Clone)]
+struct Serum3CancelOrder {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    market_name: String,
+
+    #[clap(long, value_enum)]
+    side: CliSide,
+
+    #[clap(long)]
+    order_id: u128,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct Serum3CancelAllOrders {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    market_name: String,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct Serum3SettleFunds {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    market_name: String,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct TokenConditionalSwapCreate {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    /// token name, like "SOL"
+    #[clap(long)]
+    buy_token: String,
+
+    #[clap(long)]
+    sell_token: String,
+
+    /// in ui units of the buy token, unlimited by default
+    #[clap(long)]
+    max_buy: Option<f64>,
+
+    /// in ui units of the sell token, unlimited by default
+    #[clap(long)]
+    max_sell: Option<f64>,
+
+    /// in ui "sell token per buy token" units
+    #[clap(long, default_value = "0")]
+    price_lower_limit: f64,
+
+    /// in ui "sell token per buy token" units, unlimited by default
+    #[clap(long)]
+    price_upper_limit: Option<f64>,
+
+    #[clap(long, default_value = "0.01")]
+    price_premium_rate: f64,
+
+    #[clap(long)]
+    allow_creating_deposits: bool,
+
+    #[clap(long)]
+    allow_creating_borrows: bool,
+
+    /// in seconds from now, 0 means no expiry
+    #[clap(long, default_value = "0")]
+    expiry: u64,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
+#[derive(Args, Debug, Clone)]
+struct TokenConditionalSwapCancel {
+    #[clap(long)]
+    account: String,
+
+    /// also pays for everything
+    #[clap(short, long)]
+    owner: String,
+
+    #[clap(long)]
+    id: u64,
+
+    #[clap(flatten)]
+    rpc: Rpc,
+}
+
 #[derive(Subcommand, Debug, Clone)]
 enum Command {
     CreateAccount(CreateAccount),
     Deposit(Deposit),
+    Withdraw(Withdraw),
+    AccountEdit(AccountEdit),
+    AccountExpand(AccountExpand),
+    AccountClose(AccountClose),
     JupiterSwap(JupiterSwap),
     GroupAddress {
         #[clap(short, long)]
@@ -225,9 +489,16 @@ enum Command {
         output: String,
     },
     PerpPlaceOrder(PerpPlaceOrder),
+    PerpCancelOrder(PerpCancelOrder),
+    PerpCancelAllOrders(PerpCancelAllOrders),
     Serum3CloseOpenOrders(Serum3CloseOpenOrders),
     Serum3CreateOpenOrders(Serum3CreateOpenOrders),
     Serum3PlaceOrder(Serum3PlaceOrder),
+    Serum3CancelOrder(Serum3CancelOrder),
+    Serum3CancelAllOrders(Serum3CancelAllOrders),
+    Serum3SettleFunds(Serum3SettleFunds),
+    TokenConditionalSwapCreate(TokenConditionalSwapCreate),
+    TokenConditionalSwapCancel(TokenConditionalSwapCancel),
 }
 
 impl Rpc {
@@ -249,6 +520,16 @@ impl Rpc {
     }
 }
 
+fn perp_market_index_by_name(client: &MangoClient, name: &str) -> anyhow::Result<PerpMarketIndex> {
+    client
+        .context
+        .perp_markets
+        .values()
+        .find(|p| p.name == name)
+        .map(|p| p.perp_market_index)
+        .ok_or_else(|| anyhow::anyhow!("no perp market named {}", name))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     mango_v4_client::tracing_subscriber_init();
@@ -299,6 +580,69 @@ async fn main() -> Result<(), anyhow::Error> {
             let txsig = client.token_deposit(mint, cmd.amount, false).await?;
             println!("{}", txsig);
         }
+        Command::Withdraw(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let mint = pubkey_from_cli(&cmd.mint);
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let txsig = client
+                .token_withdraw(mint, cmd.amount, cmd.allow_borrow)
+                .await?;
+            println!("{}", txsig);
+        }
+        Command::AccountEdit(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let temporary_delegate_expiry = match cmd.temporary_delegate_expiry {
+                Some(expiry) => {
+                    Some(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + expiry)
+                }
+                None => None,
+            };
+            let txsig = client
+                .account_edit(
+                    cmd.name,
+                    cmd.delegate.as_deref().map(pubkey_from_cli),
+                    cmd.temporary_delegate.as_deref().map(pubkey_from_cli),
+                    temporary_delegate_expiry,
+                )
+                .await?;
+            println!("{}", txsig);
+        }
+        Command::AccountExpand(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let header = client.mango_account().await?.header;
+            let txsig = client
+                .account_expand(
+                    cmd.token_count.unwrap_or(header.token_count),
+                    cmd.serum3_count.unwrap_or(header.serum3_count),
+                    cmd.perp_count.unwrap_or(header.perp_count),
+                    cmd.perp_oo_count.unwrap_or(header.perp_oo_count),
+                    cmd.token_conditional_swap_count
+                        .unwrap_or(header.token_conditional_swap_count),
+                    cmd.openbook_v2_count.unwrap_or(header.openbook_v2_count),
+                    cmd.perp_trigger_order_count
+                        .unwrap_or(header.perp_trigger_order_count),
+                    cmd.fixed_term_loan_count
+                        .unwrap_or(header.fixed_term_loan_count),
+                )
+                .await?;
+            println!("{}", txsig);
+        }
+        Command::AccountClose(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let txsig = client.account_close().await?;
+            println!("{}", txsig);
+        }
         Command::JupiterSwap(cmd) => {
             let client = cmd.rpc.client(Some(&cmd.owner))?;
             let account = pubkey_from_cli(&cmd.account);
@@ -387,6 +731,28 @@ async fn main() -> Result<(), anyhow::Error> {
                 .await?;
             println!("{}", txsig);
         }
+        Command::PerpCancelOrder(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let market_index = perp_market_index_by_name(&client, &cmd.market_name)?;
+
+            let txsig = client.perp_cancel_order(market_index, cmd.order_id).await?;
+            println!("{}", txsig);
+        }
+        Command::PerpCancelAllOrders(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let market_index = perp_market_index_by_name(&client, &cmd.market_name)?;
+
+            let txsig = client
+                .perp_cancel_all_orders(market_index, cmd.limit)
+                .await?;
+            println!("{}", txsig);
+        }
         Command::Serum3CreateOpenOrders(cmd) => {
             let client = cmd.rpc.client(Some(&cmd.owner))?;
             let account = pubkey_from_cli(&cmd.account);
@@ -446,6 +812,83 @@ async fn main() -> Result<(), anyhow::Error> {
                 .await?;
             println!("{}", txsig);
         }
+        Command::Serum3CancelOrder(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+
+            let txsig = client
+                .serum3_cancel_order(
+                    &cmd.market_name,
+                    match cmd.side {
+                        CliSide::Bid => Serum3Side::Bid,
+                        CliSide::Ask => Serum3Side::Ask,
+                    },
+                    cmd.order_id,
+                )
+                .await?;
+            println!("{}", txsig);
+        }
+        Command::Serum3CancelAllOrders(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+
+            let order_ids = client.serum3_cancel_all_orders(&cmd.market_name).await?;
+            println!("{:?}", order_ids);
+        }
+        Command::Serum3SettleFunds(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+
+            let txsig = client.serum3_settle_funds(&cmd.market_name).await?;
+            println!("{}", txsig);
+        }
+        Command::TokenConditionalSwapCreate(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+            let buy_token_index = client.context.token_by_name(&cmd.buy_token).token_index;
+            let sell_token_index = client.context.token_by_name(&cmd.sell_token).token_index;
+            let expiry_timestamp = if cmd.expiry > 0 {
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + cmd.expiry
+            } else {
+                u64::MAX
+            };
+
+            let ixs = client.token_conditional_swap_create_v2_instruction(
+                buy_token_index,
+                sell_token_index,
+                cmd.max_buy.unwrap_or(f64::INFINITY),
+                cmd.max_sell.unwrap_or(f64::INFINITY),
+                expiry_timestamp,
+                cmd.price_lower_limit,
+                cmd.price_upper_limit.unwrap_or(f64::MAX),
+                cmd.price_premium_rate,
+                cmd.allow_creating_deposits,
+                cmd.allow_creating_borrows,
+                TokenConditionalSwapDisplayPriceStyle::SellTokenPerBuyToken,
+                TokenConditionalSwapIntention::Unknown,
+            )?;
+            let txsig = client
+                .send_and_confirm_owner_tx(ixs.to_instructions())
+                .await?;
+            println!("{}", txsig);
+        }
+        Command::TokenConditionalSwapCancel(cmd) => {
+            let client = cmd.rpc.client(Some(&cmd.owner))?;
+            let account = pubkey_from_cli(&cmd.account);
+            let owner = Arc::new(keypair_from_cli(&cmd.owner));
+            let client = MangoClient::new_for_existing_account(client, account, owner).await?;
+
+            let txsig = client.token_conditional_swap_cancel(cmd.id).await?;
+            println!("{}", txsig);
+        }
     };
 
     Ok(())
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 use mango_v4::accounts_zerocopy::KeyedAccount;
-use mango_v4::state::OracleAccountInfos;
+use mango_v4::state::{determine_oracle_type, OracleAccountInfos};
 use mango_v4_client::{Client, MangoGroupContext};
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
@@ -56,6 +56,14 @@ pub async fn run(client: &Client, group: Pubkey) -> anyhow::Result<()> {
                 account: account_opt.unwrap(),
             };
 
+            let oracle_type = match determine_oracle_type(&keyed_account) {
+                Ok(t) => t,
+                Err(e) => {
+                    error!("unknown oracle type for {pubkey}: {e:?}");
+                    continue;
+                }
+            };
+
             let bank_opt = banks.get(pubkey);
             let perp_opt = perp_markets.get(pubkey);
             let mut price = None;
@@ -80,7 +88,7 @@ pub async fn run(client: &Client, group: Pubkey) -> anyhow::Result<()> {
                 }
             }
             if let Some(p) = price {
-                info!("{pubkey},{p}");
+                info!("{pubkey},{oracle_type:?},{p}");
             }
         }
     }
@@ -98,6 +98,7 @@ pub async fn runner(
     interval_update_funding: u64,
     interval_check_for_changes_and_abort: u64,
     interval_charge_collateral_fees: u64,
+    interval_settle_fixed_term_loans: u64,
     extra_jobs: Vec<JoinHandle<()>>,
 ) -> Result<(), anyhow::Error> {
     let handles1 = mango_client
@@ -158,6 +159,7 @@ pub async fn runner(
         futures::future::join_all(handles2),
         futures::future::join_all(handles3),
         loop_charge_collateral_fees(mango_client.clone(), interval_charge_collateral_fees),
+        loop_settle_fixed_term_loans(mango_client.clone(), interval_settle_fixed_term_loans),
         MangoClient::loop_check_for_context_changes_and_abort(
             mango_client.clone(),
             Duration::from_secs(interval_check_for_changes_and_abort),
@@ -219,6 +221,24 @@ pub async fn loop_update_index_and_rate(
                 .collect::<Vec<_>>();
 
             ix.accounts.append(&mut banks);
+            // median oracle accounts go after the banks
+            ix.accounts.extend(
+                token
+                    .median_oracles
+                    .iter()
+                    .map(|&pubkey| AccountMeta::new_readonly(pubkey, false)),
+            );
+            // the rate history, if it wasn't created the program skips it
+            let rate_history = Pubkey::find_program_address(
+                &[
+                    b"TokenRateHistory".as_ref(),
+                    token.group.as_ref(),
+                    &token_index.to_le_bytes(),
+                ],
+                &mango_v4::id(),
+            )
+            .0;
+            ix.accounts.push(AccountMeta::new(rate_history, false));
 
             let sim_result = match client.simulate(vec![ix.clone()]).await {
                 Ok(response) => response.value,
@@ -394,16 +414,25 @@ pub async fn loop_update_funding(
         let pre = Instant::now();
         let ix = Instruction {
             program_id: mango_v4::id(),
-            accounts: anchor_lang::ToAccountMetas::to_account_metas(
-                &mango_v4::accounts::PerpUpdateFunding {
-                    group: perp_market.group,
-                    perp_market: pk,
-                    bids: perp_market.bids,
-                    asks: perp_market.asks,
-                    oracle: perp_market.oracle,
-                },
-                None,
-            ),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpUpdateFunding {
+                        group: perp_market.group,
+                        perp_market: pk,
+                        bids: perp_market.bids,
+                        asks: perp_market.asks,
+                        oracle: perp_market.oracle,
+                    },
+                    None,
+                );
+                ams.extend(
+                    perp_market
+                        .median_oracles
+                        .iter()
+                        .map(|&pubkey| AccountMeta::new_readonly(pubkey, false)),
+                );
+                ams
+            },
             data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpUpdateFunding {}),
         };
         let sig_result = client.send_and_confirm_permissionless_tx(vec![ix]).await;
@@ -519,6 +548,85 @@ async fn charge_collateral_fees_inner(
     Ok(())
 }
 
+/// Settles matured fixed term loans, so they don't keep accruing at their locked rate
+pub async fn loop_settle_fixed_term_loans(mango_client: Arc<MangoClient>, interval: u64) {
+    if interval == 0 {
+        return;
+    }
+
+    // Make a new one separate from the mango_client.account_fetcher,
+    // because we don't want cached responses
+    let fetcher = RpcAccountFetcher {
+        rpc: mango_client.client.new_rpc_async(),
+    };
+
+    let mut interval = mango_v4_client::delay_interval(Duration::from_secs(interval));
+    loop {
+        interval.tick().await;
+
+        match settle_fixed_term_loans_inner(&mango_client, &fetcher).await {
+            Ok(()) => {}
+            Err(err) => {
+                error!("settle_fixed_term_loans error: {err:?}");
+            }
+        }
+    }
+}
+
+async fn settle_fixed_term_loans_inner(
+    client: &MangoClient,
+    fetcher: &RpcAccountFetcher,
+) -> anyhow::Result<()> {
+    let mango_accounts = fetcher
+        .fetch_program_accounts(&mango_v4::id(), MangoAccount::DISCRIMINATOR)
+        .await
+        .context("fetching mango accounts")?
+        .into_iter()
+        .filter_map(
+            |(pk, data)| match MangoAccountValue::from_bytes(&data.data()[8..]) {
+                Ok(acc) => Some((pk, acc)),
+                Err(err) => {
+                    error!(pk=%pk, "settle_fixed_term_loans could not parse account: {err:?}");
+                    None
+                }
+            },
+        );
+
+    let mut ix_to_send = Vec::new();
+    let now_ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as u64;
+    for (pk, account) in mango_accounts {
+        let matured_loan_indexes = account
+            .all_fixed_term_loans()
+            .enumerate()
+            .filter(|(_, loan)| loan.is_configured() && loan.is_matured(now_ts))
+            .map(|(index, _)| index)
+            .collect_vec();
+        for loan_index in matured_loan_indexes {
+            let ixs = match client
+                .fixed_term_loan_settle_instruction((&pk, &account), loan_index)
+                .await
+            {
+                Ok(ixs) => ixs,
+                Err(err) => {
+                    error!(pk=%pk, loan_index, "settle_fixed_term_loans could not build instruction: {err:?}");
+                    continue;
+                }
+            };
+            ix_to_send.push(ixs);
+        }
+    }
+
+    let mut tx_builder = client.transaction_builder().await?;
+    tx_builder.signers.push(client.owner.clone());
+    let txsigs = send_batched_log_errors_no_confirm(tx_builder, &client.client, &ix_to_send).await;
+    info!("settle fixed term loans: {:?}", txsigs);
+
+    Ok(())
+}
+
 /// Try to batch the instructions into transactions and send them
 async fn send_batched_log_errors_no_confirm(
     mut tx_builder: TransactionBuilder,
@@ -64,6 +64,9 @@ struct Cli {
     #[clap(long, env, default_value_t = 300)]
     interval_charge_collateral_fees: u64,
 
+    #[clap(long, env, default_value_t = 60)]
+    interval_settle_fixed_term_loans: u64,
+
     #[clap(long, env, default_value_t = 10)]
     timeout: u64,
 
@@ -157,6 +160,7 @@ async fn main() -> Result<(), anyhow::Error> {
                 cli.interval_update_funding,
                 cli.interval_check_new_listings_and_abort,
                 cli.interval_charge_collateral_fees,
+                cli.interval_settle_fixed_term_loans,
                 prio_jobs,
             )
             .await
@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use itertools::Itertools;
 use mango_v4::health::{HealthCache, HealthType};
-use mango_v4::state::{MangoAccountValue, PerpMarketIndex, Side, TokenIndex, QUOTE_TOKEN_INDEX};
+use mango_v4::state::{MangoAccountValue, PerpMarketIndex, Side, TokenIndex};
 use mango_v4_client::{chain_data, MangoClient, PreparedInstructions};
 use solana_sdk::signature::Signature;
 
@@ -132,6 +132,32 @@ impl<'a> LiquidateHelper<'a> {
         Ok(Some(txsig))
     }
 
+    async fn fixed_term_loan_settle(&self) -> anyhow::Result<Option<Signature>> {
+        // Settle a random loan: the remaining debt becomes a variable borrow
+        // that can be liquidated
+        let loan_indexes = self
+            .liqee
+            .all_fixed_term_loans()
+            .enumerate()
+            .filter_map(|(index, loan)| loan.is_configured().then_some(index))
+            .collect::<Vec<usize>>();
+        if loan_indexes.is_empty() {
+            return Ok(None);
+        }
+        let loan_index = *loan_indexes.choose(&mut rand::thread_rng()).unwrap();
+
+        let txsig = self
+            .client
+            .fixed_term_loan_settle((self.pubkey, self.liqee), loan_index)
+            .await?;
+        info!(
+            loan_index,
+            %txsig,
+            "Settled fixed term loan",
+        );
+        Ok(Some(txsig))
+    }
+
     async fn perp_liq_base_or_positive_pnl(&self) -> anyhow::Result<Option<Signature>> {
         let all_perp_base_positions: anyhow::Result<
             Vec<Option<(PerpMarketIndex, i64, I80F48, I80F48)>>,
@@ -182,13 +208,13 @@ impl<'a> LiquidateHelper<'a> {
                 .fetch_fresh_mango_account(&self.client.mango_account_address)
                 .await
                 .context("getting liquidator account")?;
-            liqor.ensure_perp_position(*perp_market_index, QUOTE_TOKEN_INDEX)?;
+            liqor.ensure_perp_position(*perp_market_index, perp.settle_token_index)?;
             let mut health_cache = self.client.health_cache(&liqor).await.expect("always ok");
             let quote_bank = self
                 .client
-                .first_bank(QUOTE_TOKEN_INDEX)
+                .first_bank(perp.settle_token_index)
                 .await
-                .context("getting quote bank")?;
+                .context("getting settle bank")?;
             let max_usdc_borrow = health_cache.max_borrow_for_health_ratio(
                 &liqor,
                 &quote_bank,
@@ -526,6 +552,78 @@ impl<'a> LiquidateHelper<'a> {
     }
 
     #[instrument(skip(self), fields(pubkey = %*self.pubkey, maint = %self.maint_health))]
+    async fn openbook_v2_close_orders(&self) -> anyhow::Result<Option<Signature>> {
+        // look for any open openbook v2 orders or settleable balances
+        let openbook_oos: anyhow::Result<Vec<_>> = self
+            .liqee
+            .active_openbook_v2_orders()
+            .map(|orders| {
+                let open_orders_account = self.account_fetcher.fetch_raw(&orders.open_orders)?;
+                let open_orders =
+                    mango_v4::openbook_v2_cpi::load_open_orders(&open_orders_account)?;
+                Ok((*orders, open_orders.position))
+            })
+            .try_collect();
+        let mut openbook_force_cancels = openbook_oos?
+            .into_iter()
+            .filter_map(|(orders, position)| {
+                let can_force_cancel = position.bids_base_lots > 0
+                    || position.asks_base_lots > 0
+                    || position.base_free_native > 0
+                    || position.quote_free_native > 0
+                    || position.referrer_rebates_available > 0;
+                if can_force_cancel {
+                    Some(orders)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        if openbook_force_cancels.is_empty() {
+            return Ok(None);
+        }
+        openbook_force_cancels.shuffle(&mut rand::thread_rng());
+
+        let mut ixs = PreparedInstructions::new();
+        let mut cancelled_markets = vec![];
+        let mut tx_builder = self.client.transaction_builder().await?;
+
+        for force_cancel in openbook_force_cancels {
+            let mut new_ixs = ixs.clone();
+            new_ixs.append(
+                self.client
+                    .openbook_v2_liq_force_cancel_orders_instruction(
+                        (self.pubkey, self.liqee),
+                        force_cancel.market_index,
+                        &force_cancel.open_orders,
+                    )
+                    .await?,
+            );
+
+            let exceeds_cu_limit = new_ixs.cu > self.config.max_cu_per_transaction;
+            let exceeds_size_limit = {
+                tx_builder.instructions = new_ixs.clone().to_instructions();
+                !tx_builder.transaction_size()?.is_ok()
+            };
+            if exceeds_cu_limit || exceeds_size_limit {
+                break;
+            }
+
+            ixs = new_ixs;
+            cancelled_markets.push(force_cancel.market_index);
+        }
+
+        tx_builder.instructions = ixs.to_instructions();
+
+        let txsig = tx_builder.send_and_confirm(&self.client.client).await?;
+        info!(
+            market_indexes = ?cancelled_markets,
+            %txsig,
+            "Force cancelled openbook v2 orders",
+        );
+        Ok(Some(txsig))
+    }
+
     async fn send_liq_tx(&self) -> anyhow::Result<Option<Signature>> {
         // TODO: Should we make an attempt to settle positive PNL first?
         // The problem with it is that small market movements can continuously create
@@ -546,6 +644,9 @@ impl<'a> LiquidateHelper<'a> {
         if let Some(txsig) = self.serum3_close_orders().await? {
             return Ok(Some(txsig));
         }
+        if let Some(txsig) = self.openbook_v2_close_orders().await? {
+            return Ok(Some(txsig));
+        }
 
         if self.health_cache.has_phase1_liquidatable() {
             anyhow::bail!(
@@ -559,6 +660,10 @@ impl<'a> LiquidateHelper<'a> {
         // Phase 2: token, perp base, perp positive pnl
         //
 
+        if let Some(txsig) = self.fixed_term_loan_settle().await? {
+            return Ok(Some(txsig));
+        }
+
         if let Some(txsig) = self.perp_liq_base_or_positive_pnl().await? {
             return Ok(Some(txsig));
         }
@@ -455,7 +455,8 @@ impl Rebalancer {
                 Side::Ask => perp.asks,
             };
             let bookside = Box::new(self.account_fetcher.fetch::<BookSide>(&opposite_side_key)?);
-            if bookside.quantity_at_price(price_lots, now_ts, oracle_price_lots) <= 0 {
+            // slot expiry is not checked here, the ioc order may match fewer orders
+            if bookside.quantity_at_price(price_lots, now_ts, 0, oracle_price_lots) <= 0 {
                 warn!(
                     other_side = ?side.invert_side(),
                     %order_price,
@@ -399,7 +399,11 @@ pub async fn init(
                                         / I80F48::from_num(mkt.1.quote_lot_size))
                                     .to_num();
                                     let bookside: Vec<Order> = bookside
-                                        .iter_valid(time_now, oracle_price_lots)
+                                        .iter_valid(
+                                            time_now,
+                                            side_info.slot.max(oracle_info.slot),
+                                            oracle_price_lots,
+                                        )
                                         .map(|item| Order {
                                             price: price_lots_to_ui_perp(
                                                 item.price_lots,
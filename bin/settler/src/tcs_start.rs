@@ -227,7 +227,13 @@ impl State {
 
             let mut account_copy = account.clone();
             let sell_pos_mut = account_copy.token_position_mut(tcs.sell_token_index)?.0;
-            sell_bank.withdraw_with_fee(sell_pos_mut, incentive, now_ts)?;
+            let result = sell_bank.withdraw_with_fee(sell_pos_mut, incentive, now_ts, sell_price);
+            if result.is_anchor_error_with_code(MangoError::BankTotalBorrowLimit.into())
+                || result.is_anchor_error_with_code(MangoError::AccountBorrowLimit.into())
+            {
+                return Ok(false);
+            }
+            result?;
 
             let result = sell_bank.check_net_borrows(sell_price);
             if result.is_anchor_error_with_code(MangoError::BankNetBorrowsLimitReached.into()) {
@@ -73,12 +73,24 @@ impl AccountFetcher {
         let fallback_opt = self.fetch_keyed_account_data(bank.fallback_oracle)?;
         let sol_opt = self.fetch_keyed_account_data(pyth_mainnet_sol_oracle::ID)?;
         let usdc_opt = self.fetch_keyed_account_data(pyth_mainnet_usdc_oracle::ID)?;
+        let median_accounts = bank
+            .median_oracles
+            .iter()
+            .map(|&key| {
+                if key == Pubkey::default() {
+                    Ok(None)
+                } else {
+                    self.fetch_keyed_account_data(key)
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         let oracle_acc_infos = OracleAccountInfos {
             oracle,
             fallback_opt: fallback_opt.as_ref(),
             usdc_opt: usdc_opt.as_ref(),
             sol_opt: sol_opt.as_ref(),
+            median_opts: std::array::from_fn(|i| median_accounts[i].as_ref()),
         };
         let price = bank.oracle_price(&oracle_acc_infos, None)?;
         Ok((bank, price))
@@ -17,18 +17,22 @@ use futures::{stream, StreamExt, TryFutureExt, TryStreamExt};
 use itertools::Itertools;
 use tracing::*;
 
-use mango_v4::accounts_ix::{Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side};
+use mango_v4::accounts_ix::{
+    PerpBatchOrderParams, Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side,
+};
 use mango_v4::accounts_zerocopy::KeyedAccountSharedData;
 use mango_v4::health::HealthCache;
 use mango_v4::state::{
-    Bank, Group, MangoAccountValue, OracleAccountInfos, PerpMarket, PerpMarketIndex,
-    PlaceOrderType, SelfTradeBehavior, Serum3MarketIndex, Side, TokenIndex, INSURANCE_TOKEN_INDEX,
+    Bank, Group, MangoAccountValue, OpenbookV2MarketIndex, OracleAccountInfos, PerpMarket,
+    PerpMarketIndex, PlaceOrderType, SelfTradeBehavior, Serum3MarketIndex, Side,
+    TokenConditionalSwap, TokenConditionalSwapDisplayPriceStyle, TokenConditionalSwapIntention,
+    TokenIndex, INSURANCE_TOKEN_INDEX,
 };
 
 use crate::account_fetcher::*;
 use crate::confirm_transaction::{wait_for_transaction_confirmation, RpcConfirmTransactionConfig};
 use crate::context::MangoGroupContext;
-use crate::gpa::{fetch_anchor_account, fetch_mango_accounts};
+use crate::gpa::{fetch_anchor_account, fetch_mango_accounts, fetch_openbook_v2_markets};
 use crate::health_cache;
 use crate::priority_fees::{FixedPriorityFeeProvider, PriorityFeeProvider};
 use crate::util::PreparedInstructions;
@@ -510,6 +514,94 @@ impl MangoClient {
         .await
     }
 
+    pub async fn account_edit(
+        &self,
+        name_opt: Option<String>,
+        delegate_opt: Option<Pubkey>,
+        temporary_delegate_opt: Option<Pubkey>,
+        temporary_delegate_expiry_opt: Option<u64>,
+    ) -> anyhow::Result<Signature> {
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountEdit {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountEdit {
+                name_opt,
+                delegate_opt,
+                temporary_delegate_opt,
+                temporary_delegate_expiry_opt,
+            }),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Grows the account to the given number of slots, the fee payer pays for the rent
+    pub async fn account_expand(
+        &self,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+        fixed_term_loan_count: u8,
+    ) -> anyhow::Result<Signature> {
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountExpand {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    payer: self.client.fee_payer().pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountExpandV5 {
+                token_count,
+                serum3_count,
+                perp_count,
+                perp_oo_count,
+                token_conditional_swap_count,
+                openbook_v2_count,
+                perp_trigger_order_count,
+                fixed_term_loan_count,
+            }),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
+    /// Closes the account and returns the rent to the owner.
+    ///
+    /// The account must not have any open positions.
+    pub async fn account_close(&self) -> anyhow::Result<Signature> {
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::AccountClose {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    sol_destination: self.owner(),
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::AccountClose {
+                force_close: false,
+            }),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
     pub async fn token_deposit(
         &self,
         mint: Pubkey,
@@ -1255,6 +1347,84 @@ impl MangoClient {
             .await
     }
 
+    pub async fn openbook_v2_liq_force_cancel_orders_instruction(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: OpenbookV2MarketIndex,
+        open_orders: &Pubkey,
+    ) -> anyhow::Result<PreparedInstructions> {
+        // openbook v2 markets aren't part of the group context yet, look them up
+        let (market_address, market) =
+            fetch_openbook_v2_markets(self.client.rpc_async(), mango_v4::ID, self.group())
+                .await?
+                .into_iter()
+                .find(|(_, market)| market.market_index == market_index)
+                .ok_or_else(|| anyhow::anyhow!("no openbook v2 market {market_index}"))?;
+        let market_external = account_fetcher_fetch_anchor_account::<openbook_v2::state::Market>(
+            &*self.account_fetcher,
+            &market.openbook_v2_market_external,
+        )
+        .await?;
+        let base = self.context.token(market.base_token_index);
+        let quote = self.context.token(market.quote_token_index);
+        let (health_remaining_ams, health_cu) = self
+            .derive_health_check_remaining_account_metas(liqee.1, vec![], vec![], vec![])
+            .await
+            .unwrap();
+
+        let limit = 5;
+        let ix = PreparedInstructions::from_single(
+            Instruction {
+                program_id: mango_v4::id(),
+                accounts: {
+                    let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                        &mango_v4::accounts::OpenbookV2LiqForceCancelOrders {
+                            group: self.group(),
+                            account: *liqee.0,
+                            open_orders: *open_orders,
+                            openbook_v2_market: market_address,
+                            openbook_v2_program: market.openbook_v2_program,
+                            openbook_v2_market_external: market.openbook_v2_market_external,
+                            bids: market_external.bids,
+                            asks: market_external.asks,
+                            event_heap: market_external.event_heap,
+                            market_base_vault: market_external.market_base_vault,
+                            market_quote_vault: market_external.market_quote_vault,
+                            market_vault_signer: market_external.market_authority,
+                            quote_bank: quote.first_bank(),
+                            quote_vault: quote.first_vault(),
+                            base_bank: base.first_bank(),
+                            base_vault: base.first_vault(),
+                            token_program: Token::id(),
+                        },
+                        None,
+                    );
+                    ams.extend(health_remaining_ams.into_iter());
+                    ams
+                },
+                data: anchor_lang::InstructionData::data(
+                    &mango_v4::instruction::OpenbookV2LiqForceCancelOrders { limit },
+                ),
+            },
+            self.instruction_cu(health_cu)
+                + self.context.compute_estimates.cu_per_serum3_order_cancel * limit as u32,
+        );
+        Ok(ix)
+    }
+
+    pub async fn openbook_v2_liq_force_cancel_orders(
+        &self,
+        liqee: (&Pubkey, &MangoAccountValue),
+        market_index: OpenbookV2MarketIndex,
+        open_orders: &Pubkey,
+    ) -> anyhow::Result<Signature> {
+        let ixs = self
+            .openbook_v2_liq_force_cancel_orders_instruction(liqee, market_index, open_orders)
+            .await?;
+        self.send_and_confirm_permissionless_tx(ixs.to_instructions())
+            .await
+    }
+
     pub async fn serum3_cancel_order(
         &self,
         market_name: &str,
@@ -1452,6 +1622,95 @@ impl MangoClient {
         self.send_and_confirm_owner_tx(ixs.to_instructions()).await
     }
 
+    /// Places several orders on one perp market in one instruction, optionally
+    /// canceling all existing orders on the market first.
+    pub async fn perp_place_orders_batch_instruction(
+        &self,
+        account: &MangoAccountValue,
+        market_index: PerpMarketIndex,
+        cancel_all: bool,
+        orders: Vec<PerpBatchOrderParams>,
+        limit: u8,
+    ) -> anyhow::Result<PreparedInstructions> {
+        let mut ixs = PreparedInstructions::new();
+
+        let perp = self.context.perp(market_index);
+        let mut account = account.clone();
+
+        let close_perp_ixs_opt = self
+            .replace_perp_market_if_needed(&account, market_index)
+            .await?;
+
+        if let Some((close_perp_ixs, modified_account)) = close_perp_ixs_opt {
+            account = modified_account;
+            ixs.append(close_perp_ixs);
+        }
+
+        let (health_remaining_metas, health_cu) = self
+            .derive_health_check_remaining_account_metas(
+                &account,
+                vec![],
+                vec![],
+                vec![market_index],
+            )
+            .await?;
+
+        let compute_estimates = &self.context.compute_estimates;
+        let cancel_cu = if cancel_all {
+            compute_estimates.cu_per_perp_order_cancel * limit as u32
+        } else {
+            0
+        };
+        let match_cu =
+            compute_estimates.cu_per_perp_order_match * limit as u32 * orders.len() as u32;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: {
+                let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                    &mango_v4::accounts::PerpPlaceOrdersBatch {
+                        group: self.group(),
+                        account: self.mango_account_address,
+                        owner: self.owner(),
+                        perp_market: perp.address,
+                        bids: perp.bids,
+                        asks: perp.asks,
+                        event_queue: perp.event_queue,
+                        oracle: perp.oracle,
+                    },
+                    None,
+                );
+                ams.extend(health_remaining_metas.into_iter());
+                ams
+            },
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::PerpPlaceOrdersBatch {
+                    cancel_all,
+                    orders,
+                    limit,
+                },
+            ),
+        };
+
+        ixs.push(ix, self.instruction_cu(health_cu) + cancel_cu + match_cu);
+
+        Ok(ixs)
+    }
+
+    pub async fn perp_place_orders_batch(
+        &self,
+        market_index: PerpMarketIndex,
+        cancel_all: bool,
+        orders: Vec<PerpBatchOrderParams>,
+        limit: u8,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let ixs = self
+            .perp_place_orders_batch_instruction(&account, market_index, cancel_all, orders, limit)
+            .await?;
+        self.send_and_confirm_owner_tx(ixs.to_instructions()).await
+    }
+
     pub fn perp_cancel_all_orders_instruction(
         &self,
         market_index: PerpMarketIndex,
@@ -1485,6 +1744,42 @@ impl MangoClient {
         Ok(ixs)
     }
 
+    pub async fn perp_cancel_all_orders(
+        &self,
+        market_index: PerpMarketIndex,
+        limit: u8,
+    ) -> anyhow::Result<Signature> {
+        let ixs = self.perp_cancel_all_orders_instruction(market_index, limit)?;
+        self.send_and_confirm_owner_tx(ixs.to_instructions()).await
+    }
+
+    pub async fn perp_cancel_order(
+        &self,
+        market_index: PerpMarketIndex,
+        order_id: u128,
+    ) -> anyhow::Result<Signature> {
+        let perp = self.context.perp(market_index);
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::PerpCancelOrder {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    owner: self.owner(),
+                    perp_market: perp.address,
+                    bids: perp.bids,
+                    asks: perp.asks,
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&mango_v4::instruction::PerpCancelOrder {
+                order_id,
+            }),
+        };
+        self.send_and_confirm_owner_tx(vec![ix]).await
+    }
+
     pub async fn perp_deactivate_position(
         &self,
         market_index: PerpMarketIndex,
@@ -1626,6 +1921,59 @@ impl MangoClient {
             .await
     }
 
+    /// The settler is the client owner, the transaction must be signed by it
+    pub async fn fixed_term_loan_settle_instruction(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        fixed_term_loan_index: usize,
+    ) -> anyhow::Result<PreparedInstructions> {
+        let loan = account.1.fixed_term_loan_by_index(fixed_term_loan_index)?;
+        let token = self.context.token(loan.token_index);
+
+        let (health_remaining_ams, health_cu) = self
+            .derive_health_check_remaining_account_metas(account.1, vec![], vec![], vec![])
+            .await
+            .unwrap();
+
+        let ixs = PreparedInstructions::from_single(
+            Instruction {
+                program_id: mango_v4::id(),
+                accounts: {
+                    let mut ams = anchor_lang::ToAccountMetas::to_account_metas(
+                        &mango_v4::accounts::FixedTermLoanSettle {
+                            group: self.group(),
+                            account: *account.0,
+                            settler: self.owner(),
+                            bank: token.first_bank(),
+                        },
+                        None,
+                    );
+                    ams.extend(health_remaining_ams.into_iter());
+                    ams
+                },
+                data: anchor_lang::InstructionData::data(
+                    &mango_v4::instruction::FixedTermLoanSettle {
+                        fixed_term_loan_index: fixed_term_loan_index as u8,
+                        fixed_term_loan_id: loan.id,
+                    },
+                ),
+            },
+            self.instruction_cu(health_cu),
+        );
+        Ok(ixs)
+    }
+
+    pub async fn fixed_term_loan_settle(
+        &self,
+        account: (&Pubkey, &MangoAccountValue),
+        fixed_term_loan_index: usize,
+    ) -> anyhow::Result<Signature> {
+        let ixs = self
+            .fixed_term_loan_settle_instruction(account, fixed_term_loan_index)
+            .await?;
+        self.send_and_confirm_owner_tx(ixs.to_instructions()).await
+    }
+
     pub async fn perp_liq_base_or_positive_pnl_instruction(
         &self,
         liqee: (&Pubkey, &MangoAccountValue),
@@ -2012,6 +2360,237 @@ impl MangoClient {
         ))
     }
 
+    fn token_conditional_swap_create_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        data: Vec<u8>,
+    ) -> PreparedInstructions {
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenConditionalSwapCreate {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    buy_bank: self.context.token(buy_token_index).first_bank(),
+                    sell_bank: self.context.token(sell_token_index).first_bank(),
+                },
+                None,
+            ),
+            data,
+        };
+        PreparedInstructions::from_single(ix, self.instruction_cu(0))
+    }
+
+    /// Converts a "sell token per buy token" price from ui to native units
+    fn token_conditional_swap_native_price(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        ui_price: f64,
+    ) -> f64 {
+        let buy_decimals = self.context.token(buy_token_index).decimals as i32;
+        let sell_decimals = self.context.token(sell_token_index).decimals as i32;
+        ui_price * 10f64.powi(sell_decimals - buy_decimals)
+    }
+
+    /// Creates a FixedPremium token conditional swap on the MangoClient's account.
+    ///
+    /// Amounts are in ui units of the buy and sell token (f64::INFINITY for no limit)
+    /// and prices are in ui "sell token per buy token" units, regardless of the
+    /// display_price_style.
+    #[allow(clippy::too_many_arguments)]
+    pub fn token_conditional_swap_create_v2_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: f64,
+        max_sell: f64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+    ) -> anyhow::Result<PreparedInstructions> {
+        let native_price = |ui_price| {
+            self.token_conditional_swap_native_price(buy_token_index, sell_token_index, ui_price)
+        };
+        let data = anchor_lang::InstructionData::data(
+            &mango_v4::instruction::TokenConditionalSwapCreateV2 {
+                max_buy: self.context.token(buy_token_index).ui_to_native(max_buy),
+                max_sell: self.context.token(sell_token_index).ui_to_native(max_sell),
+                expiry_timestamp,
+                price_lower_limit: native_price(price_lower_limit),
+                price_upper_limit: native_price(price_upper_limit),
+                price_premium_rate,
+                allow_creating_deposits,
+                allow_creating_borrows,
+                display_price_style,
+                intention,
+            },
+        );
+        Ok(self.token_conditional_swap_create_instruction(buy_token_index, sell_token_index, data))
+    }
+
+    /// Creates a PremiumAuction token conditional swap on the MangoClient's account.
+    ///
+    /// Units are like for token_conditional_swap_create_v2_instruction().
+    #[allow(clippy::too_many_arguments)]
+    pub fn token_conditional_swap_create_premium_auction_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: f64,
+        max_sell: f64,
+        expiry_timestamp: u64,
+        price_lower_limit: f64,
+        price_upper_limit: f64,
+        max_price_premium_rate: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        intention: TokenConditionalSwapIntention,
+        duration_seconds: u64,
+    ) -> anyhow::Result<PreparedInstructions> {
+        let native_price = |ui_price| {
+            self.token_conditional_swap_native_price(buy_token_index, sell_token_index, ui_price)
+        };
+        let data = anchor_lang::InstructionData::data(
+            &mango_v4::instruction::TokenConditionalSwapCreatePremiumAuction {
+                max_buy: self.context.token(buy_token_index).ui_to_native(max_buy),
+                max_sell: self.context.token(sell_token_index).ui_to_native(max_sell),
+                expiry_timestamp,
+                price_lower_limit: native_price(price_lower_limit),
+                price_upper_limit: native_price(price_upper_limit),
+                max_price_premium_rate,
+                allow_creating_deposits,
+                allow_creating_borrows,
+                display_price_style,
+                intention,
+                duration_seconds,
+            },
+        );
+        Ok(self.token_conditional_swap_create_instruction(buy_token_index, sell_token_index, data))
+    }
+
+    /// Creates a LinearAuction token conditional swap on the MangoClient's account.
+    ///
+    /// Units are like for token_conditional_swap_create_v2_instruction().
+    #[allow(clippy::too_many_arguments)]
+    pub fn token_conditional_swap_create_linear_auction_instruction(
+        &self,
+        buy_token_index: TokenIndex,
+        sell_token_index: TokenIndex,
+        max_buy: f64,
+        max_sell: f64,
+        expiry_timestamp: u64,
+        price_start: f64,
+        price_end: f64,
+        allow_creating_deposits: bool,
+        allow_creating_borrows: bool,
+        display_price_style: TokenConditionalSwapDisplayPriceStyle,
+        start_timestamp: u64,
+        duration_seconds: u64,
+    ) -> anyhow::Result<PreparedInstructions> {
+        let native_price = |ui_price| {
+            self.token_conditional_swap_native_price(buy_token_index, sell_token_index, ui_price)
+        };
+        let data = anchor_lang::InstructionData::data(
+            &mango_v4::instruction::TokenConditionalSwapCreateLinearAuction {
+                max_buy: self.context.token(buy_token_index).ui_to_native(max_buy),
+                max_sell: self.context.token(sell_token_index).ui_to_native(max_sell),
+                expiry_timestamp,
+                price_start: native_price(price_start),
+                price_end: native_price(price_end),
+                allow_creating_deposits,
+                allow_creating_borrows,
+                display_price_style,
+                start_timestamp,
+                duration_seconds,
+            },
+        );
+        Ok(self.token_conditional_swap_create_instruction(buy_token_index, sell_token_index, data))
+    }
+
+    pub fn token_conditional_swap_cancel_instruction(
+        &self,
+        account: &MangoAccountValue,
+        token_conditional_swap_id: u64,
+    ) -> anyhow::Result<PreparedInstructions> {
+        let (tcs_index, tcs) = account.token_conditional_swap_by_id(token_conditional_swap_id)?;
+
+        let ix = Instruction {
+            program_id: mango_v4::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &mango_v4::accounts::TokenConditionalSwapCancel {
+                    group: self.group(),
+                    account: self.mango_account_address,
+                    authority: self.owner(),
+                    buy_bank: self.context.token(tcs.buy_token_index).first_bank(),
+                    sell_bank: self.context.token(tcs.sell_token_index).first_bank(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &mango_v4::instruction::TokenConditionalSwapCancel {
+                    token_conditional_swap_index: tcs_index.try_into().unwrap(),
+                    token_conditional_swap_id,
+                },
+            ),
+        };
+        Ok(PreparedInstructions::from_single(
+            ix,
+            self.instruction_cu(0),
+        ))
+    }
+
+    pub async fn token_conditional_swap_cancel(
+        &self,
+        token_conditional_swap_id: u64,
+    ) -> anyhow::Result<Signature> {
+        let account = self.mango_account().await?;
+        let ixs =
+            self.token_conditional_swap_cancel_instruction(&account, token_conditional_swap_id)?;
+        self.send_and_confirm_owner_tx(ixs.to_instructions()).await
+    }
+
+    /// Lists the active token conditional swaps of an account, with their status
+    /// at the current bank oracle prices.
+    pub async fn token_conditional_swaps_with_status(
+        &self,
+        account: &MangoAccountValue,
+    ) -> anyhow::Result<Vec<TokenConditionalSwapWithStatus>> {
+        let now_ts: u64 = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+
+        let mut result = vec![];
+        for tcs in account.active_token_conditional_swaps() {
+            let buy_price = self.bank_oracle_price(tcs.buy_token_index).await?;
+            let sell_price = self.bank_oracle_price(tcs.sell_token_index).await?;
+            let price = buy_price.to_num::<f64>() / sell_price.to_num::<f64>();
+            let status = if tcs.is_expired(now_ts) {
+                TokenConditionalSwapStatus::Expired
+            } else if tcs.is_triggerable(price, now_ts) {
+                TokenConditionalSwapStatus::Triggerable
+            } else if tcs.is_startable(price, now_ts) {
+                TokenConditionalSwapStatus::Startable
+            } else {
+                TokenConditionalSwapStatus::Waiting
+            };
+            result.push(TokenConditionalSwapWithStatus {
+                tcs: *tcs,
+                price,
+                status,
+            });
+        }
+        Ok(result)
+    }
+
     // health region
 
     pub async fn health_region_begin_instruction(
@@ -2226,6 +2805,26 @@ pub enum MangoClientError {
     },
 }
 
+/// Whether a token conditional swap can currently be acted on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenConditionalSwapStatus {
+    Expired,
+    /// Can be triggered: the price is in range, or the auction has started
+    Triggerable,
+    /// An auction that can be started at the current price
+    Startable,
+    /// Waiting for the price to get in range, or for the auction to start
+    Waiting,
+}
+
+#[derive(Clone, Debug)]
+pub struct TokenConditionalSwapWithStatus {
+    pub tcs: TokenConditionalSwap,
+    /// The oracle price in native "sell token per buy token" units
+    pub price: f64,
+    pub status: TokenConditionalSwapStatus,
+}
+
 #[derive(Copy, Clone, Debug, Default)]
 pub struct TransactionSize {
     pub accounts: usize,
@@ -7,9 +7,9 @@ use anchor_lang::__private::bytemuck;
 use mango_v4::{
     accounts_zerocopy::{KeyedAccountReader, KeyedAccountSharedData},
     state::{
-        determine_oracle_type, load_whirlpool_state, oracle_state_unchecked, Group,
-        MangoAccountValue, OracleAccountInfos, OracleConfig, OracleConfigParams, OracleType,
-        PerpMarketIndex, Serum3MarketIndex, TokenIndex, MAX_BANKS,
+        determine_oracle_type, load_raydium_pool_state, load_whirlpool_state,
+        oracle_state_unchecked, Group, MangoAccountValue, OracleAccountInfos, OracleConfig,
+        OracleConfigParams, OracleType, PerpMarketIndex, Serum3MarketIndex, TokenIndex, MAX_BANKS,
     },
 };
 
@@ -34,6 +34,8 @@ pub struct TokenContext {
     pub banks: [Pubkey; MAX_BANKS],
     pub vaults: [Pubkey; MAX_BANKS],
     pub fallback_context: FallbackOracleContext,
+    /// Additional oracles of the bank's median oracle mode
+    pub median_oracles: Vec<Pubkey>,
     pub mint_info_address: Pubkey,
     pub decimals: u8,
     pub oracle_config: OracleConfig,
@@ -44,6 +46,11 @@ impl TokenContext {
         (native / I80F48::from(10u64.pow(self.decimals.into()))).to_num()
     }
 
+    /// Converts a ui amount to native tokens, saturating at 0 and u64::MAX
+    pub fn ui_to_native(&self, ui: f64) -> u64 {
+        (ui * 10f64.powi(self.decimals.into())) as u64
+    }
+
     pub fn first_bank(&self) -> Pubkey {
         self.banks[0]
     }
@@ -104,6 +111,8 @@ pub struct PerpMarketContext {
     pub asks: Pubkey,
     pub event_queue: Pubkey,
     pub oracle: Pubkey,
+    /// Additional oracles of the perp market's median oracle mode
+    pub median_oracles: Vec<Pubkey>,
     pub base_lot_size: i64,
     pub quote_lot_size: i64,
     pub base_decimals: u8,
@@ -268,6 +277,7 @@ impl MangoGroupContext {
                             key: mi.fallback_oracle,
                             quote_key: Pubkey::default(),
                         },
+                        median_oracles: vec![],
                         oracle_config: OracleConfigParams::default().to_oracle_config(),
                         group: mi.group,
                         mint: mi.mint,
@@ -290,6 +300,12 @@ impl MangoGroupContext {
             token.name = bank.name().into();
             token.decimals = bank.mint_decimals;
             token.oracle_config = bank.oracle_config;
+            token.median_oracles = bank
+                .median_oracles
+                .iter()
+                .filter(|key| **key != Pubkey::default())
+                .copied()
+                .collect();
             let (key, acc_info) = fallback_oracle_accounts[index].clone();
             token.fallback_context.quote_key =
                 get_fallback_quote_key(&KeyedAccountSharedData::new(key, acc_info));
@@ -350,6 +366,12 @@ impl MangoGroupContext {
                         address: *pk,
                         group: pm.group,
                         oracle: pm.oracle,
+                        median_oracles: pm
+                            .median_oracles
+                            .iter()
+                            .filter(|key| **key != Pubkey::default())
+                            .copied()
+                            .collect(),
                         perp_market_index: pm.perp_market_index,
                         settle_token_index: pm.settle_token_index,
                         asks: pm.asks,
@@ -421,6 +443,7 @@ impl MangoGroupContext {
         let mut banks = vec![];
         let mut oracles = vec![];
         let mut fallbacks = vec![];
+        let mut median_oracles = vec![];
         for position in account.active_token_positions() {
             let token = self.token(position.token_index);
             banks.push((
@@ -431,9 +454,11 @@ impl MangoGroupContext {
             if let Some(fallback_context) = fallback_contexts.get(&token.oracle) {
                 fallbacks.extend(fallback_context.keys());
             }
+            median_oracles.extend(token.median_oracles.iter().copied());
         }
 
         let serum_oos = account.active_serum3_orders().map(|&s| s.open_orders);
+        let openbook_v2_oos = account.active_openbook_v2_orders().map(|&s| s.open_orders);
         let perp_markets = account
             .active_perp_positions()
             .map(|&pa| self.perp_market_address(pa.market_index));
@@ -441,11 +466,16 @@ impl MangoGroupContext {
             .active_perp_positions()
             .map(|&pa| self.perp(pa.market_index).oracle);
         // FUTURE: implement fallback oracles for perps
+        for pa in account.active_perp_positions() {
+            median_oracles.extend(self.perp(pa.market_index).median_oracles.iter().copied());
+        }
 
         let fallback_oracles: Vec<Pubkey> = fallbacks
             .into_iter()
             .unique()
             .filter(|key| !oracles.contains(key) && key != &Pubkey::default())
+            .chain(median_oracles)
+            .unique()
             .collect();
         let fallbacks_len = fallback_oracles.len();
 
@@ -466,6 +496,7 @@ impl MangoGroupContext {
             .chain(perp_markets.map(to_account_meta))
             .chain(perp_oracles.map(to_account_meta))
             .chain(serum_oos.map(to_account_meta))
+            .chain(openbook_v2_oos.map(to_account_meta))
             .chain(fallback_oracles.into_iter().map(to_account_meta))
             .collect();
 
@@ -488,6 +519,7 @@ impl MangoGroupContext {
         let mut banks = vec![];
         let mut oracles = vec![];
         let mut fallbacks = vec![];
+        let mut median_oracles = vec![];
 
         let token_indexes = account2
             .active_token_positions()
@@ -504,12 +536,17 @@ impl MangoGroupContext {
             if let Some(fallback_context) = fallback_contexts.get(&token.oracle) {
                 fallbacks.extend(fallback_context.keys());
             }
+            median_oracles.extend(token.median_oracles.iter().copied());
         }
 
         let serum_oos = account2
             .active_serum3_orders()
             .chain(account1.active_serum3_orders())
             .map(|&s| s.open_orders);
+        let openbook_v2_oos = account2
+            .active_openbook_v2_orders()
+            .chain(account1.active_openbook_v2_orders())
+            .map(|&s| s.open_orders);
         let perp_market_indexes = account2
             .active_perp_positions()
             .chain(account1.active_perp_positions())
@@ -523,11 +560,16 @@ impl MangoGroupContext {
             .iter()
             .map(|&index| self.perp(index).oracle);
         // FUTURE: implement fallback oracles for perps
+        for &index in perp_market_indexes.iter() {
+            median_oracles.extend(self.perp(index).median_oracles.iter().copied());
+        }
 
         let fallback_oracles: Vec<Pubkey> = fallbacks
             .into_iter()
             .unique()
             .filter(|key| !oracles.contains(key) && key != &Pubkey::default())
+            .chain(median_oracles)
+            .unique()
             .collect();
         let fallbacks_len = fallback_oracles.len();
 
@@ -548,6 +590,7 @@ impl MangoGroupContext {
             .chain(perp_markets.map(to_account_meta))
             .chain(perp_oracles.map(to_account_meta))
             .chain(serum_oos.map(to_account_meta))
+            .chain(openbook_v2_oos.map(to_account_meta))
             .chain(fallback_oracles.into_iter().map(to_account_meta))
             .collect();
 
@@ -715,6 +758,10 @@ fn get_fallback_quote_key(acc_info: &impl KeyedAccountReader) -> Pubkey {
                 Some(whirlpool) => whirlpool.get_quote_oracle().ok(),
                 None => None,
             },
+            OracleType::RaydiumCLMM => match load_raydium_pool_state(acc_info).ok() {
+                Some(pool) => pool.get_quote_oracle().ok(),
+                None => None,
+            },
             _ => None,
         },
         None => None,
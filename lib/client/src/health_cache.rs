@@ -16,6 +16,8 @@ pub async fn new(
 ) -> anyhow::Result<HealthCache> {
     let active_token_len = account.active_token_positions().count();
     let active_perp_len = account.active_perp_positions().count();
+    let active_serum3_len = account.active_serum3_orders().count();
+    let active_openbook_v2_len = account.active_openbook_v2_orders().count();
 
     let fallback_keys = context
         .derive_fallback_oracle_keys(fallback_config, account_fetcher)
@@ -43,8 +45,12 @@ pub async fn new(
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_openbook_v2: active_token_len * 2 + active_perp_len * 2 + active_serum3_len,
         staleness_slot: None,
-        begin_fallback_oracles: metas.len(),
+        begin_fallback_oracles: active_token_len * 2
+            + active_perp_len * 2
+            + active_serum3_len
+            + active_openbook_v2_len,
         usdc_oracle_index: metas
             .iter()
             .position(|m| m.pubkey == pyth_mainnet_usdc_oracle::ID),
@@ -64,6 +70,8 @@ pub fn new_sync(
 ) -> anyhow::Result<HealthCache> {
     let active_token_len = account.active_token_positions().count();
     let active_perp_len = account.active_perp_positions().count();
+    let active_serum3_len = account.active_serum3_orders().count();
+    let active_openbook_v2_len = account.active_openbook_v2_orders().count();
 
     let (metas, _health_cu) = context.derive_health_check_remaining_account_metas(
         account,
@@ -88,8 +96,12 @@ pub fn new_sync(
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_openbook_v2: active_token_len * 2 + active_perp_len * 2 + active_serum3_len,
         staleness_slot: None,
-        begin_fallback_oracles: metas.len(),
+        begin_fallback_oracles: active_token_len * 2
+            + active_perp_len * 2
+            + active_serum3_len
+            + active_openbook_v2_len,
         usdc_oracle_index: None,
         sol_oracle_index: None,
     };
@@ -0,0 +1,64 @@
+use fixed::types::I80F48;
+use mango_v4::state::{Bank, RateHistoryEntry, TokenRateHistory};
+
+/// Borrow and deposit rates in APR, like Bank::compute_index() applies them.
+///
+/// The borrow rate excludes the bank's loan_fee_rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InterestRates {
+    pub borrow_rate: f64,
+    pub deposit_rate: f64,
+}
+
+/// Preview the rates the bank would charge and pay at the given utilization (0..1).
+///
+/// Uses the bank's configured interest curve type. For the PID controller curve
+/// the rate doesn't depend on utilization until the next hourly update.
+pub fn rates_at_utilization(bank: &Bank, utilization: f64) -> InterestRates {
+    let utilization = I80F48::from_num(utilization.clamp(0.0, 1.0));
+    let borrow_rate = bank.compute_interest_rate(utilization);
+    InterestRates {
+        borrow_rate: borrow_rate.to_num(),
+        deposit_rate: (borrow_rate * utilization).to_num(),
+    }
+}
+
+/// Utilization over all banks of a token
+pub fn utilization(banks: &[Bank]) -> f64 {
+    let deposits: I80F48 = banks.iter().map(|b| b.native_deposits()).sum();
+    let borrows: I80F48 = banks
+        .iter()
+        .map(|b| b.native_borrows_including_fixed_term())
+        .sum();
+    if deposits <= 0 {
+        return 0.0;
+    }
+    (borrows / deposits).to_num::<f64>().clamp(0.0, 1.0)
+}
+
+/// Current rates for a token, given all of its banks
+///
+/// The deposit rate includes the interest of fixed term loans.
+pub fn current_rates(banks: &[Bank]) -> anyhow::Result<InterestRates> {
+    let bank = banks
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("no banks passed"))?;
+    let mut rates = rates_at_utilization(bank, utilization(banks));
+    let deposits: I80F48 = banks.iter().map(|b| b.native_deposits()).sum();
+    if deposits > 0 {
+        let fixed_term_interest: I80F48 = banks.iter().map(|b| b.fixed_term_yearly_interest).sum();
+        rates.deposit_rate += (fixed_term_interest / deposits).to_num::<f64>();
+    }
+    Ok(rates)
+}
+
+/// The token's hourly rate history, oldest entry first
+pub fn rate_history(rate_history: &TokenRateHistory) -> Vec<RateHistoryEntry> {
+    let mut entries = rate_history
+        .history
+        .iter_newest_first()
+        .copied()
+        .collect::<Vec<_>>();
+    entries.reverse();
+    entries
+}
@@ -13,6 +13,7 @@ mod context;
 pub mod error_tracking;
 pub mod gpa;
 pub mod health_cache;
+pub mod interest_rate;
 pub mod jupiter;
 pub mod perp_pnl;
 pub mod priority_fees;
@@ -0,0 +1,26 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Creates a fixed term loan.
+///
+/// Health accounts are passed as remaining_accounts.
+#[derive(Accounts)]
+pub struct FixedTermLoanCreate<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::FixedTermLoanCreate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen,
+        constraint = account.load()?.is_owner_or_delegate(authority.key()),
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = group)]
+    pub bank: AccountLoader<'info, Bank>,
+}
@@ -0,0 +1,27 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Settles a fixed term loan.
+///
+/// Health accounts are passed as remaining_accounts. They are needed when the owner
+/// settles, and when settling an unhealthy account's loan before maturity.
+#[derive(Accounts)]
+pub struct FixedTermLoanSettle<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::FixedTermLoanSettle) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen,
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+
+    pub settler: Signer<'info>,
+
+    #[account(mut, has_one = group)]
+    pub bank: AccountLoader<'info, Bank>,
+}
@@ -33,9 +33,10 @@ pub struct OpenbookV2CreateOpenOrders<'info> {
     pub openbook_v2_market_external: AccountLoader<'info, Market>,
 
     // initialized by this instruction via cpi to openbook_v2
+    // the group is the owner of the open orders account
     #[account(
         mut,
-        seeds = [b"OpenOrders".as_ref(), openbook_v2_market.key().as_ref(), openbook_v2_market_external.key().as_ref(), &account_num.to_le_bytes()],
+        seeds = [b"OpenOrders".as_ref(), group.key().as_ref(), openbook_v2_market_external.key().as_ref(), &account_num.to_le_bytes()],
         bump,
         seeds::program = openbook_v2_program.key(),
     )]
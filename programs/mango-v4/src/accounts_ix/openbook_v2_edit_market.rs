@@ -6,11 +6,10 @@ use crate::state::*;
 #[instruction(market_index: OpenbookV2MarketIndex)]
 pub struct OpenbookV2EditMarket<'info> {
     #[account(
-        constraint = group.load()?.openbook_v2_supported(),
-        constraint = group.load()?.admin == admin.key(),
+        // group <-> admin relation is checked at #1
+        constraint = group.load()?.openbook_v2_supported()
     )]
     pub group: AccountLoader<'info, Group>,
-
     pub admin: Signer<'info>,
 
     #[account(
@@ -33,6 +33,7 @@ pub struct OpenbookV2LiqForceCancelOrders<'info> {
     pub openbook_v2_program: Program<'info, OpenbookV2>,
 
     #[account(
+        mut,
         has_one = bids,
         has_one = asks,
         has_one = event_heap,
@@ -6,9 +6,7 @@ use openbook_v2::{program::OpenbookV2, state::Market};
 
 #[derive(Accounts)]
 pub struct OpenbookV2PlaceOrder<'info> {
-    #[account(
-        constraint = group.load()?.is_ix_enabled(IxGate::OpenbookV2PlaceOrder) @ MangoError::IxIsDisabled,
-    )]
+    /// CHECK: Instruction gate will be checked in instruction code as this is used by multiple instructions.
     pub group: AccountLoader<'info, Group>,
 
     #[account(
@@ -25,6 +23,11 @@ pub struct OpenbookV2PlaceOrder<'info> {
     /// CHECK: Validated inline by checking against the pubkey stored in the account at #2
     pub open_orders: UncheckedAccount<'info>,
 
+    #[account(
+        has_one = group,
+        has_one = openbook_v2_program,
+        has_one = openbook_v2_market_external,
+    )]
     pub openbook_v2_market: AccountLoader<'info, OpenbookV2Market>,
 
     pub openbook_v2_program: Program<'info, OpenbookV2>,
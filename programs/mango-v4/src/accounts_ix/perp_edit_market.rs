@@ -13,7 +13,8 @@ pub struct PerpEditMarket<'info> {
     )]
     pub perp_market: AccountLoader<'info, PerpMarket>,
 
-    /// The oracle account is optional and only used when reset_stable_price is set.
+    /// The oracle account is optional and only used when reset_stable_price is set
+    /// or when oracle_opt is set, in which case it must be the new oracle.
     ///
     /// CHECK: The oracle can be one of several different account types
     pub oracle: UncheckedAccount<'info>,
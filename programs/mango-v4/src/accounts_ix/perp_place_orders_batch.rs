@@ -0,0 +1,123 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PerpPlaceOrdersBatch<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::PerpPlaceOrdersBatch) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = account.load()?.is_operational() @ MangoError::AccountIsFrozen
+        // owner is checked at #1
+    )]
+    pub account: AccountLoader<'info, MangoAccountFixed>,
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = oracle,
+    )]
+    pub perp_market: AccountLoader<'info, PerpMarket>,
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, BookSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: The oracle can be one of several different account types and the pubkey is checked above
+    pub oracle: UncheckedAccount<'info>,
+}
+
+/// Parameters of one order in a perp_place_orders_batch instruction
+///
+/// See perp_place_order_v2 and perp_place_order_pegged_v2 for details on the fields.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PerpBatchOrderParams {
+    pub side: Side,
+
+    /// If set, this is an oracle pegged order and price_lots is the offset from the oracle price
+    pub oracle_pegged: bool,
+
+    /// The limit price in lots, or the oracle price offset in lots for pegged orders
+    pub price_lots: i64,
+
+    /// Only for pegged orders: the peg limit, -1 for none
+    pub peg_limit: i64,
+
+    pub max_base_lots: i64,
+    pub max_quote_lots: i64,
+    pub client_order_id: u64,
+    pub order_type: PlaceOrderType,
+    pub self_trade_behavior: SelfTradeBehavior,
+    pub reduce_only: bool,
+
+    /// Timestamp of when the order expires, 0 for never. Expired orders are skipped.
+    pub expiry_timestamp: u64,
+
+    /// Slot at which the order expires, 0 for never. Expired orders are skipped.
+    ///
+    /// Useful for short-lived quotes, e.g. the current slot plus 5.
+    pub expiry_slot: u64,
+
+    /// Base lots to show on the book, 0 for all. If smaller than the posted quantity,
+    /// the rest is hidden and replenishes the visible part after it was filled.
+    pub display_base_lots: i64,
+}
+
+impl PerpBatchOrderParams {
+    /// Converts to an Order, returns None if the order is already expired
+    pub fn to_order(&self) -> Result<Option<Order>> {
+        let time_in_force = match Order::tif_from_expiry(self.expiry_timestamp) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        let expiry_slot = match Order::expiry_slot_if_valid(self.expiry_slot) {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+        require_gte!(self.display_base_lots, 0);
+        let params = if self.oracle_pegged {
+            require_gte!(self.peg_limit, -1);
+            OrderParams::OraclePegged {
+                price_offset_lots: self.price_lots,
+                order_type: self.order_type.to_post_order_type()?,
+                peg_limit: self.peg_limit,
+                max_oracle_staleness_slots: -1,
+                display_base_lots: self.display_base_lots,
+            }
+        } else {
+            require_gte!(self.price_lots, 0);
+            let price_lots = self.price_lots;
+            match self.order_type {
+                PlaceOrderType::Market => OrderParams::Market {},
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                _ => OrderParams::Fixed {
+                    price_lots,
+                    order_type: self.order_type.to_post_order_type()?,
+                    display_base_lots: self.display_base_lots,
+                },
+            }
+        };
+        Ok(Some(Order {
+            side: self.side,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots: self.max_quote_lots,
+            client_order_id: self.client_order_id,
+            reduce_only: self.reduce_only,
+            time_in_force,
+            expiry_slot,
+            self_trade_behavior: self.self_trade_behavior,
+            params,
+        }))
+    }
+}
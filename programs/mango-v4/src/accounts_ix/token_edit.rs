@@ -17,7 +17,8 @@ pub struct TokenEdit<'info> {
     )]
     pub mint_info: AccountLoader<'info, MintInfo>,
 
-    /// The oracle account is optional and only used when reset_stable_price is set.
+    /// The oracle account is optional and only used when reset_stable_price is set
+    /// or when oracle_opt is set, in which case it must be the new oracle.
     ///
     /// CHECK: The oracle can be one of several different account types
     pub oracle: UncheckedAccount<'info>,
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::error::*;
+use crate::state::*;
+
+// Remaining accounts:
+// - health accounts for liqee
+#[derive(Accounts)]
+pub struct TokenInsuranceFundCover<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::TokenInsuranceFundCover) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = liqee.load()?.is_operational() @ MangoError::AccountIsFrozen
+    )]
+    pub liqee: AccountLoader<'info, MangoAccountFixed>,
+
+    #[account(
+        mut,
+        has_one = group,
+        has_one = insurance_vault,
+    )]
+    pub insurance_fund: AccountLoader<'info, TokenInsuranceFund>,
+
+    #[account(mut)]
+    pub insurance_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = group,
+        constraint = bank.load()?.token_index == insurance_fund.load()?.token_index @ MangoError::InvalidBank
+    )]
+    pub bank: AccountLoader<'info, Bank>,
+
+    #[account(
+        mut,
+        address = bank.load()?.vault
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> TokenInsuranceFundCover<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.insurance_vault.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.group.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::error::*;
+use crate::state::*;
+
+/// Creates the rate history PDA of a token. Anyone may pay for it.
+#[derive(Accounts)]
+#[instruction(token_index: TokenIndex)]
+pub struct TokenRateHistoryCreate<'info> {
+    #[account(
+        constraint = group.load()?.is_ix_enabled(IxGate::TokenRateHistoryCreate) @ MangoError::IxIsDisabled,
+    )]
+    pub group: AccountLoader<'info, Group>,
+
+    #[account(
+        has_one = group,
+        constraint = mint_info.load()?.token_index == token_index,
+    )]
+    pub mint_info: AccountLoader<'info, MintInfo>,
+
+    #[account(
+        init,
+        seeds = [b"TokenRateHistory".as_ref(), group.key().as_ref(), &token_index.to_le_bytes()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<TokenRateHistory>(),
+    )]
+    pub rate_history: AccountLoader<'info, TokenRateHistory>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
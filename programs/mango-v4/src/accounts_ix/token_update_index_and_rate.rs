@@ -13,6 +13,8 @@ pub mod compute_budget {
 ///
 /// In addition to these accounts, all banks must be passed as remaining_accounts
 /// in MintInfo order.
+/// They may be followed by the median oracle accounts and by the token's
+/// TokenRateHistory, which then gets the hourly rate history entries.
 ///
 /// This instruction may only be used alongside other instructions of the same kind
 /// or ComputeBudget instructions.
@@ -145,6 +145,40 @@ pub enum MangoError {
     MissingFeedForCLMMOracle,
     #[msg("the asset does not allow liquidation")]
     TokenAssetLiquidationDisabled,
+    #[msg("no free openbook v2 open orders index")]
+    NoFreeOpenbookV2OpenOrdersIndex,
+    #[msg("openbook v2 open orders exist already")]
+    OpenbookV2OpenOrdersExistAlready,
+    #[msg("there are open or unsettled openbook v2 orders")]
+    HasOpenOrUnsettledOpenbookV2Orders,
+    #[msg("the market does not allow limit orders too far from the current oracle value")]
+    OpenbookV2PriceBandExceeded,
+    #[msg("not enough valid oracles for the median oracle quorum")]
+    OracleQuorumNotReached,
+    #[msg("pyth price update account is not a fully verified price feed account")]
+    InvalidPythPullOracle,
+    #[msg("perp trigger order is not set")]
+    PerpTriggerOrderNotSet,
+    #[msg("perp trigger order index and id don't match")]
+    PerpTriggerOrderIndexIdMismatch,
+    #[msg("perp trigger order has expired")]
+    PerpTriggerOrderExpired,
+    #[msg("perp trigger order price condition is not met")]
+    PerpTriggerOrderPriceNotReached,
+    #[msg("an isolated token can only be combined with its configured quote tokens")]
+    IsolatedTokenCombination,
+    #[msg("bank total borrow limit reached")]
+    BankTotalBorrowLimit,
+    #[msg("account borrow limit for the token reached")]
+    AccountBorrowLimit,
+    #[msg("fixed term loans are disabled or the duration is not allowed")]
+    FixedTermLoanDurationNotAllowed,
+    #[msg("fixed term loan can only be settled by others at maturity or when the account is liquidatable")]
+    FixedTermLoanNotSettleable,
+    #[msg("the triggered perp order did not fill")]
+    PerpTriggerOrderNotFilled,
+    #[msg("switchboard on-demand account is not a pull feed with samples")]
+    InvalidSwitchboardOnDemandOracle,
 }
 
 impl MangoError {
@@ -170,6 +204,7 @@ impl<T> IsAnchorErrorWithCode for anchor_lang::Result<T> {
             Err(Error::AnchorError(e)) => {
                 e.error_code_number == MangoError::OracleConfidence.error_code()
                     || e.error_code_number == MangoError::OracleStale.error_code()
+                    || e.error_code_number == MangoError::OracleQuorumNotReached.error_code()
             }
             _ => false,
         }
@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_lang::ZeroCopy;
 
 use fixed::types::I80F48;
+use openbook_v2::state::OpenOrdersAccount;
 use serum_dex::state::OpenOrders;
 
 use std::cell::Ref;
@@ -9,11 +10,14 @@ use std::collections::HashMap;
 
 use crate::accounts_zerocopy::*;
 use crate::error::*;
+use crate::openbook_v2_cpi;
 use crate::serum3_cpi;
 use crate::state::pyth_mainnet_sol_oracle;
 use crate::state::pyth_mainnet_usdc_oracle;
 use crate::state::OracleAccountInfos;
-use crate::state::{Bank, MangoAccountRef, PerpMarket, PerpMarketIndex, TokenIndex};
+use crate::state::{
+    Bank, MangoAccountRef, PerpMarket, PerpMarketIndex, TokenIndex, MAX_MEDIAN_ORACLES,
+};
 
 /// This trait abstracts how to find accounts needed for the health computation.
 ///
@@ -35,6 +39,12 @@ pub trait AccountRetriever {
 
     fn serum_oo(&self, active_serum_oo_index: usize, key: &Pubkey) -> Result<&OpenOrders>;
 
+    fn openbook_v2_oo(
+        &self,
+        active_openbook_v2_oo_index: usize,
+        key: &Pubkey,
+    ) -> Result<&OpenOrdersAccount>;
+
     fn perp_market_and_oracle_price(
         &self,
         group: &Pubkey,
@@ -50,13 +60,15 @@ pub trait AccountRetriever {
 /// 3. PerpMarket accounts, in the order of account.perps.iter_active_accounts()
 /// 4. PerpMarket oracle accounts, in the order of the perp market accounts
 /// 5. serum3 OpenOrders accounts, in the order of account.serum3.iter_active()
-/// 6. fallback oracle accounts, order and existence of accounts is not guaranteed
+/// 6. openbook v2 OpenOrdersAccounts, in the order of account.openbook_v2.iter_active()
+/// 7. fallback and median oracle accounts, order and existence of accounts is not guaranteed
 pub struct FixedOrderAccountRetriever<T: KeyedAccountReader> {
     pub ais: Vec<T>,
     pub n_banks: usize,
     pub n_perps: usize,
     pub begin_perp: usize,
     pub begin_serum3: usize,
+    pub begin_openbook_v2: usize,
     pub staleness_slot: Option<u64>,
     pub begin_fallback_oracles: usize,
     pub usdc_oracle_index: Option<usize>,
@@ -69,14 +81,16 @@ pub fn new_fixed_order_account_retriever<'a, 'info>(
 ) -> Result<FixedOrderAccountRetriever<AccountInfoRef<'a, 'info>>> {
     let active_token_len = account.active_token_positions().count();
     let active_serum3_len = account.active_serum3_orders().count();
+    let active_openbook_v2_len = account.active_openbook_v2_orders().count();
     let active_perp_len = account.active_perp_positions().count();
     let expected_ais = active_token_len * 2 // banks + oracles
         + active_perp_len * 2 // PerpMarkets + Oracles
-        + active_serum3_len; // open_orders
+        + active_serum3_len // serum3 open_orders
+        + active_openbook_v2_len; // openbook v2 open_orders
     require_msg_typed!(ais.len() >= expected_ais, MangoError::InvalidHealthAccountCount,
-        "received {} accounts but expected {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos)",
+        "received {} accounts but expected {} ({} banks, {} bank oracles, {} perp markets, {} perp oracles, {} serum3 oos, {} openbook v2 oos)",
         ais.len(), expected_ais,
-        active_token_len, active_token_len, active_perp_len, active_perp_len, active_serum3_len
+        active_token_len, active_token_len, active_perp_len, active_perp_len, active_serum3_len, active_openbook_v2_len
     );
     let usdc_oracle_index = ais[..]
         .iter()
@@ -91,6 +105,7 @@ pub fn new_fixed_order_account_retriever<'a, 'info>(
         n_perps: active_perp_len,
         begin_perp: active_token_len * 2,
         begin_serum3: active_token_len * 2 + active_perp_len * 2,
+        begin_openbook_v2: active_token_len * 2 + active_perp_len * 2 + active_serum3_len,
         staleness_slot: Some(Clock::get()?.slot),
         begin_fallback_oracles: expected_ais,
         usdc_oracle_index,
@@ -120,8 +135,11 @@ impl<T: KeyedAccountReader> FixedOrderAccountRetriever<T> {
     }
 
     fn oracle_price_perp(&self, account_index: usize, perp_market: &PerpMarket) -> Result<I80F48> {
-        let oracle = &self.ais[account_index];
-        let oracle_acc_infos = OracleAccountInfos::from_reader(oracle);
+        let oracle_acc_infos = self.create_oracle_infos(
+            account_index,
+            &Pubkey::default(),
+            &perp_market.median_oracles,
+        );
         perp_market.oracle_price(&oracle_acc_infos, self.staleness_slot)
     }
 
@@ -130,17 +148,25 @@ impl<T: KeyedAccountReader> FixedOrderAccountRetriever<T> {
         &self,
         oracle_index: usize,
         fallback_key: &Pubkey,
+        median_keys: &[Pubkey; MAX_MEDIAN_ORACLES],
     ) -> OracleAccountInfos<T> {
         let oracle = &self.ais[oracle_index];
-        let fallback_opt = self.ais[self.begin_fallback_oracles..]
-            .iter()
-            .find(|ai| ai.key() == fallback_key);
+        let find_extra_oracle = |key: &Pubkey| {
+            if key == &Pubkey::default() {
+                None
+            } else {
+                self.ais[self.begin_fallback_oracles..]
+                    .iter()
+                    .find(|ai| ai.key() == key)
+            }
+        };
 
         OracleAccountInfos {
             oracle,
-            fallback_opt,
+            fallback_opt: find_extra_oracle(fallback_key),
             usdc_opt: self.usdc_oracle_index.map(|i| &self.ais[i]),
             sol_opt: self.sol_oracle_index.map(|i| &self.ais[i]),
+            median_opts: median_keys.map(|key| find_extra_oracle(&key)),
         }
     }
 }
@@ -165,7 +191,8 @@ impl<T: KeyedAccountReader> AccountRetriever for FixedOrderAccountRetriever<T> {
             })?;
 
         let oracle_index = self.n_banks + active_token_position_index;
-        let oracle_acc_infos = &self.create_oracle_infos(oracle_index, &bank.fallback_oracle);
+        let oracle_acc_infos =
+            &self.create_oracle_infos(oracle_index, &bank.fallback_oracle, &bank.median_oracles);
         let oracle_price_result = bank.oracle_price(oracle_acc_infos, self.staleness_slot);
         let oracle_price = oracle_price_result.with_context(|| {
             format!(
@@ -224,6 +251,26 @@ impl<T: KeyedAccountReader> AccountRetriever for FixedOrderAccountRetriever<T> {
             )
         })
     }
+
+    fn openbook_v2_oo(
+        &self,
+        active_openbook_v2_oo_index: usize,
+        key: &Pubkey,
+    ) -> Result<&OpenOrdersAccount> {
+        let openbook_v2_oo_index = self.begin_openbook_v2 + active_openbook_v2_oo_index;
+        let ai = &self.ais[openbook_v2_oo_index];
+        (|| {
+            require_keys_eq!(*key, *ai.key());
+            openbook_v2_cpi::load_open_orders(ai)
+        })()
+        .with_context(|| {
+            format!(
+                "loading openbook v2 open orders with health account index {}, passed account {}",
+                openbook_v2_oo_index,
+                ai.key(),
+            )
+        })
+    }
 }
 
 pub struct ScannedBanksAndOracles<'a, 'info> {
@@ -260,7 +307,8 @@ impl<'a, 'info> ScannedBanksAndOracles<'a, 'info> {
             let index = self.bank_index(token_index1)?;
             let price = {
                 let bank = self.banks[index].load_fully_unchecked::<Bank>()?;
-                let oracle_acc_infos = self.create_oracle_infos(index, &bank.fallback_oracle);
+                let oracle_acc_infos =
+                    self.create_oracle_infos(index, &bank.fallback_oracle, &bank.median_oracles);
                 bank.oracle_price(&oracle_acc_infos, self.staleness_slot)?
             };
 
@@ -278,8 +326,10 @@ impl<'a, 'info> ScannedBanksAndOracles<'a, 'info> {
         let (price1, price2) = {
             let bank1 = self.banks[first].load_fully_unchecked::<Bank>()?;
             let bank2 = self.banks[second].load_fully_unchecked::<Bank>()?;
-            let oracle_infos_1 = self.create_oracle_infos(first, &bank1.fallback_oracle);
-            let oracle_infos_2 = self.create_oracle_infos(second, &bank2.fallback_oracle);
+            let oracle_infos_1 =
+                self.create_oracle_infos(first, &bank1.fallback_oracle, &bank1.median_oracles);
+            let oracle_infos_2 =
+                self.create_oracle_infos(second, &bank2.fallback_oracle, &bank2.median_oracles);
             let price1 = bank1.oracle_price(&oracle_infos_1, self.staleness_slot)?;
             let price2 = bank2.oracle_price(&oracle_infos_2, self.staleness_slot)?;
             (price1, price2)
@@ -301,7 +351,8 @@ impl<'a, 'info> ScannedBanksAndOracles<'a, 'info> {
         let index = self.bank_index(token_index)?;
         // The account was already loaded successfully during construction
         let bank = self.banks[index].load_fully_unchecked::<Bank>()?;
-        let oracle_acc_infos = self.create_oracle_infos(index, &bank.fallback_oracle);
+        let oracle_acc_infos =
+            self.create_oracle_infos(index, &bank.fallback_oracle, &bank.median_oracles);
         let price = bank.oracle_price(&oracle_acc_infos, self.staleness_slot)?;
 
         Ok((bank, price))
@@ -312,20 +363,32 @@ impl<'a, 'info> ScannedBanksAndOracles<'a, 'info> {
         &self,
         oracle_index: usize,
         fallback_key: &Pubkey,
-    ) -> OracleAccountInfos<AccountInfoRef> {
-        let oracle = &self.oracles[oracle_index];
-        let fallback_opt = if fallback_key == &Pubkey::default() {
-            None
-        } else {
-            self.fallback_oracles
-                .iter()
-                .find(|ai| ai.key == fallback_key)
+        median_keys: &[Pubkey; MAX_MEDIAN_ORACLES],
+    ) -> OracleAccountInfos<AccountInfoRef<'a, 'info>> {
+        self.create_oracle_infos_for(&self.oracles[oracle_index], fallback_key, median_keys)
+    }
+
+    /// Like create_oracle_infos(), but for an oracle account that's not a bank oracle
+    #[inline(always)]
+    fn create_oracle_infos_for<'s>(
+        &'s self,
+        oracle: &'s AccountInfoRef<'a, 'info>,
+        fallback_key: &Pubkey,
+        median_keys: &[Pubkey; MAX_MEDIAN_ORACLES],
+    ) -> OracleAccountInfos<'s, AccountInfoRef<'a, 'info>> {
+        let find_extra_oracle = |key: &Pubkey| {
+            if key == &Pubkey::default() {
+                None
+            } else {
+                self.fallback_oracles.iter().find(|ai| ai.key == key)
+            }
         };
         OracleAccountInfos {
             oracle,
-            fallback_opt,
+            fallback_opt: find_extra_oracle(fallback_key),
             usdc_opt: self.usd_oracle_index.map(|i| &self.fallback_oracles[i]),
             sol_opt: self.sol_oracle_index.map(|i| &self.fallback_oracles[i]),
+            median_opts: median_keys.map(|key| find_extra_oracle(&key)),
         }
     }
 }
@@ -336,7 +399,8 @@ impl<'a, 'info> ScannedBanksAndOracles<'a, 'info> {
 /// - an unknown number of PerpMarket accounts
 /// - the same number of oracles in the same order as the perp markets
 /// - an unknown number of serum3 OpenOrders accounts
-/// - an unknown number of fallback oracle accounts
+/// - an unknown number of openbook v2 OpenOrdersAccounts
+/// - an unknown number of fallback and median oracle accounts
 /// and retrieves accounts needed for the health computation by doing a linear
 /// scan for each request.
 pub struct ScanningAccountRetriever<'a, 'info> {
@@ -344,6 +408,7 @@ pub struct ScanningAccountRetriever<'a, 'info> {
     perp_markets: Vec<AccountInfoRef<'a, 'info>>,
     perp_oracles: Vec<AccountInfoRef<'a, 'info>>,
     serum3_oos: Vec<AccountInfoRef<'a, 'info>>,
+    openbook_v2_oos: Vec<AccountInfoRef<'a, 'info>>,
     perp_index_map: HashMap<PerpMarketIndex, usize>,
 }
 
@@ -429,7 +494,13 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
                     && serum3_cpi::has_serum_header(&x.data.borrow())
             })
             .count();
-        let fallback_oracles_start = serum3_start + n_serum3;
+        let openbook_v2_start = serum3_start + n_serum3;
+        let n_openbook_v2 = ais[openbook_v2_start..]
+            .iter()
+            .enumerate()
+            .map_while(can_load_as::<OpenOrdersAccount>)
+            .count();
+        let fallback_oracles_start = openbook_v2_start + n_openbook_v2;
         let usd_oracle_index = ais[fallback_oracles_start..]
             .iter()
             .position(|o| o.key == &pyth_mainnet_usdc_oracle::ID);
@@ -449,7 +520,10 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
             },
             perp_markets: AccountInfoRef::borrow_slice(&ais[perps_start..perp_oracles_start])?,
             perp_oracles: AccountInfoRef::borrow_slice(&ais[perp_oracles_start..serum3_start])?,
-            serum3_oos: AccountInfoRef::borrow_slice(&ais[serum3_start..fallback_oracles_start])?,
+            serum3_oos: AccountInfoRef::borrow_slice(&ais[serum3_start..openbook_v2_start])?,
+            openbook_v2_oos: AccountInfoRef::borrow_slice(
+                &ais[openbook_v2_start..fallback_oracles_start],
+            )?,
             perp_index_map,
         })
     }
@@ -484,7 +558,11 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         // The account was already loaded successfully during construction
         let perp_market = self.perp_markets[index].load_fully_unchecked::<PerpMarket>()?;
         let oracle_acc = &self.perp_oracles[index];
-        let oracle_acc_infos = OracleAccountInfos::from_reader(oracle_acc);
+        let oracle_acc_infos = self.banks_and_oracles.create_oracle_infos_for(
+            oracle_acc,
+            &Pubkey::default(),
+            &perp_market.median_oracles,
+        );
         let price =
             perp_market.oracle_price(&oracle_acc_infos, self.banks_and_oracles.staleness_slot)?;
         Ok((perp_market, price))
@@ -499,6 +577,15 @@ impl<'a, 'info> ScanningAccountRetriever<'a, 'info> {
         serum3_cpi::load_open_orders(oo)
     }
 
+    pub fn scanned_openbook_v2_oo(&self, key: &Pubkey) -> Result<&OpenOrdersAccount> {
+        let oo = self
+            .openbook_v2_oos
+            .iter()
+            .find(|ai| ai.key == key)
+            .ok_or_else(|| error_msg!("no openbook v2 open orders for key {}", key))?;
+        openbook_v2_cpi::load_open_orders(oo)
+    }
+
     pub fn into_banks_and_oracles(self) -> ScannedBanksAndOracles<'a, 'info> {
         self.banks_and_oracles
     }
@@ -526,6 +613,10 @@ impl<'a, 'info> AccountRetriever for ScanningAccountRetriever<'a, 'info> {
     fn serum_oo(&self, _account_index: usize, key: &Pubkey) -> Result<&OpenOrders> {
         self.scanned_serum_oo(key)
     }
+
+    fn openbook_v2_oo(&self, _account_index: usize, key: &Pubkey) -> Result<&OpenOrdersAccount> {
+        self.scanned_openbook_v2_oo(key)
+    }
 }
 
 #[cfg(test)]
@@ -552,6 +643,9 @@ mod tests {
         let oo1key = oo1.pubkey;
         oo1.data().native_pc_total = 20;
 
+        let mut oo2 = TestAccount::<OpenOrdersAccount>::new_zeroed();
+        let oo2key = oo2.pubkey;
+
         let mut perp1 = mock_perp_market(
             group,
             oracle2.pubkey,
@@ -583,6 +677,7 @@ mod tests {
             oracle2_account_info,
             oracle1_account_info,
             oo1.as_account_info(),
+            oo2.as_account_info(),
         ];
 
         let mut retriever =
@@ -595,6 +690,7 @@ mod tests {
         assert_eq!(retriever.perp_oracles.len(), 2);
         assert_eq!(retriever.perp_index_map.len(), 2);
         assert_eq!(retriever.serum3_oos.len(), 1);
+        assert_eq!(retriever.openbook_v2_oos.len(), 1);
 
         {
             let (b1, o1, opt_b2o2) = retriever.banks_mut_and_oracles(1, 4).unwrap();
@@ -634,6 +730,9 @@ mod tests {
 
         assert!(retriever.serum_oo(1, &Pubkey::default()).is_err());
 
+        assert!(retriever.openbook_v2_oo(0, &oo2key).is_ok());
+        assert!(retriever.openbook_v2_oo(0, &oo1key).is_err());
+
         let (perp, oracle_price) = retriever
             .perp_market_and_oracle_price(&group, 0, 9)
             .unwrap();
@@ -11,7 +11,8 @@
  * new_health_cache() function. With it, the different health types can be
  * computed.
  *
- * The HealthCache holds the data it needs in TokenInfo, Serum3Info and PerpInfo.
+ * The HealthCache holds the data it needs in TokenInfo, Serum3Info, OpenbookV2Info
+ * and PerpInfo.
  */
 
 use anchor_lang::prelude::*;
@@ -20,10 +21,11 @@ use fixed::types::I80F48;
 
 use crate::error::*;
 use crate::i80f48::LowPrecisionDivision;
+use crate::openbook_v2_cpi;
 use crate::serum3_cpi::{OpenOrdersAmounts, OpenOrdersSlim};
 use crate::state::{
-    Bank, MangoAccountRef, PerpMarket, PerpMarketIndex, PerpPosition, Serum3MarketIndex,
-    Serum3Orders, TokenIndex,
+    Bank, MangoAccountRef, OpenbookV2Orders, PerpMarket, PerpMarketIndex, PerpPosition,
+    Serum3MarketIndex, Serum3Orders, TokenIndex, MAX_ISOLATED_QUOTE_TOKENS,
 };
 
 use super::*;
@@ -177,6 +179,10 @@ pub struct TokenInfo {
     pub balance_spot: I80F48,
 
     pub allow_asset_liquidation: bool,
+
+    /// Tokens this token may be combined with, if it is in an isolated pool.
+    /// None if the token is not isolated.
+    pub isolated_quote_token_indexes: Option<[TokenIndex; MAX_ISOLATED_QUOTE_TOKENS]>,
 }
 
 /// Temporary value used during health computations
@@ -234,6 +240,8 @@ impl TokenInfo {
 
 /// Information about reserved funds on Serum3 open orders accounts.
 ///
+/// OpenbookV2 open orders accounts are tracked with the same struct, see OpenbookV2Info.
+///
 /// Note that all "free" funds on open orders accounts are added directly
 /// to the token info. This is only about dealing with the reserved funds
 /// that might end up as base OR quote tokens, depending on whether the
@@ -265,15 +273,48 @@ impl Serum3Info {
         open_orders: &impl OpenOrdersAmounts,
         base_info_index: usize,
         quote_info_index: usize,
+    ) -> Self {
+        Self::new_from_parts(
+            serum_account.market_index,
+            serum_account.lowest_placed_ask,
+            serum_account.highest_placed_bid_inv,
+            open_orders,
+            base_info_index,
+            quote_info_index,
+        )
+    }
+
+    fn new_openbook_v2(
+        openbook_account: &OpenbookV2Orders,
+        open_orders: &impl OpenOrdersAmounts,
+        base_info_index: usize,
+        quote_info_index: usize,
+    ) -> Self {
+        Self::new_from_parts(
+            openbook_account.market_index,
+            openbook_account.lowest_placed_ask,
+            openbook_account.highest_placed_bid_inv,
+            open_orders,
+            base_info_index,
+            quote_info_index,
+        )
+    }
+
+    fn new_from_parts(
+        market_index: Serum3MarketIndex,
+        lowest_placed_ask: f64,
+        highest_placed_bid_inv: f64,
+        open_orders: &impl OpenOrdersAmounts,
+        base_info_index: usize,
+        quote_info_index: usize,
     ) -> Self {
         // track the reserved amounts
         let reserved_base = I80F48::from(open_orders.native_base_reserved());
         let reserved_quote = I80F48::from(open_orders.native_quote_reserved());
 
-        let reserved_base_as_quote_lowest_ask =
-            reserved_base * I80F48::from_num(serum_account.lowest_placed_ask);
+        let reserved_base_as_quote_lowest_ask = reserved_base * I80F48::from_num(lowest_placed_ask);
         let reserved_quote_as_base_highest_bid =
-            reserved_quote * I80F48::from_num(serum_account.highest_placed_bid_inv);
+            reserved_quote * I80F48::from_num(highest_placed_bid_inv);
 
         Self {
             reserved_base,
@@ -282,7 +323,7 @@ impl Serum3Info {
             reserved_quote_as_base_highest_bid,
             base_info_index,
             quote_info_index,
-            market_index: serum_account.market_index,
+            market_index,
             has_zero_funds: open_orders.native_base_total() == 0
                 && open_orders.native_quote_total() == 0
                 && open_orders.native_rebates() == 0,
@@ -415,6 +456,12 @@ impl Serum3Info {
     }
 }
 
+/// Information about reserved funds on OpenbookV2 open orders accounts.
+///
+/// The health treatment of reserved funds is identical to Serum3, only the
+/// market_index refers to an OpenbookV2Market.
+pub type OpenbookV2Info = Serum3Info;
+
 #[derive(Clone)]
 pub(crate) struct Serum3Reserved {
     /// base tokens when the serum3info.reserved_quote get converted to base and added to reserved_base
@@ -594,6 +641,7 @@ impl PerpInfo {
 pub struct HealthCache {
     pub token_infos: Vec<TokenInfo>,
     pub(crate) serum3_infos: Vec<Serum3Info>,
+    pub(crate) openbook_v2_infos: Vec<OpenbookV2Info>,
     pub(crate) perp_infos: Vec<PerpInfo>,
     #[allow(unused)]
     pub(crate) being_liquidated: bool,
@@ -729,7 +777,7 @@ impl HealthCache {
 
         let token_balances = self.effective_token_balances(health_type);
         let (token_max_reserved, serum3_reserved) = self.compute_serum3_reservations(health_type);
-        for (serum3_info, reserved) in self.serum3_infos.iter().zip(serum3_reserved.iter()) {
+        for (serum3_info, reserved) in self.spot_infos().zip(serum3_reserved.iter()) {
             let contrib = serum3_info.health_contribution(
                 health_type,
                 &self.token_infos,
@@ -761,7 +809,7 @@ impl HealthCache {
             }
         }
 
-        for serum_info in self.serum3_infos.iter() {
+        for serum_info in self.spot_infos() {
             let quote = &self.token_infos[serum_info.quote_info_index];
             let base = &self.token_infos[serum_info.base_info_index];
             assets += serum_info.reserved_base * base.prices.oracle;
@@ -769,9 +817,8 @@ impl HealthCache {
         }
 
         for perp_info in self.perp_infos.iter() {
-            let quote_price = self.token_infos[perp_info.settle_token_index as usize]
-                .prices
-                .oracle;
+            let settle_info_index = self.token_info_index(perp_info.settle_token_index).unwrap();
+            let quote_price = self.token_infos[settle_info_index].prices.oracle;
             let quote_position_value = perp_info.quote * quote_price;
             if perp_info.quote.is_negative() {
                 liabs -= quote_position_value;
@@ -894,6 +941,48 @@ impl HealthCache {
         Ok(())
     }
 
+    /// Recompute the cached information about an openbook v2 market.
+    ///
+    /// WARNING: You must also call recompute_token_weights() after all bank
+    /// deposit/withdraw changes!
+    pub fn recompute_openbook_v2_info(
+        &mut self,
+        openbook_account: &OpenbookV2Orders,
+        open_orders: &openbook_v2_cpi::OpenOrdersSlim,
+        free_base_change: I80F48,
+        free_quote_change: I80F48,
+    ) -> Result<()> {
+        let openbook_info_index = self
+            .openbook_v2_infos
+            .iter_mut()
+            .position(|m| m.market_index == openbook_account.market_index)
+            .ok_or_else(|| {
+                error_msg!(
+                    "openbook v2 market {} not found",
+                    openbook_account.market_index
+                )
+            })?;
+
+        let openbook_info = &self.openbook_v2_infos[openbook_info_index];
+        {
+            let base_entry = &mut self.token_infos[openbook_info.base_info_index];
+            base_entry.balance_spot += free_base_change;
+        }
+        {
+            let quote_entry = &mut self.token_infos[openbook_info.quote_info_index];
+            quote_entry.balance_spot += free_quote_change;
+        }
+
+        let openbook_info = &mut self.openbook_v2_infos[openbook_info_index];
+        *openbook_info = OpenbookV2Info::new_openbook_v2(
+            openbook_account,
+            open_orders,
+            openbook_info.base_info_index,
+            openbook_info.quote_info_index,
+        );
+        Ok(())
+    }
+
     pub fn recompute_perp_info(
         &mut self,
         perp_position: &PerpPosition,
@@ -944,6 +1033,10 @@ impl HealthCache {
         self.serum3_infos.iter().any(|si| !si.has_zero_funds)
     }
 
+    pub fn has_openbook_v2_open_orders_funds(&self) -> bool {
+        self.openbook_v2_infos.iter().any(|oi| !oi.has_zero_funds)
+    }
+
     pub fn has_perp_open_orders(&self) -> bool {
         self.perp_infos.iter().any(|p| p.has_open_orders)
     }
@@ -971,7 +1064,9 @@ impl HealthCache {
     /// Phase1 is spot/perp order cancellation and spot settlement since
     /// neither of these come at a cost to the liqee
     pub fn has_phase1_liquidatable(&self) -> bool {
-        self.has_serum3_open_orders_funds() || self.has_perp_open_orders()
+        self.has_serum3_open_orders_funds()
+            || self.has_openbook_v2_open_orders_funds()
+            || self.has_perp_open_orders()
     }
 
     pub fn require_after_phase1_liquidation(&self) -> Result<()> {
@@ -979,6 +1074,10 @@ impl HealthCache {
             !self.has_serum3_open_orders_funds(),
             MangoError::HasOpenOrUnsettledSerum3Orders
         );
+        require!(
+            !self.has_openbook_v2_open_orders_funds(),
+            MangoError::HasOpenOrUnsettledOpenbookV2Orders
+        );
         require!(!self.has_perp_open_orders(), MangoError::HasOpenPerpOrders);
         Ok(())
     }
@@ -999,6 +1098,50 @@ impl HealthCache {
             || self.has_perp_positive_pnl_no_base()
     }
 
+    /// Checks that tokens in isolated pools are only combined with their quote tokens
+    ///
+    /// Token positions with a spot balance of less than one native token, positive or
+    /// negative, are ignored.
+    ///
+    /// Called from MangoAccountRefMut::check_health_post().
+    pub fn require_isolated_pools_respected(&self) -> Result<()> {
+        self.require_isolated_pools_respected_inner(None)
+    }
+
+    /// Like require_isolated_pools_respected(), but treats `token_index` as if it had a
+    /// balance. For checking before creating a balance in that token.
+    pub fn require_isolated_pools_allow_token(&self, token_index: TokenIndex) -> Result<()> {
+        self.require_isolated_pools_respected_inner(Some(token_index))
+    }
+
+    fn require_isolated_pools_respected_inner(
+        &self,
+        extra_token_index: Option<TokenIndex>,
+    ) -> Result<()> {
+        let has_balance = |ti: &TokenInfo| {
+            ti.balance_spot.abs() >= 1 || Some(ti.token_index) == extra_token_index
+        };
+        for isolated in self.token_infos.iter().filter(|ti| has_balance(ti)) {
+            let quote_token_indexes = match isolated.isolated_quote_token_indexes.as_ref() {
+                Some(indexes) => indexes,
+                None => continue,
+            };
+            for other in self.token_infos.iter().filter(|ti| has_balance(ti)) {
+                if other.token_index != isolated.token_index
+                    && !quote_token_indexes.contains(&other.token_index)
+                {
+                    return Err(error_msg_typed!(
+                        MangoError::IsolatedTokenCombination,
+                        "token {} is isolated and can't be combined with token {}",
+                        isolated.token_index,
+                        other.token_index
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn require_after_phase2_liquidation(&self) -> Result<()> {
         self.require_after_phase1_liquidation()?;
         require!(
@@ -1037,17 +1180,27 @@ impl HealthCache {
             && self.has_phase3_liquidatable()
     }
 
+    /// Iterates over the Serum3Infos and then the OpenbookV2Infos.
+    ///
+    /// The Serum3Reserved values from compute_serum3_reservations() are in the same order.
+    pub(crate) fn spot_infos(&self) -> impl Iterator<Item = &Serum3Info> {
+        self.serum3_infos
+            .iter()
+            .chain(self.openbook_v2_infos.iter())
+    }
+
     pub(crate) fn compute_serum3_reservations(
         &self,
         health_type: HealthType,
     ) -> (Vec<TokenMaxReserved>, Vec<Serum3Reserved>) {
         let mut token_max_reserved = vec![TokenMaxReserved::default(); self.token_infos.len()];
 
-        // For each serum market, compute what happened if reserved_base was converted to quote
-        // or reserved_quote was converted to base.
-        let mut serum3_reserved = Vec::with_capacity(self.serum3_infos.len());
+        // For each serum and openbook v2 market, compute what happened if reserved_base was
+        // converted to quote or reserved_quote was converted to base.
+        let mut serum3_reserved =
+            Vec::with_capacity(self.serum3_infos.len() + self.openbook_v2_infos.len());
 
-        for info in self.serum3_infos.iter() {
+        for info in self.spot_infos() {
             let quote_info = &self.token_infos[info.quote_info_index];
             let base_info = &self.token_infos[info.base_info_index];
 
@@ -1120,7 +1273,7 @@ impl HealthCache {
         }
 
         let (token_max_reserved, serum3_reserved) = self.compute_serum3_reservations(health_type);
-        for (serum3_info, reserved) in self.serum3_infos.iter().zip(serum3_reserved.iter()) {
+        for (serum3_info, reserved) in self.spot_infos().zip(serum3_reserved.iter()) {
             let contrib = serum3_info.health_contribution(
                 health_type,
                 &self.token_infos,
@@ -1180,6 +1333,7 @@ impl HealthCache {
         )
     }
 
+    /// Total potential tokens from reserved funds on serum3 and openbook v2 open orders.
     pub fn total_serum3_potential(
         &self,
         health_type: HealthType,
@@ -1187,8 +1341,7 @@ impl HealthCache {
     ) -> Result<I80F48> {
         let target_token_info_index = self.token_info_index(token_index)?;
         let total_reserved = self
-            .serum3_infos
-            .iter()
+            .spot_infos()
             .filter_map(|info| {
                 if info.quote_info_index == target_token_info_index {
                     Some(info.all_reserved_as_quote(
@@ -1261,16 +1414,19 @@ fn new_health_cache_impl(
     for (i, position) in account.active_token_positions().enumerate() {
         let bank_oracle_result =
             retriever.bank_and_oracle(&account.fixed.group, i, position.token_index);
+        // Fixed term loans are liabilities in the token, on top of the position
+        let fixed_term_owed = account.fixed_term_loans_owed(position.token_index, now_ts);
         if skip_bad_oracles
             && bank_oracle_result.is_oracle_error()
             && position.indexed_position >= 0
+            && fixed_term_owed.is_zero()
         {
             // Ignore the asset because the oracle is bad, decreasing total health
             continue;
         }
         let (bank, oracle_price) = bank_oracle_result?;
 
-        let native = position.native(bank);
+        let native = position.native(bank) - fixed_term_owed;
         let prices = Prices {
             oracle: oracle_price,
             stable: bank.stable_price(),
@@ -1292,6 +1448,9 @@ fn new_health_cache_impl(
             prices,
             balance_spot: native,
             allow_asset_liquidation: bank.allows_asset_liquidation(),
+            isolated_quote_token_indexes: bank
+                .is_isolated()
+                .then_some(bank.isolated_quote_token_indexes),
         });
     }
 
@@ -1321,6 +1480,38 @@ fn new_health_cache_impl(
         ));
     }
 
+    // Same for the openbook v2 oo accounts, building OpenbookV2Infos.
+    let mut openbook_v2_infos = Vec::with_capacity(account.active_openbook_v2_orders().count());
+    for (i, openbook_account) in account.active_openbook_v2_orders().enumerate() {
+        let oo = retriever.openbook_v2_oo(i, &openbook_account.open_orders)?;
+        let oo = openbook_v2_cpi::OpenOrdersSlim::from_oo(
+            oo,
+            openbook_account.base_lot_size,
+            openbook_account.quote_lot_size,
+        );
+
+        // find the TokenInfos for the market's base and quote tokens
+        let base_info_index =
+            find_token_info_index(&token_infos, openbook_account.base_token_index)?;
+        let quote_info_index =
+            find_token_info_index(&token_infos, openbook_account.quote_token_index)?;
+
+        // add the amounts that are freely settleable immediately to token balances
+        let base_free = I80F48::from(oo.native_base_free());
+        let quote_free = I80F48::from(oo.native_quote_free());
+        let base_info = &mut token_infos[base_info_index];
+        base_info.balance_spot += base_free;
+        let quote_info = &mut token_infos[quote_info_index];
+        quote_info.balance_spot += quote_free;
+
+        openbook_v2_infos.push(OpenbookV2Info::new_openbook_v2(
+            openbook_account,
+            &oo,
+            base_info_index,
+            quote_info_index,
+        ));
+    }
+
     // health contribution from perp accounts
     let mut perp_infos = Vec::with_capacity(account.active_perp_positions().count());
     for (i, perp_position) in account.active_perp_positions().enumerate() {
@@ -1342,6 +1533,7 @@ fn new_health_cache_impl(
     Ok(HealthCache {
         token_infos,
         serum3_infos,
+        openbook_v2_infos,
         perp_infos,
         being_liquidated: account.fixed.being_liquidated(),
     })
@@ -1462,6 +1654,74 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_health_openbook_v2() {
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+
+        let group = Pubkey::new_unique();
+
+        let (mut bank1, mut oracle1) = mock_bank_and_oracle(group, 0, 1.0, 0.2, 0.1);
+        let (mut bank2, mut oracle2) = mock_bank_and_oracle(group, 4, 5.0, 0.5, 0.3);
+        bank1
+            .data()
+            .deposit(
+                account.ensure_token_position(0).unwrap().0,
+                I80F48::from(100),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+        bank2
+            .data()
+            .withdraw_without_fee(
+                account.ensure_token_position(4).unwrap().0,
+                I80F48::from(10),
+                DUMMY_NOW_TS,
+            )
+            .unwrap();
+
+        let mut oo1 = TestAccount::<openbook_v2::state::OpenOrdersAccount>::new_zeroed();
+        let openbook_account = account.create_openbook_v2_orders(2).unwrap();
+        openbook_account.open_orders = oo1.pubkey;
+        openbook_account.base_token_index = 4;
+        openbook_account.quote_token_index = 0;
+        openbook_account.base_lot_size = 1;
+        openbook_account.quote_lot_size = 1;
+        {
+            let position = &mut oo1.data().position;
+            position.bids_quote_lots = 20;
+            position.asks_base_lots = 15;
+            position.quote_free_native = 1;
+            position.base_free_native = 3;
+            position.referrer_rebates_available = 2;
+        }
+
+        let ais = vec![
+            bank1.as_account_info(),
+            bank2.as_account_info(),
+            oracle1.as_account_info(),
+            oracle2.as_account_info(),
+            oo1.as_account_info(),
+        ];
+
+        let retriever = ScanningAccountRetriever::new_with_staleness(&ais, &group, None).unwrap();
+
+        // for bank1/oracle1
+        // including open orders (scenario: bids execute)
+        let openbook1 = 1.0 + (20.0 + 15.0 * 5.0);
+        let health1 = (100.0 + openbook1) * 0.8;
+        // for bank2/oracle2
+        let health2 = (-10.0 + 3.0) * 5.0 * 1.5;
+        assert!(health_eq(
+            compute_health(&account.borrow(), HealthType::Init, &retriever, 0).unwrap(),
+            health1 + health2
+        ));
+
+        let health_cache = new_health_cache(&account.borrow(), &retriever, 0).unwrap();
+        assert!(health_cache.has_openbook_v2_open_orders_funds());
+        assert!(health_cache.require_after_phase1_liquidation().is_err());
+    }
+
     #[derive(Default)]
     struct BankSettings {
         deposits: u64,
@@ -41,7 +41,7 @@ impl HealthCache {
         let target_amount = amount * price;
 
         let mut source_bank = source_bank.clone();
-        source_bank.withdraw_with_fee(&mut source_position, amount, now_ts)?;
+        source_bank.withdraw_with_fee(&mut source_position, amount, now_ts, source_oracle_price)?;
         let mut target_bank = target_bank.clone();
         target_bank.deposit(&mut target_position, target_amount, now_ts)?;
 
@@ -196,7 +196,7 @@ impl HealthCache {
         }
 
         let cache_after_swap = |amount: I80F48| -> Result<Option<HealthCache>> {
-            ignore_net_borrow_limit_errors(self.cache_after_swap(
+            ignore_borrow_limit_errors(self.cache_after_swap(
                 account,
                 source_bank,
                 source_oracle_price,
@@ -447,7 +447,7 @@ impl HealthCache {
             let mut position = account.token_position(bank.token_index)?.clone();
 
             let mut bank = bank.clone();
-            bank.withdraw_with_fee(&mut position, amount, now_ts)?;
+            bank.withdraw_with_fee(&mut position, amount, now_ts, token.prices.oracle)?;
             bank.check_net_borrows(token.prices.oracle)?;
 
             let mut resulting_cache = self.clone();
@@ -456,7 +456,7 @@ impl HealthCache {
             Ok(resulting_cache)
         };
         let fn_value_after_borrow = |amount: I80F48| -> Result<I80F48> {
-            Ok(ignore_net_borrow_limit_errors(cache_after_borrow(amount))?
+            Ok(ignore_borrow_limit_errors(cache_after_borrow(amount))?
                 .as_ref()
                 .map(target_fn)
                 .unwrap_or(I80F48::MIN))
@@ -628,10 +628,13 @@ fn find_maximum(
     }
 }
 
-fn ignore_net_borrow_limit_errors(maybe_cache: Result<HealthCache>) -> Result<Option<HealthCache>> {
-    // Special case net borrow errors: We want to be able to find a good
-    // swap amount even if the max swap is limited by the net borrow limit.
-    if maybe_cache.is_anchor_error_with_code(MangoError::BankNetBorrowsLimitReached.error_code()) {
+fn ignore_borrow_limit_errors(maybe_cache: Result<HealthCache>) -> Result<Option<HealthCache>> {
+    // Special case net borrow and borrow limit errors: We want to be able to find a good
+    // swap amount even if the max swap is limited by one of the borrow limits.
+    if maybe_cache.is_anchor_error_with_code(MangoError::BankNetBorrowsLimitReached.error_code())
+        || maybe_cache.is_anchor_error_with_code(MangoError::BankTotalBorrowLimit.error_code())
+        || maybe_cache.is_anchor_error_with_code(MangoError::AccountBorrowLimit.error_code())
+    {
         return Ok(None);
     }
     maybe_cache.map(|c| Some(c))
@@ -683,6 +686,7 @@ mod tests {
             prices: Prices::new_single_price(I80F48::from_num(price)),
             balance_spot: I80F48::ZERO,
             allow_asset_liquidation: true,
+            isolated_quote_token_indexes: None,
         }
     }
 
@@ -741,6 +745,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
         };
@@ -1160,6 +1165,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![PerpInfo {
                 perp_market_index: 0,
                 settle_token_index: 1,
@@ -1449,6 +1455,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
         };
@@ -1472,8 +1479,9 @@ mod tests {
             let mut position = account.token_position(bank.token_index)?.clone();
 
             let mut bank = bank.clone();
-            bank.withdraw_with_fee(&mut position, amount, now_ts)?;
-            bank.check_net_borrows(c.token_info(bank.token_index)?.prices.oracle)?;
+            let oracle_price = c.token_info(bank.token_index)?.prices.oracle;
+            bank.withdraw_with_fee(&mut position, amount, now_ts, oracle_price)?;
+            bank.check_net_borrows(oracle_price)?;
 
             let mut resulting_cache = c.clone();
             resulting_cache.adjust_token_balance(&bank, -amount)?;
@@ -1596,6 +1604,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![PerpInfo {
                 perp_market_index: 0,
                 settle_token_index: 0,
@@ -1649,6 +1658,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
         };
@@ -1669,6 +1679,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
         };
@@ -1689,6 +1700,7 @@ mod tests {
                 },
             ],
             serum3_infos: vec![],
+            openbook_v2_infos: vec![],
             perp_infos: vec![PerpInfo {
                 perp_market_index: 0,
                 base_lot_size: 3,
@@ -1724,10 +1736,89 @@ mod tests {
                 market_index: 0,
                 has_zero_funds: true,
             }],
+            openbook_v2_infos: vec![],
             perp_infos: vec![],
             being_liquidated: false,
         };
 
         assert!(leverage_eq(&health_cache, 2.0));
+
+        // perp settling in a token other than token 0: assets = 1, liabs = 0.5
+        let health_cache = HealthCache {
+            token_infos: vec![
+                TokenInfo {
+                    token_index: 0,
+                    balance_spot: I80F48::ONE,
+                    ..default_token_info(0.0, 1.0)
+                },
+                TokenInfo {
+                    token_index: 5,
+                    ..default_token_info(0.2, 2.0)
+                },
+            ],
+            serum3_infos: vec![],
+            openbook_v2_infos: vec![],
+            perp_infos: vec![PerpInfo {
+                perp_market_index: 0,
+                settle_token_index: 5,
+                base_lot_size: 3,
+                base_lots: 0,
+                quote: I80F48::from_num(-0.25),
+                ..default_perp_info(0.1, 1.1)
+            }],
+            being_liquidated: false,
+        };
+        assert!(leverage_eq(&health_cache, 1.0));
+    }
+
+    #[test]
+    fn test_isolated_pools() {
+        // token 2 is isolated and may only be combined with token 0
+        let health_cache = HealthCache {
+            token_infos: vec![
+                TokenInfo {
+                    token_index: 0,
+                    ..default_token_info(0.0, 1.0)
+                },
+                TokenInfo {
+                    token_index: 1,
+                    ..default_token_info(0.2, 2.0)
+                },
+                TokenInfo {
+                    token_index: 2,
+                    isolated_quote_token_indexes: Some([
+                        0,
+                        TokenIndex::MAX,
+                        TokenIndex::MAX,
+                        TokenIndex::MAX,
+                    ]),
+                    ..default_token_info(0.5, 3.0)
+                },
+            ],
+            serum3_infos: vec![],
+            openbook_v2_infos: vec![],
+            perp_infos: vec![],
+            being_liquidated: false,
+        };
+        let check = |balances: [f64; 3]| {
+            let mut hc = health_cache.clone();
+            for (ti, balance) in hc.token_infos.iter_mut().zip(balances) {
+                ti.balance_spot = I80F48::from_num(balance);
+            }
+            hc.require_isolated_pools_respected().is_ok()
+        };
+
+        // without a balance in the isolated token, anything goes
+        assert!(check([10.0, -5.0, 0.0]));
+        assert!(check([10.0, -5.0, 0.5]));
+
+        // the isolated token can be combined with its quote token
+        assert!(check([-10.0, 0.0, 5.0]));
+        assert!(check([10.0, 0.0, -5.0]));
+
+        // but not with other tokens, no matter the sign of the balances
+        assert!(!check([0.0, -10.0, 5.0]));
+        assert!(!check([0.0, 10.0, -5.0]));
+        assert!(!check([10.0, 1.0, 5.0]));
     }
 }
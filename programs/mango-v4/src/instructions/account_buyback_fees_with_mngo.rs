@@ -39,15 +39,21 @@ pub fn account_buyback_fees_with_mngo(
     let slot = clock.slot;
 
     let mngo_oracle_ref = &AccountInfoRef::borrow(&ctx.accounts.mngo_oracle.as_ref())?;
+    let mngo_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &mngo_bank.median_oracles)?;
     let mngo_oracle_price = mngo_bank.oracle_price(
-        &OracleAccountInfos::from_reader(mngo_oracle_ref),
+        &OracleAccountInfos::from_reader(mngo_oracle_ref)
+            .with_median_oracles(&mngo_bank.median_oracles, &mngo_median_oracle_refs),
         Some(slot),
     )?;
     let mngo_asset_price = mngo_oracle_price.min(mngo_bank.stable_price());
 
     let fees_oracle_ref = &AccountInfoRef::borrow(&ctx.accounts.fees_oracle.as_ref())?;
+    let fees_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &fees_bank.median_oracles)?;
     let fees_oracle_price = fees_bank.oracle_price(
-        &OracleAccountInfos::from_reader(fees_oracle_ref),
+        &OracleAccountInfos::from_reader(fees_oracle_ref)
+            .with_median_oracles(&fees_bank.median_oracles, &fees_median_oracle_refs),
         Some(slot),
     )?;
     let fees_liab_price = fees_oracle_price.max(fees_bank.stable_price());
@@ -187,6 +193,11 @@ pub fn account_buyback_fees_with_mngo(
         0,
         MangoError::SomeError
     );
+    require_eq!(
+        dao_account.active_openbook_v2_orders().count(),
+        0,
+        MangoError::SomeError
+    );
 
     Ok(())
 }
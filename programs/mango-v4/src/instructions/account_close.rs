@@ -19,9 +19,15 @@ pub fn account_close(ctx: Context<AccountClose>, force_close: bool) -> Result<()
         for ele in account.all_serum3_orders() {
             require_eq!(ele.is_active(), false);
         }
+        for ele in account.all_openbook_v2_orders() {
+            require_eq!(ele.is_active(), false);
+        }
         for ele in account.all_perp_positions() {
             require_eq!(ele.is_active(), false);
         }
+        for ele in account.all_fixed_term_loans() {
+            require_eq!(ele.is_configured(), false);
+        }
     }
 
     Ok(())
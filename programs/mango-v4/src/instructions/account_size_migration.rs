@@ -80,6 +80,9 @@ pub fn account_size_migration(ctx: Context<AccountSizeMigration>) -> Result<()>
             new_header.perp_count,
             new_header.perp_oo_count,
             new_header.token_conditional_swap_count,
+            new_header.openbook_v2_count,
+            new_header.perp_trigger_order_count,
+            new_header.fixed_term_loan_count,
         )?;
     }
 
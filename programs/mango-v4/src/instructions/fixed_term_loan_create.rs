@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::health::*;
+use crate::logs::{emit_stack, FixedTermLoanCreateLog, TokenBalanceLog};
+use crate::state::*;
+
+pub fn fixed_term_loan_create(
+    ctx: Context<FixedTermLoanCreate>,
+    amount: u64,
+    duration: u64,
+    auto_roll: bool,
+) -> Result<()> {
+    require_msg!(amount > 0, "loan amount must be positive");
+
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let token_index = {
+        let bank = ctx.accounts.bank.load()?;
+        require!(
+            bank.fixed_term_max_duration > 0
+                && duration > 0
+                && duration <= bank.fixed_term_max_duration,
+            MangoError::FixedTermLoanDurationNotAllowed
+        );
+        require!(
+            !bank.are_borrows_reduce_only(),
+            MangoError::TokenInReduceOnlyMode
+        );
+        bank.token_index
+    };
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let (_, raw_token_index, _) = account.ensure_token_position(token_index)?;
+
+    // Health check _after_ the token position is guaranteed to exist
+    let (pre_init_health, oracle_price) = {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("pre-loan health cache")?;
+        let oracle_price = health_cache.token_info(token_index)?.prices.oracle;
+        (account.check_health_pre(&health_cache)?, oracle_price)
+    };
+
+    // The loan is lent out of the bank's deposits like a variable rate borrow: It pays
+    // the loan origination fee and must pass the same limits as a borrowing withdraw.
+    let mut bank = ctx.accounts.bank.load_mut()?;
+    let position = account.token_position_mut_by_raw_index(raw_token_index);
+    let loan_origination_fee = (bank.loan_origination_fee_rate * I80F48::from(amount))
+        .ceil()
+        .to_num::<u64>();
+    let principal = amount + loan_origination_fee;
+
+    bank.fixed_term_borrows += I80F48::from(principal);
+    position.fixed_term_principal += principal;
+    bank.check_borrow_limits(position, oracle_price)?;
+    bank.enforce_max_utilization_on_borrow()?;
+    bank.update_net_borrows(I80F48::from(principal), now_ts);
+    bank.check_net_borrows(oracle_price)?;
+    let rate = bank.fixed_term_loan_rate();
+
+    // The borrowed tokens become a deposit on the account; the loan is the matching liability.
+    // Keep the token position active for as long as the loan exists.
+    position.increment_in_use();
+    bank.deposit(position, I80F48::from(amount), now_ts)?;
+    bank.collected_fees_native += I80F48::from(loan_origination_fee);
+    bank.check_deposit_and_oo_limit()?;
+    let indexed_position = position.indexed_position;
+
+    let id = account.fixed.next_fixed_term_loan_id;
+    account.fixed.next_fixed_term_loan_id = account.fixed.next_fixed_term_loan_id.wrapping_add(1);
+
+    let loan = account.free_fixed_term_loan_mut()?;
+    *loan = FixedTermLoan {
+        id,
+        principal,
+        rate: rate.to_num(),
+        start_timestamp: now_ts,
+        maturity_timestamp: now_ts + duration,
+        token_index,
+        is_configured: 1,
+        auto_roll: u8::from(auto_roll),
+        ..FixedTermLoan::default()
+    };
+    let maturity_timestamp = loan.maturity_timestamp;
+    bank.fixed_term_yearly_interest += loan.yearly_interest();
+
+    emit_stack(TokenBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        token_index,
+        indexed_position: indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    emit_stack(FixedTermLoanCreateLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        id,
+        token_index,
+        principal,
+        loan_origination_fee,
+        rate: rate.to_num(),
+        maturity_timestamp,
+        auto_roll,
+    });
+
+    //
+    // Health check
+    //
+    drop(bank);
+    let retriever = new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+    let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+        .context("post-loan health cache")?;
+    account.check_health_post(&health_cache, pre_init_health)?;
+
+    Ok(())
+}
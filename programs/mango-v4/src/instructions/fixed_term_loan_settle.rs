@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::health::*;
+use crate::logs::{emit_stack, FixedTermLoanSettleLog, TokenBalanceLog};
+use crate::state::*;
+
+/// Repays a fixed term loan from the account's token position.
+///
+/// The owner or delegate can settle at any time. If that creates a variable rate
+/// borrow, the same checks as for a borrowing token_withdraw apply.
+///
+/// Anyone else can settle once the loan has matured, or when the account is
+/// liquidatable: the remaining debt then becomes a variable rate borrow that can be
+/// liquidated as usual.
+pub fn fixed_term_loan_settle(
+    ctx: Context<FixedTermLoanSettle>,
+    fixed_term_loan_index: u8,
+    fixed_term_loan_id: u64,
+) -> Result<()> {
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let account_key = ctx.accounts.account.key();
+    let mut account = ctx.accounts.account.load_full_mut()?;
+
+    let loan = *account.fixed_term_loan_by_index(fixed_term_loan_index.into())?;
+    if !loan.is_configured() || loan.id != fixed_term_loan_id {
+        msg!("Fixed term loan was already settled");
+        return Ok(());
+    }
+
+    let is_matured = loan.is_matured(now_ts);
+    let is_owner = account
+        .fixed
+        .is_owner_or_delegate(ctx.accounts.settler.key());
+
+    // For the owner: pre health and the oracle price for the borrow checks
+    let owner_checks_opt = if is_owner {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("pre-settle health cache")?;
+        let oracle_price = health_cache.token_info(loan.token_index)?.prices.oracle;
+        Some((account.check_health_pre(&health_cache)?, oracle_price))
+    } else if !is_matured {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("pre-settle health cache")?;
+        require!(
+            health_cache.health(HealthType::Maint) < 0,
+            MangoError::FixedTermLoanNotSettleable
+        );
+        None
+    } else {
+        None
+    };
+
+    let mut bank = ctx.accounts.bank.load_mut()?;
+    require_eq!(bank.token_index, loan.token_index, MangoError::InvalidBank);
+
+    let (position, _) = account.token_position_mut(loan.token_index)?;
+    let principal = I80F48::from(loan.principal);
+    let interest = loan.interest(now_ts);
+    let owed = principal + interest;
+
+    // Not repaying by maturity costs a penalty on the part of the debt that needs
+    // to be turned into a variable rate borrow.
+    let penalty = if is_matured && !loan.auto_roll() {
+        let shortfall = (owed - position.native(&bank).max(I80F48::ZERO)).max(I80F48::ZERO);
+        shortfall * bank.liquidation_fee
+    } else {
+        I80F48::ZERO
+    };
+
+    // Remove the loan before repaying it, so the borrow limits don't count it twice
+    position.fixed_term_principal = position.fixed_term_principal.saturating_sub(loan.principal);
+    bank.fixed_term_borrows = (bank.fixed_term_borrows - principal).max(I80F48::ZERO);
+    bank.fixed_term_yearly_interest =
+        (bank.fixed_term_yearly_interest - loan.yearly_interest()).max(I80F48::ZERO);
+
+    // The position is kept in use by the loan, so it can't be dusted or closed here
+    if let Some((_, oracle_price)) = owner_checks_opt {
+        bank.withdraw_without_fee_with_borrow_limits(
+            position,
+            owed + penalty,
+            now_ts,
+            oracle_price,
+        )?;
+        if position.indexed_position.is_negative() {
+            require_msg_typed!(
+                !bank.are_borrows_reduce_only(),
+                MangoError::TokenInReduceOnlyMode,
+                "the remaining debt can't become a borrow"
+            );
+            bank.check_net_borrows(oracle_price)?;
+        }
+    } else {
+        // Forced settlement, like liquidation, must not be blocked by borrow limits
+        bank.withdraw_without_fee(position, owed + penalty, now_ts)?;
+    }
+    let indexed_position = position.indexed_position;
+
+    // Release the token position that fixed_term_loan_create kept in use
+    position.decrement_in_use();
+
+    // The interest was already paid to depositors through the deposit index
+    bank.collected_fees_native += penalty;
+
+    account
+        .fixed_term_loan_mut_by_index(fixed_term_loan_index.into())?
+        .set_is_configured(false);
+
+    emit_stack(TokenBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: account_key,
+        token_index: loan.token_index,
+        indexed_position: indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    emit_stack(FixedTermLoanSettleLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: account_key,
+        settler: ctx.accounts.settler.key(),
+        id: loan.id,
+        token_index: loan.token_index,
+        principal: loan.principal,
+        interest: interest.to_bits(),
+        penalty: penalty.to_bits(),
+    });
+
+    //
+    // Health check
+    //
+    if let Some((pre_init_health, _)) = owner_checks_opt {
+        drop(bank);
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("post-settle health cache")?;
+        account.check_health_post(&health_cache, pre_init_health)?;
+        bank = ctx.accounts.bank.load_mut()?;
+    }
+
+    //
+    // Deactivate the position only after the health check because the user passed in
+    // remaining_accounts for all banks/oracles, including the account that will now be
+    // deactivated.
+    //
+    let (position, raw_index) = account.token_position_mut(loan.token_index)?;
+    if !bank.dust_if_possible(position, now_ts)? {
+        account.deactivate_token_position_and_log(raw_index, account_key);
+    }
+
+    Ok(())
+}
@@ -461,6 +461,7 @@ pub fn flash_loan_end<'key, 'accounts, 'remaining, 'info>(
         if change_amount < 0 && native_after_change < 0 {
             bank.enforce_max_utilization_on_borrow()?;
             bank.check_net_borrows(*oracle_price)?;
+            bank.check_borrow_limits(position, *oracle_price)?;
         } else {
             bank.enforce_borrows_lte_deposits()?;
         }
@@ -18,8 +18,10 @@ pub fn health_region_begin<'key, 'accounts, 'remaining, 'info>(
         crate::instruction::PerpCancelOrderByClientOrderId::discriminator(),
         crate::instruction::PerpPlaceOrder::discriminator(),
         crate::instruction::PerpPlaceOrderV2::discriminator(),
+        crate::instruction::PerpPlaceOrderV3::discriminator(),
         crate::instruction::PerpPlaceOrderPegged::discriminator(),
         crate::instruction::PerpPlaceOrderPeggedV2::discriminator(),
+        crate::instruction::PerpPlaceOrderPeggedV3::discriminator(),
         crate::instruction::Serum3CancelAllOrders::discriminator(),
         crate::instruction::Serum3CancelOrder::discriminator(),
         crate::instruction::Serum3PlaceOrder::discriminator(),
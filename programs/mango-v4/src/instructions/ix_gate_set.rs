@@ -77,7 +77,6 @@ pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
     log_if_changed(&group, ix_gate, IxGate::OpenbookV2EditMarket);
     log_if_changed(&group, ix_gate, IxGate::OpenbookV2LiqForceCancelOrders);
     log_if_changed(&group, ix_gate, IxGate::OpenbookV2PlaceOrder);
-    log_if_changed(&group, ix_gate, IxGate::OpenbookV2PlaceTakeOrder);
     log_if_changed(&group, ix_gate, IxGate::OpenbookV2RegisterMarket);
     log_if_changed(&group, ix_gate, IxGate::OpenbookV2SettleFunds);
     log_if_changed(&group, ix_gate, IxGate::AdminTokenWithdrawFees);
@@ -95,6 +94,17 @@ pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
         IxGate::TokenConditionalSwapCreateLinearAuction,
     );
     log_if_changed(&group, ix_gate, IxGate::Serum3PlaceOrderV2);
+    log_if_changed(&group, ix_gate, IxGate::PerpTriggerOrderCreate);
+    log_if_changed(&group, ix_gate, IxGate::PerpTriggerOrderCancel);
+    log_if_changed(&group, ix_gate, IxGate::PerpTriggerOrderExecute);
+    log_if_changed(&group, ix_gate, IxGate::PerpEditOrder);
+    log_if_changed(&group, ix_gate, IxGate::PerpPlaceOrdersBatch);
+    log_if_changed(&group, ix_gate, IxGate::TokenInsuranceFundCreate);
+    log_if_changed(&group, ix_gate, IxGate::TokenInsuranceFundWithdraw);
+    log_if_changed(&group, ix_gate, IxGate::TokenInsuranceFundCover);
+    log_if_changed(&group, ix_gate, IxGate::FixedTermLoanCreate);
+    log_if_changed(&group, ix_gate, IxGate::FixedTermLoanSettle);
+    log_if_changed(&group, ix_gate, IxGate::TokenRateHistoryCreate);
 
     group.ix_gate = ix_gate;
 
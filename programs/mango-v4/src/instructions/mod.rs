@@ -11,6 +11,8 @@ pub use alt_extend::*;
 pub use alt_set::*;
 pub use benchmark::*;
 pub use compute_account_data::*;
+pub use fixed_term_loan_create::*;
+pub use fixed_term_loan_settle::*;
 pub use flash_loan::*;
 pub use group_close::*;
 pub use group_create::*;
@@ -18,6 +20,16 @@ pub use group_edit::*;
 pub use group_withdraw_insurance_fund::*;
 pub use health_region::*;
 pub use ix_gate_set::*;
+pub use openbook_v2_cancel_all_orders::*;
+pub use openbook_v2_cancel_order::*;
+pub use openbook_v2_close_open_orders::*;
+pub use openbook_v2_create_open_orders::*;
+pub use openbook_v2_deregister_market::*;
+pub use openbook_v2_edit_market::*;
+pub use openbook_v2_liq_force_cancel_orders::*;
+pub use openbook_v2_place_order::*;
+pub use openbook_v2_register_market::*;
+pub use openbook_v2_settle_funds::*;
 pub use perp_cancel_all_orders::*;
 pub use perp_cancel_all_orders_by_side::*;
 pub use perp_cancel_order::*;
@@ -27,13 +39,18 @@ pub use perp_consume_events::*;
 pub use perp_create_market::*;
 pub use perp_deactivate_position::*;
 pub use perp_edit_market::*;
+pub use perp_edit_order::*;
 pub use perp_force_close_position::*;
 pub use perp_liq_base_or_positive_pnl::*;
 pub use perp_liq_force_cancel_orders::*;
 pub use perp_liq_negative_pnl_or_bankruptcy::*;
 pub use perp_place_order::*;
+pub use perp_place_orders_batch::*;
 pub use perp_settle_fees::*;
 pub use perp_settle_pnl::*;
+pub use perp_trigger_order_cancel::*;
+pub use perp_trigger_order_create::*;
+pub use perp_trigger_order_execute::*;
 pub use perp_update_funding::*;
 pub use serum3_cancel_all_orders::*;
 pub use serum3_cancel_order::*;
@@ -59,8 +76,12 @@ pub use token_deposit::*;
 pub use token_deregister::*;
 pub use token_edit::*;
 pub use token_force_close_borrows_with_token::*;
+pub use token_insurance_fund_cover::*;
+pub use token_insurance_fund_create::*;
+pub use token_insurance_fund_withdraw::*;
 pub use token_liq_bankruptcy::*;
 pub use token_liq_with_token::*;
+pub use token_rate_history_create::*;
 pub use token_register::*;
 pub use token_register_trustless::*;
 pub use token_update_index_and_rate::*;
@@ -79,6 +100,8 @@ mod alt_extend;
 mod alt_set;
 mod benchmark;
 mod compute_account_data;
+mod fixed_term_loan_create;
+mod fixed_term_loan_settle;
 mod flash_loan;
 mod group_close;
 mod group_create;
@@ -86,6 +109,16 @@ mod group_edit;
 mod group_withdraw_insurance_fund;
 mod health_region;
 mod ix_gate_set;
+mod openbook_v2_cancel_all_orders;
+mod openbook_v2_cancel_order;
+mod openbook_v2_close_open_orders;
+mod openbook_v2_create_open_orders;
+mod openbook_v2_deregister_market;
+mod openbook_v2_edit_market;
+mod openbook_v2_liq_force_cancel_orders;
+mod openbook_v2_place_order;
+mod openbook_v2_register_market;
+mod openbook_v2_settle_funds;
 mod perp_cancel_all_orders;
 mod perp_cancel_all_orders_by_side;
 mod perp_cancel_order;
@@ -95,13 +128,18 @@ mod perp_consume_events;
 mod perp_create_market;
 mod perp_deactivate_position;
 mod perp_edit_market;
+mod perp_edit_order;
 mod perp_force_close_position;
 mod perp_liq_base_or_positive_pnl;
 mod perp_liq_force_cancel_orders;
 mod perp_liq_negative_pnl_or_bankruptcy;
 mod perp_place_order;
+mod perp_place_orders_batch;
 mod perp_settle_fees;
 mod perp_settle_pnl;
+mod perp_trigger_order_cancel;
+mod perp_trigger_order_create;
+mod perp_trigger_order_execute;
 mod perp_update_funding;
 mod serum3_cancel_all_orders;
 mod serum3_cancel_order;
@@ -127,8 +165,12 @@ mod token_deposit;
 mod token_deregister;
 mod token_edit;
 mod token_force_close_borrows_with_token;
+mod token_insurance_fund_cover;
+mod token_insurance_fund_create;
+mod token_insurance_fund_withdraw;
 mod token_liq_bankruptcy;
 mod token_liq_with_token;
+mod token_rate_history_create;
 mod token_register;
 mod token_register_trustless;
 mod token_update_index_and_rate;
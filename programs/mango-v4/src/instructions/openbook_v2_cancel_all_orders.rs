@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::logs::{emit_stack, OpenbookV2OpenOrdersBalanceLog};
+use crate::openbook_v2_cpi::{load_open_orders_ref, OpenOrdersSlim};
+use crate::serum3_cpi::OpenOrdersAmounts;
+use crate::state::*;
+
+pub fn openbook_v2_cancel_all_orders(ctx: Context<OpenbookV2CancelOrder>, limit: u8) -> Result<()> {
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+
+    //
+    // Validation
+    //
+    let base_lot_size;
+    let quote_lot_size;
+    {
+        // cancel all shares the cancel order instruction gate
+        let group = ctx.accounts.group.load()?;
+        require!(
+            group.is_ix_enabled(IxGate::OpenbookV2CancelOrder),
+            MangoError::IxIsDisabled
+        );
+
+        let account = ctx.accounts.account.load_full()?;
+        // account constraint #1
+        require!(
+            account
+                .fixed
+                .is_owner_or_delegate(ctx.accounts.authority.key()),
+            MangoError::SomeError
+        );
+
+        // Validate open_orders #2
+        let openbook_orders = account.openbook_v2_orders(openbook_market.market_index)?;
+        require!(
+            openbook_orders.open_orders == ctx.accounts.open_orders.key(),
+            MangoError::SomeError
+        );
+        base_lot_size = openbook_orders.base_lot_size;
+        quote_lot_size = openbook_orders.quote_lot_size;
+    }
+
+    //
+    // Cancel
+    //
+    cpi_cancel_all_orders(ctx.accounts, limit)?;
+
+    let open_orders = load_open_orders_ref(ctx.accounts.open_orders.as_ref())?;
+    let after_oo = OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size);
+    emit_stack(OpenbookV2OpenOrdersBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        market_index: openbook_market.market_index,
+        base_token_index: openbook_market.base_token_index,
+        quote_token_index: openbook_market.quote_token_index,
+        base_total: after_oo.native_base_total(),
+        base_free: after_oo.native_base_free(),
+        quote_total: after_oo.native_quote_total(),
+        quote_free: after_oo.native_quote_free(),
+        referrer_rebates_available: after_oo.native_rebates(),
+    });
+
+    Ok(())
+}
+
+fn cpi_cancel_all_orders(ctx: &OpenbookV2CancelOrder, limit: u8) -> Result<()> {
+    use crate::openbook_v2_cpi;
+    let group = ctx.group.load()?;
+    openbook_v2_cpi::CancelOrder {
+        program: ctx.openbook_v2_program.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        bids: ctx.bids.to_account_info(),
+        asks: ctx.asks.to_account_info(),
+
+        open_orders: ctx.open_orders.to_account_info(),
+        open_orders_authority: ctx.group.to_account_info(),
+    }
+    .cancel_all(&group, None, limit)
+}
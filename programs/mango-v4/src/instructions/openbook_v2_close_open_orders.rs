@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::MangoError;
+use crate::openbook_v2_cpi::{load_open_orders_ref, OpenOrdersSlim};
+use crate::serum3_cpi::OpenOrdersAmounts;
+use crate::state::*;
+
+pub fn openbook_v2_close_open_orders(ctx: Context<OpenbookV2CloseOpenOrders>) -> Result<()> {
+    //
+    // Validation
+    //
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account
+            .fixed
+            .is_owner_or_delegate(ctx.accounts.authority.key()),
+        MangoError::SomeError
+    );
+
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+
+    // Validate open_orders #2
+    let openbook_orders = account.openbook_v2_orders(openbook_market.market_index)?;
+    require!(
+        openbook_orders.open_orders == ctx.accounts.open_orders.key(),
+        MangoError::SomeError
+    );
+
+    // The open orders account must not have any orders or unsettled funds left
+    {
+        let open_orders = load_open_orders_ref(ctx.accounts.open_orders.as_ref())?;
+        let oo = OpenOrdersSlim::from_oo(
+            &open_orders,
+            openbook_orders.base_lot_size,
+            openbook_orders.quote_lot_size,
+        );
+        require!(
+            oo.native_base_total() == 0 && oo.native_quote_total() == 0,
+            MangoError::HasOpenOrUnsettledOpenbookV2Orders
+        );
+    }
+
+    //
+    // close OO
+    //
+    cpi_close_open_orders(ctx.accounts)?;
+
+    // Reduce the in_use_count on the token positions - they no longer need to be forced open.
+    // We cannot immediately dust tiny positions because we don't have the banks.
+    let (base_position, _) = account.token_position_mut(openbook_market.base_token_index)?;
+    base_position.decrement_in_use();
+    let (quote_position, _) = account.token_position_mut(openbook_market.quote_token_index)?;
+    quote_position.decrement_in_use();
+
+    // Deactivate the openbook_v2 open orders account itself
+    account.deactivate_openbook_v2_orders(openbook_market.market_index)?;
+
+    Ok(())
+}
+
+fn cpi_close_open_orders(ctx: &OpenbookV2CloseOpenOrders) -> Result<()> {
+    use crate::openbook_v2_cpi;
+    let group = ctx.group.load()?;
+    openbook_v2_cpi::CloseOpenOrders {
+        program: ctx.openbook_v2_program.to_account_info(),
+        owner: ctx.group.to_account_info(),
+        open_orders: ctx.open_orders.to_account_info(),
+        sol_destination: ctx.sol_destination.to_account_info(),
+    }
+    .call(&group)
+}
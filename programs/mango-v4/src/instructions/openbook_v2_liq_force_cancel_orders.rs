@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::health::*;
+use crate::instructions::openbook_v2_apply_settle_changes;
+use crate::instructions::openbook_v2_charge_loan_origination_fees;
+use crate::logs::{emit_stack, LoanOriginationFeeInstruction, OpenbookV2OpenOrdersBalanceLog};
+use crate::openbook_v2_cpi::{load_open_orders_ref, OpenOrdersSlim};
+use crate::serum3_cpi::OpenOrdersAmounts;
+use crate::state::*;
+
+pub fn openbook_v2_liq_force_cancel_orders(
+    ctx: Context<OpenbookV2LiqForceCancelOrders>,
+    limit: u8,
+) -> Result<()> {
+    //
+    // Validation
+    //
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+    let base_lot_size;
+    let quote_lot_size;
+    {
+        let account = ctx.accounts.account.load_full()?;
+
+        // Validate open_orders #2
+        let openbook_orders = account.openbook_v2_orders(openbook_market.market_index)?;
+        require!(
+            openbook_orders.open_orders == ctx.accounts.open_orders.key(),
+            MangoError::SomeError
+        );
+        base_lot_size = openbook_orders.base_lot_size;
+        quote_lot_size = openbook_orders.quote_lot_size;
+
+        // Validate banks and vaults #3
+        let quote_bank = ctx.accounts.quote_bank.load()?;
+        require!(
+            quote_bank.vault == ctx.accounts.quote_vault.key(),
+            MangoError::SomeError
+        );
+        require!(
+            quote_bank.token_index == openbook_market.quote_token_index,
+            MangoError::SomeError
+        );
+        let base_bank = ctx.accounts.base_bank.load()?;
+        require!(
+            base_bank.vault == ctx.accounts.base_vault.key(),
+            MangoError::SomeError
+        );
+        require!(
+            base_bank.token_index == openbook_market.base_token_index,
+            MangoError::SomeError
+        );
+    }
+
+    //
+    // Early return if if liquidation is not allowed or if market is not in force close
+    //
+    let mut health_cache = {
+        let mut account = ctx.accounts.account.load_full_mut()?;
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("create health cache")?;
+
+        let liquidatable = account.check_liquidatable(&health_cache)?;
+        let can_force_cancel = !account.fixed.is_operational()
+            || liquidatable == CheckLiquidatable::Liquidatable
+            || openbook_market.is_force_close();
+        if !can_force_cancel {
+            return Ok(());
+        }
+
+        health_cache
+    };
+
+    //
+    // Charge any open loan origination fees
+    //
+    let before_oo = {
+        let open_orders = load_open_orders_ref(ctx.accounts.open_orders.as_ref())?;
+        let before_oo = OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size);
+        let mut account = ctx.accounts.account.load_full_mut()?;
+        let mut base_bank = ctx.accounts.base_bank.load_mut()?;
+        let mut quote_bank = ctx.accounts.quote_bank.load_mut()?;
+        openbook_v2_charge_loan_origination_fees(
+            &ctx.accounts.group.key(),
+            &ctx.accounts.account.key(),
+            openbook_market.market_index,
+            &mut base_bank,
+            &mut quote_bank,
+            &mut account.borrow_mut(),
+            &before_oo,
+            None,
+            None,
+            &[],
+            LoanOriginationFeeInstruction::OpenbookV2LiqForceCancelOrders,
+        )?;
+
+        before_oo
+    };
+
+    //
+    // Before-settle tracking
+    //
+    let before_base_vault = ctx.accounts.base_vault.amount;
+    let before_quote_vault = ctx.accounts.quote_vault.amount;
+
+    //
+    // Cancel all and settle
+    //
+    cpi_cancel_all_orders(ctx.accounts, limit)?;
+    cpi_settle_funds(ctx.accounts)?;
+
+    //
+    // After-settle tracking
+    //
+    let after_oo;
+    {
+        let open_orders = load_open_orders_ref(ctx.accounts.open_orders.as_ref())?;
+        after_oo = OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size);
+
+        emit_stack(OpenbookV2OpenOrdersBalanceLog {
+            mango_group: ctx.accounts.group.key(),
+            mango_account: ctx.accounts.account.key(),
+            market_index: openbook_market.market_index,
+            base_token_index: openbook_market.base_token_index,
+            quote_token_index: openbook_market.quote_token_index,
+            base_total: after_oo.native_base_total(),
+            base_free: after_oo.native_base_free(),
+            quote_total: after_oo.native_quote_total(),
+            quote_free: after_oo.native_quote_free(),
+            referrer_rebates_available: after_oo.native_rebates(),
+        });
+    };
+
+    ctx.accounts.base_vault.reload()?;
+    ctx.accounts.quote_vault.reload()?;
+    let after_base_vault = ctx.accounts.base_vault.amount;
+    let after_quote_vault = ctx.accounts.quote_vault.amount;
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let mut base_bank = ctx.accounts.base_bank.load_mut()?;
+    let mut quote_bank = ctx.accounts.quote_bank.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+    openbook_v2_apply_settle_changes(
+        &group,
+        ctx.accounts.account.key(),
+        &mut account.borrow_mut(),
+        &mut base_bank,
+        &mut quote_bank,
+        &openbook_market,
+        before_base_vault,
+        before_quote_vault,
+        &before_oo,
+        after_base_vault,
+        after_quote_vault,
+        &after_oo,
+        Some(&mut health_cache),
+        true,
+        None,
+        &[],
+    )?;
+
+    //
+    // Health check at the end
+    //
+    let liq_end_health = health_cache.health(HealthType::LiquidationEnd);
+    account
+        .fixed
+        .maybe_recover_from_being_liquidated(liq_end_health);
+
+    Ok(())
+}
+
+fn cpi_cancel_all_orders(ctx: &OpenbookV2LiqForceCancelOrders, limit: u8) -> Result<()> {
+    use crate::openbook_v2_cpi;
+    let group = ctx.group.load()?;
+    openbook_v2_cpi::CancelOrder {
+        program: ctx.openbook_v2_program.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        bids: ctx.bids.to_account_info(),
+        asks: ctx.asks.to_account_info(),
+
+        open_orders: ctx.open_orders.to_account_info(),
+        open_orders_authority: ctx.group.to_account_info(),
+    }
+    .cancel_all(&group, None, limit)
+}
+
+fn cpi_settle_funds(ctx: &OpenbookV2LiqForceCancelOrders) -> Result<()> {
+    use crate::openbook_v2_cpi;
+    let group = ctx.group.load()?;
+    openbook_v2_cpi::SettleFunds {
+        program: ctx.openbook_v2_program.to_account_info(),
+        owner: ctx.group.to_account_info(),
+        open_orders: ctx.open_orders.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        market_authority: ctx.market_vault_signer.to_account_info(),
+        market_base_vault: ctx.market_base_vault.to_account_info(),
+        market_quote_vault: ctx.market_quote_vault.to_account_info(),
+        user_base_wallet: ctx.base_vault.to_account_info(),
+        user_quote_wallet: ctx.quote_vault.to_account_info(),
+        rebates_quote_wallet: ctx.quote_vault.to_account_info(),
+        token_program: ctx.token_program.to_account_info(),
+    }
+    .call(&group)
+}
@@ -0,0 +1,699 @@
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::*;
+use crate::i80f48::ClampToInt;
+use crate::state::*;
+
+use crate::accounts_ix::*;
+use crate::logs::{emit_stack, OpenbookV2OpenOrdersBalanceLog, TokenBalanceLog};
+use crate::openbook_v2_cpi::{load_open_orders_ref, OpenOrdersSlim};
+use crate::serum3_cpi::OpenOrdersAmounts;
+use anchor_lang::prelude::*;
+
+use fixed::types::I80F48;
+use openbook_v2::state::{PlaceOrderArgs, PlaceOrderType, SelfTradeBehavior, Side};
+
+#[allow(clippy::too_many_arguments)]
+pub fn openbook_v2_place_order(
+    ctx: Context<OpenbookV2PlaceOrder>,
+    side: Side,
+    limit_price_lots: u64,
+    max_base_qty: u64,
+    max_native_quote_qty_including_fees: u64,
+    self_trade_behavior: SelfTradeBehavior,
+    order_type: PlaceOrderType,
+    client_order_id: u64,
+    limit: u8,
+) -> Result<()> {
+    // Also required by openbook_v2's place order
+    require_gt!(limit_price_lots, 0);
+
+    {
+        let group = ctx.accounts.group.load()?;
+        require!(
+            group.is_ix_enabled(IxGate::OpenbookV2PlaceOrder),
+            MangoError::IxIsDisabled
+        );
+    }
+
+    let openbook_market = ctx.accounts.openbook_v2_market.load()?;
+    require!(
+        !openbook_market.is_reduce_only(),
+        MangoError::MarketInReduceOnlyMode
+    );
+
+    //
+    // Validation
+    //
+    let receiver_token_index;
+    {
+        let account = ctx.accounts.account.load_full()?;
+        // account constraint #1
+        require!(
+            account
+                .fixed
+                .is_owner_or_delegate(ctx.accounts.authority.key()),
+            MangoError::SomeError
+        );
+
+        // Validate open_orders #2
+        require!(
+            account
+                .openbook_v2_orders(openbook_market.market_index)?
+                .open_orders
+                == ctx.accounts.open_orders.key(),
+            MangoError::SomeError
+        );
+
+        // Validate bank and vault #3
+        let payer_bank = ctx.accounts.payer_bank.load()?;
+        require_keys_eq!(payer_bank.vault, ctx.accounts.payer_vault.key());
+        let payer_token_index = match side {
+            Side::Bid => openbook_market.quote_token_index,
+            Side::Ask => openbook_market.base_token_index,
+        };
+        require_eq!(payer_bank.token_index, payer_token_index);
+
+        receiver_token_index = match side {
+            Side::Bid => openbook_market.base_token_index,
+            Side::Ask => openbook_market.quote_token_index,
+        };
+    }
+
+    //
+    // Pre-health computation
+    //
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let retriever = new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let mut health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+        .context("pre-withdraw init health")?;
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        Some(pre_init_health)
+    } else {
+        None
+    };
+
+    // Check if the bank for the token whose balance is increased is in reduce-only mode
+    let receiver_bank_ai;
+    let receiver_bank_oracle;
+    let receiver_bank_reduce_only;
+    {
+        // The token position already exists, but we need the active_index.
+        let (_, _, active_index) = account.ensure_token_position(receiver_token_index)?;
+        let group_key = ctx.accounts.group.key();
+        let (receiver_bank, oracle) =
+            retriever.bank_and_oracle(&group_key, active_index, receiver_token_index)?;
+        receiver_bank_oracle = oracle;
+        receiver_bank_reduce_only = receiver_bank.are_deposits_reduce_only();
+
+        // The fixed_order account retriever can't give us mut references, so use the above
+        // call to .bank_and_oracle() as validation and then copy out the matching AccountInfo.
+        receiver_bank_ai = ctx.remaining_accounts[active_index].clone();
+        // Double-check that we got the right account
+        let receiver_bank2 = receiver_bank_ai.load::<Bank>()?;
+        assert_eq!(receiver_bank2.group, group_key);
+        assert_eq!(receiver_bank2.token_index, receiver_token_index);
+    }
+
+    drop(retriever);
+
+    require_msg_typed!(
+        receiver_bank_ai.is_writable,
+        MangoError::HealthAccountBankNotWritable,
+        "the receiver bank (token index {}) in the health account list must be writable",
+        receiver_token_index
+    );
+
+    //
+    // Before-order tracking
+    //
+
+    let before_vault = ctx.accounts.payer_vault.amount;
+
+    let base_lot_size;
+    let quote_lot_size;
+    {
+        let market_external = ctx.accounts.openbook_v2_market_external.load()?;
+        base_lot_size = market_external.base_lot_size;
+        quote_lot_size = market_external.quote_lot_size;
+    }
+
+    let before_bids_base_lots;
+    let before_asks_base_lots;
+    let before_oo = {
+        let open_orders = load_open_orders_ref(ctx.accounts.open_orders.as_ref())?;
+        before_bids_base_lots = open_orders.position.bids_base_lots;
+        before_asks_base_lots = open_orders.position.asks_base_lots;
+        OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size)
+    };
+
+    // Provide a readable error message in case the vault doesn't have enough tokens
+    {
+        let needed_amount = match side {
+            Side::Ask => max_base_qty
+                .checked_mul(base_lot_size as u64)
+                .ok_or_else(|| error!(MangoError::MathError))?
+                .saturating_sub(before_oo.native_base_free()),
+            Side::Bid => {
+                max_native_quote_qty_including_fees.saturating_sub(before_oo.native_quote_free())
+            }
+        };
+        if before_vault < needed_amount {
+            return err!(MangoError::InsufficentBankVaultFunds).with_context(|| {
+                format!(
+                    "bank vault does not have enough tokens, need {} but have {}",
+                    needed_amount, before_vault
+                )
+            });
+        }
+    }
+
+    //
+    // Apply the order to openbook_v2
+    //
+    let order = PlaceOrderArgs {
+        side,
+        price_lots: limit_price_lots.try_into().unwrap(),
+        max_base_lots: max_base_qty.try_into().unwrap(),
+        max_quote_lots_including_fees: (max_native_quote_qty_including_fees
+            / quote_lot_size as u64)
+            .try_into()
+            .unwrap(),
+        client_order_id,
+        order_type,
+        expiry_timestamp: 0,
+        self_trade_behavior,
+        limit,
+    };
+    cpi_place_order(ctx.accounts, order)?;
+
+    //
+    // After-order tracking
+    //
+    let after_bids_base_lots;
+    let after_asks_base_lots;
+    let after_oo = {
+        let open_orders = load_open_orders_ref(ctx.accounts.open_orders.as_ref())?;
+        after_bids_base_lots = open_orders.position.bids_base_lots;
+        after_asks_base_lots = open_orders.position.asks_base_lots;
+        OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size)
+    };
+
+    //
+    // Track the highest bid and lowest ask, to be able to evaluate worst-case health even
+    // when they cross the oracle
+    //
+    let openbook = account.openbook_v2_orders_mut(openbook_market.market_index)?;
+    if before_bids_base_lots == 0 {
+        // The 0 state means uninitialized/no value
+        openbook.highest_placed_bid_inv = 0.0;
+        openbook.lowest_placed_bid_inv = 0.0;
+    }
+    if before_asks_base_lots == 0 {
+        openbook.lowest_placed_ask = 0.0;
+        openbook.highest_placed_ask = 0.0;
+    }
+    // in the normal quote per base units
+    let limit_price = limit_price_lots as f64 * quote_lot_size as f64 / base_lot_size as f64;
+
+    let new_order_on_book = match side {
+        Side::Bid => after_bids_base_lots > before_bids_base_lots,
+        Side::Ask => after_asks_base_lots > before_asks_base_lots,
+    };
+    if new_order_on_book {
+        match side {
+            Side::Ask => {
+                openbook.lowest_placed_ask = if openbook.lowest_placed_ask == 0.0 {
+                    limit_price
+                } else {
+                    openbook.lowest_placed_ask.min(limit_price)
+                };
+                openbook.highest_placed_ask = if openbook.highest_placed_ask == 0.0 {
+                    limit_price
+                } else {
+                    openbook.highest_placed_ask.max(limit_price)
+                }
+            }
+            Side::Bid => {
+                // in base per quote units, to avoid a division in health
+                let limit_price_inv = 1.0 / limit_price;
+                openbook.highest_placed_bid_inv = if openbook.highest_placed_bid_inv == 0.0 {
+                    limit_price_inv
+                } else {
+                    // the highest bid has the lowest _inv value
+                    openbook.highest_placed_bid_inv.min(limit_price_inv)
+                };
+                openbook.lowest_placed_bid_inv = if openbook.lowest_placed_bid_inv == 0.0 {
+                    limit_price_inv
+                } else {
+                    // lowest bid has max _inv value
+                    openbook.lowest_placed_bid_inv.max(limit_price_inv)
+                }
+            }
+        }
+    }
+
+    emit_stack(OpenbookV2OpenOrdersBalanceLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        market_index: openbook_market.market_index,
+        base_token_index: openbook_market.base_token_index,
+        quote_token_index: openbook_market.quote_token_index,
+        base_total: after_oo.native_base_total(),
+        base_free: after_oo.native_base_free(),
+        quote_total: after_oo.native_quote_total(),
+        quote_free: after_oo.native_quote_free(),
+        referrer_rebates_available: after_oo.native_rebates(),
+    });
+
+    ctx.accounts.payer_vault.reload()?;
+    let after_vault = ctx.accounts.payer_vault.amount;
+
+    // Placing an order cannot increase vault balance
+    require_gte!(before_vault, after_vault);
+
+    let mut payer_bank = ctx.accounts.payer_bank.load_mut()?;
+
+    // Update the potential token tracking in banks
+    // (for init weight scaling, deposit limit checks)
+    {
+        let mut receiver_bank = receiver_bank_ai.load_mut::<Bank>()?;
+        let (base_bank, quote_bank) = match side {
+            Side::Bid => (&mut receiver_bank, &mut payer_bank),
+            Side::Ask => (&mut payer_bank, &mut receiver_bank),
+        };
+        update_bank_potential_tokens(openbook, base_bank, quote_bank, &after_oo);
+    }
+
+    // Track position before withdraw happens
+    let before_position_native = account
+        .token_position_mut(payer_bank.token_index)?
+        .0
+        .native(&payer_bank);
+
+    let payer_oracle_ref = &AccountInfoRef::borrow(&ctx.accounts.payer_oracle)?;
+    let payer_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &payer_bank.median_oracles)?;
+    let payer_bank_oracle = payer_bank.oracle_price(
+        &OracleAccountInfos::from_reader(payer_oracle_ref)
+            .with_median_oracles(&payer_bank.median_oracles, &payer_median_oracle_refs),
+        None,
+    )?;
+
+    // Charge the difference in vault balance to the user's account
+    // (must be done before limit checks like deposit limit)
+    let vault_difference = {
+        apply_vault_difference(
+            ctx.accounts.account.key(),
+            &mut account.borrow_mut(),
+            openbook_market.market_index,
+            &mut payer_bank,
+            after_vault,
+            before_vault,
+            Some(payer_bank_oracle),
+        )?
+    };
+
+    // Deposit limit check, receiver side:
+    // Placing an order can always increase the receiver bank deposits on fill.
+    {
+        let receiver_bank = receiver_bank_ai.load::<Bank>()?;
+        receiver_bank
+            .check_deposit_and_oo_limit()
+            .with_context(|| std::format!("on {}", receiver_bank.name()))?;
+    }
+
+    // Payer bank safety checks like reduce-only, net borrows, vault-to-deposits ratio
+    let withdrawn_from_vault = I80F48::from(before_vault - after_vault);
+    if withdrawn_from_vault > before_position_native {
+        require_msg_typed!(
+            !payer_bank.are_borrows_reduce_only(),
+            MangoError::TokenInReduceOnlyMode,
+            "the payer tokens cannot be borrowed"
+        );
+        payer_bank.enforce_max_utilization_on_borrow()?;
+        payer_bank.check_net_borrows(payer_bank_oracle)?;
+
+        // Deposit limit check, payer side:
+        // The payer bank deposits could increase when cancelling the order later,
+        // see the same check in serum3_place_order.
+        payer_bank
+            .check_deposit_and_oo_limit()
+            .with_context(|| std::format!("on {}", payer_bank.name()))?;
+    } else {
+        payer_bank.enforce_borrows_lte_deposits()?;
+    }
+
+    // Limit order price bands: If the order ends up on the book, ensure
+    // - a bid isn't too far below oracle
+    // - an ask isn't too far above oracle
+    let band_threshold = openbook_market.oracle_price_band();
+    if new_order_on_book && band_threshold != f32::MAX {
+        let (base_oracle, quote_oracle) = match side {
+            Side::Bid => (&receiver_bank_oracle, &payer_bank_oracle),
+            Side::Ask => (&payer_bank_oracle, &receiver_bank_oracle),
+        };
+        let base_oracle_f64 = base_oracle.to_num::<f64>();
+        let quote_oracle_f64 = quote_oracle.to_num::<f64>();
+        // this has the same units as base_oracle: USD per BASE; limit_price is in QUOTE per BASE
+        let limit_price_in_dollar = limit_price * quote_oracle_f64;
+        let band_factor = 1.0 + band_threshold as f64;
+        match side {
+            Side::Bid => {
+                require_msg_typed!(
+                    limit_price_in_dollar * band_factor >= base_oracle_f64,
+                    MangoError::OpenbookV2PriceBandExceeded,
+                    "bid price {} must be larger than {} ({}% of oracle)",
+                    limit_price,
+                    base_oracle_f64 / (quote_oracle_f64 * band_factor),
+                    (100.0 / band_factor) as u64,
+                );
+            }
+            Side::Ask => {
+                require_msg_typed!(
+                    limit_price_in_dollar <= base_oracle_f64 * band_factor,
+                    MangoError::OpenbookV2PriceBandExceeded,
+                    "ask price {} must be smaller than {} ({}% of oracle)",
+                    limit_price,
+                    base_oracle_f64 * band_factor / quote_oracle_f64,
+                    (100.0 * band_factor) as u64,
+                );
+            }
+        }
+    }
+
+    // Health cache updates for the changed account state
+    let receiver_bank = receiver_bank_ai.load::<Bank>()?;
+    // update scaled weights for receiver bank
+    health_cache.adjust_token_balance(&receiver_bank, I80F48::ZERO)?;
+    vault_difference.adjust_health_cache_token_balance(&mut health_cache, &payer_bank)?;
+    let openbook_account = account.openbook_v2_orders(openbook_market.market_index)?;
+    OODifference::new(&before_oo, &after_oo).recompute_health_cache_openbook_v2_state(
+        &mut health_cache,
+        openbook_account,
+        &after_oo,
+    )?;
+
+    // Check the receiver's reduce only flag.
+    //
+    // Note that all orders on the book executing can still cause a net deposit. That's because
+    // the total potential amount assumes all reserved amounts convert at the current
+    // oracle price.
+    if receiver_bank_reduce_only {
+        let balance = health_cache.token_info(receiver_token_index)?.balance_spot;
+        let potential =
+            health_cache.total_serum3_potential(HealthType::Maint, receiver_token_index)?;
+        require_msg_typed!(
+            balance + potential < 1,
+            MangoError::TokenInReduceOnlyMode,
+            "receiver bank does not accept deposits"
+        );
+    }
+
+    //
+    // Health check
+    //
+    if let Some(pre_init_health) = pre_health_opt {
+        account.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    Ok(())
+}
+
+struct OODifference {
+    free_base_change: I80F48,
+    free_quote_change: I80F48,
+}
+
+impl OODifference {
+    fn new(before_oo: &OpenOrdersSlim, after_oo: &OpenOrdersSlim) -> Self {
+        Self {
+            free_base_change: I80F48::from(after_oo.native_base_free())
+                - I80F48::from(before_oo.native_base_free()),
+            free_quote_change: I80F48::from(after_oo.native_quote_free())
+                - I80F48::from(before_oo.native_quote_free()),
+        }
+    }
+
+    fn recompute_health_cache_openbook_v2_state(
+        &self,
+        health_cache: &mut HealthCache,
+        openbook_account: &OpenbookV2Orders,
+        open_orders: &OpenOrdersSlim,
+    ) -> Result<()> {
+        health_cache.recompute_openbook_v2_info(
+            openbook_account,
+            open_orders,
+            self.free_base_change,
+            self.free_quote_change,
+        )
+    }
+}
+
+struct VaultDifference {
+    token_index: TokenIndex,
+    native_change: I80F48,
+}
+
+impl VaultDifference {
+    fn adjust_health_cache_token_balance(
+        &self,
+        health_cache: &mut HealthCache,
+        bank: &Bank,
+    ) -> Result<()> {
+        assert_eq!(bank.token_index, self.token_index);
+        health_cache.adjust_token_balance(bank, self.native_change)?;
+        Ok(())
+    }
+}
+
+/// Called in openbook_v2_apply_settle_changes() and place_order to adjust token positions
+/// after changing the vault balances
+/// Also logs changes to token balances
+///
+/// Borrows are checked against the bank borrow limits if `borrow_limit_price` is set.
+fn apply_vault_difference(
+    account_pk: Pubkey,
+    account: &mut MangoAccountRefMut,
+    openbook_market_index: OpenbookV2MarketIndex,
+    bank: &mut Bank,
+    vault_after: u64,
+    vault_before: u64,
+    borrow_limit_price: Option<I80F48>,
+) -> Result<VaultDifference> {
+    let needed_change = I80F48::from(vault_after) - I80F48::from(vault_before);
+
+    let (position, _) = account.token_position_mut(bank.token_index)?;
+    let native_before = position.native(bank);
+    let now_ts = Clock::get()?.unix_timestamp.try_into().unwrap();
+    if needed_change >= 0 {
+        bank.deposit(position, needed_change, now_ts)?;
+    } else if let Some(oracle_price) = borrow_limit_price {
+        bank.withdraw_without_fee_with_borrow_limits(
+            position,
+            -needed_change,
+            now_ts,
+            oracle_price,
+        )?;
+    } else {
+        bank.withdraw_without_fee(position, -needed_change, now_ts)?;
+    }
+    let native_after = position.native(bank);
+    let native_change = native_after - native_before;
+    // amount of tokens transfered to openbook_v2 reserved that were borrowed
+    let new_borrows = native_change
+        .max(native_after)
+        .min(I80F48::ZERO)
+        .abs()
+        .to_num::<u64>();
+
+    let indexed_position = position.indexed_position;
+    let market = account
+        .openbook_v2_orders_mut(openbook_market_index)
+        .unwrap();
+    let borrows_without_fee;
+    if bank.token_index == market.base_token_index {
+        borrows_without_fee = &mut market.base_borrows_without_fee;
+    } else if bank.token_index == market.quote_token_index {
+        borrows_without_fee = &mut market.quote_borrows_without_fee;
+    } else {
+        return Err(error_msg!(
+            "assert failed: apply_vault_difference called with bad token index"
+        ));
+    };
+
+    // Only for place: Add to potential borrow amount
+    *borrows_without_fee += new_borrows;
+
+    // Only for settle/liq_force_cancel: Reduce the potential borrow amounts
+    if needed_change > 0 {
+        *borrows_without_fee = (*borrows_without_fee).saturating_sub(needed_change.to_num::<u64>());
+    }
+
+    emit_stack(TokenBalanceLog {
+        mango_group: bank.group,
+        mango_account: account_pk,
+        token_index: bank.token_index,
+        indexed_position: indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    Ok(VaultDifference {
+        token_index: bank.token_index,
+        native_change,
+    })
+}
+
+/// Uses the changes in OpenOrders and vaults to adjust the user token position,
+/// collect fees and optionally adjusts the HealthCache.
+pub fn openbook_v2_apply_settle_changes(
+    group: &Group,
+    account_pk: Pubkey,
+    account: &mut MangoAccountRefMut,
+    base_bank: &mut Bank,
+    quote_bank: &mut Bank,
+    openbook_market: &OpenbookV2Market,
+    before_base_vault: u64,
+    before_quote_vault: u64,
+    before_oo: &OpenOrdersSlim,
+    after_base_vault: u64,
+    after_quote_vault: u64,
+    after_oo: &OpenOrdersSlim,
+    health_cache: Option<&mut HealthCache>,
+    fees_to_dao: bool,
+    quote_oracle: Option<&AccountInfo>,
+    median_oracle_ais: &[AccountInfo],
+) -> Result<()> {
+    let mut received_fees = 0;
+    if fees_to_dao {
+        // Example: rebates go from 100 -> 10. That means we credit 90 in fees.
+        received_fees = before_oo
+            .native_rebates()
+            .saturating_sub(after_oo.native_rebates());
+        quote_bank.collected_fees_native += I80F48::from(received_fees);
+
+        // Credit the buyback_fees at the current value of the quote token.
+        if let Some(quote_oracle_ai) = quote_oracle {
+            let clock = Clock::get()?;
+            let now_ts = clock.unix_timestamp.try_into().unwrap();
+
+            let quote_oracle_ref = &AccountInfoRef::borrow(quote_oracle_ai)?;
+            let median_oracle_refs =
+                borrow_median_oracle_ais(median_oracle_ais, &quote_bank.median_oracles)?;
+            let quote_oracle_price = quote_bank.oracle_price(
+                &OracleAccountInfos::from_reader(quote_oracle_ref)
+                    .with_median_oracles(&quote_bank.median_oracles, &median_oracle_refs),
+                Some(clock.slot),
+            )?;
+            let quote_asset_price = quote_oracle_price.min(quote_bank.stable_price());
+            account
+                .fixed
+                .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+            let fees_in_usd = I80F48::from(received_fees) * quote_asset_price;
+            account
+                .fixed
+                .accrue_buyback_fees(fees_in_usd.clamp_to_u64());
+        }
+    }
+
+    // Don't count the referrer rebate fees as part of the vault change that should be
+    // credited to the user.
+    let after_quote_vault_adjusted = after_quote_vault - received_fees;
+
+    // Settle cannot decrease vault balances
+    require_gte!(after_base_vault, before_base_vault);
+    require_gte!(after_quote_vault_adjusted, before_quote_vault);
+
+    // Credit the difference in vault balances to the user's account
+    let base_difference = apply_vault_difference(
+        account_pk,
+        account,
+        openbook_market.market_index,
+        base_bank,
+        after_base_vault,
+        before_base_vault,
+        None,
+    )?;
+    let quote_difference = apply_vault_difference(
+        account_pk,
+        account,
+        openbook_market.market_index,
+        quote_bank,
+        after_quote_vault_adjusted,
+        before_quote_vault,
+        None,
+    )?;
+
+    // Tokens were moved from open orders into banks again: also update the tracking
+    // for potential_serum_tokens on the banks.
+    {
+        let openbook_orders = account.openbook_v2_orders_mut(openbook_market.market_index)?;
+        update_bank_potential_tokens(openbook_orders, base_bank, quote_bank, after_oo);
+    }
+
+    if let Some(health_cache) = health_cache {
+        base_difference.adjust_health_cache_token_balance(health_cache, &base_bank)?;
+        quote_difference.adjust_health_cache_token_balance(health_cache, &quote_bank)?;
+
+        let openbook_account = account.openbook_v2_orders(openbook_market.market_index)?;
+        OODifference::new(before_oo, after_oo).recompute_health_cache_openbook_v2_state(
+            health_cache,
+            openbook_account,
+            after_oo,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn update_bank_potential_tokens(
+    openbook_orders: &mut OpenbookV2Orders,
+    base_bank: &mut Bank,
+    quote_bank: &mut Bank,
+    oo: &OpenOrdersSlim,
+) {
+    assert_eq!(openbook_orders.base_token_index, base_bank.token_index);
+    assert_eq!(openbook_orders.quote_token_index, quote_bank.token_index);
+
+    // Potential tokens are all tokens on the side, plus reserved on the other side
+    // converted at favorable price. This creates an overestimation of the potential
+    // base and quote tokens flowing out of this open orders account.
+    let new_base = oo.native_base_total()
+        + (oo.native_quote_reserved() as f64 * openbook_orders.lowest_placed_bid_inv) as u64;
+    let new_quote = oo.native_quote_total()
+        + (oo.native_base_reserved() as f64 * openbook_orders.highest_placed_ask) as u64;
+
+    let old_base = openbook_orders.potential_base_tokens;
+    let old_quote = openbook_orders.potential_quote_tokens;
+
+    // openbook_v2 open orders share the bank's potential token tracking with serum3
+    base_bank.update_potential_serum_tokens(old_base, new_base);
+    quote_bank.update_potential_serum_tokens(old_quote, new_quote);
+
+    openbook_orders.potential_base_tokens = new_base;
+    openbook_orders.potential_quote_tokens = new_quote;
+}
+
+fn cpi_place_order(ctx: &OpenbookV2PlaceOrder, order: PlaceOrderArgs) -> Result<()> {
+    use crate::openbook_v2_cpi;
+
+    let group = ctx.group.load()?;
+    openbook_v2_cpi::PlaceOrder {
+        program: ctx.openbook_v2_program.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        bids: ctx.bids.to_account_info(),
+        asks: ctx.asks.to_account_info(),
+        event_heap: ctx.event_heap.to_account_info(),
+        market_base_vault: ctx.market_base_vault.to_account_info(),
+        market_quote_vault: ctx.market_quote_vault.to_account_info(),
+        token_program: ctx.token_program.to_account_info(),
+
+        open_orders: ctx.open_orders.to_account_info(),
+        order_payer_token_account: ctx.payer_vault.to_account_info(),
+        user_authority: ctx.group.to_account_info(),
+    }
+    .call(&group, order)
+}
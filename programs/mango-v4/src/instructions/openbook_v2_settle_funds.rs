@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use crate::error::*;
+use crate::openbook_v2_cpi::{load_open_orders_ref, OpenOrdersSlim};
+use crate::serum3_cpi::OpenOrdersAmounts;
+use crate::state::*;
+
+use super::openbook_v2_apply_settle_changes;
+use crate::accounts_ix::*;
+use crate::logs::{
+    emit_stack, LoanOriginationFeeInstruction, OpenbookV2OpenOrdersBalanceLog, WithdrawLoanLog,
+};
+
+use crate::accounts_zerocopy::AccountInfoRef;
+
+/// Settling means moving free funds from the openbook_v2 open orders account
+/// back into the mango account wallet.
+///
+/// There will be free funds on open_orders when an order was triggered.
+///
+pub fn openbook_v2_settle_funds(
+    ctx: Context<OpenbookV2SettleFunds>,
+    fees_to_dao: bool,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let openbook_market = accounts.openbook_v2_market.load()?;
+
+    //
+    // Validation
+    //
+    let base_lot_size;
+    let quote_lot_size;
+    {
+        let account = accounts.account.load_full()?;
+        // account constraint #1
+        require!(
+            account.fixed.is_owner_or_delegate(accounts.authority.key()),
+            MangoError::SomeError
+        );
+
+        // Validate open_orders #2
+        let openbook_orders = account.openbook_v2_orders(openbook_market.market_index)?;
+        require!(
+            openbook_orders.open_orders == accounts.open_orders.key(),
+            MangoError::SomeError
+        );
+        base_lot_size = openbook_orders.base_lot_size;
+        quote_lot_size = openbook_orders.quote_lot_size;
+
+        // Validate banks and vaults #3
+        let quote_bank = accounts.quote_bank.load()?;
+        require!(
+            quote_bank.vault == accounts.quote_vault.key(),
+            MangoError::SomeError
+        );
+        require!(
+            quote_bank.token_index == openbook_market.quote_token_index,
+            MangoError::SomeError
+        );
+        require_keys_eq!(quote_bank.oracle, accounts.quote_oracle.key());
+        let base_bank = accounts.base_bank.load()?;
+        require!(
+            base_bank.vault == accounts.base_vault.key(),
+            MangoError::SomeError
+        );
+        require!(
+            base_bank.token_index == openbook_market.base_token_index,
+            MangoError::SomeError
+        );
+        require_keys_eq!(base_bank.oracle, accounts.base_oracle.key());
+    }
+
+    //
+    // Charge any open loan origination fees
+    //
+    let before_oo;
+    {
+        let open_orders = load_open_orders_ref(accounts.open_orders.as_ref())?;
+        before_oo = OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size);
+        let mut account = accounts.account.load_full_mut()?;
+        let mut base_bank = accounts.base_bank.load_mut()?;
+        let mut quote_bank = accounts.quote_bank.load_mut()?;
+        openbook_v2_charge_loan_origination_fees(
+            &accounts.group.key(),
+            &accounts.account.key(),
+            openbook_market.market_index,
+            &mut base_bank,
+            &mut quote_bank,
+            &mut account.borrow_mut(),
+            &before_oo,
+            Some(accounts.base_oracle.as_ref()),
+            Some(accounts.quote_oracle.as_ref()),
+            ctx.remaining_accounts,
+            LoanOriginationFeeInstruction::OpenbookV2SettleFunds,
+        )?;
+    }
+
+    //
+    // Settle
+    //
+    let before_base_vault = accounts.base_vault.amount;
+    let before_quote_vault = accounts.quote_vault.amount;
+
+    cpi_settle_funds(accounts)?;
+
+    //
+    // After-settle tracking
+    //
+    let after_oo = {
+        let open_orders = load_open_orders_ref(accounts.open_orders.as_ref())?;
+        OpenOrdersSlim::from_oo(&open_orders, base_lot_size, quote_lot_size)
+    };
+
+    accounts.base_vault.reload()?;
+    accounts.quote_vault.reload()?;
+    let after_base_vault = accounts.base_vault.amount;
+    let after_quote_vault = accounts.quote_vault.amount;
+
+    let mut account = accounts.account.load_full_mut()?;
+    let mut base_bank = accounts.base_bank.load_mut()?;
+    let mut quote_bank = accounts.quote_bank.load_mut()?;
+    let group = accounts.group.load()?;
+    openbook_v2_apply_settle_changes(
+        &group,
+        accounts.account.key(),
+        &mut account.borrow_mut(),
+        &mut base_bank,
+        &mut quote_bank,
+        &openbook_market,
+        before_base_vault,
+        before_quote_vault,
+        &before_oo,
+        after_base_vault,
+        after_quote_vault,
+        &after_oo,
+        None,
+        fees_to_dao,
+        Some(accounts.quote_oracle.as_ref()),
+        ctx.remaining_accounts,
+    )?;
+
+    emit_stack(OpenbookV2OpenOrdersBalanceLog {
+        mango_group: accounts.group.key(),
+        mango_account: accounts.account.key(),
+        market_index: openbook_market.market_index,
+        base_token_index: openbook_market.base_token_index,
+        quote_token_index: openbook_market.quote_token_index,
+        base_total: after_oo.native_base_total(),
+        base_free: after_oo.native_base_free(),
+        quote_total: after_oo.native_quote_total(),
+        quote_free: after_oo.native_quote_free(),
+        referrer_rebates_available: after_oo.native_rebates(),
+    });
+
+    Ok(())
+}
+
+// Charge fees if the potential borrows are bigger than the funds on the open orders account
+#[allow(clippy::too_many_arguments)]
+pub fn openbook_v2_charge_loan_origination_fees(
+    group_pubkey: &Pubkey,
+    account_pubkey: &Pubkey,
+    market_index: OpenbookV2MarketIndex,
+    base_bank: &mut Bank,
+    quote_bank: &mut Bank,
+    account: &mut MangoAccountRefMut,
+    before_oo: &OpenOrdersSlim,
+    base_oracle: Option<&AccountInfo>,
+    quote_oracle: Option<&AccountInfo>,
+    median_oracle_ais: &[AccountInfo],
+    instruction: LoanOriginationFeeInstruction,
+) -> Result<()> {
+    let openbook_account = account.openbook_v2_orders_mut(market_index).unwrap();
+
+    let now_ts = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    let oo_base_total = before_oo.native_base_total();
+    let actualized_base_loan = I80F48::from_num(
+        openbook_account
+            .base_borrows_without_fee
+            .saturating_sub(oo_base_total),
+    );
+    if actualized_base_loan > 0 {
+        openbook_account.base_borrows_without_fee = oo_base_total;
+
+        // now that the loan is actually materialized, charge the loan origination fee
+        // note: the withdraw has already happened while placing the order
+        let base_token_account = account.token_position_mut(base_bank.token_index)?.0;
+        let withdraw_result = base_bank.withdraw_loan_origination_fee(
+            base_token_account,
+            actualized_base_loan,
+            now_ts,
+        )?;
+
+        let base_oracle_price = base_oracle
+            .map(|ai| {
+                let ai_ref = &AccountInfoRef::borrow(ai)?;
+                let median_oracle_refs =
+                    borrow_median_oracle_ais(median_oracle_ais, &base_bank.median_oracles)?;
+                base_bank.oracle_price(
+                    &OracleAccountInfos::from_reader(ai_ref)
+                        .with_median_oracles(&base_bank.median_oracles, &median_oracle_refs),
+                    Some(Clock::get()?.slot),
+                )
+            })
+            .transpose()?;
+
+        emit_stack(WithdrawLoanLog {
+            mango_group: *group_pubkey,
+            mango_account: *account_pubkey,
+            token_index: base_bank.token_index,
+            loan_amount: withdraw_result.loan_amount.to_bits(),
+            loan_origination_fee: withdraw_result.loan_origination_fee.to_bits(),
+            instruction,
+            price: base_oracle_price.map(|p| p.to_bits()),
+        });
+    }
+
+    let openbook_account = account.openbook_v2_orders_mut(market_index).unwrap();
+    let oo_quote_total = before_oo.native_quote_total();
+    let actualized_quote_loan = I80F48::from_num::<u64>(
+        openbook_account
+            .quote_borrows_without_fee
+            .saturating_sub(oo_quote_total),
+    );
+    if actualized_quote_loan > 0 {
+        openbook_account.quote_borrows_without_fee = oo_quote_total;
+
+        // now that the loan is actually materialized, charge the loan origination fee
+        // note: the withdraw has already happened while placing the order
+        let quote_token_account = account.token_position_mut(quote_bank.token_index)?.0;
+        let withdraw_result = quote_bank.withdraw_loan_origination_fee(
+            quote_token_account,
+            actualized_quote_loan,
+            now_ts,
+        )?;
+
+        let quote_oracle_price = quote_oracle
+            .map(|ai| {
+                let ai_ref = &AccountInfoRef::borrow(ai)?;
+                let median_oracle_refs =
+                    borrow_median_oracle_ais(median_oracle_ais, &quote_bank.median_oracles)?;
+                quote_bank.oracle_price(
+                    &OracleAccountInfos::from_reader(ai_ref)
+                        .with_median_oracles(&quote_bank.median_oracles, &median_oracle_refs),
+                    Some(Clock::get()?.slot),
+                )
+            })
+            .transpose()?;
+
+        emit_stack(WithdrawLoanLog {
+            mango_group: *group_pubkey,
+            mango_account: *account_pubkey,
+            token_index: quote_bank.token_index,
+            loan_amount: withdraw_result.loan_amount.to_bits(),
+            loan_origination_fee: withdraw_result.loan_origination_fee.to_bits(),
+            instruction,
+            price: quote_oracle_price.map(|p| p.to_bits()),
+        });
+    }
+
+    Ok(())
+}
+
+fn cpi_settle_funds(ctx: &OpenbookV2SettleFunds) -> Result<()> {
+    use crate::openbook_v2_cpi;
+    let group = ctx.group.load()?;
+    openbook_v2_cpi::SettleFunds {
+        program: ctx.openbook_v2_program.to_account_info(),
+        owner: ctx.group.to_account_info(),
+        open_orders: ctx.open_orders.to_account_info(),
+        market: ctx.openbook_v2_market_external.to_account_info(),
+        market_authority: ctx.market_vault_signer.to_account_info(),
+        market_base_vault: ctx.market_base_vault.to_account_info(),
+        market_quote_vault: ctx.market_quote_vault.to_account_info(),
+        user_base_wallet: ctx.base_vault.to_account_info(),
+        user_quote_wallet: ctx.quote_vault.to_account_info(),
+        rebates_quote_wallet: ctx.quote_vault.to_account_info(),
+        token_program: ctx.token_program.to_account_info(),
+    }
+    .call(&group)
+}
@@ -40,6 +40,8 @@ pub fn perp_create_market(
     settle_pnl_limit_window_size_ts: u64,
     positive_pnl_liquidation_fee: f32,
     platform_liquidation_fee: f32,
+    funding_interest_rate: f32,
+    funding_dampener: f32,
 ) -> Result<()> {
     let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
 
@@ -95,10 +97,24 @@ pub fn perp_create_market(
         fees_withdrawn: 0,
         platform_liquidation_fee: I80F48::from_num(platform_liquidation_fee),
         accrued_liquidation_fees: I80F48::ZERO,
-        reserved: [0; 1848],
+        median_oracles: [Pubkey::default(); MAX_MEDIAN_ORACLES],
+        median_oracle_quorum: 0,
+        padding5: Default::default(),
+        pending_socialized_loss: I80F48::ZERO,
+        funding_sample_interval: 0,
+        funding_last_sample_ts: 0,
+        funding_window_start_ts: 0,
+        funding_window_sample_count: 0,
+        funding_window_rate_sum: I80F48::ZERO,
+        funding_twap_rate: I80F48::ZERO,
+        funding_interest_rate,
+        funding_dampener,
+        reserved: [0; 1656],
     };
+    perp_market.verify()?;
 
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+    check_is_valid_oracle(oracle_ref)?;
     if let Ok(oracle_price) =
         perp_market.oracle_price(&OracleAccountInfos::from_reader(oracle_ref), None)
     {
@@ -40,6 +40,11 @@ pub fn perp_edit_market(
     name_opt: Option<String>,
     force_close_opt: Option<bool>,
     platform_liquidation_fee_opt: Option<f32>,
+    median_oracles_opt: Option<Vec<Pubkey>>,
+    median_oracle_quorum_opt: Option<u8>,
+    funding_sample_interval_opt: Option<u64>,
+    funding_interest_rate_opt: Option<f32>,
+    funding_dampener_opt: Option<f32>,
 ) -> Result<()> {
     let group = ctx.accounts.group.load()?;
 
@@ -60,6 +65,8 @@ pub fn perp_edit_market(
     };
     if let Some(oracle) = oracle_opt {
         msg!("Oracle: old - {:?}, new - {:?}", perp_market.oracle, oracle);
+        require_keys_eq!(oracle, ctx.accounts.oracle.key());
+        check_is_valid_oracle(&AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?)?;
         perp_market.oracle = oracle;
         require_group_admin = true;
     }
@@ -67,8 +74,13 @@ pub fn perp_edit_market(
         msg!("Stable price reset");
         require_keys_eq!(perp_market.oracle, ctx.accounts.oracle.key());
         let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
-        let oracle_price =
-            perp_market.oracle_price(&OracleAccountInfos::from_reader(oracle_ref), None)?;
+        let median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+        let oracle_price = perp_market.oracle_price(
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+            None,
+        )?;
         perp_market.stable_price_model.reset_to_price(
             oracle_price.to_num(),
             Clock::get()?.unix_timestamp.try_into().unwrap(),
@@ -356,6 +368,66 @@ pub fn perp_edit_market(
         require_group_admin = true;
     };
 
+    if let Some(median_oracles) = median_oracles_opt {
+        msg!(
+            "Median oracles: old - {:?}, new - {:?}",
+            perp_market.median_oracles,
+            median_oracles
+        );
+        check_median_oracle_ais(ctx.remaining_accounts, &median_oracles)?;
+        perp_market.median_oracles = median_oracles_from_slice(&median_oracles)?;
+        require_group_admin = true;
+    }
+
+    if let Some(median_oracle_quorum) = median_oracle_quorum_opt {
+        msg!(
+            "Median oracle quorum: old - {:?}, new - {:?}",
+            perp_market.median_oracle_quorum,
+            median_oracle_quorum
+        );
+        perp_market.median_oracle_quorum = median_oracle_quorum;
+        require_group_admin = true;
+    }
+    if let Some(funding_sample_interval) = funding_sample_interval_opt {
+        msg!(
+            "Funding sample interval: old - {:?}, new - {:?}",
+            perp_market.funding_sample_interval,
+            funding_sample_interval
+        );
+        perp_market.funding_sample_interval = funding_sample_interval;
+
+        // Start sampling from scratch, the next funding update seeds the average
+        // with the instantaneous rate
+        perp_market.funding_last_sample_ts = 0;
+        perp_market.funding_window_start_ts = 0;
+        perp_market.funding_window_sample_count = 0;
+        perp_market.funding_window_rate_sum = I80F48::ZERO;
+        perp_market.funding_twap_rate = I80F48::ZERO;
+        require_group_admin = true;
+    }
+
+    if let Some(funding_interest_rate) = funding_interest_rate_opt {
+        msg!(
+            "Funding interest rate: old - {:?}, new - {:?}",
+            perp_market.funding_interest_rate,
+            funding_interest_rate
+        );
+        perp_market.funding_interest_rate = funding_interest_rate;
+        require_group_admin = true;
+    }
+
+    if let Some(funding_dampener) = funding_dampener_opt {
+        msg!(
+            "Funding dampener: old - {:?}, new - {:?}",
+            perp_market.funding_dampener,
+            funding_dampener
+        );
+        perp_market.funding_dampener = funding_dampener;
+        require_group_admin = true;
+    }
+
+    perp_market.verify()?;
+
     // account constraint #1
     if require_group_admin {
         require!(
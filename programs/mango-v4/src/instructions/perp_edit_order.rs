@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+
+use super::perp_place_order::reduce_only_max_base_lots;
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::state::*;
+
+/// Modifies the perp order with `client_order_id`.
+///
+/// If the new order has the same side, fixed price and display quantity as the existing
+/// order and only reduces its quantity, the order is changed in place on the book and keeps its queue
+/// priority (and its original expiry). Otherwise the existing order is canceled and
+/// `order` is placed instead.
+pub fn perp_edit_order(
+    ctx: Context<PerpEditOrder>,
+    client_order_id: u64,
+    mut order: Order,
+    limit: u8,
+) -> Result<Option<u128>> {
+    require_gte!(order.max_base_lots, 0);
+    require_gte!(order.max_quote_lots, 0);
+
+    let clock = Clock::get()?;
+    let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
+    let now_slot = clock.slot;
+    let oracle_price;
+
+    // Update funding if possible, like perp_place_order does.
+    {
+        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+
+        let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+        let median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+        let oracle_state = perp_market.oracle_state(
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+            None, // staleness checked in health
+        )?;
+        oracle_price = oracle_state.price;
+
+        perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts, now_slot)?;
+    }
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        MangoError::SomeError
+    );
+
+    let account_pk = ctx.accounts.account.key();
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+
+    let (slot, existing_order) = account
+        .perp_find_order_with_client_order_id(perp_market_index, client_order_id)
+        .map(|(slot, oo)| (slot, *oo))
+        .ok_or_else(|| {
+            error_msg_typed!(
+                MangoError::PerpOrderIdNotFound,
+                "could not find perp order with client order id {client_order_id} in user account"
+            )
+        })?;
+
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    //
+    // Pre-health computation
+    //
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("pre-edit init health")?;
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        Some((health_cache, pre_init_health))
+    } else {
+        None
+    };
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+
+    account
+        .fixed
+        .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+
+    //
+    // Can the order be reduced in place?
+    //
+    let existing_side = existing_order.side_and_tree().side();
+    let existing_tree = existing_order.side_and_tree().order_tree();
+    let on_book = {
+        let bookside = book.bookside(existing_side);
+        bookside
+            .find_by_key(existing_tree, existing_order.id)
+            .and_then(|handle| bookside.node(handle))
+            .and_then(|node| node.as_leaf())
+            .map(|leaf| {
+                (
+                    fixed_price_lots(leaf.price_data()),
+                    leaf.total_quantity(),
+                    leaf.display_quantity,
+                )
+            })
+    };
+    let reduce_in_place_quantity = match (&order.params, on_book) {
+        (
+            OrderParams::Fixed {
+                price_lots,
+                display_base_lots,
+                ..
+            },
+            Some((book_price_lots, book_quantity, book_display_quantity)),
+        ) if order.side == existing_side
+            && existing_tree == BookSideOrderTree::Fixed
+            && *price_lots == book_price_lots
+            && *display_base_lots == book_display_quantity =>
+        {
+            let new_quantity = order
+                .max_base_lots
+                .min(order.max_quote_lots / (*price_lots).max(1));
+            (new_quantity > 0 && new_quantity < book_quantity).then_some(new_quantity)
+        }
+        _ => None,
+    };
+
+    let order_id_opt = if let Some(new_quantity) = reduce_in_place_quantity {
+        book.reduce_order_quantity_by_slot(
+            &mut account.borrow_mut(),
+            &account_pk,
+            slot,
+            perp_market_index,
+            new_quantity,
+        )?;
+        Some(existing_order.id)
+    } else {
+        book.cancel_order_by_slot(
+            &mut account.borrow_mut(),
+            &account_pk,
+            slot,
+            perp_market_index,
+        )?;
+
+        let pp = account.perp_position(perp_market_index)?;
+        let max_base_lots = if order.reduce_only || perp_market.is_reduce_only() {
+            reduce_only_max_base_lots(pp, &order, perp_market.is_reduce_only())
+        } else {
+            order.max_base_lots
+        };
+        if perp_market.is_reduce_only() {
+            require!(
+                order.reduce_only || max_base_lots == order.max_base_lots,
+                MangoError::MarketInReduceOnlyMode
+            )
+        };
+        order.max_base_lots = max_base_lots;
+
+        book.new_order(
+            order,
+            &mut perp_market,
+            &mut event_queue,
+            oracle_price,
+            &mut account.borrow_mut(),
+            &account_pk,
+            now_ts,
+            now_slot,
+            limit,
+        )?
+    };
+
+    //
+    // Health check
+    //
+    if let Some((mut health_cache, pre_init_health)) = pre_health_opt {
+        let perp_position = account.perp_position(perp_market_index)?;
+        health_cache.recompute_perp_info(perp_position, &perp_market)?;
+        account.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    Ok(order_id_opt)
+}
@@ -35,8 +35,13 @@ pub fn perp_force_close_position(ctx: Context<PerpForceClosePosition>) -> Result
         .max(0);
     let now_slot = Clock::get()?.slot;
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
-    let oracle_price =
-        perp_market.oracle_price(&OracleAccountInfos::from_reader(oracle_ref), Some(now_slot))?;
+    let median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+    let oracle_price = perp_market.oracle_price(
+        &OracleAccountInfos::from_reader(oracle_ref)
+            .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+        Some(now_slot),
+    )?;
     let quote_transfer = I80F48::from(base_transfer * perp_market.base_lot_size) * oracle_price;
 
     account_a_perp_position.record_trade(&mut perp_market, -base_transfer, quote_transfer);
@@ -70,8 +70,11 @@ pub fn perp_liq_base_or_positive_pnl(
 
     // Get oracle price for market. Price is validated inside
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+    let median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
     let oracle_price = perp_market.oracle_price(
-        &OracleAccountInfos::from_reader(oracle_ref),
+        &OracleAccountInfos::from_reader(oracle_ref)
+            .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
         None, // checked in health
     )?;
 
@@ -192,14 +195,11 @@ pub fn perp_liq_base_or_positive_pnl(
     if !liqor.fixed.is_in_health_region() {
         let account_retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, group_pk)
             .context("create account retriever end")?;
-        let liqor_health = compute_health(
-            &liqor.borrow(),
-            HealthType::Init,
-            &account_retriever,
-            now_ts,
-        )
-        .context("compute liqor health")?;
+        let liqor_health_cache = new_health_cache(&liqor.borrow(), &account_retriever, now_ts)
+            .context("compute liqor health")?;
+        let liqor_health = liqor_health_cache.health(HealthType::Init);
         require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+        liqor_health_cache.require_isolated_pools_respected()?;
     }
 
     Ok(())
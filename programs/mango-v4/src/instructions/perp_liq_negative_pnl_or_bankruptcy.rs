@@ -34,13 +34,21 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
         perp_market_index = perp_market.perp_market_index;
         settle_token_index = perp_market.settle_token_index;
         let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
-        perp_oracle_price = perp_market
-            .oracle_price(&OracleAccountInfos::from_reader(oracle_ref), Some(now_slot))?;
+        let median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+        perp_oracle_price = perp_market.oracle_price(
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+            Some(now_slot),
+        )?;
 
         let settle_bank = ctx.accounts.settle_bank.load()?;
         let settle_oracle_ref = &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?;
+        let settle_median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &settle_bank.median_oracles)?;
         settle_token_oracle_price = settle_bank.oracle_price(
-            &OracleAccountInfos::from_reader(settle_oracle_ref),
+            &OracleAccountInfos::from_reader(settle_oracle_ref)
+                .with_median_oracles(&settle_bank.median_oracles, &settle_median_oracle_refs),
             Some(now_slot),
         )?;
         drop(settle_bank); // could be the same as insurance_bank
@@ -49,8 +57,13 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
         let insurance_oracle_ref = &AccountInfoRef::borrow(ctx.accounts.insurance_oracle.as_ref())?;
         // We're not getting the insurance token price from the HealthCache because
         // the liqee isn't guaranteed to have an insurance fund token position.
+        let insurance_median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &insurance_bank.median_oracles)?;
         insurance_token_oracle_price = insurance_bank.oracle_price(
-            &OracleAccountInfos::from_reader(insurance_oracle_ref),
+            &OracleAccountInfos::from_reader(insurance_oracle_ref).with_median_oracles(
+                &insurance_bank.median_oracles,
+                &insurance_median_oracle_refs,
+            ),
             Some(now_slot),
         )?;
     }
@@ -199,14 +212,11 @@ pub fn perp_liq_negative_pnl_or_bankruptcy(
     if !liqor.fixed.is_in_health_region() {
         let account_retriever =
             ScanningAccountRetriever::new(ctx.remaining_accounts, &mango_group)?;
-        let liqor_health = compute_health(
-            &liqor.borrow(),
-            HealthType::Init,
-            &account_retriever,
-            now_ts,
-        )
-        .context("compute liqor health")?;
+        let liqor_health_cache = new_health_cache(&liqor.borrow(), &account_retriever, now_ts)
+            .context("compute liqor health")?;
+        let liqor_health = liqor_health_cache.health(HealthType::Init);
         require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+        liqor_health_cache.require_isolated_pools_respected()?;
     }
 
     Ok(())
@@ -398,7 +408,8 @@ pub(crate) fn liquidation_action(
         let (starting_long_funding, starting_short_funding) =
             (perp_market.long_funding, perp_market.short_funding);
         if insurance_fund_exhausted && remaining_liab > 0 {
-            perp_market.socialize_loss(-remaining_liab)?;
+            let bankrupt_side = liqee_perp_position.last_position_side();
+            perp_market.socialize_loss(-remaining_liab, bankrupt_side)?;
             liqee_perp_position.record_settle(-remaining_liab, &perp_market);
             socialized_loss = remaining_liab;
             msg!("socialized loss: {}", socialized_loss);
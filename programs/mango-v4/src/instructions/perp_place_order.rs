@@ -16,7 +16,9 @@ pub fn perp_place_order(
     require_gte!(order.max_base_lots, 0);
     require_gte!(order.max_quote_lots, 0);
 
-    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    let clock = Clock::get()?;
+    let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
+    let now_slot = clock.slot;
     let oracle_price;
 
     // Update funding if possible.
@@ -31,13 +33,16 @@ pub fn perp_place_order(
         };
 
         let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+        let median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
         let oracle_state = perp_market.oracle_state(
-            &OracleAccountInfos::from_reader(oracle_ref),
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
             None, // staleness checked in health
         )?;
         oracle_price = oracle_state.price;
 
-        perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts)?;
+        perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts, now_slot)?;
     }
 
     let mut account = ctx.accounts.account.load_full_mut()?;
@@ -113,6 +118,7 @@ pub fn perp_place_order(
         &mut account.borrow_mut(),
         &account_pk,
         now_ts,
+        now_slot,
         limit,
     )?;
 
@@ -128,7 +134,11 @@ pub fn perp_place_order(
     Ok(order_id_opt)
 }
 
-fn reduce_only_max_base_lots(pp: &PerpPosition, order: &Order, market_reduce_only: bool) -> i64 {
+pub(crate) fn reduce_only_max_base_lots(
+    pp: &PerpPosition,
+    order: &Order,
+    market_reduce_only: bool,
+) -> i64 {
     let effective_pos = pp.effective_base_position_lots();
     msg!(
         "reduce only: current effective position: {} lots",
@@ -211,6 +221,7 @@ mod tests {
                 client_order_id: 0,
                 reduce_only: true,
                 time_in_force: 0,
+                expiry_slot: 0,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::Market {},
             };
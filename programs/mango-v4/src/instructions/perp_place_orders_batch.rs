@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+use super::perp_place_order::reduce_only_max_base_lots;
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::state::*;
+
+/// Places several perp orders on one market, optionally canceling all existing
+/// orders on that market first.
+///
+/// Funding is updated once and there is a single health check at the end.
+pub fn perp_place_orders_batch(
+    ctx: Context<PerpPlaceOrdersBatch>,
+    cancel_all: bool,
+    orders: Vec<PerpBatchOrderParams>,
+    limit: u8,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
+    let now_slot = clock.slot;
+    let oracle_price;
+
+    // Update funding if possible, like perp_place_order does.
+    {
+        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+
+        let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+        let median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+        let oracle_state = perp_market.oracle_state(
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+            None, // staleness checked in health
+        )?;
+        oracle_price = oracle_state.price;
+
+        perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts, now_slot)?;
+    }
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    // account constraint #1
+    require!(
+        account.fixed.is_owner_or_delegate(ctx.accounts.owner.key()),
+        MangoError::SomeError
+    );
+
+    let account_pk = ctx.accounts.account.key();
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+
+    //
+    // Create the perp position if needed
+    //
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    //
+    // Pre-health computation, _after_ perp position is created
+    //
+    let pre_health_opt = if !account.fixed.is_in_health_region() {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("pre-place-batch init health")?;
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        Some((health_cache, pre_init_health))
+    } else {
+        None
+    };
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+
+    account
+        .fixed
+        .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+
+    if cancel_all {
+        book.cancel_all_orders(
+            &mut account.borrow_mut(),
+            &account_pk,
+            &mut perp_market,
+            limit,
+            None,
+        )?;
+    }
+
+    for (i, order_params) in orders.iter().enumerate() {
+        require_gte!(order_params.max_base_lots, 0);
+        require_gte!(order_params.max_quote_lots, 0);
+
+        let mut order = match order_params.to_order()? {
+            Some(order) => order,
+            None => {
+                msg!("Order {} is already expired", i);
+                continue;
+            }
+        };
+
+        let pp = account.perp_position(perp_market_index)?;
+        let max_base_lots = if order.reduce_only || perp_market.is_reduce_only() {
+            reduce_only_max_base_lots(pp, &order, perp_market.is_reduce_only())
+        } else {
+            order.max_base_lots
+        };
+        if perp_market.is_reduce_only() {
+            require!(
+                order.reduce_only || max_base_lots == order.max_base_lots,
+                MangoError::MarketInReduceOnlyMode
+            )
+        };
+        order.max_base_lots = max_base_lots;
+
+        book.new_order(
+            order,
+            &mut perp_market,
+            &mut event_queue,
+            oracle_price,
+            &mut account.borrow_mut(),
+            &account_pk,
+            now_ts,
+            now_slot,
+            limit,
+        )?;
+    }
+
+    //
+    // Health check
+    //
+    if let Some((mut health_cache, pre_init_health)) = pre_health_opt {
+        let perp_position = account.perp_position(perp_market_index)?;
+        health_cache.recompute_perp_info(perp_position, &perp_market)?;
+        account.check_health_post(&health_cache, pre_init_health)?;
+    }
+
+    Ok(())
+}
@@ -30,13 +30,19 @@ pub fn perp_settle_fees(ctx: Context<PerpSettleFees>, max_settle_amount: u64) ->
 
     // Get oracle prices
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+    let median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
     let oracle_price = perp_market.oracle_price(
-        &OracleAccountInfos::from_reader(oracle_ref),
+        &OracleAccountInfos::from_reader(oracle_ref)
+            .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
         None, // staleness checked in health
     )?;
     let settle_oracle_ref = &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?;
+    let settle_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &settle_bank.median_oracles)?;
     let settle_token_oracle_price = settle_bank.oracle_price(
-        &OracleAccountInfos::from_reader(settle_oracle_ref),
+        &OracleAccountInfos::from_reader(settle_oracle_ref)
+            .with_median_oracles(&settle_bank.median_oracles, &settle_median_oracle_refs),
         None, // staleness checked in health
     )?;
 
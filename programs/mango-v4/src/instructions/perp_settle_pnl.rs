@@ -45,8 +45,10 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
         let retriever =
             ScanningAccountRetriever::new(ctx.remaining_accounts, &ctx.accounts.group.key())
                 .context("create account retriever")?;
-        b_max_settle = new_health_cache(&account_b.borrow(), &retriever, now_ts)?
-            .perp_max_settle(settle_token_index)?;
+        let b_cache = new_health_cache(&account_b.borrow(), &retriever, now_ts)?;
+        // Settling creates a liability in the settle token on account b
+        b_cache.require_isolated_pools_allow_token(settle_token_index)?;
+        b_max_settle = b_cache.perp_max_settle(settle_token_index)?;
         let a_cache = new_health_cache(&account_a.borrow(), &retriever, now_ts)?;
         a_liq_end_health = a_cache.health(HealthType::LiquidationEnd);
         a_maint_health = a_cache.health(HealthType::Maint);
@@ -63,13 +65,19 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
 
     // Get oracle prices
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+    let median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
     let oracle_price = perp_market.oracle_price(
-        &OracleAccountInfos::from_reader(oracle_ref),
+        &OracleAccountInfos::from_reader(oracle_ref)
+            .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
         None, // staleness checked in health
     )?;
     let settle_oracle_ref = &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?;
+    let settle_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &settle_bank.median_oracles)?;
     let settle_token_oracle_price = settle_bank.oracle_price(
-        &OracleAccountInfos::from_reader(settle_oracle_ref),
+        &OracleAccountInfos::from_reader(settle_oracle_ref)
+            .with_median_oracles(&settle_bank.median_oracles, &settle_median_oracle_refs),
         None, // staleness checked in health
     )?;
 
@@ -189,7 +197,12 @@ pub fn perp_settle_pnl(ctx: Context<PerpSettlePnl>) -> Result<()> {
     // Don't charge loan origination fees on borrows created via settling:
     // Even small loan origination fees could accumulate if a perp position is
     // settled back and forth repeatedly.
-    settle_bank.withdraw_without_fee(b_token_position, settlement, now_ts)?;
+    settle_bank.withdraw_without_fee_with_borrow_limits(
+        b_token_position,
+        settlement,
+        now_ts,
+        settle_token_oracle_price,
+    )?;
 
     emit_stack(TokenBalanceLog {
         mango_group: ctx.accounts.group.key(),
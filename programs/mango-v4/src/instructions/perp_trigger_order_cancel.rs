@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::MangoError;
+use crate::logs::{emit_stack, PerpTriggerOrderCancelLog};
+use crate::state::*;
+
+pub fn perp_trigger_order_cancel(
+    ctx: Context<PerpTriggerOrderCancel>,
+    perp_trigger_order_index: usize,
+    perp_trigger_order_id: u64,
+) -> Result<()> {
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let order = account.perp_trigger_order_mut_by_index(perp_trigger_order_index)?;
+
+    // If the trigger order is already inactive, this just is a noop
+    if !order.is_configured() {
+        return Ok(());
+    }
+
+    require_eq!(
+        order.id,
+        perp_trigger_order_id,
+        MangoError::PerpTriggerOrderIndexIdMismatch
+    );
+    *order = PerpTriggerOrder::default();
+
+    emit_stack(PerpTriggerOrderCancelLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        id: perp_trigger_order_id,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::logs::{emit_stack, PerpTriggerOrderCreateLog};
+use crate::state::*;
+
+pub fn perp_trigger_order_create(
+    ctx: Context<PerpTriggerOrderCreate>,
+    perp_trigger_order: PerpTriggerOrder,
+) -> Result<()> {
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+    if perp_trigger_order.is_expired(now_ts) {
+        msg!("Already expired, ignoring");
+        return Ok(());
+    }
+
+    let perp_market = ctx.accounts.perp_market.load()?;
+    require_eq!(
+        perp_trigger_order.perp_market_index,
+        perp_market.perp_market_index
+    );
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+
+    let id = account.fixed.next_perp_trigger_order_id;
+    account.fixed.next_perp_trigger_order_id =
+        account.fixed.next_perp_trigger_order_id.wrapping_add(1);
+
+    let order = account.free_perp_trigger_order_mut()?;
+    *order = perp_trigger_order;
+    order.id = id;
+    order.is_configured = 1;
+    order.padding = Default::default();
+    order.reserved = [0; 64];
+
+    // execute relies on these being valid enum values
+    Side::try_from(order.side).map_err(|_| error_msg!("invalid side {}", order.side))?;
+    PerpTriggerCondition::try_from(order.trigger_condition)
+        .map_err(|_| error_msg!("invalid trigger condition {}", order.trigger_condition))?;
+    require_gte!(1, order.reduce_only);
+    require_gt!(order.trigger_price, 0.0);
+    require_gt!(order.max_base_lots, 0);
+    require_gte!(order.max_slippage, 0.0);
+    require_gt!(1.0, order.max_slippage);
+
+    emit_stack(PerpTriggerOrderCreateLog {
+        mango_group: ctx.accounts.group.key(),
+        mango_account: ctx.accounts.account.key(),
+        id,
+        perp_market_index: order.perp_market_index,
+        side: order.side,
+        trigger_condition: order.trigger_condition,
+        trigger_price: order.trigger_price,
+        max_base_lots: order.max_base_lots,
+        max_slippage: order.max_slippage,
+        reduce_only: order.reduce_only(),
+        expiry_timestamp: order.expiry_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use fixed::types::I80F48;
+
+use super::perp_place_order::reduce_only_max_base_lots;
+use crate::accounts_ix::*;
+use crate::accounts_zerocopy::*;
+use crate::error::*;
+use crate::health::{new_fixed_order_account_retriever, new_health_cache};
+use crate::i80f48::ClampToInt;
+use crate::logs::{
+    emit_stack, PerpTriggerOrderCancelLog, PerpTriggerOrderExecuteLog, TokenBalanceLog,
+};
+use crate::state::*;
+
+/// Places a triggered perp trigger order as an immediate-or-cancel order on the book.
+///
+/// Anyone may call this once the oracle price satisfies the trigger condition. The
+/// caller receives a small incentive in the settle token, paid by the account.
+pub fn perp_trigger_order_execute(
+    ctx: Context<PerpTriggerOrderExecute>,
+    perp_trigger_order_index: usize,
+    perp_trigger_order_id: u64,
+    limit: u8,
+) -> Result<()> {
+    let group_pk = ctx.accounts.group.key();
+    let account_key = ctx.accounts.account.key();
+    let caller_key = ctx.accounts.caller.key();
+
+    let clock = Clock::get()?;
+    let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
+    let now_slot = clock.slot;
+
+    let trigger_order = ctx
+        .accounts
+        .account
+        .load_full()?
+        .perp_trigger_order_by_index(perp_trigger_order_index)?
+        .clone();
+    require!(
+        trigger_order.is_configured(),
+        MangoError::PerpTriggerOrderNotSet
+    );
+    require_eq!(
+        trigger_order.id,
+        perp_trigger_order_id,
+        MangoError::PerpTriggerOrderIndexIdMismatch
+    );
+
+    let (perp_market_index, settle_token_index) = {
+        let perp_market = ctx.accounts.perp_market.load()?;
+        (
+            perp_market.perp_market_index,
+            perp_market.settle_token_index,
+        )
+    };
+    require_eq!(trigger_order.perp_market_index, perp_market_index);
+
+    // Expired trigger orders are removed without paying an incentive
+    if trigger_order.is_expired(now_ts) {
+        let mut account = ctx.accounts.account.load_full_mut()?;
+        *account.perp_trigger_order_mut_by_index(perp_trigger_order_index)? =
+            PerpTriggerOrder::default();
+        emit_stack(PerpTriggerOrderCancelLog {
+            mango_group: group_pk,
+            mango_account: account_key,
+            id: perp_trigger_order_id,
+        });
+        return Ok(());
+    }
+
+    // Update funding like perp_place_order does, and check the trigger condition
+    // against a fresh oracle price.
+    let oracle_price;
+    {
+        let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+        let book = Orderbook {
+            bids: ctx.accounts.bids.load_mut()?,
+            asks: ctx.accounts.asks.load_mut()?,
+        };
+
+        let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+        let median_oracle_refs =
+            borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+        let oracle_state = perp_market.oracle_state(
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+            Some(now_slot),
+        )?;
+        oracle_price = oracle_state.price;
+
+        perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts, now_slot)?;
+    }
+    let oracle_price_f64 = oracle_price.to_num::<f64>();
+    trigger_order.check_triggerable(oracle_price_f64, now_ts)?;
+
+    let mut account = ctx.accounts.account.load_full_mut()?;
+    let mut caller = ctx.accounts.caller.load_full_mut()?;
+
+    account.ensure_perp_position(perp_market_index, settle_token_index)?;
+
+    //
+    // Pre-health computation, _after_ perp position is created
+    //
+    let (mut health_cache, pre_init_health) = {
+        let retriever =
+            new_fixed_order_account_retriever(ctx.remaining_accounts, &account.borrow())?;
+        let health_cache = new_health_cache(&account.borrow(), &retriever, now_ts)
+            .context("pre-execute init health")?;
+        let pre_init_health = account.check_health_pre(&health_cache)?;
+        (health_cache, pre_init_health)
+    };
+
+    let mut perp_market = ctx.accounts.perp_market.load_mut()?;
+    let mut book = Orderbook {
+        bids: ctx.accounts.bids.load_mut()?,
+        asks: ctx.accounts.asks.load_mut()?,
+    };
+    let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+    let group = ctx.accounts.group.load()?;
+
+    account
+        .fixed
+        .expire_buyback_fees(now_ts, group.buyback_fees_expiry_interval);
+
+    let price_lots = perp_market
+        .native_price_to_lot(I80F48::from_num(
+            trigger_order.limit_price(oracle_price_f64),
+        ))
+        .max(1);
+    let mut order = Order {
+        side: trigger_order.side(),
+        max_base_lots: trigger_order.max_base_lots,
+        max_quote_lots: i64::MAX,
+        client_order_id: trigger_order.id,
+        reduce_only: trigger_order.reduce_only(),
+        time_in_force: 0,
+        expiry_slot: 0,
+        self_trade_behavior: SelfTradeBehavior::DecrementTake,
+        params: OrderParams::ImmediateOrCancel { price_lots },
+    };
+
+    let pp = account.perp_position(perp_market_index)?;
+    let pre_base_lots = pp.effective_base_position_lots();
+    if order.reduce_only || perp_market.is_reduce_only() {
+        order.max_base_lots = reduce_only_max_base_lots(pp, &order, perp_market.is_reduce_only());
+    }
+    if perp_market.is_reduce_only() {
+        require!(
+            order.reduce_only || order.max_base_lots == trigger_order.max_base_lots,
+            MangoError::MarketInReduceOnlyMode
+        )
+    };
+
+    // A reduce-only trigger order for a position that was closed in the meantime
+    // can't do anything anymore: remove it without paying an incentive.
+    if order.max_base_lots == 0 {
+        *account.perp_trigger_order_mut_by_index(perp_trigger_order_index)? =
+            PerpTriggerOrder::default();
+        emit_stack(PerpTriggerOrderCancelLog {
+            mango_group: group_pk,
+            mango_account: account_key,
+            id: perp_trigger_order_id,
+        });
+        return Ok(());
+    }
+
+    book.new_order(
+        order,
+        &mut perp_market,
+        &mut event_queue,
+        oracle_price,
+        &mut account.borrow_mut(),
+        &account_key,
+        now_ts,
+        now_slot,
+        limit,
+    )?;
+
+    let perp_position = account.perp_position(perp_market_index)?;
+    let post_base_lots = perp_position.effective_base_position_lots();
+
+    // Don't use up the trigger order and charge the incentive for nothing: the
+    // execution can be retried once there's liquidity on the book
+    require!(
+        post_base_lots != pre_base_lots,
+        MangoError::PerpTriggerOrderNotFilled
+    );
+    health_cache.recompute_perp_info(perp_position, &perp_market)?;
+
+    //
+    // Transfer the execution incentive
+    //
+    let mut settle_bank = ctx.accounts.settle_bank.load_mut()?;
+    let settle_oracle_ref = &AccountInfoRef::borrow(ctx.accounts.settle_oracle.as_ref())?;
+    let settle_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &settle_bank.median_oracles)?;
+    let settle_oracle_price = settle_bank.oracle_price(
+        &OracleAccountInfos::from_reader(settle_oracle_ref)
+            .with_median_oracles(&settle_bank.median_oracles, &settle_median_oracle_refs),
+        None, // staleness checked in health
+    )?;
+
+    let incentive = (I80F48::from(PERP_TRIGGER_ORDER_EXECUTE_INCENTIVE) / settle_oracle_price)
+        .min(settle_bank.remaining_deposits_until_limit())
+        .max(I80F48::ZERO);
+
+    let (account_settle_token, _) = account.token_position_mut(settle_token_index)?;
+    let (caller_settle_token, _, _) = caller.ensure_token_position(settle_token_index)?;
+
+    let account_settle_pre_balance = account_settle_token.native(&settle_bank);
+    settle_bank.checked_transfer_with_fee(
+        account_settle_token,
+        incentive,
+        caller_settle_token,
+        incentive,
+        now_ts,
+        settle_oracle_price,
+    )?;
+    let account_settle_post_balance = account_settle_token.native(&settle_bank);
+    health_cache.adjust_token_balance(
+        &settle_bank,
+        account_settle_post_balance - account_settle_pre_balance,
+    )?;
+
+    emit_stack(TokenBalanceLog {
+        mango_group: group_pk,
+        mango_account: account_key,
+        token_index: settle_token_index,
+        indexed_position: account_settle_token.indexed_position.to_bits(),
+        deposit_index: settle_bank.deposit_index.to_bits(),
+        borrow_index: settle_bank.borrow_index.to_bits(),
+    });
+    emit_stack(TokenBalanceLog {
+        mango_group: group_pk,
+        mango_account: caller_key,
+        token_index: settle_token_index,
+        indexed_position: caller_settle_token.indexed_position.to_bits(),
+        deposit_index: settle_bank.deposit_index.to_bits(),
+        borrow_index: settle_bank.borrow_index.to_bits(),
+    });
+    emit_stack(PerpTriggerOrderExecuteLog {
+        mango_group: group_pk,
+        mango_account: account_key,
+        caller: caller_key,
+        id: perp_trigger_order_id,
+        perp_market_index,
+        oracle_price: oracle_price_f64,
+        base_position_change: post_base_lots - pre_base_lots,
+        incentive_token_index: settle_token_index,
+        incentive_amount: incentive.clamp_to_u64(),
+    });
+
+    //
+    // The trigger order is used up, even if the order didn't fill completely
+    //
+    *account.perp_trigger_order_mut_by_index(perp_trigger_order_index)? =
+        PerpTriggerOrder::default();
+
+    account.check_health_post(&health_cache, pre_init_health)?;
+
+    Ok(())
+}
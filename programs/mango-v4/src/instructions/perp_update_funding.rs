@@ -15,10 +15,15 @@ pub fn perp_update_funding(ctx: Context<PerpUpdateFunding>) -> Result<()> {
 
     let now_slot = Clock::get()?.slot;
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
-    let oracle_state =
-        perp_market.oracle_state(&OracleAccountInfos::from_reader(oracle_ref), Some(now_slot))?;
+    let median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &perp_market.median_oracles)?;
+    let oracle_state = perp_market.oracle_state(
+        &OracleAccountInfos::from_reader(oracle_ref)
+            .with_median_oracles(&perp_market.median_oracles, &median_oracle_refs),
+        Some(now_slot),
+    )?;
 
-    perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts)?;
+    perp_market.update_funding_and_stable_price(&book, &oracle_state, now_ts, now_slot)?;
 
     Ok(())
 }
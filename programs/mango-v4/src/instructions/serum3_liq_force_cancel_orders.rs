@@ -91,6 +91,7 @@ pub fn serum3_liq_force_cancel_orders(
             &before_oo,
             None,
             None,
+            &[],
         )?;
 
         before_oo
@@ -156,6 +157,7 @@ pub fn serum3_liq_force_cancel_orders(
         Some(&mut health_cache),
         true,
         None,
+        &[],
     )?;
 
     //
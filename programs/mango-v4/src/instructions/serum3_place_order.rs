@@ -303,6 +303,15 @@ pub fn serum3_place_order(
         .0
         .native(&payer_bank);
 
+    let payer_oracle_ref = &AccountInfoRef::borrow(&ctx.accounts.payer_oracle)?;
+    let payer_median_oracle_refs =
+        borrow_median_oracle_ais(ctx.remaining_accounts, &payer_bank.median_oracles)?;
+    let payer_bank_oracle = payer_bank.oracle_price(
+        &OracleAccountInfos::from_reader(payer_oracle_ref)
+            .with_median_oracles(&payer_bank.median_oracles, &payer_median_oracle_refs),
+        None,
+    )?;
+
     // Charge the difference in vault balance to the user's account
     // (must be done before limit checks like deposit limit)
     let vault_difference = {
@@ -313,6 +322,7 @@ pub fn serum3_place_order(
             &mut payer_bank,
             after_vault,
             before_vault,
+            Some(payer_bank_oracle),
         )?
     };
 
@@ -326,9 +336,6 @@ pub fn serum3_place_order(
     }
 
     // Payer bank safety checks like reduce-only, net borrows, vault-to-deposits ratio
-    let payer_oracle_ref = &AccountInfoRef::borrow(&ctx.accounts.payer_oracle)?;
-    let payer_bank_oracle =
-        payer_bank.oracle_price(&OracleAccountInfos::from_reader(payer_oracle_ref), None)?;
     let withdrawn_from_vault = I80F48::from(before_vault - after_vault);
     if withdrawn_from_vault > before_position_native {
         require_msg_typed!(
@@ -483,6 +490,8 @@ impl VaultDifference {
 /// Called in apply_settle_changes() and place_order to adjust token positions after
 /// changing the vault balances
 /// Also logs changes to token balances
+///
+/// Borrows are checked against the bank borrow limits if `borrow_limit_price` is set.
 fn apply_vault_difference(
     account_pk: Pubkey,
     account: &mut MangoAccountRefMut,
@@ -490,6 +499,7 @@ fn apply_vault_difference(
     bank: &mut Bank,
     vault_after: u64,
     vault_before: u64,
+    borrow_limit_price: Option<I80F48>,
 ) -> Result<VaultDifference> {
     let needed_change = I80F48::from(vault_after) - I80F48::from(vault_before);
 
@@ -498,6 +508,13 @@ fn apply_vault_difference(
     let now_ts = Clock::get()?.unix_timestamp.try_into().unwrap();
     if needed_change >= 0 {
         bank.deposit(position, needed_change, now_ts)?;
+    } else if let Some(oracle_price) = borrow_limit_price {
+        bank.withdraw_without_fee_with_borrow_limits(
+            position,
+            -needed_change,
+            now_ts,
+            oracle_price,
+        )?;
     } else {
         bank.withdraw_without_fee(position, -needed_change, now_ts)?;
     }
@@ -564,6 +581,7 @@ pub fn apply_settle_changes(
     health_cache: Option<&mut HealthCache>,
     fees_to_dao: bool,
     quote_oracle: Option<&AccountInfo>,
+    median_oracle_ais: &[AccountInfo],
 ) -> Result<()> {
     let mut received_fees = 0;
     if fees_to_dao {
@@ -579,8 +597,11 @@ pub fn apply_settle_changes(
             let now_ts = clock.unix_timestamp.try_into().unwrap();
 
             let quote_oracle_ref = &AccountInfoRef::borrow(quote_oracle_ai)?;
+            let median_oracle_refs =
+                borrow_median_oracle_ais(median_oracle_ais, &quote_bank.median_oracles)?;
             let quote_oracle_price = quote_bank.oracle_price(
-                &OracleAccountInfos::from_reader(quote_oracle_ref),
+                &OracleAccountInfos::from_reader(quote_oracle_ref)
+                    .with_median_oracles(&quote_bank.median_oracles, &median_oracle_refs),
                 Some(clock.slot),
             )?;
             let quote_asset_price = quote_oracle_price.min(quote_bank.stable_price());
@@ -610,6 +631,7 @@ pub fn apply_settle_changes(
         base_bank,
         after_base_vault,
         before_base_vault,
+        None,
     )?;
     let quote_difference = apply_vault_difference(
         account_pk,
@@ -618,6 +640,7 @@ pub fn apply_settle_changes(
         quote_bank,
         after_quote_vault_adjusted,
         before_quote_vault,
+        None,
     )?;
 
     // Tokens were moved from open orders into banks again: also update the tracking
@@ -22,6 +22,7 @@ pub fn serum3_settle_funds<'info>(
     accounts: &mut Serum3SettleFunds<'info>,
     v2: Option<&mut Serum3SettleFundsV2Extra<'info>>,
     fees_to_dao: bool,
+    median_oracle_ais: &[AccountInfo<'info>],
 ) -> Result<()> {
     let serum_market = accounts.serum_market.load()?;
 
@@ -86,6 +87,7 @@ pub fn serum3_settle_funds<'info>(
             &before_oo,
             v2.as_ref().map(|d| d.base_oracle.as_ref()),
             v2.as_ref().map(|d| d.quote_oracle.as_ref()),
+            median_oracle_ais,
         )?;
     }
 
@@ -131,6 +133,7 @@ pub fn serum3_settle_funds<'info>(
         None,
         fees_to_dao,
         v2.map(|d| d.quote_oracle.as_ref()),
+        median_oracle_ais,
     )?;
 
     emit_stack(Serum3OpenOrdersBalanceLogV2 {
@@ -160,6 +163,7 @@ pub fn charge_loan_origination_fees(
     before_oo: &OpenOrdersSlim,
     base_oracle: Option<&AccountInfo>,
     quote_oracle: Option<&AccountInfo>,
+    median_oracle_ais: &[AccountInfo],
 ) -> Result<()> {
     let serum3_account = account.serum3_orders_mut(market_index).unwrap();
 
@@ -186,8 +190,11 @@ pub fn charge_loan_origination_fees(
         let base_oracle_price = base_oracle
             .map(|ai| {
                 let ai_ref = &AccountInfoRef::borrow(ai)?;
+                let median_oracle_refs =
+                    borrow_median_oracle_ais(median_oracle_ais, &base_bank.median_oracles)?;
                 base_bank.oracle_price(
-                    &OracleAccountInfos::from_reader(ai_ref),
+                    &OracleAccountInfos::from_reader(ai_ref)
+                        .with_median_oracles(&base_bank.median_oracles, &median_oracle_refs),
                     Some(Clock::get()?.slot),
                 )
             })
@@ -226,8 +233,11 @@ pub fn charge_loan_origination_fees(
         let quote_oracle_price = quote_oracle
             .map(|ai| {
                 let ai_ref = &AccountInfoRef::borrow(ai)?;
+                let median_oracle_refs =
+                    borrow_median_oracle_ais(median_oracle_ais, &quote_bank.median_oracles)?;
                 quote_bank.oracle_price(
-                    &OracleAccountInfos::from_reader(ai_ref),
+                    &OracleAccountInfos::from_reader(ai_ref)
+                        .with_median_oracles(&quote_bank.median_oracles, &median_oracle_refs),
                     Some(Clock::get()?.slot),
                 )
             })
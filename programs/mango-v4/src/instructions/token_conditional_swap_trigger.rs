@@ -117,14 +117,11 @@ pub fn token_conditional_swap_trigger(
     );
 
     // Check liqor health, liqee health is checked inside (has to be, since tcs closure depends on it)
-    let liqor_health = compute_health(
-        &liqor.borrow(),
-        HealthType::Init,
-        &account_retriever,
-        now_ts,
-    )
-    .context("compute liqor health")?;
+    let liqor_health_cache = new_health_cache(&liqor.borrow(), &account_retriever, now_ts)
+        .context("compute liqor health")?;
+    let liqor_health = liqor_health_cache.health(HealthType::Init);
     require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+    liqor_health_cache.require_isolated_pools_respected()?;
 
     Ok(())
 }
@@ -731,7 +728,7 @@ mod tests {
             liqee_buffer.extend_from_slice(&[0u8; 512]);
             let mut liqee = MangoAccountValue::from_bytes(&liqee_buffer).unwrap();
             {
-                liqee.resize_dynamic_content(3, 5, 4, 6, 1).unwrap();
+                liqee.resize_dynamic_content(3, 5, 4, 6, 1, 0, 0).unwrap();
                 liqee.ensure_token_position(0).unwrap();
                 liqee.ensure_token_position(1).unwrap();
             }
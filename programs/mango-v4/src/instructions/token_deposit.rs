@@ -196,8 +196,13 @@ pub fn token_deposit(ctx: Context<TokenDeposit>, amount: u64, reduce_only: bool)
             let bank = ctx.accounts.bank.load()?;
 
             let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
-            let oracle_result =
-                bank.oracle_price(&OracleAccountInfos::from_reader(oracle_ref), Some(now_slot));
+            let median_oracle_refs =
+                borrow_median_oracle_ais(ctx.remaining_accounts, &bank.median_oracles)?;
+            let oracle_result = bank.oracle_price(
+                &OracleAccountInfos::from_reader(oracle_ref)
+                    .with_median_oracles(&bank.median_oracles, &median_oracle_refs),
+                Some(now_slot),
+            );
             if let Err(e) = oracle_result {
                 msg!("oracle must be valid when creating a new token position");
                 return Err(e);
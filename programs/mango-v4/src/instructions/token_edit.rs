@@ -55,14 +55,40 @@ pub fn token_edit(
     platform_liquidation_fee: Option<f32>,
     disable_asset_liquidation_opt: Option<bool>,
     collateral_fee_per_day: Option<f32>,
+    median_oracles_opt: Option<Vec<Pubkey>>,
+    median_oracle_quorum_opt: Option<u8>,
+    isolated_opt: Option<bool>,
+    isolated_quote_token_indexes_opt: Option<Vec<TokenIndex>>,
+    borrow_limit_opt: Option<u64>,
+    account_borrow_limit_quote_opt: Option<u64>,
+    interest_curve_type_opt: Option<u8>,
+    pid_kp_opt: Option<f32>,
+    pid_ki_opt: Option<f32>,
+    pid_kd_opt: Option<f32>,
+    fixed_term_rate_premium_opt: Option<f32>,
+    fixed_term_max_duration_opt: Option<u64>,
 ) -> Result<()> {
     let group = ctx.accounts.group.load()?;
 
     let mut mint_info = ctx.accounts.mint_info.load_mut()?;
-    mint_info.verify_banks_ais(ctx.remaining_accounts)?;
+    // The banks may be followed by the median oracle accounts, which are needed for
+    // resetting the stable price in the median oracle mode
+    let (bank_ais, median_oracle_ais) = ctx
+        .remaining_accounts
+        .split_at(mint_info.num_banks().min(ctx.remaining_accounts.len()));
+    mint_info.verify_banks_ais(bank_ais)?;
+
+    // New oracles must be passed, to check them
+    if let Some(oracle) = oracle_opt {
+        require_keys_eq!(oracle, ctx.accounts.oracle.key());
+        check_is_valid_oracle(&AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?)?;
+    }
+    if let Some(median_oracles) = median_oracles_opt.as_ref() {
+        check_median_oracle_ais(median_oracle_ais, median_oracles)?;
+    }
 
     let mut require_group_admin = false;
-    for ai in ctx.remaining_accounts.iter() {
+    for ai in bank_ais.iter() {
         let mut bank = ai.load_mut::<Bank>()?;
 
         if let Some(oracle_config) = oracle_config_opt.as_ref() {
@@ -99,8 +125,13 @@ pub fn token_edit(
             msg!("Stable price reset");
             require_keys_eq!(bank.oracle, ctx.accounts.oracle.key());
             let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
-            let oracle_price =
-                bank.oracle_price(&OracleAccountInfos::from_reader(oracle_ref), None)?;
+            let median_oracle_refs =
+                borrow_median_oracle_ais(median_oracle_ais, &bank.median_oracles)?;
+            let oracle_price = bank.oracle_price(
+                &OracleAccountInfos::from_reader(oracle_ref)
+                    .with_median_oracles(&bank.median_oracles, &median_oracle_refs),
+                None,
+            )?;
             bank.stable_price_model.reset_to_price(
                 oracle_price.to_num(),
                 Clock::get()?.unix_timestamp.try_into().unwrap(),
@@ -510,6 +541,132 @@ pub fn token_edit(
             bank.disable_asset_liquidation = u8::from(disable_asset_liquidation);
             require_group_admin = true;
         }
+
+        if let Some(median_oracles) = median_oracles_opt.as_ref() {
+            msg!(
+                "Median oracles old {:?}, new {:?}",
+                bank.median_oracles,
+                median_oracles
+            );
+            bank.median_oracles = median_oracles_from_slice(median_oracles)?;
+            require_group_admin = true;
+        }
+
+        if let Some(median_oracle_quorum) = median_oracle_quorum_opt {
+            msg!(
+                "Median oracle quorum old {:?}, new {:?}",
+                bank.median_oracle_quorum,
+                median_oracle_quorum
+            );
+            bank.median_oracle_quorum = median_oracle_quorum;
+            require_group_admin = true;
+        }
+
+        if let Some(isolated) = isolated_opt {
+            msg!("Isolated old {:?}, new {:?}", bank.isolated, isolated);
+            if isolated && !bank.is_isolated() {
+                // Start out without quote tokens, instead of whatever was in the field before
+                bank.isolated_quote_token_indexes = [TokenIndex::MAX; MAX_ISOLATED_QUOTE_TOKENS];
+            }
+            bank.isolated = u8::from(isolated);
+            require_group_admin = true;
+        }
+
+        if let Some(isolated_quote_token_indexes) = isolated_quote_token_indexes_opt.as_ref() {
+            msg!(
+                "Isolated quote token indexes old {:?}, new {:?}",
+                bank.isolated_quote_token_indexes,
+                isolated_quote_token_indexes
+            );
+            bank.isolated_quote_token_indexes =
+                isolated_quote_token_indexes_from_slice(isolated_quote_token_indexes)?;
+            require_group_admin = true;
+        }
+
+        if let Some(borrow_limit) = borrow_limit_opt {
+            msg!(
+                "Borrow limit old {:?}, new {:?}",
+                bank.borrow_limit,
+                borrow_limit
+            );
+            bank.borrow_limit = borrow_limit;
+            require_group_admin = true;
+        }
+
+        if let Some(account_borrow_limit_quote) = account_borrow_limit_quote_opt {
+            msg!(
+                "Account borrow limit quote old {:?}, new {:?}",
+                bank.account_borrow_limit_quote,
+                account_borrow_limit_quote
+            );
+            bank.account_borrow_limit_quote = account_borrow_limit_quote;
+            require_group_admin = true;
+        }
+
+        let mut pid_gains_changed = false;
+        if let Some(pid_kp) = pid_kp_opt {
+            msg!("Pid kp old {:?}, new {:?}", bank.pid_kp, pid_kp);
+            bank.pid_kp = pid_kp;
+            pid_gains_changed = true;
+            require_group_admin = true;
+        }
+
+        if let Some(pid_ki) = pid_ki_opt {
+            msg!("Pid ki old {:?}, new {:?}", bank.pid_ki, pid_ki);
+            bank.pid_ki = pid_ki;
+            pid_gains_changed = true;
+            require_group_admin = true;
+        }
+
+        if let Some(pid_kd) = pid_kd_opt {
+            msg!("Pid kd old {:?}, new {:?}", bank.pid_kd, pid_kd);
+            bank.pid_kd = pid_kd;
+            pid_gains_changed = true;
+            require_group_admin = true;
+        }
+
+        if let Some(interest_curve_type) = interest_curve_type_opt {
+            msg!(
+                "Interest curve type old {:?}, new {:?}",
+                bank.interest_curve_type,
+                interest_curve_type
+            );
+            let new_type = InterestCurveType::try_from(interest_curve_type)
+                .map_err(|_| error!(MangoError::SomeError))?;
+            // Continue from the current rate instead of jumping to the controller's initial output
+            if new_type == InterestCurveType::PidController
+                && bank.interest_curve_type() != InterestCurveType::PidController
+            {
+                bank.reset_pid_state();
+                pid_gains_changed = false;
+            }
+            bank.interest_curve_type = interest_curve_type;
+            require_group_admin = true;
+        }
+
+        if pid_gains_changed && bank.interest_curve_type() == InterestCurveType::PidController {
+            bank.reset_pid_state();
+        }
+
+        if let Some(fixed_term_rate_premium) = fixed_term_rate_premium_opt {
+            msg!(
+                "Fixed term rate premium old {:?}, new {:?}",
+                bank.fixed_term_rate_premium,
+                fixed_term_rate_premium
+            );
+            bank.fixed_term_rate_premium = fixed_term_rate_premium;
+            require_group_admin = true;
+        }
+
+        if let Some(fixed_term_max_duration) = fixed_term_max_duration_opt {
+            msg!(
+                "Fixed term max duration old {:?}, new {:?}",
+                bank.fixed_term_max_duration,
+                fixed_term_max_duration
+            );
+            bank.fixed_term_max_duration = fixed_term_max_duration;
+            require_group_admin = true;
+        }
     }
 
     // account constraint #1
@@ -527,7 +684,7 @@ pub fn token_edit(
     }
 
     // Assumes that there is at least one bank
-    let bank = ctx.remaining_accounts.first().unwrap().load_mut::<Bank>()?;
+    let bank = bank_ais.first().unwrap().load_mut::<Bank>()?;
     bank.verify()?;
 
     emit_stack(TokenMetaDataLogV2 {
@@ -114,8 +114,11 @@ pub fn token_force_close_borrows_with_token(
             liab_bank.deposit_with_dusting(liqee_liab_position, liab_transfer, now_ts)?;
         let liqee_liab_indexed_position = liqee_liab_position.indexed_position;
 
-        let liqor_liab_withdraw_result =
-            liab_bank.withdraw_with_fee(liqor_liab_position, liab_transfer, now_ts)?;
+        let liqor_liab_withdraw_result = liab_bank.withdraw_with_fee_without_borrow_limits(
+            liqor_liab_position,
+            liab_transfer,
+            now_ts,
+        )?;
         let liqor_liab_indexed_position = liqor_liab_position.indexed_position;
         let liqee_liab_native_after = liqee_liab_position.native(liab_bank);
 
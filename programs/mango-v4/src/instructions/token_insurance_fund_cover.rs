@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use fixed::types::I80F48;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::health::*;
+use crate::logs::{emit_stack, TokenBalanceLog, TokenInsuranceFundCoverLog};
+use crate::state::*;
+
+/// Uses a token's dedicated insurance fund to pay off a bankrupt account's negative
+/// balance in that token.
+///
+/// The negative balance includes negative perp pnl of markets settling in the token, so
+/// this can also cover perp losses before they get socialized.
+pub fn token_insurance_fund_cover(
+    ctx: Context<TokenInsuranceFundCover>,
+    max_cover_amount: u64,
+) -> Result<()> {
+    let group_pk = ctx.accounts.group.key();
+    let liqee_pk = ctx.accounts.liqee.key();
+    let now_ts: u64 = Clock::get()?.unix_timestamp.try_into().unwrap();
+
+    let token_index = ctx.accounts.insurance_fund.load()?.token_index;
+
+    let mut liqee = ctx.accounts.liqee.load_full_mut()?;
+    let mut liqee_health_cache = {
+        let retriever = ScanningAccountRetriever::new(ctx.remaining_accounts, &group_pk)
+            .context("create account retriever")?;
+        new_health_cache(&liqee.borrow(), &retriever, now_ts)
+            .context("create liqee health cache")?
+    };
+    liqee_health_cache.require_after_phase2_liquidation()?;
+    liqee.fixed.set_being_liquidated(true);
+
+    // Only negative token balances, including negative perp pnl in the token, are covered
+    let liqee_health_token_balances =
+        liqee_health_cache.effective_token_balances(HealthType::LiquidationEnd);
+    let liqee_health_balance = liqee_health_token_balances
+        [liqee_health_cache.token_info_index(token_index)?]
+    .spot_and_perp;
+    require_gt!(I80F48::ZERO, liqee_health_balance);
+
+    let cover_amount = (-liqee_health_balance)
+        .ceil()
+        .to_num::<u64>()
+        .min(max_cover_amount)
+        .min(ctx.accounts.insurance_vault.amount);
+    if cover_amount == 0 {
+        msg!("nothing to cover");
+        return Ok(());
+    }
+    let cover_amount_i80f48 = I80F48::from(cover_amount);
+
+    let mut bank = ctx.accounts.bank.load_mut()?;
+    let (liqee_token, liqee_raw_token_index, _) = liqee.ensure_token_position(token_index)?;
+    let initial_native = liqee_token.native(&bank);
+    // enable dusting, because the ceil() above can bring the position slightly above zero
+    let liqee_token_active = bank.deposit_with_dusting(liqee_token, cover_amount_i80f48, now_ts)?;
+    let end_native = liqee_token.native(&bank);
+
+    emit_stack(TokenBalanceLog {
+        mango_group: group_pk,
+        mango_account: liqee_pk,
+        token_index,
+        indexed_position: liqee_token.indexed_position.to_bits(),
+        deposit_index: bank.deposit_index.to_bits(),
+        borrow_index: bank.borrow_index.to_bits(),
+    });
+
+    liqee_health_cache.adjust_token_balance(&bank, end_native - initial_native)?;
+    drop(bank);
+
+    // move the covered amount from the insurance fund into the bank vault
+    {
+        let group = ctx.accounts.group.load()?;
+        let group_seeds = group_seeds!(group);
+        token::transfer(
+            ctx.accounts.transfer_ctx().with_signer(&[group_seeds]),
+            cover_amount,
+        )?;
+    }
+
+    let mut insurance_fund = ctx.accounts.insurance_fund.load_mut()?;
+    insurance_fund.total_covered += cover_amount;
+
+    let liqee_liq_end_health = liqee_health_cache.health(HealthType::LiquidationEnd);
+    liqee
+        .fixed
+        .maybe_recover_from_being_liquidated(liqee_liq_end_health);
+
+    if !liqee_token_active {
+        liqee.deactivate_token_position_and_log(liqee_raw_token_index, liqee_pk);
+    }
+
+    emit_stack(TokenInsuranceFundCoverLog {
+        mango_group: group_pk,
+        liqee: liqee_pk,
+        token_index,
+        initial_balance: liqee_health_balance.to_bits(),
+        cover_amount,
+        total_covered: insurance_fund.total_covered,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn token_insurance_fund_create(
+    ctx: Context<TokenInsuranceFundCreate>,
+    token_index: TokenIndex,
+) -> Result<()> {
+    let mut insurance_fund = ctx.accounts.insurance_fund.load_init()?;
+    *insurance_fund = TokenInsuranceFund {
+        group: ctx.accounts.group.key(),
+        token_index,
+        bump: *ctx
+            .bumps
+            .get("insurance_fund")
+            .ok_or(MangoError::SomeError)?,
+        padding: Default::default(),
+        mint: ctx.accounts.mint.key(),
+        insurance_vault: ctx.accounts.insurance_vault.key(),
+        total_covered: 0,
+        total_withdrawn: 0,
+        reserved: [0; 256],
+    };
+
+    Ok(())
+}
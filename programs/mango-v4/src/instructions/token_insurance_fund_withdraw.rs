@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+use crate::{accounts_ix::TokenInsuranceFundWithdraw, group_seeds};
+
+pub fn token_insurance_fund_withdraw(
+    ctx: Context<TokenInsuranceFundWithdraw>,
+    amount: u64,
+) -> Result<()> {
+    let group = ctx.accounts.group.load()?;
+    let amount = amount.min(ctx.accounts.insurance_vault.amount);
+
+    let group_seeds = group_seeds!(group);
+    token::transfer(
+        ctx.accounts.transfer_ctx().with_signer(&[group_seeds]),
+        amount,
+    )?;
+
+    let mut insurance_fund = ctx.accounts.insurance_fund.load_mut()?;
+    insurance_fund.total_withdrawn += amount;
+
+    Ok(())
+}
@@ -150,8 +150,11 @@ pub fn token_liq_bankruptcy(
             // transfer liab from liqee to liqor
             let (liqor_liab, liqor_liab_raw_token_index, _) =
                 liqor.ensure_token_position(liab_token_index)?;
-            let liqor_liab_withdraw_result =
-                liab_bank.withdraw_with_fee(liqor_liab, liab_transfer, now_ts)?;
+            let liqor_liab_withdraw_result = liab_bank.withdraw_with_fee_without_borrow_limits(
+                liqor_liab,
+                liab_transfer,
+                now_ts,
+            )?;
 
             // liqor liab
             emit_stack(TokenBalanceLog {
@@ -165,13 +168,12 @@ pub fn token_liq_bankruptcy(
 
             // Check liqor's health
             if !liqor.fixed.is_in_health_region() {
-                let liqor_health = compute_health(
-                    &liqor.borrow(),
-                    HealthType::Init,
-                    &account_retriever,
-                    now_ts,
-                )?;
+                let liqor_health_cache =
+                    new_health_cache(&liqor.borrow(), &account_retriever, now_ts)
+                        .context("compute liqor health")?;
+                let liqor_health = liqor_health_cache.health(HealthType::Init);
                 require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+                liqor_health_cache.require_isolated_pools_respected()?;
             }
 
             if liqor_liab_withdraw_result
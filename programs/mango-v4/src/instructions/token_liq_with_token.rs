@@ -70,14 +70,11 @@ pub fn token_liq_with_token(
 
     // Check liqor's health
     if !liqor.fixed.is_in_health_region() {
-        let liqor_health = compute_health(
-            &liqor.borrow(),
-            HealthType::Init,
-            &account_retriever,
-            now_ts,
-        )
-        .context("compute liqor health")?;
+        let liqor_health_cache = new_health_cache(&liqor.borrow(), &account_retriever, now_ts)
+            .context("compute liqor health")?;
+        let liqor_health = liqor_health_cache.health(HealthType::Init);
         require!(liqor_health >= 0, MangoError::HealthMustBePositive);
+        liqor_health_cache.require_isolated_pools_respected()?;
     }
 
     Ok(())
@@ -245,8 +242,11 @@ pub(crate) fn liquidation_action(
 
     let (liqor_liab_position, liqor_liab_raw_index, _) =
         liqor.ensure_token_position(liab_token_index)?;
-    let liqor_liab_withdraw_result =
-        liab_bank.withdraw_with_fee(liqor_liab_position, liab_transfer, now_ts)?;
+    let liqor_liab_withdraw_result = liab_bank.withdraw_with_fee_without_borrow_limits(
+        liqor_liab_position,
+        liab_transfer,
+        now_ts,
+    )?;
     let liqor_liab_indexed_position = liqor_liab_position.indexed_position;
     let liqee_liab_native_after = liqee_liab_position.native(liab_bank);
 
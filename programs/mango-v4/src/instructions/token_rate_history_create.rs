@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts_ix::*;
+use crate::error::*;
+use crate::state::*;
+
+pub fn token_rate_history_create(
+    ctx: Context<TokenRateHistoryCreate>,
+    token_index: TokenIndex,
+) -> Result<()> {
+    // The history is too large to be built on the stack. The zeroed account
+    // data already is an empty history, so only set the header.
+    let mut rate_history = ctx.accounts.rate_history.load_init()?;
+    rate_history.group = ctx.accounts.group.key();
+    rate_history.token_index = token_index;
+    rate_history.bump = *ctx.bumps.get("rate_history").ok_or(MangoError::SomeError)?;
+
+    Ok(())
+}
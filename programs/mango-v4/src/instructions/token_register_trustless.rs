@@ -110,7 +110,27 @@ pub fn token_register_trustless(
         collected_liquidation_fees: I80F48::ZERO,
         collected_collateral_fees: I80F48::ZERO,
         collateral_fee_per_day: 0.0, // TODO
-        reserved: [0; 1900],
+        median_oracles: [Pubkey::default(); MAX_MEDIAN_ORACLES],
+        median_oracle_quorum: 0,
+        isolated: 0,
+        isolated_quote_token_indexes: [TokenIndex::MAX; MAX_ISOLATED_QUOTE_TOKENS],
+        padding2: Default::default(),
+        borrow_limit: 0,
+        account_borrow_limit_quote: 0,
+        interest_curve_type: InterestCurveType::PiecewiseLinear.into(),
+        padding3: Default::default(),
+        pid_integral: 0.0,
+        pid_rate: 0.0,
+        pid_kp: 0.0,
+        pid_ki: 0.0,
+        pid_kd: 0.0,
+        pid_last_error: 0.0,
+        fixed_term_borrows: I80F48::ZERO,
+        fixed_term_rate_premium: 0.0,
+        padding4: Default::default(),
+        fixed_term_max_duration: 0,
+        fixed_term_yearly_interest: I80F48::ZERO,
+        reserved: [0; 1688],
     };
     let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
     if let Ok(oracle_price) = bank.oracle_price(&OracleAccountInfos::from_reader(oracle_ref), None)
@@ -122,6 +142,7 @@ pub fn token_register_trustless(
     }
 
     bank.verify()?;
+    check_is_valid_oracle(&AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?)?;
     check_is_valid_fallback_oracle(&AccountInfoRef::borrow(
         ctx.accounts.fallback_oracle.as_ref(),
     )?)?;
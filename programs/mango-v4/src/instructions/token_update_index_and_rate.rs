@@ -3,7 +3,9 @@ use anchor_lang::prelude::*;
 use crate::accounts_ix::*;
 use crate::error::MangoError;
 use crate::logs::{emit_stack, UpdateIndexLog, UpdateRateLogV2};
-use crate::state::{OracleAccountInfos, HOUR};
+use crate::state::{
+    borrow_median_oracle_ais, OracleAccountInfos, RateHistoryEntry, TokenRateHistory, HOUR,
+};
 use crate::{
     accounts_zerocopy::{AccountInfoRef, LoadMutZeroCopyRef, LoadZeroCopyRef},
     state::Bank,
@@ -49,10 +51,12 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
 
     let mint_info = ctx.accounts.mint_info.load()?;
 
-    ctx.accounts
-        .mint_info
-        .load()?
-        .verify_banks_ais(ctx.remaining_accounts)?;
+    // The banks are followed by the median oracle accounts of the median oracle mode
+    // and optionally the token's TokenRateHistory
+    let (bank_ais, extra_ais) = ctx
+        .remaining_accounts
+        .split_at(mint_info.num_banks().min(ctx.remaining_accounts.len()));
+    mint_info.verify_banks_ais(bank_ais)?;
 
     let clock = Clock::get()?;
     let now_ts: u64 = clock.unix_timestamp.try_into().unwrap();
@@ -60,16 +64,20 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
     // compute indexed_total
     let mut indexed_total_deposits = I80F48::ZERO;
     let mut indexed_total_borrows = I80F48::ZERO;
-    for ai in ctx.remaining_accounts.iter() {
+    let mut fixed_term_borrows = I80F48::ZERO;
+    let mut fixed_term_yearly_interest = I80F48::ZERO;
+    for ai in bank_ais.iter() {
         let bank = ai.load::<Bank>()?;
         indexed_total_deposits += bank.indexed_deposits;
         indexed_total_borrows += bank.indexed_borrows;
+        fixed_term_borrows += bank.fixed_term_borrows;
+        fixed_term_yearly_interest += bank.fixed_term_yearly_interest;
     }
 
     // compute and set latest index and average utilization on each bank
     // also update moving average prices
-    {
-        let mut some_bank = ctx.remaining_accounts[0].load_mut::<Bank>()?;
+    let (borrow_rate, deposit_rate) = {
+        let mut some_bank = bank_ais[0].load_mut::<Bank>()?;
 
         // Limit the maximal time interval that interest is applied for. This means we won't use
         // a fixed interest rate for a very long time period in exceptional circumstances, like
@@ -78,20 +86,29 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
         let diff_ts =
             I80F48::from_num((now_ts - some_bank.index_last_updated).min(max_interest_timestep));
 
-        let (deposit_index, borrow_index, borrow_fees, borrow_rate, deposit_rate) =
-            some_bank.compute_index(indexed_total_deposits, indexed_total_borrows, diff_ts)?;
+        let (deposit_index, borrow_index, borrow_fees, borrow_rate, deposit_rate) = some_bank
+            .compute_index(
+                indexed_total_deposits,
+                indexed_total_borrows,
+                fixed_term_borrows,
+                fixed_term_yearly_interest,
+                diff_ts,
+            )?;
 
         some_bank.collected_fees_native += borrow_fees;
 
         let new_avg_utilization = some_bank.compute_new_avg_utilization(
             indexed_total_deposits,
             indexed_total_borrows,
+            fixed_term_borrows,
             now_ts,
         );
 
         let oracle_ref = &AccountInfoRef::borrow(ctx.accounts.oracle.as_ref())?;
+        let median_oracle_refs = borrow_median_oracle_ais(extra_ais, &some_bank.median_oracles)?;
         let price = some_bank.oracle_price(
-            &OracleAccountInfos::from_reader(oracle_ref),
+            &OracleAccountInfos::from_reader(oracle_ref)
+                .with_median_oracles(&some_bank.median_oracles, &median_oracle_refs),
             Some(clock.slot),
         )?;
 
@@ -130,7 +147,7 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
         msg!("borrow_index {}", borrow_index);
         msg!("avg_utilization {}", new_avg_utilization);
 
-        for ai in ctx.remaining_accounts.iter() {
+        for ai in bank_ais.iter() {
             let mut bank = ai.load_mut::<Bank>()?;
 
             bank.index_last_updated = now_ts;
@@ -152,11 +169,13 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
                 bank.maint_weight_shift_end = 0;
             }
         }
-    }
+
+        (borrow_rate, deposit_rate)
+    };
 
     // compute optimal rates, and max rate and set them on the bank
     {
-        let mut some_bank = ctx.remaining_accounts[0].load_mut::<Bank>()?;
+        let mut some_bank = bank_ais[0].load_mut::<Bank>()?;
 
         let diff_ts = I80F48::from_num(now_ts - some_bank.bank_rate_last_updated);
 
@@ -182,6 +201,18 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
             let max_rate = some_bank.max_rate;
             let scaling = some_bank.interest_curve_scaling;
             let target_util = some_bank.interest_target_utilization;
+            let pid_integral = some_bank.pid_integral;
+            let pid_rate = some_bank.pid_rate;
+            let pid_last_error = some_bank.pid_last_error;
+
+            let rate_history_entry = RateHistoryEntry {
+                timestamp: now_ts,
+                deposit_rate: deposit_rate.to_num(),
+                borrow_rate: borrow_rate.to_num(),
+                avg_utilization: some_bank.avg_utilization.to_num(),
+                deposit_index: some_bank.deposit_index,
+                borrow_index: some_bank.borrow_index,
+            };
 
             emit_stack(UpdateRateLogV2 {
                 mango_group: mint_info.group.key(),
@@ -198,7 +229,7 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
             drop(some_bank);
 
             // Apply the new parameters to all banks
-            for ai in ctx.remaining_accounts.iter() {
+            for ai in bank_ais.iter() {
                 let mut bank = ai.load_mut::<Bank>()?;
 
                 bank.bank_rate_last_updated = now_ts;
@@ -207,6 +238,19 @@ pub fn token_update_index_and_rate(ctx: Context<TokenUpdateIndexAndRate>) -> Res
                 bank.rate0 = rate0;
                 bank.rate1 = rate1;
                 bank.max_rate = max_rate;
+                bank.pid_integral = pid_integral;
+                bank.pid_rate = pid_rate;
+                bank.pid_last_error = pid_last_error;
+            }
+
+            if let Some(rate_history_ai) = extra_ais
+                .iter()
+                .find(|ai| ai.load::<TokenRateHistory>().is_ok())
+            {
+                let mut rate_history = rate_history_ai.load_mut::<TokenRateHistory>()?;
+                require_keys_eq!(rate_history.group, mint_info.group);
+                require_eq!(rate_history.token_index, mint_info.token_index);
+                rate_history.history.push(rate_history_entry);
             }
         }
     }
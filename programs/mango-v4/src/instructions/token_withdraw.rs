@@ -84,6 +84,7 @@ pub fn token_withdraw(ctx: Context<TokenWithdraw>, amount: u64, allow_borrow: bo
         position,
         amount_i80f48,
         Clock::get()?.unix_timestamp.try_into().unwrap(),
+        unsafe_oracle_state.price,
     )?;
     let native_position_after = position.native(&bank);
 
@@ -176,6 +177,7 @@ pub fn token_withdraw(ctx: Context<TokenWithdraw>, amount: u64, allow_borrow: bo
             let post_init_health = health_cache.health(HealthType::Init);
             account.check_health_pre_checks(&health_cache, post_init_health)?;
             account.check_health_post_checks(I80F48::MAX, post_init_health)?;
+            health_cache.require_isolated_pools_respected()?;
         }
     }
 
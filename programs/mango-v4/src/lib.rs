@@ -21,6 +21,7 @@ pub mod events;
 pub mod health;
 pub mod i80f48;
 pub mod logs;
+pub mod openbook_v2_cpi;
 pub mod serum3_cpi;
 pub mod state;
 pub mod types;
@@ -33,10 +34,10 @@ pub mod instructions;
 compile_error!("compiling the program entrypoint without 'enable-gpl' makes no sense, enable it or use the 'cpi' or 'client' features");
 
 use state::{
-    IxGate, OpenbookV2MarketIndex, OracleConfigParams, PerpMarketIndex, PlaceOrderType,
-    SelfTradeBehavior, Serum3MarketIndex, Side, TokenConditionalSwap,
-    TokenConditionalSwapDisplayPriceStyle, TokenConditionalSwapIntention, TokenConditionalSwapType,
-    TokenIndex, TCS_START_INCENTIVE,
+    IxGate, OpenbookV2MarketIndex, OracleConfigParams, PerpMarketIndex, PerpTriggerCondition,
+    PerpTriggerOrder, PlaceOrderType, SelfTradeBehavior, Serum3MarketIndex, Side,
+    TokenConditionalSwap, TokenConditionalSwapDisplayPriceStyle, TokenConditionalSwapIntention,
+    TokenConditionalSwapType, TokenIndex, TCS_START_INCENTIVE,
 };
 
 declare_id!("5JfWyyooqZbKpA9ZbZSrbPke4TKyxV2mo5wcLEptQ5NG");
@@ -115,6 +116,42 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    pub fn token_insurance_fund_create(
+        ctx: Context<TokenInsuranceFundCreate>,
+        token_index: TokenIndex,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_insurance_fund_create(ctx, token_index)?;
+        Ok(())
+    }
+
+    pub fn token_insurance_fund_withdraw(
+        ctx: Context<TokenInsuranceFundWithdraw>,
+        amount: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_insurance_fund_withdraw(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn token_insurance_fund_cover(
+        ctx: Context<TokenInsuranceFundCover>,
+        max_cover_amount: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_insurance_fund_cover(ctx, max_cover_amount)?;
+        Ok(())
+    }
+
+    pub fn token_rate_history_create(
+        ctx: Context<TokenRateHistoryCreate>,
+        token_index: TokenIndex,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::token_rate_history_create(ctx, token_index)?;
+        Ok(())
+    }
+
     pub fn ix_gate_set(ctx: Context<IxGateSet>, ix_gate: u128) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::ix_gate_set(ctx, ix_gate)?;
@@ -253,6 +290,18 @@ pub mod mango_v4 {
         platform_liquidation_fee_opt: Option<f32>,
         disable_asset_liquidation_opt: Option<bool>,
         collateral_fee_per_day_opt: Option<f32>,
+        median_oracles_opt: Option<Vec<Pubkey>>,
+        median_oracle_quorum_opt: Option<u8>,
+        isolated_opt: Option<bool>,
+        isolated_quote_token_indexes_opt: Option<Vec<TokenIndex>>,
+        borrow_limit_opt: Option<u64>,
+        account_borrow_limit_quote_opt: Option<u64>,
+        interest_curve_type_opt: Option<u8>,
+        pid_kp_opt: Option<f32>,
+        pid_ki_opt: Option<f32>,
+        pid_kd_opt: Option<f32>,
+        fixed_term_rate_premium_opt: Option<f32>,
+        fixed_term_max_duration_opt: Option<u64>,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::token_edit(
@@ -297,6 +346,18 @@ pub mod mango_v4 {
             platform_liquidation_fee_opt,
             disable_asset_liquidation_opt,
             collateral_fee_per_day_opt,
+            median_oracles_opt,
+            median_oracle_quorum_opt,
+            isolated_opt,
+            isolated_quote_token_indexes_opt,
+            borrow_limit_opt,
+            account_borrow_limit_quote_opt,
+            interest_curve_type_opt,
+            pid_kp_opt,
+            pid_ki_opt,
+            pid_kd_opt,
+            fixed_term_rate_premium_opt,
+            fixed_term_max_duration_opt,
         )?;
         Ok(())
     }
@@ -347,6 +408,9 @@ pub mod mango_v4 {
             perp_count,
             perp_oo_count,
             0,
+            0,
+            0,
+            0,
             name,
         )?;
         Ok(())
@@ -374,6 +438,40 @@ pub mod mango_v4 {
             perp_count,
             perp_oo_count,
             token_conditional_swap_count,
+            0,
+            0,
+            0,
+            name,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_create_v3(
+        ctx: Context<AccountCreateV3>,
+        account_num: u32,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        name: String,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_create(
+            &ctx.accounts.account,
+            *ctx.bumps.get("account").ok_or(MangoError::SomeError)?,
+            ctx.accounts.group.key(),
+            ctx.accounts.owner.key(),
+            account_num,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            0,
+            0,
             name,
         )?;
         Ok(())
@@ -387,7 +485,17 @@ pub mod mango_v4 {
         perp_oo_count: u8,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
-        instructions::account_expand(ctx, token_count, serum3_count, perp_count, perp_oo_count, 0)?;
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            0,
+            0,
+            0,
+            0,
+        )?;
         Ok(())
     }
 
@@ -407,6 +515,84 @@ pub mod mango_v4 {
             perp_count,
             perp_oo_count,
             token_conditional_swap_count,
+            0,
+            0,
+            0,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_expand_v3(
+        ctx: Context<AccountExpand>,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            0,
+            0,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_expand_v4(
+        ctx: Context<AccountExpand>,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            perp_trigger_order_count,
+            0,
+        )?;
+        Ok(())
+    }
+
+    pub fn account_expand_v5(
+        ctx: Context<AccountExpand>,
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+        fixed_term_loan_count: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::account_expand(
+            ctx,
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            perp_trigger_order_count,
+            fixed_term_loan_count,
         )?;
         Ok(())
     }
@@ -736,6 +922,7 @@ pub mod mango_v4 {
             &mut ctx.accounts.v1,
             Some(&mut ctx.accounts.v2),
             fees_to_dao,
+            ctx.remaining_accounts,
         )?;
         Ok(())
     }
@@ -852,6 +1039,8 @@ pub mod mango_v4 {
         settle_pnl_limit_window_size_ts: u64,
         positive_pnl_liquidation_fee: f32,
         platform_liquidation_fee: f32,
+        funding_interest_rate: f32,
+        funding_dampener: f32,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::perp_create_market(
@@ -884,6 +1073,8 @@ pub mod mango_v4 {
             settle_pnl_limit_window_size_ts,
             positive_pnl_liquidation_fee,
             platform_liquidation_fee,
+            funding_interest_rate,
+            funding_dampener,
         )?;
         Ok(())
     }
@@ -922,6 +1113,11 @@ pub mod mango_v4 {
         name_opt: Option<String>,
         force_close_opt: Option<bool>,
         platform_liquidation_fee_opt: Option<f32>,
+        median_oracles_opt: Option<Vec<Pubkey>>,
+        median_oracle_quorum_opt: Option<u8>,
+        funding_sample_interval_opt: Option<u64>,
+        funding_interest_rate_opt: Option<f32>,
+        funding_dampener_opt: Option<f32>,
     ) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::perp_edit_market(
@@ -957,6 +1153,11 @@ pub mod mango_v4 {
             name_opt,
             force_close_opt,
             platform_liquidation_fee_opt,
+            median_oracles_opt,
+            median_oracle_quorum_opt,
+            funding_sample_interval_opt,
+            funding_interest_rate_opt,
+            funding_dampener_opt,
         )?;
         Ok(())
     }
@@ -1020,6 +1221,7 @@ pub mod mango_v4 {
             client_order_id,
             reduce_only,
             time_in_force,
+            expiry_slot: 0,
             self_trade_behavior: SelfTradeBehavior::default(),
             params: match order_type {
                 PlaceOrderType::Market => OrderParams::Market {},
@@ -1027,6 +1229,7 @@ pub mod mango_v4 {
                 _ => OrderParams::Fixed {
                     price_lots,
                     order_type: order_type.to_post_order_type()?,
+                    display_base_lots: 0,
                 },
             },
         };
@@ -1085,6 +1288,7 @@ pub mod mango_v4 {
             client_order_id,
             reduce_only,
             time_in_force,
+            expiry_slot: 0,
             self_trade_behavior,
             params: match order_type {
                 PlaceOrderType::Market => OrderParams::Market {},
@@ -1092,6 +1296,87 @@ pub mod mango_v4 {
                 _ => OrderParams::Fixed {
                     price_lots,
                     order_type: order_type.to_post_order_type()?,
+                    display_base_lots: 0,
+                },
+            },
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_place_order(ctx, order, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_order_v3(
+        ctx: Context<PerpPlaceOrder>,
+        side: Side,
+
+        // The price in lots (quote lots per base lots)
+        // - fill orders on the book up to this price or
+        // - place an order on the book at this price.
+        // - ignored for Market orders and potentially adjusted for PostOnlySlide orders.
+        price_lots: i64,
+
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        reduce_only: bool,
+
+        // Timestamp of when order expires
+        //
+        // Send 0 if you want the order to never expire.
+        // Timestamps in the past mean the instruction is skipped.
+        // Timestamps in the future are reduced to now + 65535s.
+        expiry_timestamp: u64,
+
+        // Slot at which the order expires
+        //
+        // Send 0 if you want the order to never expire.
+        // Slots in the past mean the instruction is skipped.
+        expiry_slot: u64,
+
+        // Maximum number of orders from the book to fill.
+        //
+        // Use this to limit compute used during order matching.
+        // When the limit is reached, processing stops and the instruction succeeds.
+        limit: u8,
+    ) -> Result<Option<u128>> {
+        require_gte!(price_lots, 0);
+
+        use crate::state::{Order, OrderParams};
+        let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
+            Some(t) => t,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let expiry_slot = match Order::expiry_slot_if_valid(expiry_slot) {
+            Some(s) => s,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let order = Order {
+            side,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            reduce_only,
+            time_in_force,
+            expiry_slot,
+            self_trade_behavior,
+            params: match order_type {
+                PlaceOrderType::Market => OrderParams::Market {},
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                _ => OrderParams::Fixed {
+                    price_lots,
+                    order_type: order_type.to_post_order_type()?,
+                    display_base_lots: 0,
                 },
             },
         };
@@ -1159,12 +1444,14 @@ pub mod mango_v4 {
             client_order_id,
             reduce_only,
             time_in_force,
+            expiry_slot: 0,
             self_trade_behavior: SelfTradeBehavior::DecrementTake,
             params: OrderParams::OraclePegged {
                 price_offset_lots,
                 order_type: order_type.to_post_order_type()?,
                 peg_limit,
                 max_oracle_staleness_slots,
+                display_base_lots: 0,
             },
         };
         #[cfg(feature = "enable-gpl")]
@@ -1232,12 +1519,102 @@ pub mod mango_v4 {
             client_order_id,
             reduce_only,
             time_in_force,
+            expiry_slot: 0,
+            self_trade_behavior,
+            params: OrderParams::OraclePegged {
+                price_offset_lots,
+                order_type: order_type.to_post_order_type()?,
+                peg_limit,
+                max_oracle_staleness_slots,
+                display_base_lots: 0,
+            },
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_place_order(ctx, order, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_place_order_pegged_v3(
+        ctx: Context<PerpPlaceOrder>,
+        side: Side,
+
+        // The adjustment from the oracle price, in lots (quote lots per base lots).
+        // Orders on the book may be filled at oracle + adjustment (depends on order type).
+        price_offset_lots: i64,
+
+        // The limit at which the pegged order shall expire.
+        // May be -1 to denote no peg limit.
+        //
+        // Example: An bid pegged to -20 with peg_limit 100 would expire if the oracle hits 121.
+        peg_limit: i64,
+
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        client_order_id: u64,
+        order_type: PlaceOrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        reduce_only: bool,
+
+        // Timestamp of when order expires
+        //
+        // Send 0 if you want the order to never expire.
+        // Timestamps in the past mean the instruction is skipped.
+        // Timestamps in the future are reduced to now + 65535s.
+        expiry_timestamp: u64,
+
+        // Slot at which the order expires
+        //
+        // Send 0 if you want the order to never expire.
+        // Slots in the past mean the instruction is skipped.
+        expiry_slot: u64,
+
+        // Maximum number of orders from the book to fill.
+        //
+        // Use this to limit compute used during order matching.
+        // When the limit is reached, processing stops and the instruction succeeds.
+        limit: u8,
+
+        // Oracle staleness limit, in slots. Set to -1 to disable.
+        //
+        // WARNING: Not currently implemented.
+        max_oracle_staleness_slots: i32,
+    ) -> Result<Option<u128>> {
+        require_gte!(peg_limit, -1);
+        require_eq!(max_oracle_staleness_slots, -1); // unimplemented
+
+        use crate::state::{Order, OrderParams};
+        let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
+            Some(t) => t,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let expiry_slot = match Order::expiry_slot_if_valid(expiry_slot) {
+            Some(s) => s,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let order = Order {
+            side,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            reduce_only,
+            time_in_force,
+            expiry_slot,
             self_trade_behavior,
             params: OrderParams::OraclePegged {
                 price_offset_lots,
                 order_type: order_type.to_post_order_type()?,
                 peg_limit,
                 max_oracle_staleness_slots,
+                display_base_lots: 0,
             },
         };
         #[cfg(feature = "enable-gpl")]
@@ -1262,6 +1639,94 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    /// Places several perp orders on one market with a single health check.
+    ///
+    /// If `cancel_all` is set, all of the account's orders on the market are canceled
+    /// first, like perp_cancel_all_orders. `limit` applies to the cancellation and to
+    /// the matching of each order separately.
+    pub fn perp_place_orders_batch(
+        ctx: Context<PerpPlaceOrdersBatch>,
+        cancel_all: bool,
+        orders: Vec<PerpBatchOrderParams>,
+        limit: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_place_orders_batch(ctx, cancel_all, orders, limit)?;
+        Ok(())
+    }
+
+    /// Modifies the perp order with `client_order_id` in a single instruction.
+    ///
+    /// If only the quantity of a resting fixed-price order is reduced, it is changed
+    /// in place and keeps its priority. Otherwise the order is canceled and replaced
+    /// by the new order, which keeps the same client_order_id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_edit_order(
+        ctx: Context<PerpEditOrder>,
+        client_order_id: u64,
+        side: Side,
+        price_lots: i64,
+        max_base_lots: i64,
+        max_quote_lots: i64,
+        order_type: PlaceOrderType,
+        self_trade_behavior: SelfTradeBehavior,
+        reduce_only: bool,
+
+        // Timestamp of when the replacement order expires, see perp_place_order_v2.
+        //
+        // Ignored when the order is reduced in place.
+        // If it is in the past, the instruction is skipped and the order is left unchanged.
+        expiry_timestamp: u64,
+
+        // Slot at which the replacement order expires, see perp_place_order_v3.
+        //
+        // Handled like expiry_timestamp.
+        expiry_slot: u64,
+        limit: u8,
+    ) -> Result<Option<u128>> {
+        require_gte!(price_lots, 0);
+
+        use crate::state::{Order, OrderParams};
+        let time_in_force = match Order::tif_from_expiry(expiry_timestamp) {
+            Some(t) => t,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let expiry_slot = match Order::expiry_slot_if_valid(expiry_slot) {
+            Some(s) => s,
+            None => {
+                msg!("Order is already expired");
+                return Ok(None);
+            }
+        };
+        let order = Order {
+            side,
+            max_base_lots,
+            max_quote_lots,
+            client_order_id,
+            reduce_only,
+            time_in_force,
+            expiry_slot,
+            self_trade_behavior,
+            params: match order_type {
+                PlaceOrderType::Market => OrderParams::Market {},
+                PlaceOrderType::ImmediateOrCancel => OrderParams::ImmediateOrCancel { price_lots },
+                _ => OrderParams::Fixed {
+                    price_lots,
+                    order_type: order_type.to_post_order_type()?,
+                    display_base_lots: 0,
+                },
+            },
+        };
+        #[cfg(feature = "enable-gpl")]
+        return instructions::perp_edit_order(ctx, client_order_id, order, limit);
+
+        #[cfg(not(feature = "enable-gpl"))]
+        Ok(None)
+    }
+
     pub fn perp_cancel_all_orders(ctx: Context<PerpCancelAllOrders>, limit: u8) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::perp_cancel_all_orders(ctx, limit)?;
@@ -1615,6 +2080,88 @@ pub mod mango_v4 {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn perp_trigger_order_create(
+        ctx: Context<PerpTriggerOrderCreate>,
+        side: Side,
+        trigger_condition: PerpTriggerCondition,
+        trigger_price: f64,
+        max_base_lots: i64,
+        max_slippage: f64,
+        reduce_only: bool,
+        expiry_timestamp: u64,
+    ) -> Result<()> {
+        let order = PerpTriggerOrder {
+            id: u64::MAX, // set inside
+            trigger_price,
+            max_base_lots,
+            max_slippage,
+            expiry_timestamp,
+            perp_market_index: ctx.accounts.perp_market.load()?.perp_market_index,
+            side: side.into(),
+            trigger_condition: trigger_condition.into(),
+            is_configured: 1,
+            reduce_only: u8::from(reduce_only),
+            padding: Default::default(),
+            reserved: [0; 64],
+        };
+
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_trigger_order_create(ctx, order)?;
+        Ok(())
+    }
+
+    pub fn perp_trigger_order_cancel(
+        ctx: Context<PerpTriggerOrderCancel>,
+        perp_trigger_order_index: u8,
+        perp_trigger_order_id: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_trigger_order_cancel(
+            ctx,
+            perp_trigger_order_index.into(),
+            perp_trigger_order_id,
+        )?;
+        Ok(())
+    }
+
+    pub fn perp_trigger_order_execute(
+        ctx: Context<PerpTriggerOrderExecute>,
+        perp_trigger_order_index: u8,
+        perp_trigger_order_id: u64,
+        limit: u8,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::perp_trigger_order_execute(
+            ctx,
+            perp_trigger_order_index.into(),
+            perp_trigger_order_id,
+            limit,
+        )?;
+        Ok(())
+    }
+
+    pub fn fixed_term_loan_create(
+        ctx: Context<FixedTermLoanCreate>,
+        amount: u64,
+        duration: u64,
+        auto_roll: bool,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::fixed_term_loan_create(ctx, amount, duration, auto_roll)?;
+        Ok(())
+    }
+
+    pub fn fixed_term_loan_settle(
+        ctx: Context<FixedTermLoanSettle>,
+        fixed_term_loan_index: u8,
+        fixed_term_loan_id: u64,
+    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::fixed_term_loan_settle(ctx, fixed_term_loan_index, fixed_term_loan_id)?;
+        Ok(())
+    }
+
     pub fn token_charge_collateral_fees(ctx: Context<TokenChargeCollateralFees>) -> Result<()> {
         #[cfg(feature = "enable-gpl")]
         instructions::token_charge_collateral_fees(ctx)?;
@@ -1652,7 +2199,10 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2RegisterMarket>,
         market_index: OpenbookV2MarketIndex,
         name: String,
+        oracle_price_band: f32,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_register_market(ctx, market_index, name, oracle_price_band)?;
         Ok(())
     }
 
@@ -1660,11 +2210,23 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2EditMarket>,
         reduce_only_opt: Option<bool>,
         force_close_opt: Option<bool>,
+        name_opt: Option<String>,
+        oracle_price_band_opt: Option<f32>,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_edit_market(
+            ctx,
+            reduce_only_opt,
+            force_close_opt,
+            name_opt,
+            oracle_price_band_opt,
+        )?;
         Ok(())
     }
 
     pub fn openbook_v2_deregister_market(ctx: Context<OpenbookV2DeregisterMarket>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_deregister_market(ctx)?;
         Ok(())
     }
 
@@ -1672,13 +2234,18 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2CreateOpenOrders>,
         account_num: u32,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_create_open_orders(ctx, account_num)?;
         Ok(())
     }
 
     pub fn openbook_v2_close_open_orders(ctx: Context<OpenbookV2CloseOpenOrders>) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_close_open_orders(ctx)?;
         Ok(())
     }
 
+    /// requires the receiver_bank in the health account list to be writable
     #[allow(clippy::too_many_arguments)]
     pub fn openbook_v2_place_order(
         ctx: Context<OpenbookV2PlaceOrder>,
@@ -1691,20 +2258,25 @@ pub mod mango_v4 {
         client_order_id: u64,
         limit: u16,
     ) -> Result<()> {
-        Ok(())
-    }
+        use openbook_v2::state::{PlaceOrderType, SelfTradeBehavior, Side};
+        let side = Side::try_from(side).map_err(|_| error!(MangoError::SomeError))?;
+        let self_trade_behavior = SelfTradeBehavior::try_from(self_trade_behavior)
+            .map_err(|_| error!(MangoError::SomeError))?;
+        let order_type =
+            PlaceOrderType::try_from(order_type).map_err(|_| error!(MangoError::SomeError))?;
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn openbook_v2_place_taker_order(
-        ctx: Context<OpenbookV2PlaceTakeOrder>,
-        side: u8, // openbook_v2::state::Side
-        limit_price: u64,
-        max_base_qty: u64,
-        max_native_quote_qty_including_fees: u64,
-        self_trade_behavior: u8, // openbook_v2::state::SelfTradeBehavior
-        client_order_id: u64,
-        limit: u16,
-    ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_place_order(
+            ctx,
+            side,
+            limit_price,
+            max_base_qty,
+            max_native_quote_qty_including_fees,
+            self_trade_behavior,
+            order_type,
+            client_order_id,
+            limit.min(u8::MAX.into()) as u8,
+        )?;
         Ok(())
     }
 
@@ -1713,6 +2285,11 @@ pub mod mango_v4 {
         side: u8, // openbook_v2::state::Side
         order_id: u128,
     ) -> Result<()> {
+        use openbook_v2::state::Side;
+        let side = Side::try_from(side).map_err(|_| error!(MangoError::SomeError))?;
+
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_cancel_order(ctx, side, order_id)?;
         Ok(())
     }
 
@@ -1720,6 +2297,8 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2SettleFunds>,
         fees_to_dao: bool,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_settle_funds(ctx, fees_to_dao)?;
         Ok(())
     }
 
@@ -1727,6 +2306,8 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2LiqForceCancelOrders>,
         limit: u8,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_liq_force_cancel_orders(ctx, limit)?;
         Ok(())
     }
 
@@ -1734,6 +2315,8 @@ pub mod mango_v4 {
         ctx: Context<OpenbookV2CancelOrder>,
         limit: u8,
     ) -> Result<()> {
+        #[cfg(feature = "enable-gpl")]
+        instructions::openbook_v2_cancel_all_orders(ctx, limit)?;
         Ok(())
     }
 
@@ -387,6 +387,20 @@ pub struct Serum3OpenOrdersBalanceLogV2 {
     pub referrer_rebates_accrued: u64,
 }
 
+#[event]
+pub struct OpenbookV2OpenOrdersBalanceLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub market_index: u16,
+    pub base_token_index: u16,
+    pub quote_token_index: u16,
+    pub base_total: u64,
+    pub base_free: u64,
+    pub quote_total: u64,
+    pub quote_free: u64,
+    pub referrer_rebates_available: u64,
+}
+
 #[derive(PartialEq, Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize)]
 #[repr(u8)]
 pub enum LoanOriginationFeeInstruction {
@@ -398,6 +412,9 @@ pub enum LoanOriginationFeeInstruction {
     Serum3SettleFunds,
     TokenWithdraw,
     TokenConditionalSwapTrigger,
+    OpenbookV2LiqForceCancelOrders,
+    OpenbookV2PlaceOrder,
+    OpenbookV2SettleFunds,
 }
 
 #[event]
@@ -499,6 +516,17 @@ pub struct Serum3RegisterMarketLog {
     pub serum_program_external: Pubkey,
 }
 
+#[event]
+pub struct OpenbookV2RegisterMarketLog {
+    pub mango_group: Pubkey,
+    pub openbook_market: Pubkey,
+    pub market_index: u16,
+    pub base_token_index: u16,
+    pub quote_token_index: u16,
+    pub openbook_program: Pubkey,
+    pub openbook_market_external: Pubkey,
+}
+
 #[event]
 pub struct PerpLiqBaseOrPositivePnlLog {
     pub mango_group: Pubkey,
@@ -788,3 +816,92 @@ pub struct TokenCollateralFeeLog {
     pub asset_usage_fraction: i128,
     pub fee: i128,
 }
+
+#[event]
+pub struct PerpTriggerOrderCreateLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub id: u64,
+    pub perp_market_index: u16,
+    pub side: u8,
+    pub trigger_condition: u8,
+    pub trigger_price: f64,
+    pub max_base_lots: i64,
+    pub max_slippage: f64,
+    pub reduce_only: bool,
+    pub expiry_timestamp: u64,
+}
+
+#[event]
+pub struct PerpTriggerOrderCancelLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub id: u64,
+}
+
+#[event]
+pub struct PerpTriggerOrderExecuteLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub caller: Pubkey,
+    pub id: u64,
+    pub perp_market_index: u16,
+    pub oracle_price: f64,
+    pub base_position_change: i64,
+    pub incentive_token_index: u16,
+    pub incentive_amount: u64,
+}
+
+#[event]
+pub struct PerpSocializedLossLog {
+    pub mango_group: Pubkey,
+    pub perp_market_index: u16,
+    /// The socialized loss in settle token native units (I80F48)
+    pub loss: i128,
+    /// A SocializedLossTarget value
+    pub target: u8,
+    pub open_interest: i64,
+    pub starting_long_funding: i128,
+    pub starting_short_funding: i128,
+    pub ending_long_funding: i128,
+    pub ending_short_funding: i128,
+    pub pending_socialized_loss: i128,
+}
+
+#[event]
+pub struct TokenInsuranceFundCoverLog {
+    pub mango_group: Pubkey,
+    pub liqee: Pubkey,
+    pub token_index: u16,
+    /// The liqee's negative token balance before covering, including perp pnl (I80F48)
+    pub initial_balance: i128,
+    pub cover_amount: u64,
+    pub total_covered: u64,
+}
+
+#[event]
+pub struct FixedTermLoanCreateLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub id: u64,
+    pub token_index: u16,
+    pub principal: u64,
+    pub loan_origination_fee: u64,
+    pub rate: f64,
+    pub maturity_timestamp: u64,
+    pub auto_roll: bool,
+}
+
+#[event]
+pub struct FixedTermLoanSettleLog {
+    pub mango_group: Pubkey,
+    pub mango_account: Pubkey,
+    pub settler: Pubkey,
+    pub id: u64,
+    pub token_index: u16,
+    pub principal: u64,
+    /// Interest paid to depositors (I80F48)
+    pub interest: i128,
+    /// Penalty for a forced repayment at maturity, added to the bank's fees (I80F48)
+    pub penalty: i128,
+}
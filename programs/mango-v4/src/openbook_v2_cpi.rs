@@ -0,0 +1,281 @@
+use anchor_lang::prelude::*;
+use openbook_v2::state::{OpenOrdersAccount, PlaceOrderArgs, Side};
+
+use std::cell::Ref;
+
+use crate::accounts_zerocopy::*;
+use crate::serum3_cpi::OpenOrdersAmounts;
+use crate::state::*;
+
+pub fn load_open_orders_ref<'a>(acc: &'a AccountInfo) -> Result<Ref<'a, OpenOrdersAccount>> {
+    acc.load::<OpenOrdersAccount>()
+}
+
+pub fn load_open_orders(acc: &impl AccountReader) -> Result<&OpenOrdersAccount> {
+    acc.load::<OpenOrdersAccount>()
+}
+
+/// The funds of an openbook v2 open orders account, in native units
+///
+/// Openbook v2 tracks reserved funds in lots, so the market's lot sizes
+/// are needed for the conversion.
+#[derive(Debug)]
+pub struct OpenOrdersSlim {
+    native_base_free: u64,
+    native_base_reserved: u64,
+    native_quote_free: u64,
+    native_quote_reserved: u64,
+    referrer_rebates_available: u64,
+}
+impl OpenOrdersSlim {
+    pub fn from_oo(oo: &OpenOrdersAccount, base_lot_size: i64, quote_lot_size: i64) -> Self {
+        let position = &oo.position;
+        Self {
+            native_base_free: position.base_free_native,
+            native_base_reserved: (position.asks_base_lots * base_lot_size) as u64,
+            native_quote_free: position.quote_free_native,
+            native_quote_reserved: (position.bids_quote_lots * quote_lot_size) as u64
+                + position.locked_maker_fees,
+            referrer_rebates_available: position.referrer_rebates_available,
+        }
+    }
+}
+
+impl OpenOrdersAmounts for OpenOrdersSlim {
+    fn native_base_reserved(&self) -> u64 {
+        self.native_base_reserved
+    }
+    fn native_quote_reserved(&self) -> u64 {
+        self.native_quote_reserved
+    }
+    fn native_base_free(&self) -> u64 {
+        self.native_base_free
+    }
+    fn native_quote_free(&self) -> u64 {
+        self.native_quote_free
+    }
+    fn native_base_total(&self) -> u64 {
+        self.native_base_free + self.native_base_reserved
+    }
+    fn native_quote_total(&self) -> u64 {
+        self.native_quote_free + self.native_quote_reserved
+    }
+    fn native_rebates(&self) -> u64 {
+        self.referrer_rebates_available
+    }
+}
+
+/// Open orders accounts are owned by the group, so the group signs all
+/// calls into openbook v2.
+pub struct InitOpenOrders<'info> {
+    /// CHECK: cpi
+    pub program: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub payer: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub owner: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub open_orders: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> InitOpenOrders<'info> {
+    pub fn call(self, group: &Group, account_num: u32) -> Result<()> {
+        let accounts = openbook_v2::cpi::accounts::InitOpenOrders {
+            payer: self.payer,
+            owner: self.owner,
+            delegate_account: None,
+            open_orders_account: self.open_orders,
+            market: self.market,
+            system_program: self.system_program,
+        };
+
+        let seeds = group_seeds!(group);
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(self.program, accounts, signer_seeds);
+        openbook_v2::cpi::init_open_orders(ctx, account_num)
+    }
+}
+
+pub struct CloseOpenOrders<'info> {
+    /// CHECK: cpi
+    pub program: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub owner: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub open_orders: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub sol_destination: AccountInfo<'info>,
+}
+
+impl<'info> CloseOpenOrders<'info> {
+    pub fn call(self, group: &Group) -> Result<()> {
+        let accounts = openbook_v2::cpi::accounts::CloseOpenOrdersAccount {
+            owner: self.owner,
+            open_orders_account: self.open_orders,
+            sol_destination: self.sol_destination,
+        };
+
+        let seeds = group_seeds!(group);
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(self.program, accounts, signer_seeds);
+        openbook_v2::cpi::close_open_orders_account(ctx)
+    }
+}
+
+pub struct SettleFunds<'info> {
+    /// CHECK: cpi
+    pub program: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub owner: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub open_orders: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market_authority: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market_base_vault: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market_quote_vault: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub user_base_wallet: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub user_quote_wallet: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub rebates_quote_wallet: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> SettleFunds<'info> {
+    pub fn call(self, group: &Group) -> Result<()> {
+        let accounts = openbook_v2::cpi::accounts::SettleFunds {
+            owner: self.owner,
+            open_orders_account: self.open_orders,
+            market: self.market,
+            market_authority: self.market_authority,
+            market_base_vault: self.market_base_vault,
+            market_quote_vault: self.market_quote_vault,
+            user_base_account: self.user_base_wallet,
+            user_quote_account: self.user_quote_wallet,
+            referrer_account: Some(self.rebates_quote_wallet),
+            token_program: self.token_program,
+        };
+
+        let seeds = group_seeds!(group);
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(self.program, accounts, signer_seeds);
+        openbook_v2::cpi::settle_funds(ctx)
+    }
+}
+
+pub struct PlaceOrder<'info> {
+    /// CHECK: cpi
+    pub program: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub bids: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub asks: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub event_heap: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market_base_vault: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market_quote_vault: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub token_program: AccountInfo<'info>,
+
+    /// CHECK: cpi
+    pub open_orders: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub order_payer_token_account: AccountInfo<'info>,
+    /// must cover the open_orders and the order_payer_token_account
+    /// CHECK: cpi
+    pub user_authority: AccountInfo<'info>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    pub fn call(self, group: &Group, order: PlaceOrderArgs) -> Result<()> {
+        // Bids pay quote tokens, asks pay base tokens
+        let market_vault = match order.side {
+            Side::Bid => self.market_quote_vault,
+            Side::Ask => self.market_base_vault,
+        };
+        let accounts = openbook_v2::cpi::accounts::PlaceOrder {
+            signer: self.user_authority,
+            open_orders_account: self.open_orders,
+            open_orders_admin: None,
+            user_token_account: self.order_payer_token_account,
+            market: self.market,
+            bids: self.bids,
+            asks: self.asks,
+            event_heap: self.event_heap,
+            market_vault,
+            oracle_a: None,
+            oracle_b: None,
+            token_program: self.token_program,
+        };
+
+        let seeds = group_seeds!(group);
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(self.program, accounts, signer_seeds);
+        openbook_v2::cpi::place_order(ctx, order)?;
+        Ok(())
+    }
+}
+
+pub struct CancelOrder<'info> {
+    /// CHECK: cpi
+    pub program: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub market: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub bids: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub asks: AccountInfo<'info>,
+
+    /// CHECK: cpi
+    pub open_orders: AccountInfo<'info>,
+    /// CHECK: cpi
+    pub open_orders_authority: AccountInfo<'info>,
+}
+
+impl<'info> CancelOrder<'info> {
+    fn accounts(
+        self,
+    ) -> (
+        AccountInfo<'info>,
+        openbook_v2::cpi::accounts::CancelOrder<'info>,
+    ) {
+        let accounts = openbook_v2::cpi::accounts::CancelOrder {
+            signer: self.open_orders_authority,
+            open_orders_account: self.open_orders,
+            market: self.market,
+            bids: self.bids,
+            asks: self.asks,
+        };
+        (self.program, accounts)
+    }
+
+    pub fn cancel_one(self, group: &Group, order_id: u128) -> Result<()> {
+        let (program, accounts) = self.accounts();
+        let seeds = group_seeds!(group);
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+        openbook_v2::cpi::cancel_order(ctx, order_id)
+    }
+
+    pub fn cancel_all(self, group: &Group, side_opt: Option<Side>, limit: u8) -> Result<()> {
+        let (program, accounts) = self.accounts();
+        let seeds = group_seeds!(group);
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+        openbook_v2::cpi::cancel_all_orders(ctx, side_opt, limit)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{OracleAccountInfos, OracleConfig, TokenIndex, TokenPosition};
+use super::{OracleAccountInfos, OracleConfig, TokenIndex, TokenPosition, MAX_MEDIAN_ORACLES};
 use crate::accounts_zerocopy::KeyedAccountReader;
 use crate::error::*;
 use crate::i80f48::ClampToInt;
@@ -8,6 +8,7 @@ use crate::util;
 use anchor_lang::prelude::*;
 use derivative::Derivative;
 use fixed::types::I80F48;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use oracle::oracle_log_context;
 use static_assertions::const_assert_eq;
 
@@ -19,6 +20,34 @@ pub const DAY_I80F48: I80F48 = I80F48::from_bits(86_400 * I80F48::ONE.to_bits())
 pub const ONE_BPS: I80F48 = I80F48::from_bits(28147497671);
 pub const YEAR_I80F48: I80F48 = I80F48::from_bits(31_536_000 * I80F48::ONE.to_bits());
 
+/// Max number of tokens an isolated token can be combined with
+pub const MAX_ISOLATED_QUOTE_TOKENS: usize = 4;
+
+/// The interest rate model used by a bank, stored in Bank::interest_curve_type
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
+#[repr(u8)]
+pub enum InterestCurveType {
+    /// Linear segments through zero_util_rate, util0/rate0, util1/rate1 and max_rate,
+    /// multiplied by interest_curve_scaling
+    PiecewiseLinear = 0,
+    /// Linear from zero_util_rate to util0/rate0, then growing exponentially
+    /// to max_rate at full utilization. Multiplied by interest_curve_scaling.
+    KinkedExponential = 1,
+    /// The rate is set hourly by a PID controller that steers the average utilization
+    /// towards interest_target_utilization. Bounded by the scaled zero_util_rate and max_rate.
+    PidController = 2,
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 #[account(zero_copy)]
@@ -229,8 +258,80 @@ pub struct Bank {
     /// The daily collateral fees rate for fully utilized collateral.
     pub collateral_fee_per_day: f32,
 
+    /// Oracles that are used together with `oracle` in the median oracle mode.
+    /// Unused entries are Pubkey::default().
+    pub median_oracles: [Pubkey; MAX_MEDIAN_ORACLES],
+
+    /// Minimum number of valid oracles needed for the median oracle mode.
+    /// If zero, the median oracle mode is disabled and only `oracle` and
+    /// `fallback_oracle` are used.
+    pub median_oracle_quorum: u8,
+
+    /// If set to 1, this token is in an isolated pool: accounts that have a deposit or
+    /// borrow in it may only have other balances in isolated_quote_token_indexes.
+    ///
+    /// This keeps risky tokens from being used in cross margin with all other tokens.
+    pub isolated: u8,
+
+    /// Tokens that may be combined with this token if it is isolated.
+    /// Unused entries are TokenIndex::MAX.
+    pub isolated_quote_token_indexes: [TokenIndex; MAX_ISOLATED_QUOTE_TOKENS],
+
+    #[derivative(Debug = "ignore")]
+    pub padding2: [u8; 2],
+
+    /// Max total borrows of the bank, in token native. Zero means none.
+    pub borrow_limit: u64,
+
+    /// Max borrows of a single account, in quote native. Zero means none.
+    ///
+    /// Borrows are valued at the max of the oracle and stable price, like for
+    /// net_borrow_limit_per_window_quote.
+    pub account_borrow_limit_quote: u64,
+
+    /// Selects the interest rate model, see InterestCurveType
+    pub interest_curve_type: u8,
+
+    #[derivative(Debug = "ignore")]
+    pub padding3: [u8; 7],
+
+    /// Accumulated utilization error of the PID controller interest curve
+    pub pid_integral: f64,
+
+    /// Current borrow rate (APR) set by the PID controller interest curve
+    pub pid_rate: f64,
+
+    /// Proportional, integral and derivative gains of the PID controller interest curve
+    pub pid_kp: f32,
+    pub pid_ki: f32,
+    pub pid_kd: f32,
+
+    /// Utilization error seen at the previous PID controller update
+    pub pid_last_error: f32,
+
+    /// Outstanding principal of all fixed term loans from this bank, in native tokens
+    ///
+    /// These loans are credited to the borrowers as deposits, so this is what the bank
+    /// is owed in addition to the variable rate borrows. They count as borrows for
+    /// utilization and the borrow limits.
+    pub fixed_term_borrows: I80F48,
+
+    /// Added to the current borrow rate (including loan_fee_rate) to get the locked
+    /// rate of new fixed term loans
+    pub fixed_term_rate_premium: f32,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 1900],
+    pub padding4: [u8; 4],
+
+    /// Longest allowed fixed term loan duration in seconds. Zero disables fixed term loans.
+    pub fixed_term_max_duration: u64,
+
+    /// Interest per year that all fixed term loans from this bank accrue at their locked
+    /// rates, in native tokens. It's paid to depositors through the deposit index.
+    pub fixed_term_yearly_interest: I80F48,
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 1688],
 }
 const_assert_eq!(
     size_of::<Bank>(),
@@ -269,11 +370,36 @@ const_assert_eq!(
         + 8
         + 16 * 4
         + 4
-        + 1900
+        + 32 * MAX_MEDIAN_ORACLES
+        + 1
+        + 1
+        + 2 * MAX_ISOLATED_QUOTE_TOKENS
+        + 2
+        + 8 * 2
+        + 1
+        + 7
+        + 8 * 2
+        + 4 * 4
+        + 16
+        + 4
+        + 4
+        + 8
+        + 16
+        + 1688
 );
 const_assert_eq!(size_of::<Bank>(), 3064);
 const_assert_eq!(size_of::<Bank>() % 8, 0);
 
+/// Builds the isolated_quote_token_indexes array, filling unused entries with TokenIndex::MAX
+pub fn isolated_quote_token_indexes_from_slice(
+    token_indexes: &[TokenIndex],
+) -> Result<[TokenIndex; MAX_ISOLATED_QUOTE_TOKENS]> {
+    require_gte!(MAX_ISOLATED_QUOTE_TOKENS, token_indexes.len());
+    let mut result = [TokenIndex::MAX; MAX_ISOLATED_QUOTE_TOKENS];
+    result[..token_indexes.len()].copy_from_slice(token_indexes);
+    Ok(result)
+}
+
 pub struct WithdrawResult {
     pub position_is_active: bool,
     pub loan_origination_fee: I80F48,
@@ -379,7 +505,27 @@ impl Bank {
             zero_util_rate: existing_bank.zero_util_rate,
             platform_liquidation_fee: existing_bank.platform_liquidation_fee,
             collateral_fee_per_day: existing_bank.collateral_fee_per_day,
-            reserved: [0; 1900],
+            median_oracles: existing_bank.median_oracles,
+            median_oracle_quorum: existing_bank.median_oracle_quorum,
+            isolated: existing_bank.isolated,
+            isolated_quote_token_indexes: existing_bank.isolated_quote_token_indexes,
+            padding2: [0; 2],
+            borrow_limit: existing_bank.borrow_limit,
+            account_borrow_limit_quote: existing_bank.account_borrow_limit_quote,
+            interest_curve_type: existing_bank.interest_curve_type,
+            padding3: [0; 7],
+            pid_integral: existing_bank.pid_integral,
+            pid_rate: existing_bank.pid_rate,
+            pid_kp: existing_bank.pid_kp,
+            pid_ki: existing_bank.pid_ki,
+            pid_kd: existing_bank.pid_kd,
+            pid_last_error: existing_bank.pid_last_error,
+            fixed_term_borrows: I80F48::ZERO,
+            fixed_term_rate_premium: existing_bank.fixed_term_rate_premium,
+            padding4: Default::default(),
+            fixed_term_max_duration: existing_bank.fixed_term_max_duration,
+            fixed_term_yearly_interest: I80F48::ZERO,
+            reserved: [0; 1688],
         }
     }
 
@@ -417,6 +563,26 @@ impl Bank {
             require_eq!(self.maint_asset_weight, I80F48::ZERO);
         }
         require_gte!(self.collateral_fee_per_day, 0.0);
+        oracle::verify_median_oracle_quorum(&self.median_oracles, self.median_oracle_quorum)?;
+        require_gte!(1, self.isolated);
+        if self.is_isolated() {
+            require!(
+                !self
+                    .isolated_quote_token_indexes
+                    .contains(&self.token_index),
+                MangoError::SomeError
+            );
+        }
+        require!(
+            InterestCurveType::try_from(self.interest_curve_type).is_ok(),
+            MangoError::SomeError
+        );
+        require_gte!(self.pid_kp, 0.0);
+        require_gte!(self.pid_ki, 0.0);
+        require_gte!(self.pid_kd, 0.0);
+        require_gte!(self.fixed_term_borrows, 0);
+        require_gte!(self.fixed_term_yearly_interest, 0);
+        require_gte!(self.fixed_term_rate_premium, 0.0);
         Ok(())
     }
 
@@ -442,11 +608,25 @@ impl Bank {
         self.disable_asset_liquidation == 0
     }
 
+    pub fn is_isolated(&self) -> bool {
+        self.isolated == 1
+    }
+
+    pub fn interest_curve_type(&self) -> InterestCurveType {
+        InterestCurveType::try_from(self.interest_curve_type).unwrap()
+    }
+
     #[inline(always)]
     pub fn native_borrows(&self) -> I80F48 {
         self.borrow_index * self.indexed_borrows
     }
 
+    /// Variable rate borrows plus the principal of fixed term loans
+    #[inline(always)]
+    pub fn native_borrows_including_fixed_term(&self) -> I80F48 {
+        self.native_borrows() + self.fixed_term_borrows
+    }
+
     #[inline(always)]
     pub fn native_deposits(&self) -> I80F48 {
         self.deposit_index * self.indexed_deposits
@@ -488,7 +668,7 @@ impl Bank {
     /// Keep some in reserve to satisfy non-borrow withdraws.
     fn enforce_max_utilization(&self, max_utilization: I80F48) -> Result<()> {
         let bank_native_deposits = self.native_deposits();
-        let bank_native_borrows = self.native_borrows();
+        let bank_native_borrows = self.native_borrows_including_fixed_term();
 
         if bank_native_borrows > max_utilization * bank_native_deposits {
             return err!(MangoError::BankBorrowLimitReached).with_context(|| {
@@ -617,6 +797,9 @@ impl Bank {
     ///
     /// native_amount must be >= 0
     /// fractional withdraws can be relevant during liquidation, for example
+    ///
+    /// Doesn't check borrow_limit and account_borrow_limit_quote: Use
+    /// `withdraw_without_fee_with_borrow_limits()` when the account chooses to borrow.
     pub fn withdraw_without_fee(
         &mut self,
         position: &mut TokenPosition,
@@ -629,6 +812,30 @@ impl Bank {
                 native_amount,
                 false,
                 !position.is_in_use(),
+                None,
+                now_ts,
+            )?
+            .position_is_active;
+
+        Ok(position_is_active)
+    }
+
+    /// Like `withdraw_without_fee()` but fails if a borrow is created that exceeds
+    /// borrow_limit or account_borrow_limit_quote.
+    pub fn withdraw_without_fee_with_borrow_limits(
+        &mut self,
+        position: &mut TokenPosition,
+        native_amount: I80F48,
+        now_ts: u64,
+        oracle_price: I80F48,
+    ) -> Result<bool> {
+        let position_is_active = self
+            .withdraw_internal_wrapper(
+                position,
+                native_amount,
+                false,
+                !position.is_in_use(),
+                Some(oracle_price),
                 now_ts,
             )?
             .position_is_active;
@@ -645,7 +852,7 @@ impl Bank {
         native_amount: I80F48,
         now_ts: u64,
     ) -> Result<bool> {
-        self.withdraw_internal_wrapper(position, native_amount, false, true, now_ts)
+        self.withdraw_internal_wrapper(position, native_amount, false, true, None, now_ts)
             .map(|withdraw_result| withdraw_result.position_is_active || position.is_in_use())
     }
 
@@ -657,22 +864,54 @@ impl Bank {
     ///
     /// native_amount must be >= 0
     /// fractional withdraws can be relevant during liquidation, for example
+    ///
+    /// Fails if a borrow is created that exceeds borrow_limit or account_borrow_limit_quote.
     pub fn withdraw_with_fee(
         &mut self,
         position: &mut TokenPosition,
         native_amount: I80F48,
         now_ts: u64,
+        oracle_price: I80F48,
+    ) -> Result<WithdrawResult> {
+        self.withdraw_internal_wrapper(
+            position,
+            native_amount,
+            true,
+            !position.is_in_use(),
+            Some(oracle_price),
+            now_ts,
+        )
+    }
+
+    /// Like `withdraw_with_fee()` but doesn't check borrow_limit and account_borrow_limit_quote.
+    ///
+    /// Liquidation moves existing borrows to the liqor and must not be blocked by the limits.
+    pub fn withdraw_with_fee_without_borrow_limits(
+        &mut self,
+        position: &mut TokenPosition,
+        native_amount: I80F48,
+        now_ts: u64,
     ) -> Result<WithdrawResult> {
-        self.withdraw_internal_wrapper(position, native_amount, true, !position.is_in_use(), now_ts)
+        self.withdraw_internal_wrapper(
+            position,
+            native_amount,
+            true,
+            !position.is_in_use(),
+            None,
+            now_ts,
+        )
     }
 
     /// Internal function to withdraw funds
+    ///
+    /// If `borrow_limit_price` is set and a borrow is created, the borrow limits are checked.
     fn withdraw_internal_wrapper(
         &mut self,
         position: &mut TokenPosition,
         native_amount: I80F48,
         with_loan_origination_fee: bool,
         allow_dusting: bool,
+        borrow_limit_price: Option<I80F48>,
         now_ts: u64,
     ) -> Result<WithdrawResult> {
         let opening_indexed_position = position.indexed_position;
@@ -684,7 +923,13 @@ impl Bank {
             now_ts,
         );
         self.update_cumulative_interest(position, opening_indexed_position);
-        res
+        let withdraw_result = res?;
+        if let Some(oracle_price) = borrow_limit_price {
+            if withdraw_result.has_loan() {
+                self.check_borrow_limits(position, oracle_price)?;
+            }
+        }
+        Ok(withdraw_result)
     }
 
     /// Internal function to withdraw funds
@@ -772,6 +1017,7 @@ impl Bank {
                 loan_origination_fee,
                 false,
                 !position.is_in_use(),
+                None,
                 now_ts,
             )?
             .position_is_active;
@@ -817,6 +1063,7 @@ impl Bank {
         position: &mut TokenPosition,
         native_amount: I80F48,
         now_ts: u64,
+        oracle_price: I80F48,
     ) -> Result<WithdrawResult> {
         if native_amount >= 0 {
             Ok(WithdrawResult {
@@ -825,7 +1072,7 @@ impl Bank {
                 loan_amount: I80F48::ZERO,
             })
         } else {
-            self.withdraw_with_fee(position, -native_amount, now_ts)
+            self.withdraw_with_fee(position, -native_amount, now_ts, oracle_price)
         }
     }
 
@@ -846,7 +1093,8 @@ impl Bank {
         let before_deposits = self.indexed_deposits;
 
         let withdraw_result = if !source_amount.is_zero() {
-            let withdraw_result = self.withdraw_with_fee(source, source_amount, now_ts)?;
+            let withdraw_result =
+                self.withdraw_with_fee(source, source_amount, now_ts, oracle_price)?;
             require!(
                 source.indexed_position >= 0 || !self.are_borrows_reduce_only(),
                 MangoError::TokenInReduceOnlyMode
@@ -932,6 +1180,48 @@ impl Bank {
         Ok(())
     }
 
+    /// Checks the bank's total borrows against borrow_limit and the borrows of
+    /// `position` against account_borrow_limit_quote.
+    ///
+    /// Both include fixed term loans.
+    pub fn check_borrow_limits(
+        &self,
+        position: &TokenPosition,
+        oracle_price: I80F48,
+    ) -> Result<()> {
+        if self.borrow_limit > 0 {
+            let borrows = self.native_borrows_including_fixed_term();
+            if borrows > I80F48::from(self.borrow_limit) {
+                return Err(error_msg_typed!(
+                    MangoError::BankTotalBorrowLimit,
+                    "total borrow limit exceeded: borrows: {}, limit: {}",
+                    borrows,
+                    self.borrow_limit,
+                ));
+            }
+        }
+
+        if self.account_borrow_limit_quote > 0 {
+            // Fixed term loans were credited to the position, only the part that's not
+            // covered by the position is borrowed
+            let account_borrows = (I80F48::from(position.fixed_term_principal)
+                - position.native(self))
+            .max(I80F48::ZERO);
+            let price = oracle_price.max(self.stable_price());
+            let account_borrows_quote = account_borrows * price;
+            if account_borrows_quote > I80F48::from(self.account_borrow_limit_quote) {
+                return Err(error_msg_typed!(
+                    MangoError::AccountBorrowLimit,
+                    "account borrow limit exceeded: borrows in quote: {}, limit: {}",
+                    account_borrows_quote,
+                    self.account_borrow_limit_quote,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn remaining_deposits_until_limit(&self) -> I80F48 {
         if self.deposit_limit == 0 {
             return I80F48::MAX;
@@ -1003,14 +1293,19 @@ impl Bank {
         &self,
         indexed_total_deposits: I80F48,
         indexed_total_borrows: I80F48,
+        fixed_term_borrows: I80F48,
+        fixed_term_yearly_interest: I80F48,
         diff_ts: I80F48,
     ) -> Result<(I80F48, I80F48, I80F48, I80F48, I80F48)> {
         // compute index based on utilization
         let native_total_deposits = self.deposit_index * indexed_total_deposits;
         let native_total_borrows = self.borrow_index * indexed_total_borrows;
 
-        let instantaneous_utilization =
-            Self::instantaneous_utilization(native_total_deposits, native_total_borrows);
+        // Fixed term loans are lent out of the same deposits
+        let instantaneous_utilization = Self::instantaneous_utilization(
+            native_total_deposits,
+            native_total_borrows + fixed_term_borrows,
+        );
 
         let borrow_rate = self.compute_interest_rate(instantaneous_utilization);
 
@@ -1023,8 +1318,16 @@ impl Bank {
         //   new_borrow_index = old_borrow_index * (1 * borrow_rate)
         // we have
         //   deposit_rate = borrow_rate * (old_borrow_index * indexed_borrows) / (old_deposit_index * indexed_deposits)
-        // and the latter factor is exactly instantaneous_utilization.
-        let deposit_rate = borrow_rate * instantaneous_utilization;
+        // and the latter factor is the utilization by variable rate borrows only.
+        // Fixed term loans accrue their locked interest to depositors on top of that.
+        let variable_utilization =
+            Self::instantaneous_utilization(native_total_deposits, native_total_borrows);
+        let fixed_term_deposit_rate = if native_total_deposits.is_positive() {
+            fixed_term_yearly_interest / native_total_deposits
+        } else {
+            I80F48::ZERO
+        };
+        let deposit_rate = borrow_rate * variable_utilization + fixed_term_deposit_rate;
 
         // The loan fee rate is not distributed to depositors.
         let borrow_rate_with_fees = borrow_rate + self.loan_fee_rate;
@@ -1064,16 +1367,39 @@ impl Bank {
     /// returns the current interest rate in APR
     #[inline(always)]
     pub fn compute_interest_rate(&self, utilization: I80F48) -> I80F48 {
-        Bank::interest_rate_curve_calculator(
-            utilization,
-            self.zero_util_rate,
-            self.util0,
-            self.rate0,
-            self.util1,
-            self.rate1,
-            self.max_rate,
-            self.interest_curve_scaling,
-        )
+        match self.interest_curve_type() {
+            InterestCurveType::PiecewiseLinear => Bank::interest_rate_curve_calculator(
+                utilization,
+                self.zero_util_rate,
+                self.util0,
+                self.rate0,
+                self.util1,
+                self.rate1,
+                self.max_rate,
+                self.interest_curve_scaling,
+            ),
+            InterestCurveType::KinkedExponential => Bank::kinked_exponential_curve_calculator(
+                utilization,
+                self.zero_util_rate,
+                self.util0,
+                self.rate0,
+                self.max_rate,
+                self.interest_curve_scaling,
+            ),
+            // The controller rate doesn't depend on the instantaneous utilization
+            InterestCurveType::PidController => I80F48::from_num(self.pid_rate),
+        }
+    }
+
+    /// The locked rate (APR) for a fixed term loan created now
+    pub fn fixed_term_loan_rate(&self) -> I80F48 {
+        let utilization = Self::instantaneous_utilization(
+            self.native_deposits(),
+            self.native_borrows_including_fixed_term(),
+        );
+        self.compute_interest_rate(utilization)
+            + self.loan_fee_rate
+            + I80F48::from_num(self.fixed_term_rate_premium)
     }
 
     /// calculator function that can be used to compute an interest
@@ -1113,11 +1439,50 @@ impl Bank {
         }
     }
 
+    /// calculator function for the kinked exponential curve: linear up to util0,
+    /// then rate0 * (max_rate / rate0)^((utilization - util0) / (1 - util0))
+    #[inline(always)]
+    pub fn kinked_exponential_curve_calculator(
+        utilization: I80F48,
+        zero_util_rate: I80F48,
+        util0: I80F48,
+        rate0: I80F48,
+        max_rate: I80F48,
+        scaling: f64,
+    ) -> I80F48 {
+        // Clamp to avoid negative or extremely high interest
+        let utilization = utilization.max(I80F48::ZERO).min(I80F48::ONE);
+
+        let v = if utilization <= util0 {
+            let slope = (rate0 - zero_util_rate) / util0;
+            zero_util_rate + slope * utilization
+        } else {
+            let extra_util = utilization - util0;
+            let exponent = (extra_util / (I80F48::ONE - util0)).to_num::<f64>();
+            if rate0 <= I80F48::ZERO {
+                // can't grow exponentially from zero, fall back to linear
+                let slope = (max_rate - rate0) / (I80F48::ONE - util0);
+                rate0 + slope * extra_util
+            } else {
+                let growth = (max_rate / rate0).to_num::<f64>();
+                I80F48::from_num(rate0.to_num::<f64>() * growth.powf(exponent))
+            }
+        };
+
+        // scaling will be 0 when it's introduced
+        if scaling == 0.0 {
+            v
+        } else {
+            v * I80F48::from_num(scaling)
+        }
+    }
+
     // compute new avg utilization
     pub fn compute_new_avg_utilization(
         &self,
         indexed_total_deposits: I80F48,
         indexed_total_borrows: I80F48,
+        fixed_term_borrows: I80F48,
         now_ts: u64,
     ) -> I80F48 {
         if now_ts == 0 {
@@ -1125,7 +1490,7 @@ impl Bank {
         }
 
         let native_total_deposits = self.deposit_index * indexed_total_deposits;
-        let native_total_borrows = self.borrow_index * indexed_total_borrows;
+        let native_total_borrows = self.borrow_index * indexed_total_borrows + fixed_term_borrows;
         let instantaneous_utilization =
             Self::instantaneous_utilization(native_total_deposits, native_total_borrows);
 
@@ -1150,6 +1515,11 @@ impl Bank {
         // also clamp to avoid unusually quick interest rate curve changes
         let avg_util = self.avg_utilization.to_num::<f64>().max(0.0).min(1.0);
 
+        if self.interest_curve_type() == InterestCurveType::PidController {
+            self.update_pid_rate(avg_util, target_util);
+            return;
+        }
+
         // move rates up when utilization is above optimal utilization, and vice versa
         // util factor is between -1 (avg util = 0) and +1 (avg util = 100%)
         let util_factor = if avg_util > target_util {
@@ -1162,13 +1532,81 @@ impl Bank {
         self.interest_curve_scaling = (self.interest_curve_scaling * adjustment).max(1.0)
     }
 
+    /// The range the PID controller rate is clamped to
+    fn pid_rate_bounds(&self) -> (f64, f64) {
+        let scaling = self.interest_curve_scaling.max(1.0);
+        (
+            self.zero_util_rate.to_num::<f64>() * scaling,
+            self.max_rate.to_num::<f64>() * scaling,
+        )
+    }
+
+    fn update_pid_rate(&mut self, avg_util: f64, target_util: f64) {
+        let (min_rate, max_rate) = self.pid_rate_bounds();
+
+        let error = avg_util - target_util;
+        let derivative = error - self.pid_last_error as f64;
+        let integral = self.pid_integral + error;
+
+        let rate = min_rate
+            + self.pid_kp as f64 * error
+            + self.pid_ki as f64 * integral
+            + self.pid_kd as f64 * derivative;
+
+        // anti-windup: stop integrating while the output is saturated in the error's direction
+        let saturated = (rate >= max_rate && error > 0.0) || (rate <= min_rate && error < 0.0);
+        if !saturated {
+            self.pid_integral = integral;
+        }
+        self.pid_rate = rate.max(min_rate).min(max_rate);
+        self.pid_last_error = error as f32;
+    }
+
+    /// Restarts the PID controller such that it continues from the rate the bank
+    /// currently charges at avg_utilization.
+    ///
+    /// Must be called before switching to the PID controller curve or changing its gains.
+    pub fn reset_pid_state(&mut self) {
+        let (min_rate, max_rate) = self.pid_rate_bounds();
+        let rate = self
+            .compute_interest_rate(self.avg_utilization)
+            .to_num::<f64>()
+            .max(min_rate)
+            .min(max_rate);
+        self.pid_rate = rate;
+        self.pid_integral = if self.pid_ki > 0.0 {
+            (rate - min_rate) / self.pid_ki as f64
+        } else {
+            0.0
+        };
+        self.pid_last_error = 0.0;
+    }
+
+    pub fn is_median_oracle_mode(&self) -> bool {
+        self.median_oracle_quorum > 0
+    }
+
     /// Tries to return the primary oracle price, and if there is a confidence or staleness issue returns the fallback oracle price if possible.
+    ///
+    /// In median oracle mode, returns the median price of all valid oracles instead.
     pub fn oracle_price<T: KeyedAccountReader>(
         &self,
         oracle_acc_infos: &OracleAccountInfos<T>,
         staleness_slot: Option<u64>,
     ) -> Result<I80F48> {
         require_keys_eq!(self.oracle, *oracle_acc_infos.oracle.key());
+        if self.is_median_oracle_mode() {
+            let state = oracle::median_oracle_state(
+                oracle_acc_infos,
+                &self.median_oracles,
+                self.median_oracle_quorum,
+                &self.oracle_config,
+                self.mint_decimals,
+                staleness_slot,
+            )
+            .with_context(|| format!("median oracle for {}", self.name()))?;
+            return Ok(state.price);
+        }
         let primary_state = oracle::oracle_state_unchecked(oracle_acc_infos, self.mint_decimals)?;
         let primary_ok =
             primary_state.check_confidence_and_maybe_staleness(&self.oracle_config, staleness_slot);
@@ -1325,8 +1763,9 @@ mod tests {
             cumulative_deposit_interest: 0.0,
             cumulative_borrow_interest: 0.0,
             previous_index: I80F48::ZERO,
+            fixed_term_principal: 0,
             padding: Default::default(),
-            reserved: [0; 128],
+            reserved: [0; 120],
         };
 
         account.indexed_position = indexed(I80F48::from_num(start), &bank);
@@ -1347,11 +1786,11 @@ mod tests {
         let dummy_now_ts = 1 as u64;
         let dummy_price = I80F48::ZERO;
         let is_active = if use_withdraw {
-            bank.withdraw_with_fee(&mut account, change, dummy_now_ts)
+            bank.withdraw_with_fee(&mut account, change, dummy_now_ts, dummy_price)
                 .unwrap()
                 .position_is_active
         } else {
-            bank.change_with_fee(&mut account, change, dummy_now_ts)
+            bank.change_with_fee(&mut account, change, dummy_now_ts, dummy_price)
                 .unwrap()
                 .position_is_active
         };
@@ -1452,8 +1891,9 @@ mod tests {
             cumulative_deposit_interest: 0.0,
             cumulative_borrow_interest: 0.0,
             previous_index: I80F48::ZERO,
+            fixed_term_principal: 0,
             padding: Default::default(),
-            reserved: [0; 128],
+            reserved: [0; 120],
         };
 
         //
@@ -1556,7 +1996,8 @@ mod tests {
             let mut a1 = account_proto.clone();
             bank.deposit(&mut a1, amount, 0).unwrap();
             let mut a2 = account_proto.clone();
-            bank.withdraw_with_fee(&mut a2, amount, 0).unwrap();
+            bank.withdraw_with_fee(&mut a2, amount, 0, I80F48::ONE)
+                .unwrap();
 
             bank.net_borrow_limit_per_window_quote = 100;
             bank.net_borrows_in_window = 200;
@@ -1584,8 +2025,12 @@ mod tests {
 
         let compute_new_avg_utilization_runner =
             |bank: &mut Bank, utilization: I80F48, now_ts: u64| {
-                bank.avg_utilization =
-                    bank.compute_new_avg_utilization(I80F48::ONE, utilization, now_ts);
+                bank.avg_utilization = bank.compute_new_avg_utilization(
+                    I80F48::ONE,
+                    utilization,
+                    I80F48::ZERO,
+                    now_ts,
+                );
                 bank.index_last_updated = now_ts;
             };
 
@@ -1658,6 +2103,116 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_borrow_limits() -> Result<()> {
+        let mut bank = Bank::zeroed();
+        bank.net_borrow_limit_window_size_ts = 100;
+        bank.deposit_index = I80F48::ONE;
+        bank.borrow_index = I80F48::ONE;
+        bank.borrow_limit = 1000;
+        bank.account_borrow_limit_quote = 1200;
+        let price = I80F48::from(2);
+
+        let mut account0 = TokenPosition::default();
+        let mut account1 = TokenPosition::default();
+
+        // withdrawing deposits is not limited
+        bank.deposit(&mut account0, I80F48::from(2000), 0)?;
+        bank.withdraw_with_fee(&mut account0, I80F48::from(2000), 0, price)?;
+
+        // the per-account limit applies to the quote value of the account's total borrows
+        bank.withdraw_with_fee(&mut account0, I80F48::from(500), 0, price)?;
+        bank.withdraw_with_fee(&mut account0, I80F48::from(100), 0, price)?;
+        let result = bank.withdraw_with_fee(&mut account0, I80F48::from(1), 0, price);
+        assert!(result.is_anchor_error_with_code(MangoError::AccountBorrowLimit.error_code()));
+
+        // the total limit applies over all accounts
+        bank.withdraw_with_fee(&mut account1, I80F48::from(399), 0, price)?;
+        let result = bank.withdraw_with_fee(&mut account1, I80F48::from(2), 0, price);
+        assert!(result.is_anchor_error_with_code(MangoError::BankTotalBorrowLimit.error_code()));
+
+        // borrowing without the loan origination fee is limited too
+        let result =
+            bank.withdraw_without_fee_with_borrow_limits(&mut account1, I80F48::from(1), 0, price);
+        assert!(result.is_anchor_error_with_code(MangoError::BankTotalBorrowLimit.error_code()));
+
+        // liquidation isn't limited
+        bank.withdraw_with_fee_without_borrow_limits(&mut account1, I80F48::from(2), 0)?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_fixed_term_borrows_count_as_borrows() -> Result<()> {
+        let mut bank = Bank::zeroed();
+        bank.net_borrow_limit_window_size_ts = 100;
+        bank.deposit_index = I80F48::ONE;
+        bank.borrow_index = I80F48::ONE;
+        bank.min_vault_to_deposits_ratio = 0.2;
+        bank.account_borrow_limit_quote = 500;
+        bank.util0 = I80F48::from_num(0.5);
+        bank.rate0 = I80F48::from_num(0.1);
+        bank.util1 = I80F48::from_num(0.75);
+        bank.rate1 = I80F48::from_num(0.5);
+        bank.max_rate = I80F48::from_num(1.0);
+        bank.interest_curve_scaling = 1.0;
+
+        let mut lender = TokenPosition::default();
+        bank.deposit(&mut lender, I80F48::from(1000), 0)?;
+        bank.fixed_term_borrows = I80F48::from(500);
+        bank.fixed_term_yearly_interest = I80F48::from(50);
+
+        // fixed term loans raise utilization and the variable rate, and depositors
+        // receive their locked interest instead of the variable rate
+        let (deposit_index, _, _, borrow_rate, deposit_rate) = bank.compute_index(
+            bank.indexed_deposits,
+            bank.indexed_borrows,
+            bank.fixed_term_borrows,
+            bank.fixed_term_yearly_interest,
+            I80F48::from(3600),
+        )?;
+        assert_eq!(
+            borrow_rate,
+            bank.compute_interest_rate(I80F48::from_num(0.5))
+        );
+        assert!((deposit_rate.to_num::<f64>() - 0.05).abs() < 1e-9);
+        assert!(
+            ((deposit_index - bank.deposit_index).to_num::<f64>() - 0.05 * 3600.0 / 31_536_000.0)
+                .abs()
+                < 1e-12
+        );
+        let avg_utilization = bank.compute_new_avg_utilization(
+            bank.indexed_deposits,
+            bank.indexed_borrows,
+            bank.fixed_term_borrows,
+            1,
+        );
+        assert_eq!(avg_utilization, I80F48::from_num(0.5));
+
+        // the vault reserve is shared with fixed term loans
+        let mut borrower = TokenPosition::default();
+        bank.withdraw_without_fee(&mut borrower, I80F48::from(299), 0)?;
+        bank.enforce_max_utilization_on_borrow()?;
+        bank.withdraw_without_fee(&mut borrower, I80F48::from(2), 0)?;
+        let result = bank.enforce_max_utilization_on_borrow();
+        assert!(result.is_anchor_error_with_code(MangoError::BankBorrowLimitReached.error_code()));
+
+        // the account borrow limit applies to the loan principal not covered by the position
+        let mut position = TokenPosition {
+            fixed_term_principal: 400,
+            ..TokenPosition::default()
+        };
+        bank.deposit(&mut position, I80F48::from(400), 0)?;
+        bank.check_borrow_limits(&position, I80F48::ONE)?;
+        bank.withdraw_without_fee(&mut position, I80F48::from(500), 0)?;
+        bank.check_borrow_limits(&position, I80F48::ONE)?;
+        bank.withdraw_without_fee(&mut position, I80F48::from(1), 0)?;
+        let result = bank.check_borrow_limits(&position, I80F48::ONE);
+        assert!(result.is_anchor_error_with_code(MangoError::AccountBorrowLimit.error_code()));
+
+        Ok(())
+    }
+
     #[test]
     pub fn test_bank_maint_weight_shift() -> Result<()> {
         let mut bank = Bank::zeroed();
@@ -1733,6 +2288,8 @@ mod tests {
                 .compute_index(
                     bank.indexed_deposits,
                     bank.indexed_borrows,
+                    I80F48::ZERO,
+                    I80F48::ZERO,
                     I80F48::from(interval),
                 )
                 .unwrap();
@@ -1792,4 +2349,92 @@ mod tests {
         assert!(d(interest(0.75 + delta), 7.0) <= eps);
         assert!(d(interest(1.0 - delta), 13.0) <= eps);
     }
+
+    #[test]
+    fn test_bank_kinked_exponential_interest_rate_curve() {
+        let mut bank = Bank::zeroed();
+        bank.interest_curve_type = InterestCurveType::KinkedExponential.into();
+        bank.zero_util_rate = I80F48::from(1);
+        bank.rate0 = I80F48::from(2);
+        bank.max_rate = I80F48::from(32);
+        bank.util0 = I80F48::from_num(0.5);
+
+        let interest = |v: f64| {
+            bank.compute_interest_rate(I80F48::from_num(v))
+                .to_num::<f64>()
+        };
+        let d = |a: f64, b: f64| (a - b).abs();
+
+        let eps = 0.0001;
+        assert!(d(interest(0.0), 1.0) <= eps);
+        assert!(d(interest(0.25), 1.5) <= eps);
+        assert!(d(interest(0.5), 2.0) <= eps);
+        // doubles every 1/8 of utilization above the kink
+        assert!(d(interest(0.625), 4.0) <= eps);
+        assert!(d(interest(0.75), 8.0) <= eps);
+        assert!(d(interest(1.0), 32.0) <= eps);
+        assert!(d(interest(1.5), 32.0) <= eps);
+
+        let mut scaled_bank = bank;
+        scaled_bank.interest_curve_scaling = 2.0;
+        let scaled_interest = scaled_bank
+            .compute_interest_rate(I80F48::from_num(0.75))
+            .to_num::<f64>();
+        assert!(d(scaled_interest, 16.0) <= eps);
+    }
+
+    #[test]
+    fn test_bank_pid_interest_rate() {
+        let mut bank = Bank::zeroed();
+        bank.zero_util_rate = I80F48::from_num(0.01);
+        bank.rate0 = I80F48::from_num(0.1);
+        bank.rate1 = I80F48::from_num(0.2);
+        bank.max_rate = I80F48::from_num(1.0);
+        bank.util0 = I80F48::from_num(0.5);
+        bank.util1 = I80F48::from_num(0.75);
+        bank.interest_curve_scaling = 1.0;
+        bank.interest_target_utilization = 0.5;
+        bank.avg_utilization = I80F48::from_num(0.5);
+        bank.pid_kp = 0.1;
+        bank.pid_ki = 0.1;
+
+        // switching continues from the current rate
+        bank.reset_pid_state();
+        bank.interest_curve_type = InterestCurveType::PidController.into();
+        let rate = |bank: &Bank| bank.compute_interest_rate(I80F48::ZERO).to_num::<f64>();
+        let d = |a: f64, b: f64| (a - b).abs();
+        assert!(d(rate(&bank), 0.1) <= 0.0001);
+
+        // at target the rate doesn't change
+        bank.update_interest_rate_scaling();
+        assert!(d(rate(&bank), 0.1) <= 0.0001);
+
+        // above target the rate increases every step
+        bank.avg_utilization = I80F48::from_num(0.9);
+        let mut last_rate = rate(&bank);
+        for _ in 0..5 {
+            bank.update_interest_rate_scaling();
+            assert!(rate(&bank) > last_rate);
+            last_rate = rate(&bank);
+        }
+        assert_eq!(bank.interest_curve_scaling, 1.0);
+
+        // it's bounded by max_rate and doesn't wind up while saturated
+        for _ in 0..100 {
+            bank.update_interest_rate_scaling();
+        }
+        assert!(d(rate(&bank), 1.0) <= 0.0001);
+        let integral = bank.pid_integral;
+        bank.update_interest_rate_scaling();
+        assert_eq!(bank.pid_integral, integral);
+
+        // below target it comes back down, but not below zero_util_rate
+        bank.avg_utilization = I80F48::from_num(0.0);
+        bank.update_interest_rate_scaling();
+        assert!(rate(&bank) < 1.0);
+        for _ in 0..100 {
+            bank.update_interest_rate_scaling();
+        }
+        assert!(d(rate(&bank), 0.01) <= 0.0001);
+    }
 }
@@ -6,6 +6,8 @@ use fixed::types::I80F48;
 
 use crate::events::{Equity, TokenEquity};
 use crate::health::ScanningAccountRetriever;
+use crate::openbook_v2_cpi::OpenOrdersSlim;
+use crate::serum3_cpi::OpenOrdersAmounts;
 
 use super::MangoAccountRef;
 
@@ -49,6 +51,34 @@ pub fn compute_equity(
         token_equity_map.insert(serum_account.quote_token_index, new_equity);
     }
 
+    // token contributions from OpenbookV2
+    for openbook_account in account.active_openbook_v2_orders() {
+        let oo = retriever.scanned_openbook_v2_oo(&openbook_account.open_orders)?;
+        let oo = OpenOrdersSlim::from_oo(
+            oo,
+            openbook_account.base_lot_size,
+            openbook_account.quote_lot_size,
+        );
+
+        // note base token value
+        let (_bank, oracle_price) =
+            retriever.scanned_bank_and_oracle(openbook_account.base_token_index)?;
+        let accumulated_equity = token_equity_map
+            .get(&openbook_account.base_token_index)
+            .unwrap_or(&I80F48::ZERO);
+        let new_equity = accumulated_equity + I80F48::from(oo.native_base_total()) * oracle_price;
+        token_equity_map.insert(openbook_account.base_token_index, new_equity);
+
+        // note quote token value
+        let (_bank, oracle_price) =
+            retriever.scanned_bank_and_oracle(openbook_account.quote_token_index)?;
+        let accumulated_equity = token_equity_map
+            .get(&openbook_account.quote_token_index)
+            .unwrap_or(&I80F48::ZERO);
+        let new_equity = accumulated_equity + I80F48::from(oo.native_quote_total()) * oracle_price;
+        token_equity_map.insert(openbook_account.quote_token_index, new_equity);
+    }
+
     let tokens = token_equity_map
         .iter()
         .map(|tuple| TokenEquity {
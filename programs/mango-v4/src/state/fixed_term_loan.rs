@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use derivative::Derivative;
+use fixed::types::I80F48;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::state::*;
+
+/// A borrow at a rate that is locked for a fixed term.
+///
+/// The borrowed tokens are credited to the account's token position on creation,
+/// and the loan is an additional liability in that token until it is settled.
+/// Like variable rate borrows, the loan is lent out of the bank's deposits and counts
+/// towards its utilization. Interest accrues linearly at `rate` until settlement and
+/// is paid to the bank's depositors continuously through the deposit index.
+///
+/// At maturity anyone can settle the loan: the amount owed is taken from the
+/// account's token position, which becomes a variable rate borrow if it's not
+/// enough. If `auto_roll` isn't set, that shortfall is charged the bank's
+/// liquidation_fee as a penalty for not repaying in time.
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Derivative, PartialEq)]
+#[derivative(Debug)]
+pub struct FixedTermLoan {
+    pub id: u64,
+
+    /// Borrowed amount plus the loan origination fee, in native tokens
+    pub principal: u64,
+
+    /// Locked interest rate, as APR
+    pub rate: f64,
+
+    pub start_timestamp: u64,
+
+    /// Timestamp at which the loan is due
+    pub maturity_timestamp: u64,
+
+    pub token_index: TokenIndex,
+
+    /// If this struct is in use. (fixed term loans are stored in a static-length array)
+    pub is_configured: u8,
+
+    /// If 1, settling at maturity rolls the debt into a variable rate borrow without penalty
+    pub auto_roll: u8,
+
+    pub padding: [u8; 4],
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 64],
+}
+
+const_assert_eq!(size_of::<FixedTermLoan>(), 8 * 5 + 2 + 1 * 2 + 4 + 64);
+const_assert_eq!(size_of::<FixedTermLoan>(), 112);
+const_assert_eq!(size_of::<FixedTermLoan>() % 8, 0);
+
+impl Default for FixedTermLoan {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            principal: 0,
+            rate: 0.0,
+            start_timestamp: 0,
+            maturity_timestamp: u64::MAX,
+            token_index: TokenIndex::MAX,
+            is_configured: 0,
+            auto_roll: 0,
+            padding: Default::default(),
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl FixedTermLoan {
+    /// Whether the entry is in use
+    pub fn is_configured(&self) -> bool {
+        self.is_configured == 1
+    }
+
+    pub fn set_is_configured(&mut self, is_configured: bool) {
+        self.is_configured = u8::from(is_configured);
+    }
+
+    pub fn auto_roll(&self) -> bool {
+        self.auto_roll == 1
+    }
+
+    pub fn is_matured(&self, now_ts: u64) -> bool {
+        now_ts >= self.maturity_timestamp
+    }
+
+    /// Interest accrued per year at the locked rate, in native tokens
+    pub fn yearly_interest(&self) -> I80F48 {
+        I80F48::from(self.principal) * I80F48::from_num(self.rate)
+    }
+
+    /// Interest accrued until now_ts, in native tokens
+    pub fn interest(&self, now_ts: u64) -> I80F48 {
+        let elapsed = I80F48::from(now_ts.saturating_sub(self.start_timestamp));
+        self.yearly_interest() * elapsed / YEAR_I80F48
+    }
+
+    /// Principal plus accrued interest, in native tokens
+    pub fn owed(&self, now_ts: u64) -> I80F48 {
+        I80F48::from(self.principal) + self.interest(now_ts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_term_loan_owed() {
+        let loan = FixedTermLoan {
+            principal: 1_000_000,
+            rate: 0.1,
+            start_timestamp: 1000,
+            maturity_timestamp: 1000 + 31_536_000 / 2,
+            is_configured: 1,
+            ..FixedTermLoan::default()
+        };
+        assert!(!loan.is_matured(1000));
+        assert!(loan.is_matured(1000 + 31_536_000 / 2));
+        assert_eq!(loan.owed(500), I80F48::from(1_000_000));
+        assert_eq!(loan.owed(1000), I80F48::from(1_000_000));
+        assert!((loan.owed(1000 + 31_536_000 / 2).to_num::<f64>() - 1_050_000.0).abs() < 0.01);
+        // keeps accruing at the locked rate until settled
+        assert!((loan.owed(1000 + 31_536_000).to_num::<f64>() - 1_100_000.0).abs() < 0.01);
+    }
+}
@@ -12,15 +12,15 @@ pub type TokenIndex = u16;
 /// incorrect assumption.
 pub const QUOTE_TOKEN_INDEX: TokenIndex = 0;
 
-/// The token index used for the insurance fund.
+/// The token index used for the group insurance fund.
 ///
-/// We should eventually generalize insurance funds.
+/// Tokens can additionally have a dedicated TokenInsuranceFund in their own token.
 pub const INSURANCE_TOKEN_INDEX: TokenIndex = 0;
 
-/// The token index used for settling perp markets.
+/// The default token index used for settling perp markets.
 ///
-/// We should eventually generalize to make the whole perp quote (and settle) token
-/// configurable.
+/// Each perp market stores its own PerpMarket::settle_token_index, which may differ
+/// from this. Only perp markets settling in this token accrue buyback fees.
 pub const PERP_SETTLE_TOKEN_INDEX: TokenIndex = 0;
 
 /// The token index used in AccountBuybackFeesWithMngo to exchange for MNGO
@@ -235,7 +235,7 @@ pub enum IxGate {
     OpenbookV2EditMarket = 59,
     OpenbookV2LiqForceCancelOrders = 60,
     OpenbookV2PlaceOrder = 61,
-    OpenbookV2PlaceTakeOrder = 62,
+    // 62 was OpenbookV2PlaceTakeOrder, don't reuse it
     OpenbookV2RegisterMarket = 63,
     OpenbookV2SettleFunds = 64,
     AdminTokenWithdrawFees = 65,
@@ -245,6 +245,17 @@ pub enum IxGate {
     TokenConditionalSwapCreatePremiumAuction = 69,
     TokenConditionalSwapCreateLinearAuction = 70,
     Serum3PlaceOrderV2 = 71,
+    PerpTriggerOrderCreate = 72,
+    PerpTriggerOrderCancel = 73,
+    PerpTriggerOrderExecute = 74,
+    PerpEditOrder = 75,
+    PerpPlaceOrdersBatch = 76,
+    TokenInsuranceFundCreate = 77,
+    TokenInsuranceFundWithdraw = 78,
+    TokenInsuranceFundCover = 79,
+    FixedTermLoanCreate = 80,
+    FixedTermLoanSettle = 81,
+    TokenRateHistoryCreate = 82,
     // NOTE: Adding new variants requires matching changes in ts and the ix_gate_set instruction.
 }
 
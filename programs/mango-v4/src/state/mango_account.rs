@@ -18,23 +18,26 @@ use crate::util;
 
 use super::BookSideOrderTree;
 use super::FillEvent;
+use super::FixedTermLoan;
 use super::LeafNode;
+use super::OpenbookV2MarketIndex;
 use super::PerpMarket;
 use super::PerpMarketIndex;
 use super::PerpOpenOrder;
+use super::PerpTriggerOrder;
 use super::Serum3MarketIndex;
 use super::TokenConditionalSwap;
 use super::TokenIndex;
 use super::FREE_ORDER_SLOT;
 use super::{dynamic_account::*, Group};
-use super::{PerpPosition, Serum3Orders, TokenPosition};
+use super::{OpenbookV2Orders, PerpPosition, Serum3Orders, TokenPosition};
 use super::{Side, SideAndOrderTree};
 
 type BorshVecLength = u32;
 const BORSH_VEC_PADDING_BYTES: usize = 4;
 const BORSH_VEC_SIZE_BYTES: usize = 4;
 const DEFAULT_MANGO_ACCOUNT_VERSION: u8 = 1;
-const DYNAMIC_RESERVED_BYTES: usize = 64;
+const DYNAMIC_RESERVED_BYTES: usize = 40;
 
 // Return variants for check_liquidatable method, should be wrapped in a Result
 // for a future possiblity of returning any error
@@ -157,8 +160,14 @@ pub struct MangoAccount {
     /// Time at which the last collateral fee was charged
     pub last_collateral_fee_charge: u64,
 
+    /// Next id to use when adding a perp trigger order
+    pub next_perp_trigger_order_id: u64,
+
+    /// Next id to use when adding a fixed term loan
+    pub next_fixed_term_loan_id: u64,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 152],
+    pub reserved: [u8; 136],
 
     // dynamic
     pub header_version: u8,
@@ -184,9 +193,20 @@ pub struct MangoAccount {
     #[derivative(Debug = "ignore")]
     pub padding8: u32,
     pub token_conditional_swaps: Vec<TokenConditionalSwap>,
+    #[derivative(Debug = "ignore")]
+    pub padding9: u32,
+    // Maps openbook_v2_market_index -> open orders for each openbook v2 market
+    // that is active on this MangoAccount.
+    pub openbook_v2: Vec<OpenbookV2Orders>,
+    #[derivative(Debug = "ignore")]
+    pub padding10: u32,
+    pub perp_trigger_orders: Vec<PerpTriggerOrder>,
+    #[derivative(Debug = "ignore")]
+    pub padding11: u32,
+    pub fixed_term_loans: Vec<FixedTermLoan>,
 
     #[derivative(Debug = "ignore")]
-    pub reserved_dynamic: [u8; 64],
+    pub reserved_dynamic: [u8; 40],
 }
 
 impl MangoAccount {
@@ -212,7 +232,9 @@ impl MangoAccount {
             temporary_delegate: Pubkey::default(),
             temporary_delegate_expiry: 0,
             last_collateral_fee_charge: 0,
-            reserved: [0; 152],
+            next_perp_trigger_order_id: 0,
+            next_fixed_term_loan_id: 0,
+            reserved: [0; 136],
             header_version: DEFAULT_MANGO_ACCOUNT_VERSION,
             padding3: Default::default(),
             padding4: Default::default(),
@@ -225,7 +247,13 @@ impl MangoAccount {
             perp_open_orders: vec![PerpOpenOrder::default(); 6],
             padding8: Default::default(),
             token_conditional_swaps: vec![TokenConditionalSwap::default(); 2],
-            reserved_dynamic: [0; 64],
+            padding9: Default::default(),
+            openbook_v2: vec![OpenbookV2Orders::default(); 3],
+            padding10: Default::default(),
+            perp_trigger_orders: vec![PerpTriggerOrder::default(); 2],
+            padding11: Default::default(),
+            fixed_term_loans: vec![FixedTermLoan::default(); 2],
+            reserved_dynamic: [0; 40],
         }
     }
 
@@ -236,6 +264,9 @@ impl MangoAccount {
         perp_count: u8,
         perp_oo_count: u8,
         token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+        fixed_term_loan_count: u8,
     ) -> usize {
         8 + size_of::<MangoAccountFixed>()
             + Self::dynamic_size(
@@ -244,6 +275,9 @@ impl MangoAccount {
                 perp_count,
                 perp_oo_count,
                 token_conditional_swap_count,
+                openbook_v2_count,
+                perp_trigger_order_count,
+                fixed_term_loan_count,
             )
     }
 
@@ -281,7 +315,7 @@ impl MangoAccount {
             + BORSH_VEC_PADDING_BYTES
     }
 
-    pub fn dynamic_reserved_bytes_offset(
+    pub fn dynamic_openbook_v2_vec_offset(
         token_count: u8,
         serum3_count: u8,
         perp_count: u8,
@@ -295,6 +329,67 @@ impl MangoAccount {
             perp_oo_count,
         ) + (BORSH_VEC_SIZE_BYTES
             + size_of::<TokenConditionalSwap>() * usize::from(token_conditional_swap_count))
+            + BORSH_VEC_PADDING_BYTES
+    }
+
+    pub fn dynamic_perp_trigger_order_vec_offset(
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+    ) -> usize {
+        Self::dynamic_openbook_v2_vec_offset(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+        ) + (BORSH_VEC_SIZE_BYTES + size_of::<OpenbookV2Orders>() * usize::from(openbook_v2_count))
+            + BORSH_VEC_PADDING_BYTES
+    }
+
+    pub fn dynamic_fixed_term_loan_vec_offset(
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+    ) -> usize {
+        Self::dynamic_perp_trigger_order_vec_offset(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+        ) + (BORSH_VEC_SIZE_BYTES
+            + size_of::<PerpTriggerOrder>() * usize::from(perp_trigger_order_count))
+            + BORSH_VEC_PADDING_BYTES
+    }
+
+    pub fn dynamic_reserved_bytes_offset(
+        token_count: u8,
+        serum3_count: u8,
+        perp_count: u8,
+        perp_oo_count: u8,
+        token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+        fixed_term_loan_count: u8,
+    ) -> usize {
+        Self::dynamic_fixed_term_loan_vec_offset(
+            token_count,
+            serum3_count,
+            perp_count,
+            perp_oo_count,
+            token_conditional_swap_count,
+            openbook_v2_count,
+            perp_trigger_order_count,
+        ) + (BORSH_VEC_SIZE_BYTES + size_of::<FixedTermLoan>() * usize::from(fixed_term_loan_count))
     }
 
     pub fn dynamic_size(
@@ -303,6 +398,9 @@ impl MangoAccount {
         perp_count: u8,
         perp_oo_count: u8,
         token_conditional_swap_count: u8,
+        openbook_v2_count: u8,
+        perp_trigger_order_count: u8,
+        fixed_term_loan_count: u8,
     ) -> usize {
         Self::dynamic_reserved_bytes_offset(
             token_count,
@@ -310,6 +408,9 @@ impl MangoAccount {
             perp_count,
             perp_oo_count,
             token_conditional_swap_count,
+            openbook_v2_count,
+            perp_trigger_order_count,
+            fixed_term_loan_count,
         ) + DYNAMIC_RESERVED_BYTES
     }
 }
@@ -337,11 +438,13 @@ pub struct MangoAccountFixed {
     pub temporary_delegate: Pubkey,
     pub temporary_delegate_expiry: u64,
     pub last_collateral_fee_charge: u64,
-    pub reserved: [u8; 152],
+    pub next_perp_trigger_order_id: u64,
+    pub next_fixed_term_loan_id: u64,
+    pub reserved: [u8; 136],
 }
 const_assert_eq!(
     size_of::<MangoAccountFixed>(),
-    32 * 4 + 8 + 8 * 8 + 32 + 8 + 8 + 152
+    32 * 4 + 8 + 8 * 8 + 32 + 8 + 8 + 8 + 8 + 136
 );
 const_assert_eq!(size_of::<MangoAccountFixed>(), 400);
 const_assert_eq!(size_of::<MangoAccountFixed>() % 8, 0);
@@ -473,6 +576,9 @@ pub struct MangoAccountDynamicHeader {
     pub perp_count: u8,
     pub perp_oo_count: u8,
     pub token_conditional_swap_count: u8,
+    pub openbook_v2_count: u8,
+    pub perp_trigger_order_count: u8,
+    pub fixed_term_loan_count: u8,
 }
 
 impl DynamicHeader for MangoAccountDynamicHeader {
@@ -529,12 +635,76 @@ impl DynamicHeader for MangoAccountDynamicHeader {
                     0
                 };
 
+                let openbook_v2_vec_offset = MangoAccount::dynamic_openbook_v2_vec_offset(
+                    token_count,
+                    serum3_count,
+                    perp_count,
+                    perp_oo_count,
+                    token_conditional_swap_count,
+                );
+                let openbook_v2_count =
+                    if dynamic_data.len() > openbook_v2_vec_offset + BORSH_VEC_SIZE_BYTES {
+                        u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
+                            dynamic_data,
+                            openbook_v2_vec_offset,
+                            BORSH_VEC_SIZE_BYTES
+                        ]))
+                        .unwrap()
+                    } else {
+                        0
+                    };
+
+                let perp_trigger_order_vec_offset =
+                    MangoAccount::dynamic_perp_trigger_order_vec_offset(
+                        token_count,
+                        serum3_count,
+                        perp_count,
+                        perp_oo_count,
+                        token_conditional_swap_count,
+                        openbook_v2_count,
+                    );
+                let perp_trigger_order_count =
+                    if dynamic_data.len() > perp_trigger_order_vec_offset + BORSH_VEC_SIZE_BYTES {
+                        u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
+                            dynamic_data,
+                            perp_trigger_order_vec_offset,
+                            BORSH_VEC_SIZE_BYTES
+                        ]))
+                        .unwrap()
+                    } else {
+                        0
+                    };
+
+                let fixed_term_loan_vec_offset = MangoAccount::dynamic_fixed_term_loan_vec_offset(
+                    token_count,
+                    serum3_count,
+                    perp_count,
+                    perp_oo_count,
+                    token_conditional_swap_count,
+                    openbook_v2_count,
+                    perp_trigger_order_count,
+                );
+                let fixed_term_loan_count =
+                    if dynamic_data.len() > fixed_term_loan_vec_offset + BORSH_VEC_SIZE_BYTES {
+                        u8::try_from(BorshVecLength::from_le_bytes(*array_ref![
+                            dynamic_data,
+                            fixed_term_loan_vec_offset,
+                            BORSH_VEC_SIZE_BYTES
+                        ]))
+                        .unwrap()
+                    } else {
+                        0
+                    };
+
                 Ok(Self {
                     token_count,
                     serum3_count,
                     perp_count,
                     perp_oo_count,
                     token_conditional_swap_count,
+                    openbook_v2_count,
+                    perp_trigger_order_count,
+                    fixed_term_loan_count,
                 })
             }
             _ => err!(MangoError::NotImplementedError).context("unexpected header version number"),
@@ -564,6 +734,9 @@ impl MangoAccountDynamicHeader {
             self.perp_count,
             self.perp_oo_count,
             self.token_conditional_swap_count,
+            self.openbook_v2_count,
+            self.perp_trigger_order_count,
+            self.fixed_term_loan_count,
         )
     }
 
@@ -609,6 +782,43 @@ impl MangoAccountDynamicHeader {
             + raw_index * size_of::<TokenConditionalSwap>()
     }
 
+    // offset into dynamic data where 1st OpenbookV2Orders would be found
+    pub fn openbook_v2_offset(&self, raw_index: usize) -> usize {
+        MangoAccount::dynamic_openbook_v2_vec_offset(
+            self.token_count,
+            self.serum3_count,
+            self.perp_count,
+            self.perp_oo_count,
+            self.token_conditional_swap_count,
+        ) + BORSH_VEC_SIZE_BYTES
+            + raw_index * size_of::<OpenbookV2Orders>()
+    }
+
+    fn perp_trigger_order_offset(&self, raw_index: usize) -> usize {
+        MangoAccount::dynamic_perp_trigger_order_vec_offset(
+            self.token_count,
+            self.serum3_count,
+            self.perp_count,
+            self.perp_oo_count,
+            self.token_conditional_swap_count,
+            self.openbook_v2_count,
+        ) + BORSH_VEC_SIZE_BYTES
+            + raw_index * size_of::<PerpTriggerOrder>()
+    }
+
+    fn fixed_term_loan_offset(&self, raw_index: usize) -> usize {
+        MangoAccount::dynamic_fixed_term_loan_vec_offset(
+            self.token_count,
+            self.serum3_count,
+            self.perp_count,
+            self.perp_oo_count,
+            self.token_conditional_swap_count,
+            self.openbook_v2_count,
+            self.perp_trigger_order_count,
+        ) + BORSH_VEC_SIZE_BYTES
+            + raw_index * size_of::<FixedTermLoan>()
+    }
+
     fn reserved_bytes_offset(&self) -> usize {
         MangoAccount::dynamic_reserved_bytes_offset(
             self.token_count,
@@ -616,6 +826,9 @@ impl MangoAccountDynamicHeader {
             self.perp_count,
             self.perp_oo_count,
             self.token_conditional_swap_count,
+            self.openbook_v2_count,
+            self.perp_trigger_order_count,
+            self.fixed_term_loan_count,
         )
     }
 
@@ -634,6 +847,15 @@ impl MangoAccountDynamicHeader {
     pub fn token_conditional_swap_count(&self) -> usize {
         self.token_conditional_swap_count.into()
     }
+    pub fn openbook_v2_count(&self) -> usize {
+        self.openbook_v2_count.into()
+    }
+    pub fn perp_trigger_order_count(&self) -> usize {
+        self.perp_trigger_order_count.into()
+    }
+    pub fn fixed_term_loan_count(&self) -> usize {
+        self.fixed_term_loan_count.into()
+    }
 
     pub fn zero() -> Self {
         Self {
@@ -642,11 +864,17 @@ impl MangoAccountDynamicHeader {
             perp_count: 0,
             perp_oo_count: 0,
             token_conditional_swap_count: 0,
+            openbook_v2_count: 0,
+            perp_trigger_order_count: 0,
+            fixed_term_loan_count: 0,
         }
     }
 
     pub fn expected_health_accounts(&self) -> usize {
-        self.token_count() * 2 + self.serum3_count() + self.perp_count() * 2
+        self.token_count() * 2
+            + self.serum3_count()
+            + self.perp_count() * 2
+            + self.openbook_v2_count()
     }
 
     pub fn max_health_accounts() -> usize {
@@ -657,7 +885,7 @@ impl MangoAccountDynamicHeader {
     ///
     /// - Check that the total health accounts stay limited
     ///   (this coverers token, perp, serum position limits)
-    /// - Check that if perp oo/tcs size increases, it is bounded by the limits
+    /// - Check that if perp oo/tcs/trigger order/fixed term loan size increases, it is bounded by the limits
     /// - If a field doesn't change, don't error if it exceeds the limits
     ///   (might have been expanded earlier when it was valid to do)
     pub fn check_resize_from(&self, prev: &Self) -> Result<()> {
@@ -675,6 +903,14 @@ impl MangoAccountDynamicHeader {
             require_gte!(64, self.token_conditional_swap_count);
         }
 
+        if self.perp_trigger_order_count > prev.perp_trigger_order_count {
+            require_gte!(64, self.perp_trigger_order_count);
+        }
+
+        if self.fixed_term_loan_count > prev.fixed_term_loan_count {
+            require_gte!(64, self.fixed_term_loan_count);
+        }
+
         Ok(())
     }
 }
@@ -820,6 +1056,42 @@ impl<
             .filter(|serum3_order| serum3_order.is_active())
     }
 
+    pub fn openbook_v2_orders(
+        &self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<&OpenbookV2Orders> {
+        self.all_openbook_v2_orders()
+            .find(|p| p.is_active_for_market(market_index))
+            .ok_or_else(|| {
+                error_msg!(
+                    "openbook v2 orders for market index {} not found",
+                    market_index
+                )
+            })
+    }
+
+    pub(crate) fn openbook_v2_orders_by_raw_index_unchecked(
+        &self,
+        raw_index: usize,
+    ) -> &OpenbookV2Orders {
+        get_helper(self.dynamic(), self.header().openbook_v2_offset(raw_index))
+    }
+
+    pub fn openbook_v2_orders_by_raw_index(&self, raw_index: usize) -> Result<&OpenbookV2Orders> {
+        require_gt!(self.header().openbook_v2_count(), raw_index);
+        Ok(self.openbook_v2_orders_by_raw_index_unchecked(raw_index))
+    }
+
+    pub fn all_openbook_v2_orders(&self) -> impl Iterator<Item = &OpenbookV2Orders> + '_ {
+        (0..self.header().openbook_v2_count())
+            .map(|i| self.openbook_v2_orders_by_raw_index_unchecked(i))
+    }
+
+    pub fn active_openbook_v2_orders(&self) -> impl Iterator<Item = &OpenbookV2Orders> + '_ {
+        self.all_openbook_v2_orders()
+            .filter(|openbook_v2_order| openbook_v2_order.is_active())
+    }
+
     pub fn perp_position(&self, market_index: PerpMarketIndex) -> Result<&PerpPosition> {
         self.all_perp_positions()
             .find(|p| p.is_active_for_market(market_index))
@@ -922,6 +1194,75 @@ impl<
             .ok_or_else(|| error_msg!("no free token conditional swap index"))
     }
 
+    fn perp_trigger_order_by_index_unchecked(&self, index: usize) -> &PerpTriggerOrder {
+        get_helper(
+            self.dynamic(),
+            self.header().perp_trigger_order_offset(index),
+        )
+    }
+
+    pub fn perp_trigger_order_by_index(&self, index: usize) -> Result<&PerpTriggerOrder> {
+        require_gt!(self.header().perp_trigger_order_count(), index);
+        Ok(self.perp_trigger_order_by_index_unchecked(index))
+    }
+
+    pub fn perp_trigger_order_by_id(&self, id: u64) -> Result<(usize, &PerpTriggerOrder)> {
+        let index = self
+            .all_perp_trigger_orders()
+            .position(|order| order.is_configured() && order.id == id)
+            .ok_or_else(|| error_msg!("perp trigger order with id {} not found", id))?;
+        Ok((index, self.perp_trigger_order_by_index_unchecked(index)))
+    }
+
+    pub fn all_perp_trigger_orders(&self) -> impl Iterator<Item = &PerpTriggerOrder> {
+        (0..self.header().perp_trigger_order_count())
+            .map(|i| self.perp_trigger_order_by_index_unchecked(i))
+    }
+
+    pub fn active_perp_trigger_orders(&self) -> impl Iterator<Item = &PerpTriggerOrder> {
+        self.all_perp_trigger_orders()
+            .filter(|order| order.is_configured())
+    }
+
+    pub fn perp_trigger_order_free_index(&self) -> Result<usize> {
+        self.all_perp_trigger_orders()
+            .position(|order| !order.is_configured())
+            .ok_or_else(|| error_msg!("no free perp trigger order index"))
+    }
+
+    fn fixed_term_loan_by_index_unchecked(&self, index: usize) -> &FixedTermLoan {
+        get_helper(self.dynamic(), self.header().fixed_term_loan_offset(index))
+    }
+
+    pub fn fixed_term_loan_by_index(&self, index: usize) -> Result<&FixedTermLoan> {
+        require_gt!(self.header().fixed_term_loan_count(), index);
+        Ok(self.fixed_term_loan_by_index_unchecked(index))
+    }
+
+    pub fn all_fixed_term_loans(&self) -> impl Iterator<Item = &FixedTermLoan> {
+        (0..self.header().fixed_term_loan_count())
+            .map(|i| self.fixed_term_loan_by_index_unchecked(i))
+    }
+
+    pub fn active_fixed_term_loans(&self) -> impl Iterator<Item = &FixedTermLoan> {
+        self.all_fixed_term_loans()
+            .filter(|loan| loan.is_configured())
+    }
+
+    pub fn fixed_term_loan_free_index(&self) -> Result<usize> {
+        self.all_fixed_term_loans()
+            .position(|loan| !loan.is_configured())
+            .ok_or_else(|| error_msg!("no free fixed term loan index"))
+    }
+
+    /// Sum of the amounts owed on all fixed term loans in the token, in native tokens
+    pub fn fixed_term_loans_owed(&self, token_index: TokenIndex, now_ts: u64) -> I80F48 {
+        self.active_fixed_term_loans()
+            .filter(|loan| loan.token_index == token_index)
+            .map(|loan| loan.owed(now_ts))
+            .sum()
+    }
+
     pub fn borrow(&self) -> MangoAccountRef {
         MangoAccountRef {
             header: self.header(),
@@ -1015,8 +1356,9 @@ impl<
                     cumulative_deposit_interest: 0.0,
                     cumulative_borrow_interest: 0.0,
                     previous_index: I80F48::ZERO,
+                    fixed_term_principal: 0,
                     padding: Default::default(),
-                    reserved: [0; 128],
+                    reserved: [0; 120],
                 };
             }
             Ok((v, raw_index, bank_index))
@@ -1122,6 +1464,67 @@ impl<
             .ok_or_else(|| error_msg!("serum3 orders for market index {} not found", market_index))
     }
 
+    // get mut OpenbookV2Orders at raw_index
+    pub fn openbook_v2_orders_mut_by_raw_index(
+        &mut self,
+        raw_index: usize,
+    ) -> &mut OpenbookV2Orders {
+        let offset = self.header().openbook_v2_offset(raw_index);
+        get_helper_mut(self.dynamic_mut(), offset)
+    }
+
+    pub fn create_openbook_v2_orders(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<&mut OpenbookV2Orders> {
+        if self.openbook_v2_orders(market_index).is_ok() {
+            return err!(MangoError::OpenbookV2OpenOrdersExistAlready);
+        }
+
+        let raw_index_opt = self.all_openbook_v2_orders().position(|p| !p.is_active());
+        if let Some(raw_index) = raw_index_opt {
+            *(self.openbook_v2_orders_mut_by_raw_index(raw_index)) = OpenbookV2Orders {
+                market_index: market_index as OpenbookV2MarketIndex,
+                ..OpenbookV2Orders::default()
+            };
+            Ok(self.openbook_v2_orders_mut_by_raw_index(raw_index))
+        } else {
+            err!(MangoError::NoFreeOpenbookV2OpenOrdersIndex)
+        }
+    }
+
+    pub fn deactivate_openbook_v2_orders(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<()> {
+        let raw_index = self
+            .all_openbook_v2_orders()
+            .position(|p| p.is_active_for_market(market_index))
+            .ok_or_else(|| {
+                error_msg!("openbook v2 open orders index {} not found", market_index)
+            })?;
+        self.openbook_v2_orders_mut_by_raw_index(raw_index)
+            .market_index = OpenbookV2MarketIndex::MAX;
+        Ok(())
+    }
+
+    pub fn openbook_v2_orders_mut(
+        &mut self,
+        market_index: OpenbookV2MarketIndex,
+    ) -> Result<&mut OpenbookV2Orders> {
+        let raw_index_opt = self
+            .all_openbook_v2_orders()
+            .position(|p| p.is_active_for_market(market_index));
+        raw_index_opt
+            .map(|raw_index| self.openbook_v2_orders_mut_by_raw_index(raw_index))
+            .ok_or_else(|| {
+                error_msg!(
+                    "openbook v2 orders for market index {} not found",
+                    market_index
+                )
+            })
+    }
+
     // get mut PerpPosition at raw_index
     pub fn perp_position_mut_by_raw_index(&mut self, raw_index: usize) -> &mut PerpPosition {
         let offset = self.header().perp_offset(raw_index);
@@ -1234,7 +1637,7 @@ impl<
         order: &LeafNode,
     ) -> Result<()> {
         let perp_account = self.perp_position_mut(perp_market_index)?;
-        perp_account.adjust_maker_lots(side, order.quantity);
+        perp_account.adjust_maker_lots(side, order.total_quantity());
         let slot = order.owner_slot as usize;
 
         let oo = self.perp_order_mut_by_raw_index(slot);
@@ -1242,7 +1645,7 @@ impl<
         oo.side_and_tree = SideAndOrderTree::new(side, order_tree).into();
         oo.id = order.key;
         oo.client_id = order.client_order_id;
-        oo.quantity = order.quantity;
+        oo.quantity = order.total_quantity();
         Ok(())
     }
 
@@ -1391,6 +1794,35 @@ impl<
         Ok(tcs)
     }
 
+    pub fn perp_trigger_order_mut_by_index(
+        &mut self,
+        index: usize,
+    ) -> Result<&mut PerpTriggerOrder> {
+        let count: usize = self.header().perp_trigger_order_count.into();
+        require_gt!(count, index);
+        let offset = self.header().perp_trigger_order_offset(index);
+        Ok(get_helper_mut(self.dynamic_mut(), offset))
+    }
+
+    pub fn free_perp_trigger_order_mut(&mut self) -> Result<&mut PerpTriggerOrder> {
+        let index = self.perp_trigger_order_free_index()?;
+        let order = self.perp_trigger_order_mut_by_index(index)?;
+        Ok(order)
+    }
+
+    pub fn fixed_term_loan_mut_by_index(&mut self, index: usize) -> Result<&mut FixedTermLoan> {
+        let count: usize = self.header().fixed_term_loan_count.into();
+        require_gt!(count, index);
+        let offset = self.header().fixed_term_loan_offset(index);
+        Ok(get_helper_mut(self.dynamic_mut(), offset))
+    }
+
+    pub fn free_fixed_term_loan_mut(&mut self) -> Result<&mut FixedTermLoan> {
+        let index = self.fixed_term_loan_free_index()?;
+        let loan = self.fixed_term_loan_mut_by_index(index)?;
+        Ok(loan)
+    }
+
     pub fn check_health_pre(&mut self, health_cache: &HealthCache) -> Result<I80F48> {
         let pre_init_health = health_cache.health(HealthType::Init);
         msg!("pre_init_health: {}", pre_init_health);
@@ -1427,6 +1859,7 @@ impl<
         let post_init_health = health_cache.health(HealthType::Init);
         msg!("post_init_health: {}", post_init_health);
         self.check_health_post_checks(pre_init_health, post_init_health)?;
+        health_cache.require_isolated_pools_respected()?;
         Ok(post_init_health)
     }
 
@@ -1523,6 +1956,24 @@ impl<
         self.write_borsh_vec_length_and_padding(offset, count)
     }
 
+    fn write_openbook_v2_length(&mut self) {
+        let offset = self.header().openbook_v2_offset(0);
+        let count = self.header().openbook_v2_count;
+        self.write_borsh_vec_length_and_padding(offset, count)
+    }
+
+    fn write_perp_trigger_order_length(&mut self) {
+        let offset = self.header().perp_trigger_order_offset(0);
+        let count = self.header().perp_trigger_order_count;
+        self.write_borsh_vec_length_and_padding(offset, count)
+    }
+
+    fn write_fixed_term_loan_length(&mut self) {
+        let offset = self.header().fixed_term_loan_offset(0);
+        let count = self.header().fixed_term_loan_count;
+        self.write_borsh_vec_length_and_padding(offset, count)
+    }
+
     pub fn resize_dynamic_content(
         &mut self,
         new_token_count: u8,
@@ -1530,6 +1981,9 @@ impl<
         new_perp_count: u8,
         new_perp_oo_count: u8,
         new_token_conditional_swap_count: u8,
+        new_openbook_v2_count: u8,
+        new_perp_trigger_order_count: u8,
+        new_fixed_term_loan_count: u8,
     ) -> Result<()> {
         let new_header = MangoAccountDynamicHeader {
             token_count: new_token_count,
@@ -1537,6 +1991,9 @@ impl<
             perp_count: new_perp_count,
             perp_oo_count: new_perp_oo_count,
             token_conditional_swap_count: new_token_conditional_swap_count,
+            openbook_v2_count: new_openbook_v2_count,
+            perp_trigger_order_count: new_perp_trigger_order_count,
+            fixed_term_loan_count: new_fixed_term_loan_count,
         };
         let old_header = self.header().clone();
 
@@ -1657,12 +2114,78 @@ impl<
             active_tcs += 1;
         }
 
+        let mut active_openbook_v2_orders = 0;
+        for i in 0..old_header.openbook_v2_count() {
+            let src = old_header.openbook_v2_offset(i);
+            let pos: &OpenbookV2Orders = get_helper(dynamic, src);
+            if !pos.is_active() {
+                continue;
+            }
+            if i != active_openbook_v2_orders {
+                let dst = old_header.openbook_v2_offset(active_openbook_v2_orders);
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[dst],
+                        &mut dynamic[src],
+                        size_of::<OpenbookV2Orders>(),
+                    );
+                }
+            }
+            active_openbook_v2_orders += 1;
+        }
+
+        let mut active_perp_trigger_orders = 0;
+        for i in 0..old_header.perp_trigger_order_count() {
+            let src = old_header.perp_trigger_order_offset(i);
+            let pos: &PerpTriggerOrder = get_helper(dynamic, src);
+            if !pos.is_configured() {
+                continue;
+            }
+            if i != active_perp_trigger_orders {
+                let dst = old_header.perp_trigger_order_offset(active_perp_trigger_orders);
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[dst],
+                        &mut dynamic[src],
+                        size_of::<PerpTriggerOrder>(),
+                    );
+                }
+            }
+            active_perp_trigger_orders += 1;
+        }
+
+        let mut active_fixed_term_loans = 0;
+        for i in 0..old_header.fixed_term_loan_count() {
+            let src = old_header.fixed_term_loan_offset(i);
+            let pos: &FixedTermLoan = get_helper(dynamic, src);
+            if !pos.is_configured() {
+                continue;
+            }
+            if i != active_fixed_term_loans {
+                let dst = old_header.fixed_term_loan_offset(active_fixed_term_loans);
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[dst],
+                        &mut dynamic[src],
+                        size_of::<FixedTermLoan>(),
+                    );
+                }
+            }
+            active_fixed_term_loans += 1;
+        }
+
         // Check that the new allocations can fit the existing data
         require_gte!(new_header.token_count(), active_token_positions);
         require_gte!(new_header.serum3_count(), active_serum3_orders);
         require_gte!(new_header.perp_count(), active_perp_positions);
         require_gte!(new_header.perp_oo_count(), blocked_perp_oo);
         require_gte!(new_header.token_conditional_swap_count(), active_tcs);
+        require_gte!(new_header.openbook_v2_count(), active_openbook_v2_orders);
+        require_gte!(
+            new_header.perp_trigger_order_count(),
+            active_perp_trigger_orders
+        );
+        require_gte!(new_header.fixed_term_loan_count(), active_fixed_term_loans);
 
         // First move pass: go left-to-right and move any blocks that need to be moved
         // to the left. This will never overwrite other data, because:
@@ -1720,6 +2243,45 @@ impl<
                     );
                 }
             }
+
+            let old_openbook_v2_start = old_header.openbook_v2_offset(0);
+            let new_openbook_v2_start = new_header.openbook_v2_offset(0);
+            if new_openbook_v2_start < old_openbook_v2_start && active_openbook_v2_orders > 0 {
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[new_openbook_v2_start],
+                        &mut dynamic[old_openbook_v2_start],
+                        size_of::<OpenbookV2Orders>() * active_openbook_v2_orders,
+                    );
+                }
+            }
+
+            let old_perp_trigger_order_start = old_header.perp_trigger_order_offset(0);
+            let new_perp_trigger_order_start = new_header.perp_trigger_order_offset(0);
+            if new_perp_trigger_order_start < old_perp_trigger_order_start
+                && active_perp_trigger_orders > 0
+            {
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[new_perp_trigger_order_start],
+                        &mut dynamic[old_perp_trigger_order_start],
+                        size_of::<PerpTriggerOrder>() * active_perp_trigger_orders,
+                    );
+                }
+            }
+
+            let old_fixed_term_loan_start = old_header.fixed_term_loan_offset(0);
+            let new_fixed_term_loan_start = new_header.fixed_term_loan_offset(0);
+            if new_fixed_term_loan_start < old_fixed_term_loan_start && active_fixed_term_loans > 0
+            {
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[new_fixed_term_loan_start],
+                        &mut dynamic[old_fixed_term_loan_start],
+                        size_of::<FixedTermLoan>() * active_fixed_term_loans,
+                    );
+                }
+            }
         }
 
         // Second move pass: Go right-to-left and move everything to the right if needed.
@@ -1729,6 +2291,45 @@ impl<
         // - if the block to the right was moved to the left, we know that its start will
         //   be >= our block's end
         {
+            let old_fixed_term_loan_start = old_header.fixed_term_loan_offset(0);
+            let new_fixed_term_loan_start = new_header.fixed_term_loan_offset(0);
+            if new_fixed_term_loan_start > old_fixed_term_loan_start && active_fixed_term_loans > 0
+            {
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[new_fixed_term_loan_start],
+                        &mut dynamic[old_fixed_term_loan_start],
+                        size_of::<FixedTermLoan>() * active_fixed_term_loans,
+                    );
+                }
+            }
+
+            let old_perp_trigger_order_start = old_header.perp_trigger_order_offset(0);
+            let new_perp_trigger_order_start = new_header.perp_trigger_order_offset(0);
+            if new_perp_trigger_order_start > old_perp_trigger_order_start
+                && active_perp_trigger_orders > 0
+            {
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[new_perp_trigger_order_start],
+                        &mut dynamic[old_perp_trigger_order_start],
+                        size_of::<PerpTriggerOrder>() * active_perp_trigger_orders,
+                    );
+                }
+            }
+
+            let old_openbook_v2_start = old_header.openbook_v2_offset(0);
+            let new_openbook_v2_start = new_header.openbook_v2_offset(0);
+            if new_openbook_v2_start > old_openbook_v2_start && active_openbook_v2_orders > 0 {
+                unsafe {
+                    sol_memmove(
+                        &mut dynamic[new_openbook_v2_start],
+                        &mut dynamic[old_openbook_v2_start],
+                        size_of::<OpenbookV2Orders>() * active_openbook_v2_orders,
+                    );
+                }
+            }
+
             let old_tcs_start = old_header.token_conditional_swap_offset(0);
             let new_tcs_start = new_header.token_conditional_swap_offset(0);
             if new_tcs_start > old_tcs_start && active_tcs > 0 {
@@ -1798,6 +2399,18 @@ impl<
                 *get_helper_mut(dynamic, new_header.token_conditional_swap_offset(i)) =
                     TokenConditionalSwap::default();
             }
+            for i in active_openbook_v2_orders..new_header.openbook_v2_count() {
+                *get_helper_mut(dynamic, new_header.openbook_v2_offset(i)) =
+                    OpenbookV2Orders::default();
+            }
+            for i in active_perp_trigger_orders..new_header.perp_trigger_order_count() {
+                *get_helper_mut(dynamic, new_header.perp_trigger_order_offset(i)) =
+                    PerpTriggerOrder::default();
+            }
+            for i in active_fixed_term_loans..new_header.fixed_term_loan_count() {
+                *get_helper_mut(dynamic, new_header.fixed_term_loan_offset(i)) =
+                    FixedTermLoan::default();
+            }
         }
         {
             let offset = new_header.reserved_bytes_offset();
@@ -1814,6 +2427,9 @@ impl<
         self.write_perp_length();
         self.write_perp_oo_length();
         self.write_token_conditional_swap_length();
+        self.write_openbook_v2_length();
+        self.write_perp_trigger_order_length();
+        self.write_fixed_term_loan_length();
 
         Ok(())
     }
@@ -1899,6 +2515,9 @@ mod tests {
             account.perps.len() as u8,
             account.perp_open_orders.len() as u8,
             account.token_conditional_swaps.len() as u8,
+            account.openbook_v2.len() as u8,
+            account.perp_trigger_orders.len() as u8,
+            account.fixed_term_loans.len() as u8,
         );
         assert_eq!(expected_space, 8 + bytes.len());
 
@@ -1932,9 +2551,20 @@ mod tests {
             .token_conditional_swaps
             .resize(12, TokenConditionalSwap::default());
         account.token_conditional_swaps[0].buy_token_index = 14;
+        account.openbook_v2.resize(5, OpenbookV2Orders::default());
+        account.openbook_v2[0].market_index = 15;
+        account
+            .perp_trigger_orders
+            .resize(3, PerpTriggerOrder::default());
+        account.perp_trigger_orders[0].perp_market_index = 16;
+        account.fixed_term_loans.resize(4, FixedTermLoan::default());
+        account.fixed_term_loans[0].token_index = 17;
 
         let account_bytes = AnchorSerialize::try_to_vec(&account).unwrap();
-        assert_eq!(8 + account_bytes.len(), MangoAccount::space(8, 8, 4, 8, 12));
+        assert_eq!(
+            8 + account_bytes.len(),
+            MangoAccount::space(8, 8, 4, 8, 12, 5, 3, 4)
+        );
 
         let account2 = MangoAccountValue::from_bytes(&account_bytes).unwrap();
         assert_eq!(account.group, account2.fixed.group);
@@ -1997,6 +2627,35 @@ mod tests {
                 .unwrap()
                 .buy_token_index
         );
+        assert_eq!(
+            account.openbook_v2.len(),
+            account2.all_openbook_v2_orders().count()
+        );
+        assert_eq!(
+            account.openbook_v2[0].market_index,
+            account2
+                .openbook_v2_orders_by_raw_index_unchecked(0)
+                .market_index
+        );
+        assert_eq!(
+            account.perp_trigger_orders.len(),
+            account2.all_perp_trigger_orders().count()
+        );
+        assert_eq!(
+            account.perp_trigger_orders[0].perp_market_index,
+            account2
+                .perp_trigger_order_by_index(0)
+                .unwrap()
+                .perp_market_index
+        );
+        assert_eq!(
+            account.fixed_term_loans.len(),
+            account2.all_fixed_term_loans().count()
+        );
+        assert_eq!(
+            account.fixed_term_loans[0].token_index,
+            account2.fixed_term_loan_by_index(0).unwrap().token_index
+        );
     }
 
     #[test]
@@ -2108,6 +2767,62 @@ mod tests {
         assert!(account.serum3_orders_mut(7).is_err());
     }
 
+    #[test]
+    fn test_openbook_v2_orders() {
+        let mut account = make_test_account();
+        assert!(account.openbook_v2_orders(1).is_err());
+        assert!(account.openbook_v2_orders_mut(3).is_err());
+        assert_eq!(
+            account
+                .openbook_v2_orders_by_raw_index_unchecked(0)
+                .market_index,
+            OpenbookV2MarketIndex::MAX
+        );
+
+        assert_eq!(
+            account.create_openbook_v2_orders(1).unwrap().market_index,
+            1
+        );
+        assert_eq!(
+            account.create_openbook_v2_orders(7).unwrap().market_index,
+            7
+        );
+        assert_eq!(
+            account.create_openbook_v2_orders(42).unwrap().market_index,
+            42
+        );
+        assert!(account.create_openbook_v2_orders(7).is_err());
+        assert!(account.create_openbook_v2_orders(9).is_err());
+        assert_eq!(account.active_openbook_v2_orders().count(), 3);
+
+        assert!(account.deactivate_openbook_v2_orders(7).is_ok());
+        assert_eq!(
+            account
+                .openbook_v2_orders_by_raw_index_unchecked(1)
+                .market_index,
+            OpenbookV2MarketIndex::MAX
+        );
+        assert!(account.create_openbook_v2_orders(8).is_ok());
+        assert_eq!(
+            account
+                .openbook_v2_orders_by_raw_index_unchecked(1)
+                .market_index,
+            8
+        );
+
+        assert_eq!(account.active_openbook_v2_orders().count(), 3);
+        assert!(account.deactivate_openbook_v2_orders(1).is_ok());
+        assert!(account.openbook_v2_orders(1).is_err());
+        assert!(account.openbook_v2_orders_mut(1).is_err());
+        assert!(account.openbook_v2_orders(8).is_ok());
+        assert!(account.openbook_v2_orders(42).is_ok());
+        assert_eq!(account.active_openbook_v2_orders().count(), 2);
+
+        assert_eq!(account.openbook_v2_orders_mut(42).unwrap().market_index, 42);
+        assert_eq!(account.openbook_v2_orders_mut(8).unwrap().market_index, 8);
+        assert!(account.openbook_v2_orders_mut(7).is_err());
+    }
+
     #[test]
     fn test_perp_positions() {
         let mut account = make_test_account();
@@ -2294,22 +3009,22 @@ mod tests {
         account
             .perp_open_orders
             .resize(header.perp_oo_count(), PerpOpenOrder::default());
-        let mut bytes = AnchorSerialize::try_to_vec(&account).unwrap();
-
-        // The MangoAccount struct is missing some dynamic fields, add space for them
-        let expected_space = header.account_size();
-        bytes.extend(vec![0u8; expected_space - bytes.len()]);
-
-        // Set the length of these dynamic parts
-        let (fixed, dynamic) = bytes.split_at_mut(size_of::<MangoAccountFixed>());
-        let mut out_header = MangoAccountDynamicHeader::from_bytes(dynamic).unwrap();
-        out_header.token_conditional_swap_count = header.token_conditional_swap_count;
-        let mut account = MangoAccountRefMut {
-            header: &mut out_header,
-            fixed: bytemuck::from_bytes_mut(fixed),
-            dynamic,
-        };
-        account.write_token_conditional_swap_length();
+        account.token_conditional_swaps.resize(
+            header.token_conditional_swap_count(),
+            TokenConditionalSwap::default(),
+        );
+        account
+            .openbook_v2
+            .resize(header.openbook_v2_count(), OpenbookV2Orders::default());
+        account.perp_trigger_orders.resize(
+            header.perp_trigger_order_count(),
+            PerpTriggerOrder::default(),
+        );
+        account
+            .fixed_term_loans
+            .resize(header.fixed_term_loan_count(), FixedTermLoan::default());
+        let bytes = AnchorSerialize::try_to_vec(&account).unwrap();
+        assert_eq!(8 + bytes.len(), header.account_size());
 
         MangoAccountValue::from_bytes(&bytes).unwrap()
     }
@@ -2409,6 +3124,72 @@ mod tests {
             );
         }
 
+        assert_eq!(
+            account.all_openbook_v2_orders().count(),
+            header.openbook_v2_count()
+        );
+        assert_eq!(
+            account.active_openbook_v2_orders().count(),
+            active.openbook_v2_count()
+        );
+        for i in 0..active.openbook_v2_count() {
+            assert_eq!(
+                account.openbook_v2_orders_by_raw_index(i)?.market_index,
+                i as OpenbookV2MarketIndex
+            );
+        }
+        for i in active.openbook_v2_count()..header.openbook_v2_count() {
+            let def = OpenbookV2Orders::default().try_to_vec().unwrap();
+            assert_eq!(
+                account
+                    .openbook_v2_orders_by_raw_index(i)?
+                    .try_to_vec()
+                    .unwrap(),
+                def
+            );
+        }
+
+        assert_eq!(
+            account.all_perp_trigger_orders().count(),
+            header.perp_trigger_order_count()
+        );
+        assert_eq!(
+            account.active_perp_trigger_orders().count(),
+            active.perp_trigger_order_count()
+        );
+        for i in 0..active.perp_trigger_order_count() {
+            assert_eq!(account.perp_trigger_order_by_index(i)?.id, i as u64);
+        }
+        for i in active.perp_trigger_order_count()..header.perp_trigger_order_count() {
+            let def = PerpTriggerOrder::default().try_to_vec().unwrap();
+            assert_eq!(
+                account
+                    .perp_trigger_order_by_index(i)?
+                    .try_to_vec()
+                    .unwrap(),
+                def
+            );
+        }
+
+        assert_eq!(
+            account.all_fixed_term_loans().count(),
+            header.fixed_term_loan_count()
+        );
+        assert_eq!(
+            account.active_fixed_term_loans().count(),
+            active.fixed_term_loan_count()
+        );
+        for i in 0..active.fixed_term_loan_count() {
+            assert_eq!(account.fixed_term_loan_by_index(i)?.id, i as u64);
+        }
+        for i in active.fixed_term_loan_count()..header.fixed_term_loan_count() {
+            let def = FixedTermLoan::default().try_to_vec().unwrap();
+            assert_eq!(
+                account.fixed_term_loan_by_index(i)?.try_to_vec().unwrap(),
+                def
+            );
+        }
+
         assert!(account.dynamic_reserved_bytes().iter().all(|&v| v == 0));
 
         Ok(())
@@ -2422,6 +3203,9 @@ mod tests {
             perp_count: 6,
             perp_oo_count: 7,
             token_conditional_swap_count: 8,
+            openbook_v2_count: 2,
+            perp_trigger_order_count: 3,
+            fixed_term_loan_count: 3,
         };
         let mut account = make_resize_test_account(&header);
 
@@ -2460,12 +3244,34 @@ mod tests {
         make_tcs(2, 0);
         make_tcs(4, 1);
 
+        account.create_openbook_v2_orders(3)?;
+        account.create_openbook_v2_orders(0)?;
+        account.deactivate_openbook_v2_orders(3)?;
+
+        let mut make_trigger_order = |raw_index: usize, id| {
+            let mut order = account.perp_trigger_order_mut_by_index(raw_index).unwrap();
+            order.set_is_configured(true);
+            order.id = id;
+        };
+        make_trigger_order(2, 0);
+
+        let mut make_fixed_term_loan = |raw_index: usize, id| {
+            let mut loan = account.fixed_term_loan_mut_by_index(raw_index).unwrap();
+            loan.set_is_configured(true);
+            loan.id = id;
+        };
+        make_fixed_term_loan(1, 0);
+        make_fixed_term_loan(2, 1);
+
         let active = MangoAccountDynamicHeader {
             token_count: 2,
             serum3_count: 2,
             perp_count: 4,
             perp_oo_count: 5,
             token_conditional_swap_count: 2,
+            openbook_v2_count: 1,
+            perp_trigger_order_count: 1,
+            fixed_term_loan_count: 2,
         };
 
         // Resizing to the same size just removes the empty spaces
@@ -2477,6 +3283,9 @@ mod tests {
                 header.perp_count,
                 header.perp_oo_count,
                 header.token_conditional_swap_count,
+                header.openbook_v2_count,
+                header.perp_trigger_order_count,
+                header.fixed_term_loan_count,
             )?;
             check_account_active_and_order(&ta, &active)?;
         }
@@ -2490,6 +3299,9 @@ mod tests {
                 active.perp_count,
                 active.perp_oo_count,
                 active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
             )?;
             check_account_active_and_order(&ta, &active)?;
         }
@@ -2503,6 +3315,9 @@ mod tests {
                 active.perp_count,
                 active.perp_oo_count,
                 active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
             )
             .unwrap_err();
             ta.resize_dynamic_content(
@@ -2511,6 +3326,9 @@ mod tests {
                 active.perp_count,
                 active.perp_oo_count,
                 active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
             )
             .unwrap_err();
             ta.resize_dynamic_content(
@@ -2519,6 +3337,9 @@ mod tests {
                 active.perp_count - 1,
                 active.perp_oo_count,
                 active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
             )
             .unwrap_err();
             ta.resize_dynamic_content(
@@ -2527,6 +3348,9 @@ mod tests {
                 active.perp_count,
                 active.perp_oo_count - 1,
                 active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
             )
             .unwrap_err();
             ta.resize_dynamic_content(
@@ -2535,6 +3359,42 @@ mod tests {
                 active.perp_count,
                 active.perp_oo_count,
                 active.token_conditional_swap_count - 1,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
+            )
+            .unwrap_err();
+            ta.resize_dynamic_content(
+                active.token_count,
+                active.serum3_count,
+                active.perp_count,
+                active.perp_oo_count,
+                active.token_conditional_swap_count,
+                active.openbook_v2_count - 1,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count,
+            )
+            .unwrap_err();
+            ta.resize_dynamic_content(
+                active.token_count,
+                active.serum3_count,
+                active.perp_count,
+                active.perp_oo_count,
+                active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count - 1,
+                active.fixed_term_loan_count,
+            )
+            .unwrap_err();
+            ta.resize_dynamic_content(
+                active.token_count,
+                active.serum3_count,
+                active.perp_count,
+                active.perp_oo_count,
+                active.token_conditional_swap_count,
+                active.openbook_v2_count,
+                active.perp_trigger_order_count,
+                active.fixed_term_loan_count - 1,
             )
             .unwrap_err();
         }
@@ -2553,6 +3413,9 @@ mod tests {
                 perp_count: 4,
                 perp_oo_count: 8,
                 token_conditional_swap_count: 4,
+                openbook_v2_count: 2,
+                perp_trigger_order_count: 3,
+                fixed_term_loan_count: 3,
             };
             let mut account = make_resize_test_account(&header);
 
@@ -2563,6 +3426,9 @@ mod tests {
                 perp_oo_count: rng.gen_range(0..header.perp_oo_count + 1),
                 token_conditional_swap_count: rng
                     .gen_range(0..header.token_conditional_swap_count + 1),
+                openbook_v2_count: rng.gen_range(0..header.openbook_v2_count + 1),
+                perp_trigger_order_count: rng.gen_range(0..header.perp_trigger_order_count + 1),
+                fixed_term_loan_count: rng.gen_range(0..header.fixed_term_loan_count + 1),
             };
 
             let options = (0..header.token_count()).collect_vec();
@@ -2597,12 +3463,39 @@ mod tests {
                 tcs.id = i as u64;
             }
 
+            let options = (0..header.openbook_v2_count()).collect_vec();
+            let selected = options.choose_multiple(&mut rng, active.openbook_v2_count());
+            for (i, index) in selected.sorted().enumerate() {
+                account
+                    .openbook_v2_orders_mut_by_raw_index(*index)
+                    .market_index = i as OpenbookV2MarketIndex;
+            }
+
+            let options = (0..header.perp_trigger_order_count()).collect_vec();
+            let selected = options.choose_multiple(&mut rng, active.perp_trigger_order_count());
+            for (i, index) in selected.sorted().enumerate() {
+                let order = account.perp_trigger_order_mut_by_index(*index).unwrap();
+                order.set_is_configured(true);
+                order.id = i as u64;
+            }
+
+            let options = (0..header.fixed_term_loan_count()).collect_vec();
+            let selected = options.choose_multiple(&mut rng, active.fixed_term_loan_count());
+            for (i, index) in selected.sorted().enumerate() {
+                let loan = account.fixed_term_loan_mut_by_index(*index).unwrap();
+                loan.set_is_configured(true);
+                loan.id = i as u64;
+            }
+
             let target = MangoAccountDynamicHeader {
                 token_count: rng.gen_range(active.token_count..6),
                 serum3_count: rng.gen_range(active.serum3_count..7),
                 perp_count: rng.gen_range(active.perp_count..6),
                 perp_oo_count: rng.gen_range(active.perp_oo_count..16),
                 token_conditional_swap_count: rng.gen_range(active.token_conditional_swap_count..8),
+                openbook_v2_count: rng.gen_range(active.openbook_v2_count..3),
+                perp_trigger_order_count: rng.gen_range(active.perp_trigger_order_count..5),
+                fixed_term_loan_count: rng.gen_range(active.fixed_term_loan_count..5),
             };
 
             let target_size = target.account_size();
@@ -2619,6 +3512,9 @@ mod tests {
                     target.perp_count,
                     target.perp_oo_count,
                     target.token_conditional_swap_count,
+                    target.openbook_v2_count,
+                    target.perp_trigger_order_count,
+                    target.fixed_term_loan_count,
                 )
                 .unwrap();
 
@@ -2909,7 +3805,9 @@ mod tests {
                 temporary_delegate: fixed.temporary_delegate,
                 temporary_delegate_expiry: fixed.temporary_delegate_expiry,
                 last_collateral_fee_charge: fixed.last_collateral_fee_charge,
-                reserved: [0u8; 152],
+                next_perp_trigger_order_id: fixed.next_perp_trigger_order_id,
+                next_fixed_term_loan_id: fixed.next_fixed_term_loan_id,
+                reserved: [0u8; 136],
 
                 header_version: *zerocopy_reader.header_version(),
                 padding3: Default::default(),
@@ -2932,6 +3830,24 @@ mod tests {
                     .cloned()
                     .collect_vec(),
 
+                padding9: Default::default(),
+                openbook_v2: zerocopy_reader
+                    .all_openbook_v2_orders()
+                    .cloned()
+                    .collect_vec(),
+
+                padding10: Default::default(),
+                perp_trigger_orders: zerocopy_reader
+                    .all_perp_trigger_orders()
+                    .cloned()
+                    .collect_vec(),
+
+                padding11: Default::default(),
+                fixed_term_loans: zerocopy_reader
+                    .all_fixed_term_loans()
+                    .cloned()
+                    .collect_vec(),
+
                 reserved_dynamic: zerocopy_reader.dynamic_reserved_bytes().try_into().unwrap(),
             };
 
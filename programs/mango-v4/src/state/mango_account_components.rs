@@ -42,13 +42,18 @@ pub struct TokenPosition {
     // Cumulative borrow interest in token native units
     pub cumulative_borrow_interest: f64,
 
+    /// Principal of the account's fixed term loans in this token, in native tokens.
+    ///
+    /// Counts towards the bank's account_borrow_limit_quote.
+    pub fixed_term_principal: u64,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 128],
+    pub reserved: [u8; 120],
 }
 
 const_assert_eq!(
     size_of::<TokenPosition>(),
-    16 + 2 + 2 + 4 + 16 + 8 + 8 + 128
+    16 + 2 + 2 + 4 + 16 + 8 + 8 + 8 + 120
 );
 const_assert_eq!(size_of::<TokenPosition>(), 184);
 const_assert_eq!(size_of::<TokenPosition>() % 8, 0);
@@ -62,8 +67,9 @@ impl Default for TokenPosition {
             cumulative_deposit_interest: 0.0,
             cumulative_borrow_interest: 0.0,
             previous_index: I80F48::ZERO,
+            fixed_term_principal: 0,
             padding: Default::default(),
-            reserved: [0; 128],
+            reserved: [0; 120],
         }
     }
 }
@@ -202,6 +208,102 @@ impl Default for Serum3Orders {
     }
 }
 
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize, Derivative, PartialEq)]
+#[derivative(Debug)]
+pub struct OpenbookV2Orders {
+    pub open_orders: Pubkey,
+
+    /// Tracks the amount of borrows that have flowed into the open orders account.
+    /// These borrows did not have the loan origination fee applied, and that may happen
+    /// later (in openbook_v2_settle_funds) if we can guarantee that the funds were used.
+    /// In particular a place-on-book, cancel, settle should not cost fees.
+    pub base_borrows_without_fee: u64,
+    pub quote_borrows_without_fee: u64,
+
+    pub market_index: OpenbookV2MarketIndex,
+
+    /// Store the base/quote token index, so health computations don't need
+    /// to get passed the static OpenbookV2Market to find which tokens a market
+    /// uses and look up the correct oracles.
+    pub base_token_index: TokenIndex,
+    pub quote_token_index: TokenIndex,
+
+    #[derivative(Debug = "ignore")]
+    pub padding: [u8; 2],
+
+    /// Track something like the highest open bid / lowest open ask, in native/native units.
+    ///
+    /// Same semantics as in Serum3Orders.
+    ///
+    /// 0 is a special "unset" state.
+    pub highest_placed_bid_inv: f64,
+    pub lowest_placed_ask: f64,
+
+    /// An overestimate of the amount of tokens that might flow out of the open orders account.
+    ///
+    /// The bank still considers these amounts user deposits (see Bank::potential_serum_tokens)
+    /// and that value needs to be updated in conjunction with these numbers.
+    pub potential_base_tokens: u64,
+    pub potential_quote_tokens: u64,
+
+    /// Track lowest bid/highest ask, same way as for highest bid/lowest ask.
+    ///
+    /// 0 is a special "unset" state.
+    pub lowest_placed_bid_inv: f64,
+    pub highest_placed_ask: f64,
+
+    /// Lot sizes of the external market, copied at open orders creation.
+    ///
+    /// The openbook v2 open orders account stores reserved amounts in lots, so health
+    /// computations need these to convert them to native units without having
+    /// to load the external market.
+    pub base_lot_size: i64,
+    pub quote_lot_size: i64,
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 128],
+}
+const_assert_eq!(
+    size_of::<OpenbookV2Orders>(),
+    32 + 8 * 2 + 2 * 3 + 2 + 6 * 8 + 8 * 2 + 128
+);
+const_assert_eq!(size_of::<OpenbookV2Orders>(), 248);
+const_assert_eq!(size_of::<OpenbookV2Orders>() % 8, 0);
+
+impl OpenbookV2Orders {
+    pub fn is_active(&self) -> bool {
+        self.market_index != OpenbookV2MarketIndex::MAX
+    }
+
+    pub fn is_active_for_market(&self, market_index: OpenbookV2MarketIndex) -> bool {
+        self.market_index == market_index
+    }
+}
+
+impl Default for OpenbookV2Orders {
+    fn default() -> Self {
+        Self {
+            open_orders: Pubkey::default(),
+            market_index: OpenbookV2MarketIndex::MAX,
+            base_token_index: TokenIndex::MAX,
+            quote_token_index: TokenIndex::MAX,
+            padding: Default::default(),
+            base_borrows_without_fee: 0,
+            quote_borrows_without_fee: 0,
+            highest_placed_bid_inv: 0.0,
+            lowest_placed_ask: 0.0,
+            potential_base_tokens: 0,
+            potential_quote_tokens: 0,
+            lowest_placed_bid_inv: 0.0,
+            highest_placed_ask: 0.0,
+            base_lot_size: 0,
+            quote_lot_size: 0,
+            reserved: [0; 128],
+        }
+    }
+}
+
 #[zero_copy]
 #[derive(AnchorSerialize, AnchorDeserialize, Derivative, PartialEq)]
 #[derivative(Debug)]
@@ -323,12 +425,20 @@ pub struct PerpPosition {
     /// price and current price of the base position is the overall pnl.
     pub realized_pnl_for_position_native: I80F48,
 
+    /// Side of the most recent non-zero base position: 0 if unknown, 1 for long, 2 for short.
+    ///
+    /// Used to charge socialized losses to the other side when this position goes bankrupt.
+    pub last_position_side: u8,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 88],
+    pub padding2: [u8; 7],
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 80],
 }
 const_assert_eq!(
     size_of::<PerpPosition>(),
-    2 + 2 + 4 + 8 + 8 + 16 + 8 + 16 * 2 + 8 * 2 + 8 * 2 + 8 * 5 + 8 + 2 * 16 + 8 + 16 + 88
+    2 + 2 + 4 + 8 + 8 + 16 + 8 + 16 * 2 + 8 * 2 + 8 * 2 + 8 * 5 + 8 + 2 * 16 + 8 + 16 + 1 + 7 + 80
 );
 const_assert_eq!(size_of::<PerpPosition>(), 304);
 const_assert_eq!(size_of::<PerpPosition>() % 8, 0);
@@ -359,7 +469,9 @@ impl Default for PerpPosition {
             settle_pnl_limit_settled_in_current_window_native: 0,
             recurring_settle_pnl_allowance: 0,
             realized_pnl_for_position_native: I80F48::ZERO,
-            reserved: [0; 88],
+            last_position_side: 0,
+            padding2: Default::default(),
+            reserved: [0; 80],
         }
     }
 }
@@ -426,6 +538,20 @@ impl PerpPosition {
         let start = self.base_position_lots;
         self.base_position_lots += base_change;
         perp_market.open_interest += self.base_position_lots.abs() - start.abs();
+        match self.base_position_lots.cmp(&0) {
+            Ordering::Greater => self.last_position_side = 1,
+            Ordering::Less => self.last_position_side = 2,
+            Ordering::Equal => {}
+        }
+    }
+
+    /// The side of the most recent non-zero base position, if known
+    pub fn last_position_side(&self) -> Option<Side> {
+        match self.last_position_side {
+            1 => Some(Side::Bid),
+            2 => Some(Side::Ask),
+            _ => None,
+        }
     }
 
     /// The amount of funding this account still needs to pay, in native quote
@@ -1,6 +1,7 @@
 pub use bank::*;
 pub use dynamic_account::*;
 pub use equity::*;
+pub use fixed_term_loan::*;
 pub use group::*;
 pub use mango_account::*;
 pub use mango_account_components::*;
@@ -10,13 +11,20 @@ pub use oracle::*;
 pub use orca_cpi::*;
 pub use orderbook::*;
 pub use perp_market::*;
+pub use perp_trigger_order::*;
+pub use pyth_receiver_cpi::*;
+pub use rate_history::*;
+pub use raydium_cpi::*;
 pub use serum3_market::*;
 pub use stable_price::*;
+pub use switchboard_on_demand_cpi::*;
 pub use token_conditional_swap::*;
+pub use token_insurance_fund::*;
 
 mod bank;
 mod dynamic_account;
 mod equity;
+mod fixed_term_loan;
 mod group;
 mod mango_account;
 mod mango_account_components;
@@ -26,6 +34,12 @@ mod oracle;
 mod orca_cpi;
 mod orderbook;
 mod perp_market;
+mod perp_trigger_order;
+mod pyth_receiver_cpi;
+mod rate_history;
+mod raydium_cpi;
 mod serum3_market;
 mod stable_price;
+mod switchboard_on_demand_cpi;
 mod token_conditional_swap;
+mod token_insurance_fund;
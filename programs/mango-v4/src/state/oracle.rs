@@ -1,18 +1,26 @@
 use std::mem::size_of;
 
 use anchor_lang::prelude::*;
-use anchor_lang::{AnchorDeserialize};
+use anchor_lang::{AnchorDeserialize, Discriminator};
 use derivative::Derivative;
 use fixed::types::{I80F48, U64F64};
 
 use static_assertions::const_assert_eq;
+use switchboard_program::FastRoundResultAccountData;
+use switchboard_v2::AggregatorAccountData;
 
 use crate::accounts_zerocopy::*;
 
 use crate::error::*;
-use crate::state::load_whirlpool_state;
+use crate::state::{
+    load_price_update_v2_state, load_pull_feed_result_state, load_raydium_pool_state,
+    load_whirlpool_state, pyth_push_oracle_feed_address,
+};
 
-use super::orca_mainnet_whirlpool;
+use super::{
+    orca_mainnet_whirlpool, pyth_mainnet_receiver, raydium_mainnet_clmm,
+    switchboard_on_demand_mainnet_oracle,
+};
 
 const DECIMAL_CONSTANT_ZERO_INDEX: i8 = 12;
 const DECIMAL_CONSTANTS: [I80F48; 25] = [
@@ -50,6 +58,10 @@ pub const QUOTE_DECIMALS: i8 = 6;
 pub const SOL_DECIMALS: i8 = 9;
 pub const QUOTE_NATIVE_TO_UI: I80F48 = power_of_ten(-QUOTE_DECIMALS);
 
+/// Number of oracles, in addition to the primary one, that can be configured
+/// for the median oracle mode of banks and perp markets.
+pub const MAX_MEDIAN_ORACLES: usize = 3;
+
 pub mod switchboard_v1_devnet_oracle {
     use solana_program::declare_id;
     declare_id!("7azgmy1pFXHikv36q1zZASvFq5vFa39TT9NweVugKKTU");
@@ -108,13 +120,17 @@ impl OracleConfigParams {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, AnchorSerialize, AnchorDeserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, AnchorSerialize, AnchorDeserialize)]
 pub enum OracleType {
     Pyth,
-    //Stub,
-    //SwitchboardV1,
-    //SwitchboardV2,
     OrcaCLMM,
+    // New variants must be appended: the serialized value is the variant position
+    Stub,
+    SwitchboardV1,
+    SwitchboardV2,
+    RaydiumCLMM,
+    PythPull,
+    SwitchboardOnDemand,
 }
 
 pub struct OracleState {
@@ -175,26 +191,90 @@ const_assert_eq!(size_of::<StubOracle>() % 8, 0);
 
 pub fn determine_oracle_type(acc_info: &impl KeyedAccountReader) -> Result<OracleType> {
     let data = acc_info.data();
+    if data.len() < 8 {
+        return Err(error_msg_typed!(
+            MangoError::UnknownOracleType,
+            "oracle account {} data is too short",
+            acc_info.key()
+        ));
+    }
 
-    if acc_info.owner() == &orca_mainnet_whirlpool::ID {
+    if u32::from_le_bytes(data[0..4].try_into().unwrap()) == pyth_sdk_solana::state::MAGIC {
+        return Ok(OracleType::Pyth);
+    } else if data[0..8] == StubOracle::discriminator() {
+        return Ok(OracleType::Stub);
+    }
+    // https://github.com/switchboard-xyz/switchboard-v2/blob/main/libraries/rs/src/aggregator.rs#L114
+    // note: disc is not public, hence the copy pasta
+    else if data[0..8] == [217, 230, 65, 101, 201, 162, 27, 125] {
+        return Ok(OracleType::SwitchboardV2);
+    }
+    // note: this is the only known way of checking this
+    else if acc_info.owner() == &switchboard_v1_devnet_oracle::ID
+        || acc_info.owner() == &switchboard_v2_mainnet_oracle::ID
+    {
+        return Ok(OracleType::SwitchboardV1);
+    } else if acc_info.owner() == &orca_mainnet_whirlpool::ID {
         return Ok(OracleType::OrcaCLMM);
+    } else if acc_info.owner() == &raydium_mainnet_clmm::ID {
+        return Ok(OracleType::RaydiumCLMM);
+    } else if acc_info.owner() == &pyth_mainnet_receiver::ID {
+        return Ok(OracleType::PythPull);
+    } else if acc_info.owner() == &switchboard_on_demand_mainnet_oracle::ID {
+        return Ok(OracleType::SwitchboardOnDemand);
     }
 
     Err(MangoError::UnknownOracleType.into())
 }
 
+/// Checks an oracle account that is configured on a bank or perp market.
+///
+/// Pyth pull oracles must be the push oracle's price feed account for their feed id.
+/// Deriving that address is too expensive for every price read, so it's only done here.
+pub fn check_is_valid_oracle(acc_info: &impl KeyedAccountReader) -> Result<()> {
+    if let Ok(OracleType::PythPull) = determine_oracle_type(acc_info) {
+        let update = load_price_update_v2_state(acc_info)?;
+        require_keys_eq!(
+            pyth_push_oracle_feed_address(&update.feed_id),
+            *acc_info.key(),
+            MangoError::InvalidPythPullOracle
+        );
+    }
+    Ok(())
+}
+
+/// Calls check_is_valid_oracle() on each of the `median_oracles`, which must be among `ais`
+pub fn check_median_oracle_ais(ais: &[AccountInfo], median_oracles: &[Pubkey]) -> Result<()> {
+    for key in median_oracles.iter().filter(|k| **k != Pubkey::default()) {
+        let ai = ais
+            .iter()
+            .find(|ai| ai.key == key)
+            .ok_or_else(|| error_msg!("median oracle account {} must be passed", key))?;
+        check_is_valid_oracle(&AccountInfoRef::borrow(ai)?)?;
+    }
+    Ok(())
+}
+
 pub fn check_is_valid_fallback_oracle(acc_info: &impl KeyedAccountReader) -> Result<()> {
     if acc_info.key() == &Pubkey::default() {
         return Ok(());
     };
+    check_is_valid_oracle(acc_info)?;
     let oracle_type = determine_oracle_type(acc_info)?;
-    if oracle_type == OracleType::OrcaCLMM {
-        let whirlpool = load_whirlpool_state(acc_info)?;
-
-        let has_usdc_token = whirlpool.token_mint_a == usdc_mint_mainnet::ID
-            || whirlpool.token_mint_b == usdc_mint_mainnet::ID;
-        let has_sol_token = whirlpool.token_mint_a == sol_mint_mainnet::ID
-            || whirlpool.token_mint_b == sol_mint_mainnet::ID;
+    let mints = match oracle_type {
+        OracleType::OrcaCLMM => {
+            let whirlpool = load_whirlpool_state(acc_info)?;
+            Some((whirlpool.token_mint_a, whirlpool.token_mint_b))
+        }
+        OracleType::RaydiumCLMM => {
+            let pool = load_raydium_pool_state(acc_info)?;
+            Some((pool.token_mint_0, pool.token_mint_1))
+        }
+        _ => None,
+    };
+    if let Some((mint_a, mint_b)) = mints {
+        let has_usdc_token = mint_a == usdc_mint_mainnet::ID || mint_b == usdc_mint_mainnet::ID;
+        let has_sol_token = mint_a == sol_mint_mainnet::ID || mint_b == sol_mint_mainnet::ID;
         require!(
             has_usdc_token || has_sol_token,
             MangoError::InvalidCLMMOracle
@@ -203,6 +283,30 @@ pub fn check_is_valid_fallback_oracle(acc_info: &impl KeyedAccountReader) -> Res
     Ok(())
 }
 
+/// Whether the price of a CLMM pool between mint_a and mint_b must be inverted
+/// to get the price of the non-quote token in the quote token (USDC, else SOL).
+pub fn clmm_is_inverted(mint_a: &Pubkey, mint_b: &Pubkey) -> bool {
+    mint_a == &usdc_mint_mainnet::ID
+        || (mint_a == &sol_mint_mainnet::ID && mint_b != &usdc_mint_mainnet::ID)
+}
+
+/// The pyth feed that is needed to convert the CLMM pool price to USD
+pub fn clmm_quote_oracle(mint_a: &Pubkey, mint_b: &Pubkey) -> Result<Pubkey> {
+    let mint = if clmm_is_inverted(mint_a, mint_b) {
+        mint_a
+    } else {
+        mint_b
+    };
+
+    if mint == &usdc_mint_mainnet::ID {
+        return Ok(pyth_mainnet_usdc_oracle::ID);
+    } else if mint == &sol_mint_mainnet::ID {
+        return Ok(pyth_mainnet_sol_oracle::ID);
+    } else {
+        return Err(MangoError::MissingFeedForCLMMOracle.into());
+    }
+}
+
 /// Get the pyth agg price if it's available, otherwise take the prev price.
 ///
 /// Returns the publish slot in addition to the price info.
@@ -256,12 +360,57 @@ fn get_pyth_state(
         oracle_type: OracleType::Pyth,
     })
 }
+
+/// Slot duration used to convert the age of a pyth pull price into slots
+const PYTH_PULL_MS_PER_SLOT: u64 = 400;
+
+/// Pyth pull price updates carry the time they were published, but can be posted
+/// to the chain long after. Estimate the slot of the publish time from the price's
+/// age, so that max_staleness_slots applies to the publish time and not the posting.
+///
+/// Without access to the clock (off-chain), the posted slot is used.
+fn pyth_pull_publish_slot(posted_slot: u64, publish_time: i64) -> u64 {
+    match Clock::get() {
+        Ok(clock) => {
+            let age_ms = (clock.unix_timestamp.saturating_sub(publish_time).max(0) as u64)
+                .saturating_mul(1000);
+            clock
+                .slot
+                .saturating_sub(age_ms / PYTH_PULL_MS_PER_SLOT)
+                .min(posted_slot)
+        }
+        Err(_) => posted_slot,
+    }
+}
+
+fn get_pyth_pull_state(
+    acc_info: &(impl KeyedAccountReader + ?Sized),
+    base_decimals: u8,
+) -> Result<OracleState> {
+    let update = load_price_update_v2_state(acc_info)?;
+    let last_update_slot = pyth_pull_publish_slot(update.posted_slot, update.publish_time);
+
+    let decimals = (update.exponent as i8) + QUOTE_DECIMALS - (base_decimals as i8);
+    let decimal_adj = power_of_ten(decimals);
+    let price = I80F48::from_num(update.price) * decimal_adj;
+    let deviation = I80F48::from_num(update.conf) * decimal_adj;
+    require_gte!(price, 0);
+    Ok(OracleState {
+        price,
+        last_update_slot,
+        deviation,
+        oracle_type: OracleType::PythPull,
+    })
+}
 /// Contains all oracle account infos that could be used to read price
 pub struct OracleAccountInfos<'a, T: KeyedAccountReader> {
     pub oracle: &'a T,
     pub fallback_opt: Option<&'a T>,
     pub usdc_opt: Option<&'a T>,
     pub sol_opt: Option<&'a T>,
+    /// Accounts for the median oracle mode, at the same positions as the
+    /// median oracle keys on the bank or perp market.
+    pub median_opts: [Option<&'a T>; MAX_MEDIAN_ORACLES],
 }
 
 impl<'a, T: KeyedAccountReader> OracleAccountInfos<'a, T> {
@@ -271,8 +420,123 @@ impl<'a, T: KeyedAccountReader> OracleAccountInfos<'a, T> {
             fallback_opt: None,
             usdc_opt: None,
             sol_opt: None,
+            median_opts: [None; MAX_MEDIAN_ORACLES],
         }
     }
+
+    /// Uses the accounts in `median_ais` for the median oracle mode with `median_keys`
+    pub fn with_median_oracles(
+        mut self,
+        median_keys: &[Pubkey; MAX_MEDIAN_ORACLES],
+        median_ais: &'a [T],
+    ) -> Self {
+        self.median_opts = median_keys.map(|key| {
+            if key == Pubkey::default() {
+                None
+            } else {
+                median_ais.iter().find(|ai| *ai.key() == key)
+            }
+        });
+        self
+    }
+}
+
+/// Borrows the accounts in `ais` that are one of the `median_keys`.
+///
+/// Instructions that read an oracle outside of the health computation expect the
+/// median oracle accounts in their remaining accounts. All other remaining accounts,
+/// like the health accounts, are skipped without borrowing them.
+pub fn borrow_median_oracle_ais<'a, 'info>(
+    ais: &'a [AccountInfo<'info>],
+    median_keys: &[Pubkey; MAX_MEDIAN_ORACLES],
+) -> Result<Vec<AccountInfoRef<'a, 'info>>> {
+    ais.iter()
+        .filter(|ai| *ai.key != Pubkey::default() && median_keys.contains(ai.key))
+        .map(AccountInfoRef::borrow)
+        .collect()
+}
+
+/// Converts a list of median oracle keys to the fixed-size array stored on banks and perp markets
+pub fn median_oracles_from_slice(keys: &[Pubkey]) -> Result<[Pubkey; MAX_MEDIAN_ORACLES]> {
+    require_gte!(MAX_MEDIAN_ORACLES, keys.len());
+    let mut median_oracles = [Pubkey::default(); MAX_MEDIAN_ORACLES];
+    median_oracles[..keys.len()].copy_from_slice(keys);
+    Ok(median_oracles)
+}
+
+/// Checks that the quorum can be reached by the primary oracle and the configured median oracles
+pub fn verify_median_oracle_quorum(
+    median_oracles: &[Pubkey; MAX_MEDIAN_ORACLES],
+    quorum: u8,
+) -> Result<()> {
+    let oracle_count = 1 + median_oracles
+        .iter()
+        .filter(|key| **key != Pubkey::default())
+        .count();
+    require_gte!(oracle_count, quorum as usize);
+    Ok(())
+}
+
+/// Returns the median price of the primary oracle and the median oracles that pass
+/// the confidence and staleness checks.
+///
+/// Median oracles that weren't passed in `acc_infos` or fail the checks are skipped.
+/// At least `quorum` oracles must remain, otherwise OracleQuorumNotReached is returned.
+/// With an even number of valid oracles, the average of the middle two prices is used.
+pub fn median_oracle_state<T: KeyedAccountReader>(
+    acc_infos: &OracleAccountInfos<T>,
+    median_oracles: &[Pubkey; MAX_MEDIAN_ORACLES],
+    quorum: u8,
+    config: &OracleConfig,
+    base_decimals: u8,
+    staleness_slot: Option<u64>,
+) -> Result<OracleState> {
+    let mut states = Vec::with_capacity(1 + MAX_MEDIAN_ORACLES);
+    let oracles = std::iter::once(Some(acc_infos.oracle)).chain(
+        median_oracles
+            .iter()
+            .zip(acc_infos.median_opts.iter())
+            .filter(|(key, _)| **key != Pubkey::default())
+            .map(|(key, ai_opt)| ai_opt.filter(|ai| ai.key() == key)),
+    );
+    for oracle in oracles.flatten() {
+        let single_infos = OracleAccountInfos {
+            oracle,
+            fallback_opt: None,
+            usdc_opt: acc_infos.usdc_opt,
+            sol_opt: acc_infos.sol_opt,
+            median_opts: [None; MAX_MEDIAN_ORACLES],
+        };
+        let state = match oracle_state_unchecked(&single_infos, base_decimals) {
+            Ok(state) => state,
+            Err(_) => continue,
+        };
+        if state
+            .check_confidence_and_maybe_staleness(config, staleness_slot)
+            .is_ok()
+        {
+            states.push(state);
+        }
+    }
+
+    require_msg_typed!(
+        states.len() >= quorum as usize,
+        MangoError::OracleQuorumNotReached,
+        "{} valid oracles, but the quorum is {}",
+        states.len(),
+        quorum
+    );
+
+    states.sort_by(|a, b| a.price.cmp(&b.price));
+    let middle = states.len() / 2;
+    if states.len() % 2 == 0 {
+        let upper_price = states[middle].price;
+        let mut state = states.swap_remove(middle - 1);
+        state.price = (state.price + upper_price) / 2;
+        Ok(state)
+    } else {
+        Ok(states.swap_remove(middle))
+    }
 }
 
 /// Returns the price of one native base token, in native quote tokens
@@ -314,36 +578,205 @@ fn oracle_state_unchecked_inner<T: KeyedAccountReader>(
 
     Ok(match oracle_type {
         OracleType::Pyth => get_pyth_state(oracle_info, base_decimals)?,
-        OracleType::OrcaCLMM => {
-            let whirlpool = load_whirlpool_state(oracle_info)?;
-
-            let inverted = whirlpool.is_inverted();
-            let quote_state = if inverted {
-                quote_state_unchecked(acc_infos, &whirlpool.token_mint_a)?
+        OracleType::PythPull => get_pyth_pull_state(oracle_info, base_decimals)?,
+        OracleType::Stub => {
+            let stub = oracle_info.load::<StubOracle>()?;
+            let deviation = if stub.deviation == 0 {
+                // allows the confidence check to pass even for negative prices
+                I80F48::MIN
             } else {
-                quote_state_unchecked(acc_infos, &whirlpool.token_mint_b)?
+                stub.deviation
             };
-
-            let clmm_price = if inverted {
-                let sqrt_price = U64F64::from_bits(whirlpool.sqrt_price).to_num::<f64>();
-                let inverted_price = sqrt_price * sqrt_price;
-                I80F48::from_num(1.0f64 / inverted_price)
+            let last_update_slot = if stub.last_update_slot == 0 {
+                // ensure staleness checks will never fail
+                u64::MAX
             } else {
-                let sqrt_price = U64F64::from_bits(whirlpool.sqrt_price);
-                I80F48::from_num(sqrt_price * sqrt_price)
+                stub.last_update_slot
             };
+            OracleState {
+                price: stub.price,
+                last_update_slot,
+                deviation,
+                oracle_type: OracleType::Stub,
+            }
+        }
+        OracleType::SwitchboardV2 => {
+            fn from_foreign_error(e: impl std::fmt::Display) -> Error {
+                error_msg!("{}", e)
+            }
+
+            let feed = bytemuck::from_bytes::<AggregatorAccountData>(&data[8..]);
+            let feed_result = feed.get_result().map_err(from_foreign_error)?;
+            let ui_price: f64 = feed_result.try_into().map_err(from_foreign_error)?;
+            let ui_deviation: f64 = feed
+                .latest_confirmed_round
+                .std_deviation
+                .try_into()
+                .map_err(from_foreign_error)?;
+
+            // The round_open_slot is an underestimate of the last update slot: Reporters will see
+            // the round opening and only then start executing the price tasks.
+            let last_update_slot = feed.latest_confirmed_round.round_open_slot;
+
+            let decimals = QUOTE_DECIMALS - (base_decimals as i8);
+            let decimal_adj = power_of_ten(decimals);
+            let price = I80F48::from_num(ui_price) * decimal_adj;
+            let deviation = I80F48::from_num(ui_deviation) * decimal_adj;
+            require_gte!(price, 0);
+            OracleState {
+                price,
+                last_update_slot,
+                deviation,
+                oracle_type: OracleType::SwitchboardV2,
+            }
+        }
+        OracleType::SwitchboardV1 => {
+            let result = FastRoundResultAccountData::deserialize(data).map_err(|e| {
+                error_msg_typed!(
+                    MangoError::UnexpectedOracle,
+                    "could not parse switchboard v1 result account {}: {}",
+                    oracle_info.key(),
+                    e
+                )
+            })?;
+            let ui_price = I80F48::from_num(result.result.result);
+            // V1 results don't store the std-dev of the responses. Use the largest std-dev
+            // that responses within [min_response, max_response] can have, which is half
+            // of the range (Popoviciu's inequality).
+            let ui_deviation =
+                I80F48::from_num((result.result.max_response - result.result.min_response) / 2.0);
+            let last_update_slot = result.result.round_open_slot;
+
+            let decimals = QUOTE_DECIMALS - (base_decimals as i8);
+            let decimal_adj = power_of_ten(decimals);
+            let price = ui_price * decimal_adj;
+            let deviation = ui_deviation * decimal_adj;
+            require_gte!(price, 0);
+            OracleState {
+                price,
+                last_update_slot,
+                deviation,
+                oracle_type: OracleType::SwitchboardV1,
+            }
+        }
+        OracleType::SwitchboardOnDemand => {
+            let result = load_pull_feed_result_state(oracle_info)?;
+            let ui_price = I80F48::from_num(result.value);
+            let ui_deviation = I80F48::from_num(result.std_dev);
+            // The slot at which the current result was computed
+            let last_update_slot = result.slot;
 
-            let price = clmm_price * quote_state.price;
+            let decimals = QUOTE_DECIMALS - (base_decimals as i8);
+            let decimal_adj = power_of_ten(decimals);
+            let price = ui_price * decimal_adj;
+            let deviation = ui_deviation * decimal_adj;
+            require_gte!(price, 0);
             OracleState {
                 price,
-                last_update_slot: quote_state.last_update_slot,
-                deviation: quote_state.deviation,
-                oracle_type: OracleType::OrcaCLMM,
+                last_update_slot,
+                deviation,
+                oracle_type: OracleType::SwitchboardOnDemand,
             }
         }
+        OracleType::OrcaCLMM => {
+            let whirlpool = load_whirlpool_state(oracle_info)?;
+            clmm_state_unchecked(
+                acc_infos,
+                whirlpool.liquidity,
+                whirlpool.sqrt_price,
+                &whirlpool.token_mint_a,
+                &whirlpool.token_mint_b,
+                OracleType::OrcaCLMM,
+            )?
+        }
+        OracleType::RaydiumCLMM => {
+            let pool = load_raydium_pool_state(oracle_info)?;
+            clmm_state_unchecked(
+                acc_infos,
+                pool.liquidity,
+                pool.sqrt_price,
+                &pool.token_mint_0,
+                &pool.token_mint_1,
+                OracleType::RaydiumCLMM,
+            )?
+        }
     })
 }
 
+/// Size of the hypothetical trade, in USD, whose price impact on a CLMM pool's
+/// active liquidity is used as the confidence of the pool price.
+const CLMM_CONFIDENCE_TRADE_USD: f64 = 10_000.0;
+
+/// Price of a CLMM pool with a Q64.64 sqrt price of mint_b in mint_a, converted
+/// to USD via the pyth feed of the pool's quote token.
+///
+/// The deviation combines the quote feed's deviation with the price impact of a
+/// CLMM_CONFIDENCE_TRADE_USD trade against the pool's active liquidity, so that
+/// OracleConfig::conf_filter rejects thin pools.
+fn clmm_state_unchecked<T: KeyedAccountReader>(
+    acc_infos: &OracleAccountInfos<T>,
+    liquidity: u128,
+    sqrt_price: u128,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    oracle_type: OracleType,
+) -> Result<OracleState> {
+    let inverted = clmm_is_inverted(mint_a, mint_b);
+    let quote_state = if inverted {
+        quote_state_unchecked(acc_infos, mint_a)?
+    } else {
+        quote_state_unchecked(acc_infos, mint_b)?
+    };
+
+    let clmm_price = if inverted {
+        let sqrt_price = U64F64::from_bits(sqrt_price).to_num::<f64>();
+        let inverted_price = sqrt_price * sqrt_price;
+        I80F48::from_num(1.0f64 / inverted_price)
+    } else {
+        let sqrt_price = U64F64::from_bits(sqrt_price);
+        I80F48::from_num(sqrt_price * sqrt_price)
+    };
+
+    let price = clmm_price * quote_state.price;
+    let price_impact =
+        clmm_price_impact(liquidity, sqrt_price, inverted, quote_state.price.to_num());
+    let deviation = clmm_price * quote_state.deviation + price * I80F48::from_num(price_impact);
+    Ok(OracleState {
+        price,
+        last_update_slot: quote_state.last_update_slot,
+        deviation,
+        oracle_type,
+    })
+}
+
+/// Relative price change caused by selling CLMM_CONFIDENCE_TRADE_USD worth of the
+/// quote token into the pool, assuming the active liquidity stays constant.
+///
+/// quote_price is the price of one native quote token in native USD. Capped at 1.
+fn clmm_price_impact(liquidity: u128, sqrt_price: u128, inverted: bool, quote_price: f64) -> f64 {
+    if liquidity == 0 || sqrt_price == 0 || quote_price <= 0.0 {
+        return 1.0;
+    }
+    let liquidity = liquidity as f64;
+    let sqrt_price = U64F64::from_bits(sqrt_price).to_num::<f64>();
+    let quote_native =
+        CLMM_CONFIDENCE_TRADE_USD * power_of_ten(QUOTE_DECIMALS).to_num::<f64>() / quote_price;
+
+    // The quote token is token b when not inverted: adding dy moves sqrt(P) by dy / L.
+    // Otherwise it is token a: adding dx moves 1/sqrt(P) by dx / L.
+    let sqrt_change = if inverted {
+        quote_native * sqrt_price / liquidity
+    } else {
+        quote_native / (liquidity * sqrt_price)
+    };
+    let impact = (1.0 + sqrt_change) * (1.0 + sqrt_change) - 1.0;
+    if impact.is_finite() {
+        impact.min(1.0)
+    } else {
+        1.0
+    }
+}
+
 fn quote_state_unchecked<T: KeyedAccountReader>(
     acc_infos: &OracleAccountInfos<T>,
     quote_mint: &Pubkey,
@@ -385,6 +818,10 @@ pub fn oracle_log_context(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::{
+        PRICE_UPDATE_V2_DISCRIMINATOR, PULL_FEED_ACCOUNT_DATA_DISCRIMINATOR,
+        RAYDIUM_POOL_DISCRIMINATOR, RAYDIUM_POOL_LEN,
+    };
     use solana_program_test::{find_file, read_file};
     use std::{cell::RefCell, path::PathBuf, str::FromStr};
 
@@ -395,6 +832,26 @@ mod tests {
         d.push("resources/test");
 
         let fixtures = vec![
+            (
+                "J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix",
+                OracleType::Pyth,
+                Pubkey::default(),
+            ),
+            (
+                "Gnt27xtC473ZT2Mw5u8wZ68Z3gULkSTb5DuxJy7eJotD",
+                OracleType::Pyth,
+                Pubkey::default(),
+            ),
+            (
+                "8k7F9Xb36oFJsjpCKpsXvg4cgBRoZtwNTc3EzG5Ttd2o",
+                OracleType::SwitchboardV1,
+                switchboard_v1_devnet_oracle::ID,
+            ),
+            (
+                "GvDMxPzN1sCj7L26YDK2HnMRXEQmQ2aemov8YBtPS7vR",
+                OracleType::SwitchboardV2,
+                switchboard_v2_mainnet_oracle::ID,
+            ),
             (
                 "83v8iPyZihDEjDdY8RdZddyZNyUtXngz69Lgo9Kt5d6d",
                 OracleType::OrcaCLMM,
@@ -412,7 +869,276 @@ mod tests {
                 data: data.borrow(),
             };
             assert!(determine_oracle_type(ai).unwrap() == fixture.1);
+            if fixture.1 != OracleType::OrcaCLMM {
+                let state = oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 6)?;
+                assert!(state.oracle_type == fixture.1);
+                assert!(state.price > 0);
+                assert!(state.deviation >= 0);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_stub_oracle() -> Result<()> {
+        let stub = StubOracle {
+            group: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            price: I80F48::from_num(1.5),
+            last_update_ts: 0,
+            last_update_slot: 0,
+            deviation: I80F48::ZERO,
+            reserved: [0; 104],
+        };
+        let mut stub_data = StubOracle::discriminator().to_vec();
+        stub_data.extend_from_slice(bytemuck::bytes_of(&stub));
+        let data = RefCell::new(&mut stub_data[..]);
+        let key = Pubkey::new_unique();
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &crate::ID,
+            data: data.borrow(),
+        };
+        assert!(determine_oracle_type(ai).unwrap() == OracleType::Stub);
+
+        let state = oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 6)?;
+        assert_eq!(state.price, I80F48::from_num(1.5));
+        // unset deviation and slot never fail confidence or staleness checks
+        assert_eq!(state.deviation, I80F48::MIN);
+        assert_eq!(state.last_update_slot, u64::MAX);
+        let config = OracleConfigParams {
+            conf_filter: 0.1,
+            max_staleness_slots: Some(10),
+        }
+        .to_oracle_config();
+        state.check_confidence_and_maybe_staleness(&config, Some(1000))?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_short_oracle_data() {
+        let mut short_data = vec![0u8; 4];
+        let data = RefCell::new(&mut short_data[..]);
+        let key = Pubkey::new_unique();
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &crate::ID,
+            data: data.borrow(),
+        };
+        assert!(determine_oracle_type(ai).is_err());
+    }
+
+    #[test]
+    pub fn test_malformed_switchboard_v1_oracle() {
+        let mut short_data = vec![0u8; 10];
+        let data = RefCell::new(&mut short_data[..]);
+        let key = Pubkey::new_unique();
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &switchboard_v1_devnet_oracle::ID,
+            data: data.borrow(),
+        };
+        assert!(determine_oracle_type(ai).unwrap() == OracleType::SwitchboardV1);
+        assert!(oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 6).is_err());
+    }
+
+    #[test]
+    pub fn test_oracle_type_serialization_is_stable() {
+        // Previously serialized values must keep decoding to the same type
+        assert_eq!(OracleType::Pyth.try_to_vec().unwrap(), vec![0]);
+        assert_eq!(OracleType::OrcaCLMM.try_to_vec().unwrap(), vec![1]);
+        assert_eq!(
+            OracleType::SwitchboardOnDemand.try_to_vec().unwrap(),
+            vec![7]
+        );
+    }
+
+    #[test]
+    pub fn test_pyth_pull_oracle() -> Result<()> {
+        let feed_id = [7u8; 32];
+        let feed_address = pyth_push_oracle_feed_address(&feed_id);
+        let price_update = |write_authority: &Pubkey, verification: &[u8]| {
+            let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+            data.extend_from_slice(write_authority.as_ref());
+            data.extend_from_slice(verification);
+            data.extend_from_slice(&feed_id);
+            data.extend_from_slice(&15_000_000_000i64.to_le_bytes()); // price
+            data.extend_from_slice(&10_000_000u64.to_le_bytes()); // conf
+            data.extend_from_slice(&(-8i32).to_le_bytes()); // exponent
+            data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // publish time
+            data.extend_from_slice(&1_699_999_999i64.to_le_bytes()); // prev publish time
+            data.extend_from_slice(&15_000_000_000i64.to_le_bytes()); // ema price
+            data.extend_from_slice(&10_000_000u64.to_le_bytes()); // ema conf
+            data.extend_from_slice(&1234u64.to_le_bytes()); // posted slot
+            data
+        };
+
+        // fully verified update in the push oracle's feed account
+        let mut full_data = price_update(&feed_address, &[1]);
+        let data = RefCell::new(&mut full_data[..]);
+        let ai = &AccountInfoRef {
+            key: &feed_address,
+            owner: &pyth_mainnet_receiver::ID,
+            data: data.borrow(),
+        };
+        assert_eq!(determine_oracle_type(ai)?, OracleType::PythPull);
+        check_is_valid_oracle(ai)?;
+        let state = oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 9)?;
+        // 150 USD per SOL is 0.15 native USDC per lamport
+        assert!((state.price.to_num::<f64>() - 0.15).abs() < 1e-9);
+        assert!((state.deviation.to_num::<f64>() - 0.0001).abs() < 1e-9);
+        assert_eq!(state.last_update_slot, 1234);
+
+        // partially verified updates are rejected
+        let mut partial_data = price_update(&feed_address, &[0, 5]);
+        let data = RefCell::new(&mut partial_data[..]);
+        let ai = &AccountInfoRef {
+            key: &feed_address,
+            owner: &pyth_mainnet_receiver::ID,
+            data: data.borrow(),
+        };
+        assert!(oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 9).is_err());
+
+        // an account that is not the feed account of its feed id can't be configured
+        let other_key = Pubkey::new_unique();
+        let mut other_data = price_update(&other_key, &[1]);
+        let data = RefCell::new(&mut other_data[..]);
+        let ai = &AccountInfoRef {
+            key: &other_key,
+            owner: &pyth_mainnet_receiver::ID,
+            data: data.borrow(),
+        };
+        assert!(check_is_valid_oracle(ai).is_err());
+
+        // an account that is not its own write authority is rejected when reading
+        let mut other_data = price_update(&Pubkey::new_unique(), &[1]);
+        let data = RefCell::new(&mut other_data[..]);
+        let ai = &AccountInfoRef {
+            key: &feed_address,
+            owner: &pyth_mainnet_receiver::ID,
+            data: data.borrow(),
+        };
+        assert!(oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 9).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_switchboard_on_demand_oracle() -> Result<()> {
+        let pull_feed = |num_samples: u8| {
+            let mut data = PULL_FEED_ACCOUNT_DATA_DISCRIMINATOR.to_vec();
+            data.resize(2264, 0);
+            data.extend_from_slice(&(150i128 * 10i128.pow(18)).to_le_bytes()); // value
+            data.extend_from_slice(&(10i128.pow(17)).to_le_bytes()); // std_dev
+            data.extend_from_slice(&[0; 64]); // mean, range, min_value, max_value
+            data.extend_from_slice(&[num_samples, 0, 0, 0, 0, 0, 0, 0]);
+            data.extend_from_slice(&1234u64.to_le_bytes()); // slot
+            data.extend_from_slice(&[0; 16]); // min_slot, max_slot
+            data.extend_from_slice(&[0; 800]);
+            data
+        };
+        let key = Pubkey::new_unique();
+
+        let mut feed_data = pull_feed(3);
+        let data = RefCell::new(&mut feed_data[..]);
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &switchboard_on_demand_mainnet_oracle::ID,
+            data: data.borrow(),
+        };
+        assert_eq!(determine_oracle_type(ai)?, OracleType::SwitchboardOnDemand);
+        let state = oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 9)?;
+        // 150 USD per SOL is 0.15 native USDC per lamport
+        assert!((state.price.to_num::<f64>() - 0.15).abs() < 1e-9);
+        assert!((state.deviation.to_num::<f64>() - 0.0001).abs() < 1e-9);
+        assert_eq!(state.last_update_slot, 1234);
+
+        // feeds without samples are rejected
+        let mut empty_data = pull_feed(0);
+        let data = RefCell::new(&mut empty_data[..]);
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &switchboard_on_demand_mainnet_oracle::ID,
+            data: data.borrow(),
+        };
+        assert!(oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 9).is_err());
+
+        // truncated accounts are rejected
+        let mut short_data = pull_feed(3)[..100].to_vec();
+        let data = RefCell::new(&mut short_data[..]);
+        let ai = &AccountInfoRef {
+            key: &key,
+            owner: &switchboard_on_demand_mainnet_oracle::ID,
+            data: data.borrow(),
+        };
+        assert!(oracle_state_unchecked(&OracleAccountInfos::from_reader(ai), 9).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_median_oracle_state() -> Result<()> {
+        // the last stub has a deviation that fails the confidence filter
+        let prices_and_deviations = [(2.0, 0.0), (1.0, 0.0), (10.0, 0.0), (5.0, 4.0)];
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut datas: Vec<Vec<u8>> = prices_and_deviations
+            .iter()
+            .map(|(price, deviation)| {
+                let stub = StubOracle {
+                    group: Pubkey::new_unique(),
+                    mint: Pubkey::new_unique(),
+                    price: I80F48::from_num(*price),
+                    last_update_ts: 0,
+                    last_update_slot: 0,
+                    deviation: I80F48::from_num(*deviation),
+                    reserved: [0; 104],
+                };
+                let mut data = StubOracle::discriminator().to_vec();
+                data.extend_from_slice(bytemuck::bytes_of(&stub));
+                data
+            })
+            .collect();
+        let cells: Vec<RefCell<&mut [u8]>> =
+            datas.iter_mut().map(|d| RefCell::new(&mut d[..])).collect();
+        let ais: Vec<AccountInfoRef> = cells
+            .iter()
+            .zip(keys.iter())
+            .map(|(data, key)| AccountInfoRef {
+                key,
+                owner: &crate::ID,
+                data: data.borrow(),
+            })
+            .collect();
+
+        let config = OracleConfigParams {
+            conf_filter: 0.1,
+            max_staleness_slots: None,
         }
+        .to_oracle_config();
+        let median_oracles = median_oracles_from_slice(&keys[1..])?;
+        let acc_infos = OracleAccountInfos {
+            oracle: &ais[0],
+            fallback_opt: None,
+            usdc_opt: None,
+            sol_opt: None,
+            median_opts: [Some(&ais[1]), Some(&ais[2]), Some(&ais[3])],
+        };
+
+        // three valid sources: median of 1, 2, 10
+        let state = median_oracle_state(&acc_infos, &median_oracles, 3, &config, 6, None)?;
+        assert_eq!(state.price, I80F48::from_num(2.0));
+        assert!(median_oracle_state(&acc_infos, &median_oracles, 4, &config, 6, None).is_err());
+
+        // missing accounts are skipped and an even count averages the middle prices
+        let acc_infos = OracleAccountInfos {
+            median_opts: [Some(&ais[1]), None, Some(&ais[3])],
+            ..acc_infos
+        };
+        let state = median_oracle_state(&acc_infos, &median_oracles, 2, &config, 6, None)?;
+        assert_eq!(state.price, I80F48::from_num(1.5));
+        assert!(median_oracle_state(&acc_infos, &median_oracles, 3, &config, 6, None).is_err());
 
         Ok(())
     }
@@ -452,7 +1178,13 @@ mod tests {
                 OracleType::OrcaCLMM,
                 orca_mainnet_whirlpool::ID,
                 9, // SOL/USDC pool
-            )
+            ),
+            (
+                "Gnt27xtC473ZT2Mw5u8wZ68Z3gULkSTb5DuxJy7eJotD",
+                OracleType::Pyth,
+                Pubkey::default(),
+                6,
+            ),
         ];
 
         let clmm_file = format!("resources/test/{}.bin", fixtures[0].0);
@@ -480,12 +1212,14 @@ mod tests {
             fallback_opt: None,
             usdc_opt: None,
             sol_opt: None,
+            median_opts: [None; MAX_MEDIAN_ORACLES],
         };
         let orca_ais = OracleAccountInfos {
             oracle: ai,
             fallback_opt: None,
             usdc_opt: Some(usdc_ai),
             sol_opt: None,
+            median_opts: [None; MAX_MEDIAN_ORACLES],
         };
         let usdc = oracle_state_unchecked(&usdc_ais, usdc_decimals).unwrap();
         let orca = oracle_state_unchecked(&orca_ais, base_decimals).unwrap();
@@ -525,6 +1259,7 @@ mod tests {
                 fallback_opt: None,
                 usdc_opt: None,
                 sol_opt: None,
+                median_opts: [None; MAX_MEDIAN_ORACLES],
             };
             assert!(oracle_state_unchecked(&oracle_infos, base_decimals)
                 .is_anchor_error_with_code(6068));
@@ -532,4 +1267,89 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    pub fn test_raydium_clmm_price() -> Result<()> {
+        let usdc_file = "resources/test/Gnt27xtC473ZT2Mw5u8wZ68Z3gULkSTb5DuxJy7eJotD.bin";
+        let mut usdc_data = read_file(find_file(usdc_file).unwrap());
+        let usdc_data_cell = RefCell::new(&mut usdc_data[..]);
+        let usdc_ai = &AccountInfoRef {
+            key: &pyth_mainnet_usdc_oracle::ID,
+            owner: &Pubkey::default(),
+            data: usdc_data_cell.borrow(),
+        };
+        let usdc = oracle_state_unchecked(&OracleAccountInfos::from_reader(usdc_ai), 6)?;
+
+        // SOL/USDC pools at 60 USDC/SOL (0.06 in native/native), in both mint orders,
+        // with deep and thin active liquidity
+        let fixtures = vec![
+            (
+                sol_mint_mainnet::ID,
+                usdc_mint_mainnet::ID,
+                0.06f64,
+                1e15 as u128,
+                true,
+            ),
+            (
+                usdc_mint_mainnet::ID,
+                sol_mint_mainnet::ID,
+                1.0 / 0.06f64,
+                1e15 as u128,
+                true,
+            ),
+            (
+                sol_mint_mainnet::ID,
+                usdc_mint_mainnet::ID,
+                0.06f64,
+                1e9 as u128,
+                false,
+            ),
+            (
+                usdc_mint_mainnet::ID,
+                sol_mint_mainnet::ID,
+                1.0 / 0.06f64,
+                0,
+                false,
+            ),
+        ];
+        let config = OracleConfigParams {
+            conf_filter: 0.1,
+            max_staleness_slots: None,
+        }
+        .to_oracle_config();
+
+        for (mint_0, mint_1, pool_price, liquidity, confident) in fixtures {
+            let mut pool_data = vec![0u8; RAYDIUM_POOL_LEN];
+            pool_data[0..8].copy_from_slice(&RAYDIUM_POOL_DISCRIMINATOR);
+            pool_data[73..105].copy_from_slice(mint_0.as_ref());
+            pool_data[105..137].copy_from_slice(mint_1.as_ref());
+            pool_data[237..253].copy_from_slice(&liquidity.to_le_bytes());
+            let sqrt_price = U64F64::from_num(pool_price.sqrt());
+            pool_data[253..269].copy_from_slice(&sqrt_price.to_bits().to_le_bytes());
+            let pool_data_cell = RefCell::new(&mut pool_data[..]);
+            let pool_key = Pubkey::new_unique();
+            let pool_ai = &AccountInfoRef {
+                key: &pool_key,
+                owner: &raydium_mainnet_clmm::ID,
+                data: pool_data_cell.borrow(),
+            };
+            assert!(determine_oracle_type(pool_ai).unwrap() == OracleType::RaydiumCLMM);
+            check_is_valid_fallback_oracle(pool_ai)?;
+
+            let pool_ais = OracleAccountInfos {
+                oracle: pool_ai,
+                fallback_opt: None,
+                usdc_opt: Some(usdc_ai),
+                sol_opt: None,
+                median_opts: [None; MAX_MEDIAN_ORACLES],
+            };
+            let pool = oracle_state_unchecked(&pool_ais, 9)?;
+            assert!(pool.oracle_type == OracleType::RaydiumCLMM);
+            let expected = I80F48::from_num(0.06) * usdc.price;
+            assert!((pool.price - expected).abs() < I80F48::from_num(1e-9));
+            assert_eq!(pool.check_confidence(&config).is_ok(), confident);
+        }
+
+        Ok(())
+    }
 }
@@ -3,9 +3,7 @@ use solana_program::pubkey::Pubkey;
 
 use crate::{accounts_zerocopy::KeyedAccountReader, error::MangoError};
 
-use super::{
-    pyth_mainnet_sol_oracle, pyth_mainnet_usdc_oracle, sol_mint_mainnet, usdc_mint_mainnet,
-};
+use super::{clmm_is_inverted, clmm_quote_oracle};
 
 pub mod orca_mainnet_whirlpool {
     use solana_program::declare_id;
@@ -16,6 +14,8 @@ pub const ORCA_WHIRLPOOL_LEN: usize = 653;
 pub const ORCA_WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
 
 pub struct WhirlpoolState {
+    // active liquidity at the current tick
+    pub liquidity: u128, // 16
     // Q64.64
     pub sqrt_price: u128,     // 16
     pub token_mint_a: Pubkey, // 32
@@ -24,25 +24,11 @@ pub struct WhirlpoolState {
 
 impl WhirlpoolState {
     pub fn is_inverted(&self) -> bool {
-        self.token_mint_a == usdc_mint_mainnet::ID
-            || (self.token_mint_a == sol_mint_mainnet::ID
-                && self.token_mint_b != usdc_mint_mainnet::ID)
+        clmm_is_inverted(&self.token_mint_a, &self.token_mint_b)
     }
 
     pub fn get_quote_oracle(&self) -> Result<Pubkey> {
-        let mint = if self.is_inverted() {
-            self.token_mint_a
-        } else {
-            self.token_mint_b
-        };
-
-        if mint == usdc_mint_mainnet::ID {
-            return Ok(pyth_mainnet_usdc_oracle::ID);
-        } else if mint == sol_mint_mainnet::ID {
-            return Ok(pyth_mainnet_sol_oracle::ID);
-        } else {
-            return Err(MangoError::MissingFeedForCLMMOracle.into());
-        }
+        clmm_quote_oracle(&self.token_mint_a, &self.token_mint_b)
     }
 }
 
@@ -61,6 +47,8 @@ pub fn load_whirlpool_state(acc_info: &impl KeyedAccountReader) -> Result<Whirlp
         MangoError::InvalidCLMMOracle
     );
 
+    let liquidity_bytes: &[u8; 16] = &data[49..65].try_into().unwrap();
+    let liquidity = u128::from_le_bytes(*liquidity_bytes);
     let price_bytes: &[u8; 16] = &data[65..81].try_into().unwrap();
     let sqrt_price = u128::from_le_bytes(*price_bytes);
     let a: &[u8; 32] = &(&data[101..133]).try_into().unwrap();
@@ -69,6 +57,7 @@ pub fn load_whirlpool_state(acc_info: &impl KeyedAccountReader) -> Result<Whirlp
     let mint_b = Pubkey::from(*b);
 
     Ok(WhirlpoolState {
+        liquidity,
         sqrt_price,
         token_mint_a: mint_a,
         token_mint_b: mint_b,
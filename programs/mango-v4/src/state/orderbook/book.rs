@@ -2,11 +2,13 @@ use crate::error::*;
 use crate::logs::{emit_stack, FilledPerpOrderLog, PerpTakerTradeLog};
 use crate::state::{
     orderbook::bookside::*, EventQueue, MangoAccountRefMut, PerpMarket, PerpMarketIndex,
+    FEE_BUYBACK_QUOTE_TOKEN_INDEX,
 };
 use anchor_lang::prelude::*;
 use bytemuck::cast;
 use fixed::types::I80F48;
 use std::cell::RefMut;
+use std::collections::VecDeque;
 
 use super::*;
 
@@ -49,6 +51,7 @@ impl<'a> Orderbook<'a> {
         mango_account: &mut MangoAccountRefMut,
         mango_account_pk: &Pubkey,
         now_ts: u64,
+        now_slot: u64,
         mut limit: u8,
     ) -> std::result::Result<Option<u128>, Error> {
         let side = order.side;
@@ -57,7 +60,7 @@ impl<'a> Orderbook<'a> {
         let oracle_price_lots = market.native_price_to_lot(oracle_price);
         let post_only = order.is_post_only();
         let mut post_target = order.post_target();
-        let (price_lots, price_data) = order.price(now_ts, oracle_price_lots, self)?;
+        let (price_lots, price_data) = order.price(now_ts, now_slot, oracle_price_lots, self)?;
 
         // generate new order id
         let order_id = market.gen_order_id(side, price_data);
@@ -79,16 +82,38 @@ impl<'a> Orderbook<'a> {
         let mut remaining_quote_lots = order.max_quote_lots;
         let mut decremented_base_lots = 0i64;
         let mut decremented_quote_lots = 0i64;
-        let mut orders_to_change: Vec<(BookSideOrderHandle, i64)> = vec![];
+        let mut orders_to_change: Vec<(BookSideOrderHandle, i64, i64)> = vec![];
         let mut orders_to_delete: Vec<(BookSideOrderTree, u128)> = vec![];
         let mut number_of_dropped_expired_orders = 0;
         let opposing_bookside = self.bookside_mut(other_side);
-        for best_opposing in opposing_bookside.iter_all_including_invalid(now_ts, oracle_price_lots)
-        {
+        let mut opposing_orders = opposing_bookside
+            .iter_all_including_invalid(now_ts, now_slot, oracle_price_lots)
+            .peekable();
+        // Orders at the current price whose visible quantity was filled and that have
+        // hidden quantity left, with the index of their entry in orders_to_change
+        let mut hidden_orders: VecDeque<(BookSideIterItem, usize)> = VecDeque::new();
+        loop {
             if remaining_base_lots == 0 || remaining_quote_lots == 0 {
                 break;
             }
 
+            // Hidden quantity only matches once the visible quantity at its price is used up
+            let price_level_done = match (hidden_orders.front(), opposing_orders.peek()) {
+                (Some(_), None) => true,
+                (Some((hidden, _)), Some(next)) => {
+                    next.is_valid() && next.price_lots != hidden.price_lots
+                }
+                (None, _) => false,
+            };
+            let (best_opposing, hidden_change_index) = if price_level_done {
+                let (item, change_index) = hidden_orders.pop_front().unwrap();
+                (item, Some(change_index))
+            } else if let Some(item) = opposing_orders.next() {
+                (item, None)
+            } else {
+                break;
+            };
+
             if !best_opposing.is_valid() {
                 // Remove the order from the book unless we've done that enough
                 if number_of_dropped_expired_orders < DROP_EXPIRED_ORDER_LIMIT {
@@ -125,8 +150,13 @@ impl<'a> Orderbook<'a> {
                 break;
             }
 
+            let available_base_lots = if hidden_change_index.is_some() {
+                best_opposing.node.hidden_quantity
+            } else {
+                best_opposing.node.quantity
+            };
             let match_base_lots = remaining_base_lots
-                .min(best_opposing.node.quantity)
+                .min(available_base_lots)
                 .min(max_match_by_quote);
             let match_quote_lots = match_base_lots * best_opposing_price;
 
@@ -162,12 +192,29 @@ impl<'a> Orderbook<'a> {
             remaining_quote_lots -= match_quote_lots;
             assert!(remaining_quote_lots >= 0);
 
-            let new_best_opposing_quantity = best_opposing.node.quantity - match_base_lots;
+            // A match of the hidden quantity comes after the whole visible quantity
+            let filled_base_lots = if hidden_change_index.is_some() {
+                best_opposing.node.quantity + match_base_lots
+            } else {
+                match_base_lots
+            };
+            let (new_best_opposing_quantity, new_best_opposing_hidden_quantity) =
+                best_opposing.node.quantities_after_fill(filled_base_lots);
             let maker_out = new_best_opposing_quantity == 0;
             if maker_out {
                 orders_to_delete.push((best_opposing.handle.order_tree, best_opposing.node.key));
+            } else if let Some(change_index) = hidden_change_index {
+                orders_to_change[change_index].1 = new_best_opposing_quantity;
+                orders_to_change[change_index].2 = new_best_opposing_hidden_quantity;
             } else {
-                orders_to_change.push((best_opposing.handle, new_best_opposing_quantity));
+                orders_to_change.push((
+                    BookSideOrderHandle {
+                        node: best_opposing.handle.node,
+                        order_tree: best_opposing.handle.order_tree,
+                    },
+                    new_best_opposing_quantity,
+                    new_best_opposing_hidden_quantity,
+                ));
             }
 
             // order_would_self_trade is only true in the DecrementTake case, in which we don't charge fees
@@ -206,7 +253,16 @@ impl<'a> Orderbook<'a> {
                 perp_market_index: market.perp_market_index,
                 seq_num,
             });
+
+            if hidden_change_index.is_none()
+                && match_base_lots == best_opposing.node.quantity
+                && best_opposing.node.hidden_quantity > 0
+            {
+                hidden_orders.push_back((best_opposing, orders_to_change.len() - 1));
+            }
         }
+        drop(hidden_orders);
+        drop(opposing_orders);
         let total_quote_lots_taken = order.max_quote_lots - remaining_quote_lots;
         let total_base_lots_taken = order.max_base_lots - remaining_base_lots;
         assert!(total_quote_lots_taken >= 0);
@@ -237,13 +293,14 @@ impl<'a> Orderbook<'a> {
         }
 
         // Apply changes to matched asks (handles invalidate on delete!)
-        for (handle, new_quantity) in orders_to_change {
-            opposing_bookside
+        for (handle, new_quantity, new_hidden_quantity) in orders_to_change {
+            let leaf = opposing_bookside
                 .node_mut(handle.node)
                 .unwrap()
                 .as_leaf_mut()
-                .unwrap()
-                .quantity = new_quantity;
+                .unwrap();
+            leaf.quantity = new_quantity;
+            leaf.hidden_quantity = new_hidden_quantity;
         }
         for (component, key) in orders_to_delete {
             let _removed_leaf = opposing_bookside.remove_by_key(component, key).unwrap();
@@ -282,8 +339,9 @@ impl<'a> Orderbook<'a> {
 
             if bookside.is_full() {
                 // If this bid is higher than lowest bid, boot that bid and insert this one
-                let (worst_order, worst_price) =
-                    bookside.remove_worst(now_ts, oracle_price_lots).unwrap();
+                let (worst_order, worst_price) = bookside
+                    .remove_worst(now_ts, now_slot, oracle_price_lots)
+                    .unwrap();
                 // MangoErrorCode::OutOfSpace
                 require!(
                     side.is_price_better(price_lots, worst_price),
@@ -299,17 +357,19 @@ impl<'a> Orderbook<'a> {
             }
 
             let owner_slot = mango_account.perp_next_order_slot()?;
-            let new_order = LeafNode::new(
+            let mut new_order = LeafNode::new(
                 owner_slot as u8,
                 order_id,
                 *mango_account_pk,
                 book_base_quantity,
                 now_ts,
                 PostOrderType::Limit, // TODO: Support order types? needed?
-                order.time_in_force,
+                0,
                 order.peg_limit(),
                 order.client_order_id,
             );
+            new_order.set_expiry(order.time_in_force, order.expiry_slot);
+            new_order.set_display_quantity(order.display_base_lots());
             let _result = bookside.insert_leaf(order_tree_target, &new_order)?;
 
             // TODO OPT remove if PlacePerpOrder needs more compute
@@ -424,7 +484,7 @@ impl<'a> Orderbook<'a> {
         let on_book_quantity = if let Some(leaf_node) = leaf_node_opt {
             require_eq!(leaf_node.owner_slot as usize, slot);
             require_keys_eq!(leaf_node.owner, *mango_account_pk);
-            leaf_node.quantity
+            leaf_node.total_quantity()
         } else {
             // Old orders didn't keep track of `quantity` on the oo slot. They are not allowed
             // to be cancelled while a canceling Fill- or OutEvent is in flight.
@@ -438,6 +498,61 @@ impl<'a> Orderbook<'a> {
         Ok(())
     }
 
+    /// Reduces the quantity of the order in an open order slot in place, keeping its
+    /// position in the queue.
+    ///
+    /// Fails if the order is no longer on the book or `new_quantity` isn't smaller
+    /// than the quantity currently on the book. For iceberg orders `new_quantity` is
+    /// the total quantity and the hidden part is reduced first.
+    pub fn reduce_order_quantity_by_slot(
+        &mut self,
+        mango_account: &mut MangoAccountRefMut,
+        mango_account_pk: &Pubkey,
+        slot: usize,
+        perp_market_index: PerpMarketIndex,
+        new_quantity: i64,
+    ) -> Result<()> {
+        require_gt!(new_quantity, 0);
+
+        let oo = mango_account.perp_order_by_raw_index(slot)?;
+        if !oo.is_active_for_market(perp_market_index) {
+            return Err(error_msg_typed!(
+                MangoError::SomeError,
+                "perp orders at slot {slot} is not active for perp market {perp_market_index}"
+            ));
+        }
+
+        let side_and_tree = oo.side_and_tree();
+        let side = side_and_tree.side();
+        let book_component = side_and_tree.order_tree();
+        let order_id = oo.id;
+
+        let bookside = self.bookside_mut(side);
+        let leaf_node = bookside
+            .find_by_key(book_component, order_id)
+            .and_then(|handle| bookside.node_mut(handle))
+            .and_then(|node| node.as_leaf_mut())
+            .ok_or_else(|| {
+                error_msg_typed!(MangoError::PerpOrderIdNotFound, "no perp order with id {order_id}, side {side:?}, component {book_component:?} found on the orderbook")
+            })?;
+        require_eq!(leaf_node.owner_slot as usize, slot);
+        require_keys_eq!(leaf_node.owner, *mango_account_pk);
+        require_gt!(leaf_node.total_quantity(), new_quantity);
+
+        let reduction = leaf_node.total_quantity() - new_quantity;
+        let hidden_reduction = reduction.min(leaf_node.hidden_quantity);
+        leaf_node.hidden_quantity -= hidden_reduction;
+        leaf_node.quantity -= reduction - hidden_reduction;
+
+        mango_account
+            .perp_position_mut(perp_market_index)?
+            .adjust_maker_lots(side, -reduction);
+        let oo = mango_account.perp_order_mut_by_raw_index(slot);
+        oo.quantity = (oo.quantity - reduction).max(0);
+
+        Ok(())
+    }
+
     /// Cancels an order on a side, removing it from the book and the mango account orders list
     pub fn cancel_order_by_id(
         &mut self,
@@ -456,7 +571,8 @@ impl<'a> Orderbook<'a> {
         if let Some(owner) = expected_owner {
             require_keys_eq!(leaf_node.owner, owner);
         }
-        mango_account.remove_perp_order(leaf_node.owner_slot as usize, leaf_node.quantity)?;
+        mango_account
+            .remove_perp_order(leaf_node.owner_slot as usize, leaf_node.total_quantity())?;
         Ok(leaf_node)
     }
 }
@@ -481,9 +597,11 @@ fn apply_fees(
 
     // Part of the taker fees that go to the dao, instead of paying for maker rebates
     let taker_dao_fees = (taker_fees + maker_fees.min(I80F48::ZERO)).max(I80F48::ZERO);
-    account
-        .fixed
-        .accrue_buyback_fees(taker_dao_fees.floor().to_num::<u64>());
+    if market.settle_token_index == FEE_BUYBACK_QUOTE_TOKEN_INDEX {
+        account
+            .fixed
+            .accrue_buyback_fees(taker_dao_fees.floor().to_num::<u64>());
+    }
 
     let perp_position = account.perp_position_mut(market.perp_market_index)?;
     perp_position.record_trading_fee(taker_fees);
@@ -502,9 +620,11 @@ fn apply_fees(
 /// Applies a fixed penalty fee to the account, and update the market's fees_accrued
 fn apply_penalty(market: &mut PerpMarket, account: &mut MangoAccountRefMut) -> Result<I80F48> {
     let fee_penalty = I80F48::from_num(market.fee_penalty);
-    account
-        .fixed
-        .accrue_buyback_fees(fee_penalty.floor().to_num::<u64>());
+    if market.settle_token_index == FEE_BUYBACK_QUOTE_TOKEN_INDEX {
+        account
+            .fixed
+            .accrue_buyback_fees(fee_penalty.floor().to_num::<u64>());
+    }
 
     let perp_position = account.perp_position_mut(market.perp_market_index)?;
     perp_position.record_trading_fee(fee_penalty);
@@ -49,14 +49,20 @@ impl BookSide {
     pub fn iter_valid(
         &self,
         now_ts: u64,
+        now_slot: u64,
         oracle_price_lots: i64,
     ) -> impl Iterator<Item = BookSideIterItem> {
-        BookSideIter::new(self, now_ts, oracle_price_lots).filter(|it| it.is_valid())
+        BookSideIter::new(self, now_ts, now_slot, oracle_price_lots).filter(|it| it.is_valid())
     }
 
     /// Iterate over all entries, including invalid orders
-    pub fn iter_all_including_invalid(&self, now_ts: u64, oracle_price_lots: i64) -> BookSideIter {
-        BookSideIter::new(self, now_ts, oracle_price_lots)
+    pub fn iter_all_including_invalid(
+        &self,
+        now_ts: u64,
+        now_slot: u64,
+        oracle_price_lots: i64,
+    ) -> BookSideIter {
+        BookSideIter::new(self, now_ts, now_slot, oracle_price_lots)
     }
 
     pub fn node(&self, handle: NodeHandle) -> Option<&AnyNode> {
@@ -89,7 +95,12 @@ impl BookSide {
     }
 
     /// Remove the overall worst-price order.
-    pub fn remove_worst(&mut self, now_ts: u64, oracle_price_lots: i64) -> Option<(LeafNode, i64)> {
+    pub fn remove_worst(
+        &mut self,
+        now_ts: u64,
+        now_slot: u64,
+        oracle_price_lots: i64,
+    ) -> Option<(LeafNode, i64)> {
         let worst_fixed = self.nodes.find_worst(&self.roots[0]);
         let worst_pegged = self.nodes.find_worst(&self.roots[1]);
         let side = self.nodes.order_tree_type().side();
@@ -99,6 +110,7 @@ impl BookSide {
             worst_pegged,
             true,
             now_ts,
+            now_slot,
             oracle_price_lots,
         )?;
         let price = worse.price_lots;
@@ -137,6 +149,15 @@ impl BookSide {
         self.nodes.remove_by_key(root, search_key)
     }
 
+    pub fn find_by_key(
+        &self,
+        component: BookSideOrderTree,
+        search_key: u128,
+    ) -> Option<NodeHandle> {
+        let root = &self.roots[component as usize];
+        self.nodes.find_by_key(root, search_key)
+    }
+
     pub fn side(&self) -> Side {
         self.nodes.order_tree_type().side()
     }
@@ -146,11 +167,12 @@ impl BookSide {
         &self,
         limit_price_lots: i64,
         now_ts: u64,
+        now_slot: u64,
         oracle_price_lots: i64,
     ) -> i64 {
         let side = self.side();
         let mut sum = 0;
-        for item in self.iter_valid(now_ts, oracle_price_lots) {
+        for item in self.iter_valid(now_ts, now_slot, oracle_price_lots) {
             if side.is_price_better(limit_price_lots, item.price_lots) {
                 break;
             }
@@ -160,9 +182,9 @@ impl BookSide {
     }
 
     /// Return the price of the order closest to the spread
-    pub fn best_price(&self, now_ts: u64, oracle_price_lots: i64) -> Option<i64> {
+    pub fn best_price(&self, now_ts: u64, now_slot: u64, oracle_price_lots: i64) -> Option<i64> {
         Some(
-            self.iter_valid(now_ts, oracle_price_lots)
+            self.iter_valid(now_ts, now_slot, oracle_price_lots)
                 .next()?
                 .price_lots,
         )
@@ -170,9 +192,15 @@ impl BookSide {
 
     /// Walk up the book `quantity` units and return the price at that level. If `quantity` units
     /// not on book, return None
-    pub fn impact_price(&self, quantity: i64, now_ts: u64, oracle_price_lots: i64) -> Option<i64> {
+    pub fn impact_price(
+        &self,
+        quantity: i64,
+        now_ts: u64,
+        now_slot: u64,
+        oracle_price_lots: i64,
+    ) -> Option<i64> {
         let mut sum: i64 = 0;
-        for order in self.iter_valid(now_ts, oracle_price_lots) {
+        for order in self.iter_valid(now_ts, now_slot, oracle_price_lots) {
             sum += order.node.quantity;
             if sum >= quantity {
                 return Some(order.price_lots);
@@ -187,6 +215,7 @@ impl BookSide {
         &self,
         quantity: i64,
         now_ts: u64,
+        now_slot: u64,
         oracle_price_lots: i64,
     ) -> Option<i64> {
         if quantity <= 0 {
@@ -194,7 +223,7 @@ impl BookSide {
         }
         let mut sum_qty: i64 = 0;
         let mut sum_amt: i64 = 0;
-        for order in self.iter_valid(now_ts, oracle_price_lots) {
+        for order in self.iter_valid(now_ts, now_slot, oracle_price_lots) {
             sum_qty += order.node.quantity;
             sum_amt += order.node.quantity * order.price_lots;
             let extra_qty = sum_qty - quantity;
@@ -213,6 +242,7 @@ impl BookSide {
         &self,
         amount: i64,
         now_ts: u64,
+        now_slot: u64,
         oracle_price_lots: i64,
     ) -> Option<i64> {
         if amount <= 0 {
@@ -220,7 +250,7 @@ impl BookSide {
         }
         let mut sum_qty: i64 = 0;
         let mut sum_amt: i64 = 0;
-        for order in self.iter_valid(now_ts, oracle_price_lots) {
+        for order in self.iter_valid(now_ts, now_slot, oracle_price_lots) {
             sum_qty += order.node.quantity;
             sum_amt += order.node.quantity * order.price_lots;
             let extra_amt = sum_amt - amount;
@@ -320,7 +350,7 @@ mod tests {
             let mut total = 0;
             let ascending = order_tree_type == OrderTreeType::Asks;
             let mut last_price = if ascending { 0 } else { i64::MAX };
-            for order in bookside.iter_all_including_invalid(0, oracle_price_lots) {
+            for order in bookside.iter_all_including_invalid(0, 0, oracle_price_lots) {
                 let price = order.price_lots;
                 println!("{} {:?} {price}", order.node.key, order.handle.order_tree);
                 if ascending {
@@ -401,7 +431,7 @@ mod tests {
 
         let order_prices = |now_ts: u64, oracle: i64| -> Vec<i64> {
             bookside
-                .iter_valid(now_ts, oracle)
+                .iter_valid(now_ts, 0, oracle)
                 .map(|it| it.price_lots)
                 .collect()
         };
@@ -428,33 +458,33 @@ mod tests {
         let order_prices = |now_ts: u64, oracle: i64| -> Vec<i64> {
             bookside
                 .borrow()
-                .iter_valid(now_ts, oracle)
+                .iter_valid(now_ts, 0, oracle)
                 .map(|it| it.price_lots)
                 .collect()
         };
 
         // remove pegged order
         assert_eq!(order_prices(0, 100), vec![120, 100, 90, 85, 80]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, 0, 100).unwrap();
         assert_eq!(p, 80);
         assert_eq!(order_prices(0, 100), vec![120, 100, 90, 85]);
 
         // remove fixed order (order at 190=200-10 hits the peg limit)
         assert_eq!(order_prices(0, 200), vec![185, 120, 100]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 200).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, 0, 200).unwrap();
         assert_eq!(p, 100);
         assert_eq!(order_prices(0, 200), vec![185, 120]);
 
         // remove until end
 
         assert_eq!(order_prices(0, 100), vec![120, 90, 85]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, 0, 100).unwrap();
         assert_eq!(p, 85);
         assert_eq!(order_prices(0, 100), vec![120, 90]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, 0, 100).unwrap();
         assert_eq!(p, 90);
         assert_eq!(order_prices(0, 100), vec![120]);
-        let (_, p) = bookside.borrow_mut().remove_worst(0, 100).unwrap();
+        let (_, p) = bookside.borrow_mut().remove_worst(0, 0, 100).unwrap();
         assert_eq!(p, 120);
         assert_eq!(order_prices(0, 100), Vec::<i64>::new());
     }
@@ -19,17 +19,23 @@ impl<'a> BookSideIterItem<'a> {
 /// This will skip over orders that are not currently matchable, but might be valid
 /// in the future.
 ///
-/// This may return invalid orders (tif expired, peg_limit exceeded; see is_valid) which
+/// This may return invalid orders (tif or slot expired, peg_limit exceeded; see is_valid) which
 /// users are supposed to remove from the orderbook if they can.
 pub struct BookSideIter<'a> {
     fixed_iter: OrderTreeIter<'a>,
     oracle_pegged_iter: OrderTreeIter<'a>,
     now_ts: u64,
+    now_slot: u64,
     oracle_price_lots: i64,
 }
 
 impl<'a> BookSideIter<'a> {
-    pub fn new(book_side: &'a BookSide, now_ts: u64, oracle_price_lots: i64) -> Self {
+    pub fn new(
+        book_side: &'a BookSide,
+        now_ts: u64,
+        now_slot: u64,
+        oracle_price_lots: i64,
+    ) -> Self {
         Self {
             fixed_iter: book_side
                 .nodes
@@ -38,6 +44,7 @@ impl<'a> BookSideIter<'a> {
                 .nodes
                 .iter(book_side.root(BookSideOrderTree::OraclePegged)),
             now_ts,
+            now_slot,
             oracle_price_lots,
         }
     }
@@ -83,9 +90,9 @@ fn key_for_fixed_price(key: u128, price_lots: i64) -> u128 {
 }
 
 /// Helper for the iterator returning a fixed order
-fn fixed_to_result(fixed: (NodeHandle, &LeafNode), now_ts: u64) -> BookSideIterItem {
+fn fixed_to_result(fixed: (NodeHandle, &LeafNode), now_ts: u64, now_slot: u64) -> BookSideIterItem {
     let (handle, node) = fixed;
-    let expired = node.is_expired(now_ts);
+    let expired = node.is_expired(now_ts, now_slot);
     BookSideIterItem {
         handle: BookSideOrderHandle {
             order_tree: BookSideOrderTree::Fixed,
@@ -105,9 +112,10 @@ fn fixed_to_result(fixed: (NodeHandle, &LeafNode), now_ts: u64) -> BookSideIterI
 fn oracle_pegged_to_result(
     pegged: (NodeHandle, &LeafNode, i64, OrderState),
     now_ts: u64,
+    now_slot: u64,
 ) -> BookSideIterItem {
     let (handle, node, price_lots, state) = pegged;
-    let expired = node.is_expired(now_ts);
+    let expired = node.is_expired(now_ts, now_slot);
     BookSideIterItem {
         handle: BookSideOrderHandle {
             order_tree: BookSideOrderTree::OraclePegged,
@@ -128,6 +136,7 @@ pub fn rank_orders<'a>(
     oracle_pegged: Option<(NodeHandle, &'a LeafNode)>,
     return_worse: bool,
     now_ts: u64,
+    now_slot: u64,
     oracle_price_lots: i64,
 ) -> Option<BookSideIterItem<'a>> {
     // Enrich with data that'll always be needed
@@ -145,13 +154,13 @@ pub fn rank_orders<'a>(
             };
 
             if is_better(f.1.key, key_for_fixed_price(o.1.key, o.2)) ^ return_worse {
-                Some(fixed_to_result(f, now_ts))
+                Some(fixed_to_result(f, now_ts, now_slot))
             } else {
-                Some(oracle_pegged_to_result(o, now_ts))
+                Some(oracle_pegged_to_result(o, now_ts, now_slot))
             }
         }
-        (None, Some(o)) => Some(oracle_pegged_to_result(o, now_ts)),
-        (Some(f), None) => Some(fixed_to_result(f, now_ts)),
+        (None, Some(o)) => Some(oracle_pegged_to_result(o, now_ts, now_slot)),
+        (Some(f), None) => Some(fixed_to_result(f, now_ts, now_slot)),
         (None, None) => None,
     }
 }
@@ -181,6 +190,7 @@ impl<'a> Iterator for BookSideIter<'a> {
             o_peek,
             false,
             self.now_ts,
+            self.now_slot,
             self.oracle_price_lots,
         )?;
         match better.handle.order_tree {
@@ -125,11 +125,13 @@ mod tests {
                     max_quote_lots: i64::MAX,
                     client_order_id: 0,
                     time_in_force,
+                    expiry_slot: 0,
                     reduce_only: false,
                     self_trade_behavior: SelfTradeBehavior::DecrementTake,
                     params: OrderParams::Fixed {
                         price_lots,
                         order_type: PostOrderType::Limit,
+                        display_base_lots: 0,
                     },
                 },
                 &mut perp_market,
@@ -138,6 +140,7 @@ mod tests {
                 &mut account.borrow_mut(),
                 &Pubkey::default(),
                 now_ts,
+                0,
                 u8::MAX,
             )
             .unwrap();
@@ -275,11 +278,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 42,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::Fixed {
                     price_lots,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -288,6 +293,7 @@ mod tests {
             &mut maker.borrow_mut(),
             &maker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -356,11 +362,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 43,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::Fixed {
                     price_lots,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -369,6 +377,7 @@ mod tests {
             &mut taker.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -542,11 +551,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 42,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::default(),
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -555,6 +566,7 @@ mod tests {
             &mut maker_account.borrow_mut(),
             &maker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -567,11 +579,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 43,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -580,6 +594,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -606,6 +621,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 44,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::ImmediateOrCancel { price_lots: 1000 },
@@ -616,6 +632,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -665,11 +682,13 @@ mod tests {
                     max_quote_lots,
                     client_order_id: 0,
                     time_in_force: 0,
+                    expiry_slot: 0,
                     reduce_only: false,
                     self_trade_behavior: SelfTradeBehavior::DecrementTake,
                     params: OrderParams::Fixed {
                         price_lots,
                         order_type: PostOrderType::Limit,
+                        display_base_lots: 0,
                     },
                 },
                 &mut perp_market,
@@ -677,6 +696,7 @@ mod tests {
                 oracle_price,
                 &mut account.borrow_mut(),
                 &Pubkey::default(),
+                0,
                 0, // now_ts
                 u8::MAX,
             )
@@ -730,11 +750,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 1,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::default(),
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -743,6 +765,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -755,11 +778,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 2,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::default(),
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -768,6 +793,7 @@ mod tests {
             &mut maker_account.borrow_mut(),
             &maker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -780,6 +806,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 3,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::ImmediateOrCancel { price_lots: 1000 },
@@ -790,6 +817,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -823,11 +851,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 4,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::DecrementTake,
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -836,6 +866,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -901,11 +932,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 1,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::default(),
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -914,6 +947,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -926,11 +960,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 2,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::default(),
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -939,6 +975,7 @@ mod tests {
             &mut maker_account.borrow_mut(),
             &maker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -951,11 +988,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 3,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::CancelProvide,
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -964,6 +1003,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -1022,11 +1062,13 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 1,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::default(),
                 params: OrderParams::Fixed {
                     price_lots: 1000,
                     order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
                 },
             },
             &mut market,
@@ -1035,6 +1077,7 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .unwrap();
@@ -1047,6 +1090,7 @@ mod tests {
                 max_quote_lots: i64::MAX,
                 client_order_id: 3,
                 time_in_force: 0,
+                expiry_slot: 0,
                 reduce_only: false,
                 self_trade_behavior: SelfTradeBehavior::AbortTransaction,
                 params: OrderParams::ImmediateOrCancel { price_lots: 1000 },
@@ -1057,10 +1101,274 @@ mod tests {
             &mut taker_account.borrow_mut(),
             &taker_pk,
             now_ts,
+            0,
             u8::MAX,
         )
         .expect_err("should fail");
 
         Ok(())
     }
+
+    #[test]
+    fn test_reduce_order_quantity_keeps_priority() -> Result<()> {
+        let (mut market, oracle_price, mut event_queue, book_accs) = test_setup(1000.0);
+        let mut book = book_accs.orderbook();
+        let now_ts = 1000000;
+
+        let mut accounts = (0..3)
+            .map(|_| {
+                let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+                let mut account = MangoAccountValue::from_bytes(&buffer).unwrap();
+                account
+                    .ensure_perp_position(market.perp_market_index, 0)
+                    .unwrap();
+                (account, Pubkey::new_unique())
+            })
+            .collect::<Vec<_>>();
+
+        let order = |side: Side, max_base_lots: i64, params: OrderParams| Order {
+            side,
+            max_base_lots,
+            max_quote_lots: i64::MAX,
+            client_order_id: 0,
+            time_in_force: 0,
+            expiry_slot: 0,
+            reduce_only: false,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+            params,
+        };
+
+        // two makers with bids at the same price, the first one has priority
+        for (account, pk) in accounts.iter_mut().take(2) {
+            book.new_order(
+                order(
+                    Side::Bid,
+                    10,
+                    OrderParams::Fixed {
+                        price_lots: 1000,
+                        order_type: PostOrderType::Limit,
+                        display_base_lots: 0,
+                    },
+                ),
+                &mut market,
+                &mut event_queue,
+                oracle_price,
+                &mut account.borrow_mut(),
+                pk,
+                now_ts,
+                0,
+                u8::MAX,
+            )?;
+        }
+        let first_id = accounts[0].0.perp_order_by_raw_index(0)?.id;
+        let second_id = accounts[1].0.perp_order_by_raw_index(0)?.id;
+
+        // reducing the first order keeps its key and updates the account tracking
+        {
+            let (account, pk) = &mut accounts[0];
+            book.reduce_order_quantity_by_slot(&mut account.borrow_mut(), pk, 0, 0, 4)?;
+            let oo = account.perp_order_by_raw_index(0)?;
+            assert_eq!(oo.id, first_id);
+            assert_eq!(oo.quantity, 4);
+            assert_eq!(account.perp_position(0)?.bids_base_lots, 4);
+
+            // can't increase or keep the quantity
+            assert!(book
+                .reduce_order_quantity_by_slot(&mut account.borrow_mut(), pk, 0, 0, 4)
+                .is_err());
+            assert!(book
+                .reduce_order_quantity_by_slot(&mut account.borrow_mut(), pk, 0, 0, 5)
+                .is_err());
+        }
+
+        // a taker matches the reduced first order before the second one
+        {
+            let (account, pk) = &mut accounts[2];
+            book.new_order(
+                order(
+                    Side::Ask,
+                    5,
+                    OrderParams::ImmediateOrCancel { price_lots: 1000 },
+                ),
+                &mut market,
+                &mut event_queue,
+                oracle_price,
+                &mut account.borrow_mut(),
+                pk,
+                now_ts,
+                0,
+                u8::MAX,
+            )?;
+        }
+        assert!(book
+            .bids
+            .find_by_key(BookSideOrderTree::Fixed, first_id)
+            .is_none());
+        let second_handle = book
+            .bids
+            .find_by_key(BookSideOrderTree::Fixed, second_id)
+            .unwrap();
+        assert_eq!(
+            book.bids
+                .node(second_handle)
+                .unwrap()
+                .as_leaf()
+                .unwrap()
+                .quantity,
+            9
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_iceberg_order() -> Result<()> {
+        let (mut market, oracle_price, mut event_queue, book_accs) = test_setup(1000.0);
+        let mut book = book_accs.orderbook();
+        let group = Group::zeroed();
+        let now_ts = 1000000;
+
+        let buffer = MangoAccount::default_for_tests().try_to_vec().unwrap();
+        let mut maker = MangoAccountValue::from_bytes(&buffer).unwrap();
+        let mut maker2 = MangoAccountValue::from_bytes(&buffer).unwrap();
+        let mut taker = MangoAccountValue::from_bytes(&buffer).unwrap();
+        maker.ensure_perp_position(market.perp_market_index, 0)?;
+        maker2.ensure_perp_position(market.perp_market_index, 0)?;
+        taker.ensure_perp_position(market.perp_market_index, 0)?;
+        let maker_pk = Pubkey::new_unique();
+        let maker2_pk = Pubkey::new_unique();
+        let taker_pk = Pubkey::new_unique();
+
+        let order = |side: Side, max_base_lots: i64, params: OrderParams| Order {
+            side,
+            max_base_lots,
+            max_quote_lots: i64::MAX,
+            client_order_id: 0,
+            time_in_force: 0,
+            expiry_slot: 0,
+            reduce_only: false,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+            params,
+        };
+
+        // a bid for 25 lots that only shows 10
+        book.new_order(
+            order(
+                Side::Bid,
+                25,
+                OrderParams::Fixed {
+                    price_lots: 1000,
+                    order_type: PostOrderType::Limit,
+                    display_base_lots: 10,
+                },
+            ),
+            &mut market,
+            &mut event_queue,
+            oracle_price,
+            &mut maker.borrow_mut(),
+            &maker_pk,
+            now_ts,
+            0,
+            u8::MAX,
+        )?;
+        let order_id = maker.perp_order_by_raw_index(0)?.id;
+        let leaf = |book: &Orderbook| {
+            let handle = book
+                .bids
+                .find_by_key(BookSideOrderTree::Fixed, order_id)
+                .unwrap();
+            *book.bids.node(handle).unwrap().as_leaf().unwrap()
+        };
+        assert_eq!(leaf(&book).quantity, 10);
+        assert_eq!(leaf(&book).hidden_quantity, 15);
+        assert_eq!(book.bids.quantity_at_price(1000, now_ts, 0, 1000), 10);
+        assert_eq!(maker.perp_order_by_raw_index(0)?.quantity, 25);
+        assert_eq!(maker.perp_position(0)?.bids_base_lots, 25);
+
+        // a later bid at the same price
+        book.new_order(
+            order(
+                Side::Bid,
+                5,
+                OrderParams::Fixed {
+                    price_lots: 1000,
+                    order_type: PostOrderType::Limit,
+                    display_base_lots: 0,
+                },
+            ),
+            &mut market,
+            &mut event_queue,
+            oracle_price,
+            &mut maker2.borrow_mut(),
+            &maker2_pk,
+            now_ts,
+            0,
+            u8::MAX,
+        )?;
+
+        // a taker can match into the hidden quantity, but only after the visible quantity
+        // of all orders at that price; the visible part gets replenished
+        book.new_order(
+            order(
+                Side::Ask,
+                17,
+                OrderParams::ImmediateOrCancel { price_lots: 1000 },
+            ),
+            &mut market,
+            &mut event_queue,
+            oracle_price,
+            &mut taker.borrow_mut(),
+            &taker_pk,
+            now_ts,
+            0,
+            u8::MAX,
+        )?;
+        assert_eq!(leaf(&book).quantity, 10);
+        assert_eq!(leaf(&book).hidden_quantity, 3);
+
+        let fill: FillEvent = event_queue.pop_front()?.try_into()?;
+        assert_eq!(fill.maker, maker_pk);
+        assert_eq!(fill.quantity, 10);
+        assert!(!fill.maker_out());
+        maker.execute_perp_maker(market.perp_market_index, &mut market, &fill, &group)?;
+        let fill: FillEvent = event_queue.pop_front()?.try_into()?;
+        assert_eq!(fill.maker, maker2_pk);
+        assert_eq!(fill.quantity, 5);
+        assert!(fill.maker_out());
+        let fill: FillEvent = event_queue.pop_front()?.try_into()?;
+        assert_eq!(fill.maker, maker_pk);
+        assert_eq!(fill.quantity, 2);
+        assert!(!fill.maker_out());
+        maker.execute_perp_maker(market.perp_market_index, &mut market, &fill, &group)?;
+        assert_eq!(maker.perp_order_by_raw_index(0)?.quantity, 13);
+        assert_eq!(maker.perp_position(0)?.bids_base_lots, 13);
+
+        // a partial fill of the visible part doesn't touch the hidden quantity
+        book.new_order(
+            order(
+                Side::Ask,
+                4,
+                OrderParams::ImmediateOrCancel { price_lots: 1000 },
+            ),
+            &mut market,
+            &mut event_queue,
+            oracle_price,
+            &mut taker.borrow_mut(),
+            &taker_pk,
+            now_ts,
+            0,
+            u8::MAX,
+        )?;
+        assert_eq!(leaf(&book).quantity, 6);
+        assert_eq!(leaf(&book).hidden_quantity, 3);
+        let fill: FillEvent = event_queue.pop_front()?.try_into()?;
+        maker.execute_perp_maker(market.perp_market_index, &mut market, &fill, &group)?;
+        assert_eq!(maker.perp_position(0)?.bids_base_lots, 9);
+
+        // canceling frees the hidden quantity too
+        book.cancel_order_by_slot(&mut maker.borrow_mut(), &maker_pk, 0, 0)?;
+        assert_eq!(maker.perp_position(0)?.bids_base_lots, 0);
+
+        Ok(())
+    }
 }
@@ -156,7 +156,7 @@ pub struct LeafNode {
     pub padding: [u8; 1],
 
     /// Time in seconds after `timestamp` at which the order expires.
-    /// A value of 0 means no expiry.
+    /// A value of 0 means no expiry, unless expiry_timestamp is set.
     pub time_in_force: u16,
 
     pub padding2: [u8; 2],
@@ -167,7 +167,7 @@ pub struct LeafNode {
     /// Address of the owning MangoAccount
     pub owner: Pubkey,
 
-    /// Number of base lots to buy or sell, always >=1
+    /// Number of base lots to buy or sell that are visible on the book, always >=1
     pub quantity: i64,
 
     /// The time the order was placed
@@ -182,11 +182,24 @@ pub struct LeafNode {
     /// User defined id for this order, used in FillEvents
     pub client_order_id: u64,
 
-    pub reserved: [u8; 32],
+    /// Timestamp at which the order expires, for orders whose expiry is too far
+    /// in the future to be expressed with time_in_force. 0 if unused.
+    pub expiry_timestamp: u64,
+
+    /// Slot at which the order expires. A value of 0 means no slot expiry.
+    pub expiry_slot: u64,
+
+    /// Number of base lots of an iceberg order that are not visible on the book.
+    ///
+    /// They replenish `quantity` once it is filled completely.
+    pub hidden_quantity: i64,
+
+    /// Number of base lots to show on the book for iceberg orders. 0 for regular orders.
+    pub display_quantity: i64,
 }
 const_assert_eq!(
     size_of::<LeafNode>(),
-    4 + 1 + 1 + 1 + 1 + 16 + 32 + 8 + 8 + 8 + 8 + 32
+    4 + 1 + 1 + 1 + 1 + 16 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8
 );
 const_assert_eq!(size_of::<LeafNode>(), NODE_SIZE);
 const_assert_eq!(size_of::<LeafNode>() % 8, 0);
@@ -217,8 +230,57 @@ impl LeafNode {
             timestamp,
             peg_limit,
             client_order_id,
-            reserved: [0; 32],
+            expiry_timestamp: 0,
+            expiry_slot: 0,
+            hidden_quantity: 0,
+            display_quantity: 0,
+        }
+    }
+
+    /// Turns the order into an iceberg order that only shows `display_quantity` base
+    /// lots on the book, if that is less than the order's quantity.
+    pub fn set_display_quantity(&mut self, display_quantity: i64) {
+        let total_quantity = self.total_quantity();
+        if display_quantity > 0 && display_quantity < total_quantity {
+            self.quantity = display_quantity;
+            self.hidden_quantity = total_quantity - display_quantity;
+            self.display_quantity = display_quantity;
+        }
+    }
+
+    /// Visible and hidden base lots of the order
+    #[inline(always)]
+    pub fn total_quantity(&self) -> i64 {
+        self.quantity + self.hidden_quantity
+    }
+
+    /// Visible and hidden quantity that remain after `base_lots` of the order were
+    /// filled, with the visible part replenished from the hidden part if it was used up.
+    ///
+    /// The hidden quantity has no time priority: a taker matches it only after all
+    /// visible quantity at the same price, see Orderbook::new_order().
+    pub fn quantities_after_fill(&self, base_lots: i64) -> (i64, i64) {
+        if base_lots < self.quantity {
+            return (self.quantity - base_lots, self.hidden_quantity);
         }
+        let remaining = self.total_quantity() - base_lots;
+        let visible = remaining.min(self.display_quantity.max(1));
+        (visible, remaining - visible)
+    }
+
+    /// Sets the expiry of the order from the time in force in seconds
+    ///
+    /// Time in force values that don't fit into the u16 time_in_force field are
+    /// stored as an absolute expiry_timestamp.
+    pub fn set_expiry(&mut self, time_in_force: u64, expiry_slot: u64) {
+        if let Ok(tif) = u16::try_from(time_in_force) {
+            self.time_in_force = tif;
+            self.expiry_timestamp = 0;
+        } else {
+            self.time_in_force = 0;
+            self.expiry_timestamp = self.timestamp.saturating_add(time_in_force);
+        }
+        self.expiry_slot = expiry_slot;
     }
 
     /// The order's price_data as stored in the key
@@ -230,19 +292,23 @@ impl LeafNode {
     }
 
     /// Time at which this order will expire, u64::MAX if never
+    ///
+    /// This does not take expiry_slot into account.
     #[inline(always)]
     pub fn expiry(&self) -> u64 {
-        if self.time_in_force == 0 {
-            u64::MAX
-        } else {
+        if self.time_in_force > 0 {
             self.timestamp + self.time_in_force as u64
+        } else if self.expiry_timestamp > 0 {
+            self.expiry_timestamp
+        } else {
+            u64::MAX
         }
     }
 
-    /// Returns if the order is expired at `now_ts`
+    /// Returns if the order is expired at `now_ts` or `now_slot`
     #[inline(always)]
-    pub fn is_expired(&self, now_ts: u64) -> bool {
-        self.time_in_force > 0 && now_ts >= self.timestamp + self.time_in_force as u64
+    pub fn is_expired(&self, now_ts: u64, now_slot: u64) -> bool {
+        now_ts >= self.expiry() || (self.expiry_slot > 0 && now_slot >= self.expiry_slot)
     }
 }
 
@@ -433,4 +499,51 @@ mod tests {
             assert!(l_key < r_key);
         }
     }
+
+    #[test]
+    fn leaf_node_expiry() {
+        let new_leaf = |time_in_force: u64, expiry_slot: u64| {
+            let mut leaf = LeafNode::new(
+                0,
+                0,
+                Pubkey::default(),
+                1,
+                1000,
+                PostOrderType::Limit,
+                0,
+                -1,
+                0,
+            );
+            leaf.set_expiry(time_in_force, expiry_slot);
+            leaf
+        };
+
+        // good till cancelled
+        let leaf = new_leaf(0, 0);
+        assert_eq!(leaf.expiry(), u64::MAX);
+        assert!(!leaf.is_expired(u64::MAX - 1, u64::MAX));
+
+        // short time in force
+        let leaf = new_leaf(10, 0);
+        assert_eq!(leaf.time_in_force, 10);
+        assert_eq!(leaf.expiry(), 1010);
+        assert!(!leaf.is_expired(1009, 0));
+        assert!(leaf.is_expired(1010, 0));
+
+        // time in force that does not fit into u16
+        let tif = u16::MAX as u64 + 1;
+        let leaf = new_leaf(tif, 0);
+        assert_eq!(leaf.time_in_force, 0);
+        assert_eq!(leaf.expiry(), 1000 + tif);
+        assert!(!leaf.is_expired(999 + tif, 0));
+        assert!(leaf.is_expired(1000 + tif, 0));
+
+        // slot expiry, alone and combined with a time in force
+        let leaf = new_leaf(0, 50);
+        assert!(!leaf.is_expired(2000, 49));
+        assert!(leaf.is_expired(2000, 50));
+        let leaf = new_leaf(10, 50);
+        assert!(leaf.is_expired(1010, 0));
+        assert!(leaf.is_expired(1000, 50));
+    }
 }
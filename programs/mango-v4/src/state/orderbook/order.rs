@@ -19,7 +19,10 @@ pub struct Order {
     pub reduce_only: bool,
 
     /// Number of seconds the order shall live, 0 meaning forever
-    pub time_in_force: u16,
+    pub time_in_force: u64,
+
+    /// Slot at which the order expires, 0 meaning no slot expiry
+    pub expiry_slot: u64,
 
     /// Configure how matches with order of the same owner are handled
     pub self_trade_behavior: SelfTradeBehavior,
@@ -36,33 +39,44 @@ pub enum OrderParams {
     Fixed {
         price_lots: i64,
         order_type: PostOrderType,
+        /// Base lots to show on the book when posting, 0 to show everything
+        display_base_lots: i64,
     },
     OraclePegged {
         price_offset_lots: i64,
         order_type: PostOrderType,
         peg_limit: i64,
         max_oracle_staleness_slots: i32,
+        /// Base lots to show on the book when posting, 0 to show everything
+        display_base_lots: i64,
     },
 }
 
 impl Order {
     /// Convert an input expiry timestamp to a time_in_force value
-    pub fn tif_from_expiry(expiry_timestamp: u64) -> Option<u16> {
+    pub fn tif_from_expiry(expiry_timestamp: u64) -> Option<u64> {
         let now_ts: u64 = Clock::get().unwrap().unix_timestamp.try_into().unwrap();
         if expiry_timestamp != 0 {
-            // If expiry is far in the future, clamp to u16::MAX seconds
-            let tif = expiry_timestamp.saturating_sub(now_ts).min(u16::MAX.into());
+            let tif = expiry_timestamp.saturating_sub(now_ts);
             if tif == 0 {
                 // If expiry is in the past, ignore the order
                 return None;
             }
-            Some(tif as u16)
+            Some(tif)
         } else {
             // Never expire
             Some(0)
         }
     }
 
+    /// Check an input expiry slot, returning None if the order is already expired
+    pub fn expiry_slot_if_valid(expiry_slot: u64) -> Option<u64> {
+        if expiry_slot != 0 && expiry_slot <= Clock::get().unwrap().slot {
+            return None;
+        }
+        Some(expiry_slot)
+    }
+
     /// Should this order be penalized with an extra fee?
     ///
     /// Some programs opportunistically call ioc orders, wasting lots of compute. This
@@ -95,16 +109,18 @@ impl Order {
     fn price_for_order_type(
         &self,
         now_ts: u64,
+        now_slot: u64,
         oracle_price_lots: i64,
         price_lots: i64,
         order_type: PostOrderType,
         order_book: &Orderbook,
     ) -> i64 {
         if order_type == PostOrderType::PostOnlySlide {
-            if let Some(best_other_price) = order_book
-                .bookside(self.side.invert_side())
-                .best_price(now_ts, oracle_price_lots)
-            {
+            if let Some(best_other_price) = order_book.bookside(self.side.invert_side()).best_price(
+                now_ts,
+                now_slot,
+                oracle_price_lots,
+            ) {
                 post_only_slide_limit(self.side, best_other_price, price_lots)
             } else {
                 price_lots
@@ -119,6 +135,7 @@ impl Order {
     pub fn price(
         &self,
         now_ts: u64,
+        now_slot: u64,
         oracle_price_lots: i64,
         order_book: &Orderbook,
     ) -> Result<(i64, u64)> {
@@ -128,8 +145,10 @@ impl Order {
             OrderParams::Fixed {
                 price_lots,
                 order_type,
+                ..
             } => self.price_for_order_type(
                 now_ts,
+                now_slot,
                 oracle_price_lots,
                 price_lots,
                 order_type,
@@ -143,6 +162,7 @@ impl Order {
                 let price_lots = oracle_price_lots + price_offset_lots;
                 self.price_for_order_type(
                     now_ts,
+                    now_slot,
                     oracle_price_lots,
                     price_lots,
                     order_type,
@@ -160,6 +180,19 @@ impl Order {
         Ok((price_lots, price_data))
     }
 
+    /// Base lots to show on the book for iceberg orders, 0 if the whole order is shown
+    pub fn display_base_lots(&self) -> i64 {
+        match self.params {
+            OrderParams::Fixed {
+                display_base_lots, ..
+            } => display_base_lots,
+            OrderParams::OraclePegged {
+                display_base_lots, ..
+            } => display_base_lots,
+            _ => 0,
+        }
+    }
+
     /// pegging limit for oracle peg orders, otherwise -1
     pub fn peg_limit(&self) -> i64 {
         match self.params {
@@ -191,6 +191,21 @@ impl OrderTreeNodes {
         }
     }
 
+    /// Finds the handle of the leaf with the given key, if it exists
+    pub fn find_by_key(&self, root: &OrderTreeRoot, search_key: u128) -> Option<NodeHandle> {
+        let mut node_handle: NodeHandle = root.node()?;
+        loop {
+            match self.node(node_handle)?.case()? {
+                NodeRef::Inner(inner) => {
+                    node_handle = inner.walk_down(search_key).0;
+                }
+                NodeRef::Leaf(leaf) => {
+                    return (leaf.key == search_key).then_some(node_handle);
+                }
+            }
+        }
+    }
+
     pub fn remove_by_key(
         &mut self,
         root: &mut OrderTreeRoot,
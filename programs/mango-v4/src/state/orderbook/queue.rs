@@ -346,7 +346,7 @@ impl OutEvent {
             timestamp,
             seq_num,
             node.owner,
-            node.quantity,
+            node.total_quantity(),
             node.key,
         )
     }
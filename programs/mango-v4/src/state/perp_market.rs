@@ -3,24 +3,39 @@ use std::mem::size_of;
 use anchor_lang::prelude::*;
 use derivative::Derivative;
 use fixed::types::I80F48;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 
 use oracle::oracle_log_context;
 use static_assertions::const_assert_eq;
 
 use crate::accounts_zerocopy::KeyedAccountReader;
 use crate::error::{Contextable, MangoError};
-use crate::logs::{emit_stack, PerpUpdateFundingLogV2};
+use crate::logs::{emit_stack, PerpSocializedLossLog, PerpUpdateFundingLogV2};
 use crate::state::orderbook::Side;
 use crate::state::{oracle, TokenIndex};
 use crate::util;
 
 use super::{
     orderbook, OracleAccountInfos, OracleConfig, OracleState, Orderbook, StablePriceModel,
-    DAY_I80F48,
+    DAY_I80F48, MAX_MEDIAN_ORACLES,
 };
 
 pub type PerpMarketIndex = u16;
 
+/// Length of the window over which funding rate samples are averaged
+pub const FUNDING_TWAP_WINDOW_SECONDS: u64 = 3600;
+
+/// Who a socialized loss is charged to, see PerpSocializedLossLog
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum SocializedLossTarget {
+    LongsAndShorts = 0,
+    Longs = 1,
+    Shorts = 2,
+    /// No open interest, added to pending_socialized_loss
+    Pending = 3,
+}
+
 #[account(zero_copy)]
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -198,8 +213,55 @@ pub struct PerpMarket {
     /// liquidation fees that happened. So never decreases (different to fees_accrued).
     pub accrued_liquidation_fees: I80F48,
 
+    /// Oracles that are used together with `oracle` in the median oracle mode.
+    /// Unused entries are Pubkey::default().
+    pub median_oracles: [Pubkey; MAX_MEDIAN_ORACLES],
+
+    /// Minimum number of valid oracles needed for the median oracle mode.
+    /// If zero, the median oracle mode is disabled and only `oracle` is used.
+    pub median_oracle_quorum: u8,
+
     #[derivative(Debug = "ignore")]
-    pub reserved: [u8; 1848],
+    pub padding5: [u8; 7],
+
+    /// Socialized loss in settle token native units that couldn't be charged to anyone
+    /// because the open interest was zero. It's charged to longs and shorts once there
+    /// is open interest again.
+    pub pending_socialized_loss: I80F48,
+
+    /// Minimum number of seconds between two funding rate samples.
+    ///
+    /// If zero, funding is computed from the orderbook at each funding update. Otherwise
+    /// the funding rate is sampled at most once per interval and funding is applied with
+    /// the average of the samples of the last completed FUNDING_TWAP_WINDOW_SECONDS window.
+    pub funding_sample_interval: u64,
+
+    /// Timestamp of the last funding rate sample
+    pub funding_last_sample_ts: u64,
+
+    /// Timestamp at which the current funding sampling window started
+    pub funding_window_start_ts: u64,
+
+    /// Number of funding rate samples in the current window
+    pub funding_window_sample_count: u64,
+
+    /// Sum of the funding rate samples in the current window
+    pub funding_window_rate_sum: I80F48,
+
+    /// Average funding rate of the last completed window, the rate that is applied
+    /// when funding_sample_interval is set. Seeded with the instantaneous rate when
+    /// sampling (re)starts.
+    pub funding_twap_rate: I80F48,
+
+    /// Fixed funding rate per day that longs pay to shorts, added to the orderbook premium
+    pub funding_interest_rate: f32,
+
+    /// Orderbook premiums smaller than this in absolute terms are ignored and larger
+    /// ones are reduced by it, so that small book imbalances don't cause funding.
+    pub funding_dampener: f32,
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 1656],
 }
 
 const_assert_eq!(
@@ -237,7 +299,14 @@ const_assert_eq!(
         + 3 * 16
         + 8
         + 2 * 16
-        + 1848
+        + 32 * MAX_MEDIAN_ORACLES
+        + 1
+        + 7
+        + 16
+        + 8 * 4
+        + 16 * 2
+        + 4 * 2
+        + 1656
 );
 const_assert_eq!(size_of::<PerpMarket>(), 2808);
 const_assert_eq!(size_of::<PerpMarket>() % 8, 0);
@@ -269,6 +338,18 @@ impl PerpMarket {
         I80F48::from_num(self.settle_pnl_limit_factor)
     }
 
+    pub fn verify(&self) -> Result<()> {
+        oracle::verify_median_oracle_quorum(&self.median_oracles, self.median_oracle_quorum)?;
+        require_gte!(FUNDING_TWAP_WINDOW_SECONDS, self.funding_sample_interval);
+        require!(
+            self.funding_interest_rate.is_finite(),
+            MangoError::SomeError
+        );
+        require!(self.funding_dampener.is_finite(), MangoError::SomeError);
+        require_gte!(self.funding_dampener, 0.0);
+        Ok(())
+    }
+
     pub fn gen_order_id(&mut self, side: Side, price_data: u64) -> u128 {
         self.seq_num += 1;
         orderbook::new_node_key(side, price_data, self.seq_num)
@@ -282,12 +363,29 @@ impl PerpMarket {
         Ok(self.oracle_state(oracle_acc_infos, staleness_slot)?.price)
     }
 
+    pub fn is_median_oracle_mode(&self) -> bool {
+        self.median_oracle_quorum > 0
+    }
+
+    /// Returns the checked oracle state, or in median oracle mode the median
+    /// of all valid oracles.
     pub fn oracle_state<T: KeyedAccountReader>(
         &self,
         oracle_acc_infos: &OracleAccountInfos<T>,
         staleness_slot: Option<u64>,
     ) -> Result<OracleState> {
         require_keys_eq!(self.oracle, *oracle_acc_infos.oracle.key());
+        if self.is_median_oracle_mode() {
+            return oracle::median_oracle_state(
+                oracle_acc_infos,
+                &self.median_oracles,
+                self.median_oracle_quorum,
+                &self.oracle_config,
+                self.base_decimals,
+                staleness_slot,
+            )
+            .with_context(|| format!("median oracle for {}", self.name()));
+        }
         let state = oracle::oracle_state_unchecked(oracle_acc_infos, self.base_decimals)?;
         state
             .check_confidence_and_maybe_staleness(&self.oracle_config, staleness_slot)
@@ -307,6 +405,7 @@ impl PerpMarket {
         book: &Orderbook,
         oracle_state: &OracleState,
         now_ts: u64,
+        now_slot: u64,
     ) -> Result<()> {
         if now_ts <= self.funding_last_updated {
             return Ok(());
@@ -316,12 +415,18 @@ impl PerpMarket {
         let oracle_price_lots = self.native_price_to_lot(oracle_price);
 
         // Get current book price & compare it to index price
-        let bid =
-            book.bookside(Side::Bid)
-                .impact_price(self.impact_quantity, now_ts, oracle_price_lots);
-        let ask =
-            book.bookside(Side::Ask)
-                .impact_price(self.impact_quantity, now_ts, oracle_price_lots);
+        let bid = book.bookside(Side::Bid).impact_price(
+            self.impact_quantity,
+            now_ts,
+            now_slot,
+            oracle_price_lots,
+        );
+        let ask = book.bookside(Side::Ask).impact_price(
+            self.impact_quantity,
+            now_ts,
+            now_slot,
+            oracle_price_lots,
+        );
 
         let funding_rate = match (bid, ask) {
             (Some(bid), Some(ask)) => {
@@ -329,11 +434,11 @@ impl PerpMarket {
                 let mid_price = (bid + ask) / 2;
                 let book_price = self.lot_to_native_price(mid_price);
                 let diff = book_price / oracle_price - I80F48::ONE;
-                diff.clamp(self.min_funding, self.max_funding)
+                self.funding_rate_from_premium(diff)
             }
             (Some(_bid), None) => self.max_funding,
             (None, Some(_ask)) => self.min_funding,
-            (None, None) => I80F48::ZERO,
+            (None, None) => self.funding_rate_from_premium(I80F48::ZERO),
         };
 
         // Limit the maximal time interval that funding is applied for. This means we won't use
@@ -347,13 +452,21 @@ impl PerpMarket {
         let time_factor = diff_ts / DAY_I80F48;
         let base_lot_size = I80F48::from_num(self.base_lot_size);
 
+        let applied_funding_rate = if self.funding_sample_interval > 0 {
+            self.sample_funding_rate(funding_rate, now_ts)
+        } else {
+            funding_rate
+        };
+
         // The number of native quote that one base lot should pay in funding
-        let funding_delta = oracle_price * base_lot_size * funding_rate * time_factor;
+        let funding_delta = oracle_price * base_lot_size * applied_funding_rate * time_factor;
 
         self.long_funding += funding_delta;
         self.short_funding += funding_delta;
         self.funding_last_updated = now_ts;
 
+        self.apply_pending_socialized_loss();
+
         self.stable_price_model
             .update(now_ts, oracle_price.to_num());
 
@@ -376,6 +489,54 @@ impl PerpMarket {
         Ok(())
     }
 
+    /// The daily funding rate for a relative orderbook premium over the oracle price
+    ///
+    /// The premium is dampened by funding_dampener, funding_interest_rate is added and the
+    /// result is clamped to the min_funding..max_funding range.
+    pub fn funding_rate_from_premium(&self, premium: I80F48) -> I80F48 {
+        let dampener = I80F48::from_num(self.funding_dampener);
+        let dampened_premium = if premium > dampener {
+            premium - dampener
+        } else if premium < -dampener {
+            premium + dampener
+        } else {
+            I80F48::ZERO
+        };
+        let rate = dampened_premium + I80F48::from_num(self.funding_interest_rate);
+        rate.clamp(self.min_funding, self.max_funding)
+    }
+
+    /// Records `funding_rate` as a sample if funding_sample_interval has passed since the
+    /// last sample and returns the average rate of the last completed sampling window.
+    ///
+    /// Averaging over many samples means that a momentary orderbook imbalance at the time
+    /// of a funding update can't determine the funding for the whole elapsed time.
+    pub fn sample_funding_rate(&mut self, funding_rate: I80F48, now_ts: u64) -> I80F48 {
+        if self.funding_window_start_ts == 0 {
+            // Sampling just (re)started and no window has completed: use the current
+            // rate until the first window average is available
+            self.funding_twap_rate = funding_rate;
+        }
+        if now_ts >= self.funding_window_start_ts + FUNDING_TWAP_WINDOW_SECONDS {
+            // Keep the previous average if there were no samples in the window
+            if self.funding_window_sample_count > 0 {
+                self.funding_twap_rate =
+                    self.funding_window_rate_sum / I80F48::from(self.funding_window_sample_count);
+            }
+            self.funding_window_rate_sum = I80F48::ZERO;
+            self.funding_window_sample_count = 0;
+            self.funding_window_start_ts = now_ts;
+        }
+
+        if now_ts >= self.funding_last_sample_ts + self.funding_sample_interval {
+            self.funding_window_rate_sum += funding_rate;
+            self.funding_window_sample_count += 1;
+            self.funding_last_sample_ts = now_ts;
+        }
+
+        self.funding_twap_rate
+    }
+
     /// Convert from the price stored on the book to the price used in value calculations
     pub fn lot_to_native_price(&self, price: i64) -> I80F48 {
         I80F48::from_num(price) * I80F48::from_num(self.quote_lot_size)
@@ -400,26 +561,78 @@ impl PerpMarket {
         }
     }
 
-    /// Socialize the loss in this account across all longs and shorts
+    /// Socialize the loss of a bankrupt perp position
+    ///
+    /// The loss is charged to the side opposite to `bankrupt_side`, the side of the bankrupt
+    /// account's last position, since that's where the matching positive pnl is. If the side
+    /// is unknown, it's spread across all longs and shorts.
+    ///
+    /// If there is no open interest, the loss is added to pending_socialized_loss.
     ///
     /// `loss` is in settle token native units
-    pub fn socialize_loss(&mut self, loss: I80F48) -> Result<I80F48> {
+    pub fn socialize_loss(&mut self, loss: I80F48, bankrupt_side: Option<Side>) -> Result<()> {
         require_gte!(0, loss);
 
-        // TODO convert into only socializing on one side
-        // native settle token per contract open interest
-        let socialized_loss = if self.open_interest == 0 {
-            // AUDIT: think about the following:
-            // This is kind of an unfortunate situation. This means socialized loss occurs on the
-            // last person to call settle_pnl on their profits. Any advice on better mechanism
-            // would be appreciated. Luckily, this will be an extremely rare situation.
-            I80F48::ZERO
+        let target = if self.open_interest == 0 {
+            SocializedLossTarget::Pending
         } else {
-            loss / I80F48::from(self.open_interest)
+            match bankrupt_side {
+                Some(Side::Bid) => SocializedLossTarget::Shorts,
+                Some(Side::Ask) => SocializedLossTarget::Longs,
+                None => SocializedLossTarget::LongsAndShorts,
+            }
         };
-        self.long_funding -= socialized_loss;
-        self.short_funding += socialized_loss;
-        Ok(socialized_loss)
+        self.charge_socialized_loss(loss, target);
+        Ok(())
+    }
+
+    /// Charges pending_socialized_loss to longs and shorts if there is open interest
+    pub fn apply_pending_socialized_loss(&mut self) {
+        if self.pending_socialized_loss <= 0 || self.open_interest == 0 {
+            return;
+        }
+        let loss = -self.pending_socialized_loss;
+        self.pending_socialized_loss = I80F48::ZERO;
+        self.charge_socialized_loss(loss, SocializedLossTarget::LongsAndShorts);
+    }
+
+    fn charge_socialized_loss(&mut self, loss: I80F48, target: SocializedLossTarget) {
+        let (starting_long_funding, starting_short_funding) =
+            (self.long_funding, self.short_funding);
+
+        // open_interest counts the lots of both sides, each side has half of it
+        let open_interest = I80F48::from(self.open_interest);
+        match target {
+            SocializedLossTarget::LongsAndShorts => {
+                // native settle token per contract open interest
+                let socialized_loss = loss / open_interest;
+                self.long_funding -= socialized_loss;
+                self.short_funding += socialized_loss;
+            }
+            SocializedLossTarget::Longs => {
+                self.long_funding -= loss * I80F48::from(2) / open_interest;
+            }
+            SocializedLossTarget::Shorts => {
+                self.short_funding += loss * I80F48::from(2) / open_interest;
+            }
+            SocializedLossTarget::Pending => {
+                self.pending_socialized_loss -= loss;
+            }
+        }
+        msg!("socialized loss {} charged to {:?}", -loss, target);
+
+        emit_stack(PerpSocializedLossLog {
+            mango_group: self.group,
+            perp_market_index: self.perp_market_index,
+            loss: (-loss).to_bits(),
+            target: target.into(),
+            open_interest: self.open_interest,
+            starting_long_funding: starting_long_funding.to_bits(),
+            starting_short_funding: starting_short_funding.to_bits(),
+            ending_long_funding: self.long_funding.to_bits(),
+            ending_short_funding: self.short_funding.to_bits(),
+            pending_socialized_loss: self.pending_socialized_loss.to_bits(),
+        });
     }
 
     /// Returns the fee for settling `settlement` when the account with positive unsettled pnl
@@ -537,7 +750,140 @@ impl PerpMarket {
             fees_withdrawn: 0,
             platform_liquidation_fee: I80F48::ZERO,
             accrued_liquidation_fees: I80F48::ZERO,
-            reserved: [0; 1848],
+            median_oracles: [Pubkey::default(); MAX_MEDIAN_ORACLES],
+            median_oracle_quorum: 0,
+            padding5: Default::default(),
+            pending_socialized_loss: I80F48::ZERO,
+            funding_sample_interval: 0,
+            funding_last_sample_ts: 0,
+            funding_window_start_ts: 0,
+            funding_window_sample_count: 0,
+            funding_window_rate_sum: I80F48::ZERO,
+            funding_twap_rate: I80F48::ZERO,
+            funding_interest_rate: 0.0,
+            funding_dampener: 0.0,
+            reserved: [0; 1656],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socialize_loss() {
+        let mut market = PerpMarket::default_for_tests();
+        market.open_interest = 20; // 10 lots long, 10 lots short
+
+        // a bankrupt long is paid for by the shorts only
+        market
+            .socialize_loss(I80F48::from(-100), Some(Side::Bid))
+            .unwrap();
+        assert_eq!(market.long_funding, I80F48::ZERO);
+        assert_eq!(market.short_funding, I80F48::from(-10));
+
+        // a bankrupt short is paid for by the longs only
+        market
+            .socialize_loss(I80F48::from(-50), Some(Side::Ask))
+            .unwrap();
+        assert_eq!(market.long_funding, I80F48::from(5));
+        assert_eq!(market.short_funding, I80F48::from(-10));
+
+        // unknown side: both sides pay
+        market.socialize_loss(I80F48::from(-20), None).unwrap();
+        assert_eq!(market.long_funding, I80F48::from(6));
+        assert_eq!(market.short_funding, I80F48::from(-11));
+
+        // without open interest the loss stays pending until there are positions again
+        market.open_interest = 0;
+        market
+            .socialize_loss(I80F48::from(-40), Some(Side::Bid))
+            .unwrap();
+        assert_eq!(market.pending_socialized_loss, I80F48::from(40));
+        market.apply_pending_socialized_loss();
+        assert_eq!(market.pending_socialized_loss, I80F48::from(40));
+
+        market.open_interest = 4;
+        market.apply_pending_socialized_loss();
+        assert_eq!(market.pending_socialized_loss, I80F48::ZERO);
+        assert_eq!(market.long_funding, I80F48::from(16));
+        assert_eq!(market.short_funding, I80F48::from(-21));
+    }
+
+    #[test]
+    fn test_sample_funding_rate() {
+        let mut market = PerpMarket::default_for_tests();
+        market.funding_sample_interval = 60;
+        let window = FUNDING_TWAP_WINDOW_SECONDS;
+        let start = 1_000_000;
+
+        // the first window has no completed average yet, the first sample is used
+        assert_eq!(
+            market.sample_funding_rate(I80F48::from(1), start),
+            I80F48::from(1)
+        );
+        // too early for another sample
+        assert_eq!(
+            market.sample_funding_rate(I80F48::from(100), start + 30),
+            I80F48::from(1)
+        );
+        assert_eq!(market.funding_window_sample_count, 1);
+        market.sample_funding_rate(I80F48::from(3), start + 60);
+        assert_eq!(market.funding_window_sample_count, 2);
+
+        // once the window is over its average is applied
+        assert_eq!(
+            market.sample_funding_rate(I80F48::from(5), start + window),
+            I80F48::from(2)
+        );
+        assert_eq!(market.funding_window_sample_count, 1);
+
+        // a window without samples keeps the previous average
+        market.funding_sample_interval = window * 3;
+        assert_eq!(
+            market.sample_funding_rate(I80F48::from(7), start + 2 * window),
+            I80F48::from(5)
+        );
+        assert_eq!(
+            market.sample_funding_rate(I80F48::from(7), start + 3 * window),
+            I80F48::from(5)
+        );
+
+        // restarting the sampling seeds the average with the current rate again
+        market.funding_window_start_ts = 0;
+        market.funding_window_sample_count = 0;
+        market.funding_window_rate_sum = I80F48::ZERO;
+        assert_eq!(
+            market.sample_funding_rate(I80F48::from(-2), start + 4 * window),
+            I80F48::from(-2)
+        );
+    }
+
+    #[test]
+    fn test_funding_rate_from_premium() {
+        let mut market = PerpMarket::default_for_tests();
+        market.min_funding = I80F48::from_num(-0.05);
+        market.max_funding = I80F48::from_num(0.05);
+        market.funding_interest_rate = 0.0003;
+        market.funding_dampener = 0.001;
+
+        let rate = |m: &PerpMarket, premium: f64| {
+            m.funding_rate_from_premium(I80F48::from_num(premium))
+                .to_num::<f64>()
+        };
+
+        // premiums within the dampener band only pay interest
+        assert!((rate(&market, 0.0) - 0.0003).abs() < 1e-9);
+        assert!((rate(&market, 0.0008) - 0.0003).abs() < 1e-9);
+        assert!((rate(&market, -0.0008) - 0.0003).abs() < 1e-9);
+
+        // larger premiums are reduced by the dampener
+        assert!((rate(&market, 0.01) - 0.0093).abs() < 1e-9);
+        assert!((rate(&market, -0.01) + 0.0087).abs() < 1e-9);
+
+        // the result is clamped
+        assert!((rate(&market, 0.1) - 0.05).abs() < 1e-9);
+        assert!((rate(&market, -0.1) + 0.05).abs() < 1e-9);
+    }
+}
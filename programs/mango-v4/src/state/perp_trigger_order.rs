@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+
+use derivative::Derivative;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::error::MangoError;
+use crate::state::*;
+
+/// Incentive to pay to callers who execute a trigger order, in $1e-6
+pub const PERP_TRIGGER_ORDER_EXECUTE_INCENTIVE: u64 = 1_000; // $0.001 around 10x tx fee right now
+
+#[derive(
+    Clone, Copy, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, AnchorDeserialize, AnchorSerialize,
+)]
+#[repr(u8)]
+pub enum PerpTriggerCondition {
+    /// Triggers when the oracle price is >= trigger_price
+    PriceAbove,
+    /// Triggers when the oracle price is <= trigger_price
+    PriceBelow,
+}
+
+/// A perp order that is stored on the account and placed as an immediate-or-cancel
+/// order by anyone once the oracle price crosses the trigger price.
+///
+/// Used for stop-loss and take-profit orders on perp positions.
+#[zero_copy]
+#[derive(AnchorDeserialize, AnchorSerialize, Derivative, PartialEq)]
+#[derivative(Debug)]
+pub struct PerpTriggerOrder {
+    pub id: u64,
+
+    /// The oracle price in native quote per native base at which the order triggers
+    pub trigger_price: f64,
+
+    /// Maximum number of base lots to buy or sell when triggered
+    pub max_base_lots: i64,
+
+    /// The order is placed with a limit price of oracle * (1 +- max_slippage), so
+    /// it can't fill worse than that.
+    pub max_slippage: f64,
+
+    /// timestamp until which the trigger order is valid
+    pub expiry_timestamp: u64,
+
+    pub perp_market_index: PerpMarketIndex,
+
+    /// Stores a Side enum value
+    pub side: u8,
+
+    /// Stores a PerpTriggerCondition enum value
+    pub trigger_condition: u8,
+
+    /// If this struct is in use. (trigger orders are stored in a static-length array)
+    pub is_configured: u8,
+
+    /// May only reduce the perp position, like a reduce-only perp order
+    pub reduce_only: u8,
+
+    pub padding: [u8; 2],
+
+    #[derivative(Debug = "ignore")]
+    pub reserved: [u8; 64],
+}
+
+const_assert_eq!(size_of::<PerpTriggerOrder>(), 8 * 5 + 2 + 1 * 4 + 2 + 64);
+const_assert_eq!(size_of::<PerpTriggerOrder>(), 112);
+const_assert_eq!(size_of::<PerpTriggerOrder>() % 8, 0);
+
+impl Default for PerpTriggerOrder {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            trigger_price: 0.0,
+            max_base_lots: 0,
+            max_slippage: 0.0,
+            expiry_timestamp: u64::MAX,
+            perp_market_index: PerpMarketIndex::MAX,
+            side: Side::Bid.into(),
+            trigger_condition: PerpTriggerCondition::PriceAbove.into(),
+            is_configured: 0,
+            reduce_only: 0,
+            padding: Default::default(),
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl PerpTriggerOrder {
+    /// Whether the entry is in use
+    ///
+    /// Note that it's possible for an entry to be configured but expired.
+    pub fn is_configured(&self) -> bool {
+        self.is_configured == 1
+    }
+
+    pub fn set_is_configured(&mut self, is_configured: bool) {
+        self.is_configured = u8::from(is_configured);
+    }
+
+    pub fn side(&self) -> Side {
+        self.side.try_into().unwrap()
+    }
+
+    pub fn trigger_condition(&self) -> PerpTriggerCondition {
+        self.trigger_condition.try_into().unwrap()
+    }
+
+    pub fn reduce_only(&self) -> bool {
+        self.reduce_only == 1
+    }
+
+    pub fn is_expired(&self, now_ts: u64) -> bool {
+        now_ts >= self.expiry_timestamp
+    }
+
+    /// Does the oracle price satisfy the trigger condition?
+    pub fn is_triggered(&self, oracle_price: f64) -> bool {
+        match self.trigger_condition() {
+            PerpTriggerCondition::PriceAbove => oracle_price >= self.trigger_price,
+            PerpTriggerCondition::PriceBelow => oracle_price <= self.trigger_price,
+        }
+    }
+
+    pub fn check_triggerable(&self, oracle_price: f64, now_ts: u64) -> Result<()> {
+        require!(
+            !self.is_expired(now_ts),
+            MangoError::PerpTriggerOrderExpired
+        );
+        require!(
+            self.is_triggered(oracle_price),
+            MangoError::PerpTriggerOrderPriceNotReached
+        );
+        Ok(())
+    }
+
+    /// The worst native price the triggered order may fill at
+    pub fn limit_price(&self, oracle_price: f64) -> f64 {
+        match self.side() {
+            Side::Bid => oracle_price * (1.0 + self.max_slippage),
+            Side::Ask => oracle_price * (1.0 - self.max_slippage),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perp_trigger_order_condition() {
+        let stop_loss_long = PerpTriggerOrder {
+            trigger_price: 10.0,
+            side: Side::Ask.into(),
+            trigger_condition: PerpTriggerCondition::PriceBelow.into(),
+            max_slippage: 0.1,
+            expiry_timestamp: 100,
+            ..PerpTriggerOrder::default()
+        };
+        assert!(!stop_loss_long.is_triggered(10.1));
+        assert!(stop_loss_long.is_triggered(10.0));
+        assert!(stop_loss_long.is_triggered(9.0));
+        assert!((stop_loss_long.limit_price(9.0) - 8.1).abs() < 1e-9);
+        assert!(stop_loss_long.check_triggerable(9.0, 99).is_ok());
+        assert!(stop_loss_long.check_triggerable(9.0, 100).is_err());
+        assert!(stop_loss_long.check_triggerable(11.0, 99).is_err());
+
+        let stop_loss_short = PerpTriggerOrder {
+            trigger_price: 10.0,
+            side: Side::Bid.into(),
+            trigger_condition: PerpTriggerCondition::PriceAbove.into(),
+            max_slippage: 0.1,
+            ..PerpTriggerOrder::default()
+        };
+        assert!(!stop_loss_short.is_triggered(9.9));
+        assert!(stop_loss_short.is_triggered(10.0));
+        assert!((stop_loss_short.limit_price(10.0) - 11.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey::Pubkey;
+
+use crate::{accounts_zerocopy::KeyedAccountReader, error::MangoError};
+
+pub mod pyth_mainnet_receiver {
+    use solana_program::declare_id;
+    declare_id!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+}
+
+pub mod pyth_mainnet_push_oracle {
+    use solana_program::declare_id;
+    declare_id!("pythWSnswVUd12oZpeFP8e9CVaEqJg25g1Vtc2biRsT");
+}
+
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// Length of a fully verified PriceUpdateV2, the allocated account may be larger
+pub const PRICE_UPDATE_V2_FULL_LEN: usize = 133;
+
+/// Borsh tag of VerificationLevel::Full
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+
+/// The shard under which the push oracle posts the sponsored price feed accounts
+pub const PYTH_PUSH_ORACLE_SHARD: u16 = 0;
+
+pub struct PriceUpdateV2State {
+    pub write_authority: Pubkey, // 32
+    pub feed_id: [u8; 32],       // 32
+    pub price: i64,              // 8
+    pub conf: u64,               // 8
+    pub exponent: i32,           // 4
+    pub publish_time: i64,       // 8
+    pub posted_slot: u64,        // 8
+}
+
+/// Address of the push oracle's price feed account for a feed id.
+///
+/// Only the push oracle can write to it, and it only ever writes updates for that feed id.
+pub fn pyth_push_oracle_feed_address(feed_id: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[&PYTH_PUSH_ORACLE_SHARD.to_le_bytes(), feed_id],
+        &pyth_mainnet_push_oracle::ID,
+    )
+    .0
+}
+
+/// Loads a Pyth pull oracle PriceUpdateV2 account.
+///
+/// Only fully verified updates are accepted, and the account must be its own write
+/// authority, like the push oracle's price feed accounts are.
+///
+/// That the account is the push oracle's feed account for its feed id is checked when
+/// it's configured as an oracle, see check_is_valid_oracle(): other PriceUpdateV2
+/// accounts can be overwritten with a different feed by their write authority.
+pub fn load_price_update_v2_state(
+    acc_info: &(impl KeyedAccountReader + ?Sized),
+) -> Result<PriceUpdateV2State> {
+    let data = &acc_info.data();
+    require!(
+        data.len() >= PRICE_UPDATE_V2_FULL_LEN,
+        MangoError::InvalidPythPullOracle
+    );
+    require!(
+        data[0..8] == PRICE_UPDATE_V2_DISCRIMINATOR[..],
+        MangoError::InvalidPythPullOracle
+    );
+    require!(
+        acc_info.owner() == &pyth_mainnet_receiver::ID,
+        MangoError::InvalidPythPullOracle
+    );
+    require!(
+        data[40] == VERIFICATION_LEVEL_FULL,
+        MangoError::InvalidPythPullOracle
+    );
+
+    let write_authority = Pubkey::from(<[u8; 32]>::try_from(&data[8..40]).unwrap());
+    let feed_id: [u8; 32] = data[41..73].try_into().unwrap();
+    let price = i64::from_le_bytes(data[73..81].try_into().unwrap());
+    let conf = u64::from_le_bytes(data[81..89].try_into().unwrap());
+    let exponent = i32::from_le_bytes(data[89..93].try_into().unwrap());
+    let publish_time = i64::from_le_bytes(data[93..101].try_into().unwrap());
+    let posted_slot = u64::from_le_bytes(data[125..133].try_into().unwrap());
+
+    require_keys_eq!(
+        write_authority,
+        *acc_info.key(),
+        MangoError::InvalidPythPullOracle
+    );
+
+    Ok(PriceUpdateV2State {
+        write_authority,
+        feed_id,
+        price,
+        conf,
+        exponent,
+        publish_time,
+        posted_slot,
+    })
+}
@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+use derivative::Derivative;
+use fixed::types::I80F48;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use super::{TokenIndex, YEAR_I80F48};
+
+/// Number of entries in a RateHistory, six days of hourly snapshots
+pub const RATE_HISTORY_ENTRIES: usize = 144;
+
+/// A snapshot of a bank's interest rates and indexes.
+#[zero_copy]
+#[derive(Debug, PartialEq)]
+pub struct RateHistoryEntry {
+    pub timestamp: u64,
+
+    /// Deposit rate (APR) at the time of the snapshot
+    pub deposit_rate: f64,
+
+    /// Borrow rate (APR) at the time of the snapshot, excluding the loan fee rate
+    pub borrow_rate: f64,
+
+    /// The bank's avg_utilization at the time of the snapshot
+    pub avg_utilization: f64,
+
+    pub deposit_index: I80F48,
+    pub borrow_index: I80F48,
+}
+const_assert_eq!(size_of::<RateHistoryEntry>(), 8 * 4 + 16 * 2);
+const_assert_eq!(size_of::<RateHistoryEntry>(), 64);
+const_assert_eq!(size_of::<RateHistoryEntry>() % 8, 0);
+
+impl Default for RateHistoryEntry {
+    fn default() -> Self {
+        Self {
+            timestamp: 0,
+            deposit_rate: 0.0,
+            borrow_rate: 0.0,
+            avg_utilization: 0.0,
+            deposit_index: I80F48::ZERO,
+            borrow_index: I80F48::ZERO,
+        }
+    }
+}
+
+/// Time weighted rates between two history entries, see RateHistory::twap().
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateHistoryTwap {
+    /// Effective deposit rate (APR), from the growth of the deposit index
+    pub deposit_rate: I80F48,
+
+    /// Effective borrow rate (APR), from the growth of the borrow index.
+    /// Unlike RateHistoryEntry::borrow_rate this includes the loan fee rate.
+    pub borrow_rate: I80F48,
+
+    /// Mean of the recorded avg_utilization values
+    pub avg_utilization: f64,
+
+    /// Time covered, in seconds
+    pub duration: u64,
+}
+
+/// Ring buffer of hourly rate snapshots, written by token_update_index_and_rate.
+///
+/// This allows clients to read recent rate history without indexing logs, and
+/// allows on-chain users to compute time weighted average rates.
+#[zero_copy]
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RateHistory {
+    /// Snapshots, used cyclically: the newest entry is at `last_index`
+    #[derivative(Debug = "ignore")]
+    pub entries: [RateHistoryEntry; RATE_HISTORY_ENTRIES],
+
+    /// Index of the newest entry in `entries`
+    pub last_index: u16,
+
+    /// Number of entries that were written, at most entries.len()
+    pub count: u16,
+
+    #[derivative(Debug = "ignore")]
+    pub padding: [u8; 4],
+}
+const_assert_eq!(
+    size_of::<RateHistory>(),
+    64 * RATE_HISTORY_ENTRIES + 2 * 2 + 4
+);
+const_assert_eq!(size_of::<RateHistory>(), 9224);
+const_assert_eq!(size_of::<RateHistory>() % 8, 0);
+
+impl Default for RateHistory {
+    fn default() -> Self {
+        Self {
+            entries: [RateHistoryEntry::default(); RATE_HISTORY_ENTRIES],
+            last_index: 0,
+            count: 0,
+            padding: Default::default(),
+        }
+    }
+}
+
+impl RateHistory {
+    pub fn push(&mut self, entry: RateHistoryEntry) {
+        let len = self.entries.len();
+        let index = if self.count == 0 {
+            0
+        } else {
+            (self.last_index as usize + 1) % len
+        };
+        self.entries[index] = entry;
+        self.last_index = index as u16;
+        self.count = (self.count as usize + 1).min(len) as u16;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The newest entry, if any
+    pub fn latest(&self) -> Option<&RateHistoryEntry> {
+        self.iter_newest_first().next()
+    }
+
+    /// Iterates over the written entries, starting with the newest
+    pub fn iter_newest_first(&self) -> impl Iterator<Item = &RateHistoryEntry> {
+        let len = self.entries.len();
+        let last_index = self.last_index as usize;
+        (0..self.len()).map(move |i| &self.entries[(last_index + len - i) % len])
+    }
+
+    /// Time weighted rates over the newest entries that span at most `max_duration` seconds.
+    ///
+    /// Returns None if there are fewer than two entries in that window.
+    pub fn twap(&self, max_duration: u64) -> Option<RateHistoryTwap> {
+        let newest = self.latest()?;
+        let min_timestamp = newest.timestamp.saturating_sub(max_duration);
+
+        let mut oldest = newest;
+        let mut utilization_sum = 0.0;
+        let mut count = 0;
+        for entry in self
+            .iter_newest_first()
+            .take_while(|e| e.timestamp >= min_timestamp)
+        {
+            oldest = entry;
+            utilization_sum += entry.avg_utilization;
+            count += 1;
+        }
+
+        let duration = newest.timestamp - oldest.timestamp;
+        if duration == 0
+            || !oldest.deposit_index.is_positive()
+            || !oldest.borrow_index.is_positive()
+        {
+            return None;
+        }
+
+        let annualize = YEAR_I80F48 / I80F48::from(duration);
+        Some(RateHistoryTwap {
+            deposit_rate: (newest.deposit_index / oldest.deposit_index - I80F48::ONE) * annualize,
+            borrow_rate: (newest.borrow_index / oldest.borrow_index - I80F48::ONE) * annualize,
+            avg_utilization: utilization_sum / count as f64,
+            duration,
+        })
+    }
+}
+
+/// The rate history of a token, a PDA next to its banks.
+///
+/// Created with token_rate_history_create. When it's passed to
+/// token_update_index_and_rate after the banks, the hourly rate update adds an entry.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct TokenRateHistory {
+    // ABI: Clients rely on this being at offset 8
+    pub group: Pubkey,
+    // ABI: Clients rely on this being at offset 40
+    pub token_index: TokenIndex,
+
+    pub bump: u8,
+    pub padding: [u8; 5],
+
+    pub history: RateHistory,
+
+    pub reserved: [u8; 256],
+}
+const_assert_eq!(
+    size_of::<TokenRateHistory>(),
+    32 + 2 + 1 + 5 + size_of::<RateHistory>() + 256
+);
+const_assert_eq!(size_of::<TokenRateHistory>(), 9520);
+const_assert_eq!(size_of::<TokenRateHistory>() % 8, 0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, index: f64) -> RateHistoryEntry {
+        RateHistoryEntry {
+            timestamp,
+            deposit_rate: 0.0,
+            borrow_rate: 0.0,
+            avg_utilization: timestamp as f64,
+            deposit_index: I80F48::from_num(index),
+            borrow_index: I80F48::from_num(index * 2.0),
+        }
+    }
+
+    #[test]
+    fn test_rate_history_ring_buffer() {
+        let mut history = RateHistory::default();
+        assert!(history.latest().is_none());
+        assert!(history.twap(u64::MAX).is_none());
+
+        history.push(entry(1, 1.0));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.latest().unwrap().timestamp, 1);
+        assert!(history.twap(u64::MAX).is_none());
+
+        let last = RATE_HISTORY_ENTRIES as u64 + 6;
+        for ts in 2..=last {
+            history.push(entry(ts, 1.0));
+        }
+        assert_eq!(history.len(), RATE_HISTORY_ENTRIES);
+        assert_eq!(history.latest().unwrap().timestamp, last);
+        let timestamps = history
+            .iter_newest_first()
+            .map(|e| e.timestamp)
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, (7..=last).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_rate_history_twap() {
+        let hour = 3600;
+        let mut history = RateHistory::default();
+        history.push(entry(0, 1.0));
+        history.push(entry(hour, 1.01));
+        history.push(entry(2 * hour, 1.02));
+
+        // over two hours the deposit index grew 2%, borrow index 2% too
+        let twap = history.twap(2 * hour).unwrap();
+        assert_eq!(twap.duration, 2 * hour);
+        let expected = 0.02 * 365.0 * 12.0;
+        assert!((twap.deposit_rate.to_num::<f64>() - expected).abs() < 0.001);
+        assert!((twap.borrow_rate.to_num::<f64>() - expected).abs() < 0.001);
+        assert!((twap.avg_utilization - hour as f64).abs() < 0.001);
+
+        // limiting the window only looks at the last hour
+        let twap = history.twap(hour).unwrap();
+        assert_eq!(twap.duration, hour);
+        let expected = (1.02 / 1.01 - 1.0) * 365.0 * 24.0;
+        assert!((twap.deposit_rate.to_num::<f64>() - expected).abs() < 0.001);
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey::Pubkey;
+
+use crate::{accounts_zerocopy::KeyedAccountReader, error::MangoError};
+
+use super::{clmm_is_inverted, clmm_quote_oracle};
+
+pub mod raydium_mainnet_clmm {
+    use solana_program::declare_id;
+    declare_id!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+}
+
+pub const RAYDIUM_POOL_LEN: usize = 1544;
+pub const RAYDIUM_POOL_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+pub struct RaydiumPoolState {
+    // active liquidity at the current tick
+    pub liquidity: u128, // 16
+    // Q64.64
+    pub sqrt_price: u128,     // 16
+    pub token_mint_0: Pubkey, // 32
+    pub token_mint_1: Pubkey, // 32
+}
+
+impl RaydiumPoolState {
+    pub fn is_inverted(&self) -> bool {
+        clmm_is_inverted(&self.token_mint_0, &self.token_mint_1)
+    }
+
+    pub fn get_quote_oracle(&self) -> Result<Pubkey> {
+        clmm_quote_oracle(&self.token_mint_0, &self.token_mint_1)
+    }
+}
+
+pub fn load_raydium_pool_state(acc_info: &impl KeyedAccountReader) -> Result<RaydiumPoolState> {
+    let data = &acc_info.data();
+    require!(
+        data[0..8] == RAYDIUM_POOL_DISCRIMINATOR[..],
+        MangoError::InvalidCLMMOracle
+    );
+    require!(
+        data.len() == RAYDIUM_POOL_LEN,
+        MangoError::InvalidCLMMOracle
+    );
+    require!(
+        acc_info.owner() == &raydium_mainnet_clmm::ID,
+        MangoError::InvalidCLMMOracle
+    );
+
+    let a: &[u8; 32] = &(&data[73..105]).try_into().unwrap();
+    let b: &[u8; 32] = &(&data[105..137]).try_into().unwrap();
+    let liquidity_bytes: &[u8; 16] = &data[237..253].try_into().unwrap();
+    let liquidity = u128::from_le_bytes(*liquidity_bytes);
+    let price_bytes: &[u8; 16] = &data[253..269].try_into().unwrap();
+    let sqrt_price = u128::from_le_bytes(*price_bytes);
+    let mint_0 = Pubkey::from(*a);
+    let mint_1 = Pubkey::from(*b);
+
+    Ok(RaydiumPoolState {
+        liquidity,
+        sqrt_price,
+        token_mint_0: mint_0,
+        token_mint_1: mint_1,
+    })
+}
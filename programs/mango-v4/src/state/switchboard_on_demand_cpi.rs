@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey::Pubkey;
+
+use crate::{accounts_zerocopy::KeyedAccountReader, error::MangoError};
+
+pub mod switchboard_on_demand_mainnet_oracle {
+    use solana_program::declare_id;
+    declare_id!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+}
+
+pub const PULL_FEED_ACCOUNT_DATA_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+
+/// Offset of PullFeedAccountData::result, including the discriminator
+const PULL_FEED_RESULT_OFFSET: usize = 2264;
+/// Length of the PullFeedAccountData prefix up to the end of the result
+pub const PULL_FEED_MIN_LEN: usize = PULL_FEED_RESULT_OFFSET + 128;
+
+/// Number of decimals of the fixed point values in the feed result
+const PULL_FEED_PRECISION: i32 = 18;
+
+pub struct PullFeedResultState {
+    pub value: f64,      // 16, i128 with PULL_FEED_PRECISION decimals
+    pub std_dev: f64,    // 16, i128 with PULL_FEED_PRECISION decimals
+    pub num_samples: u8, // 1
+    pub slot: u64,       // 8
+}
+
+/// Loads the current result of a Switchboard On-Demand PullFeedAccountData account.
+///
+/// Feeds without samples are rejected.
+pub fn load_pull_feed_result_state(
+    acc_info: &(impl KeyedAccountReader + ?Sized),
+) -> Result<PullFeedResultState> {
+    let data = &acc_info.data();
+    require!(
+        data.len() >= PULL_FEED_MIN_LEN,
+        MangoError::InvalidSwitchboardOnDemandOracle
+    );
+    require!(
+        data[0..8] == PULL_FEED_ACCOUNT_DATA_DISCRIMINATOR[..],
+        MangoError::InvalidSwitchboardOnDemandOracle
+    );
+    require!(
+        acc_info.owner() == &switchboard_on_demand_mainnet_oracle::ID,
+        MangoError::InvalidSwitchboardOnDemandOracle
+    );
+
+    let result = &data[PULL_FEED_RESULT_OFFSET..PULL_FEED_MIN_LEN];
+    let scale = 10f64.powi(PULL_FEED_PRECISION);
+    let value = i128::from_le_bytes(result[0..16].try_into().unwrap()) as f64 / scale;
+    let std_dev = i128::from_le_bytes(result[16..32].try_into().unwrap()) as f64 / scale;
+    // mean, range, min_value and max_value are skipped
+    let num_samples = result[96];
+    let slot = u64::from_le_bytes(result[104..112].try_into().unwrap());
+
+    require!(
+        num_samples > 0,
+        MangoError::InvalidSwitchboardOnDemandOracle
+    );
+
+    Ok(PullFeedResultState {
+        value,
+        std_dev,
+        num_samples,
+        slot,
+    })
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+use std::mem::size_of;
+
+use crate::state::*;
+
+/// An insurance fund dedicated to a single token.
+///
+/// Contrary to the group insurance fund (which holds INSURANCE_TOKEN_INDEX tokens), this
+/// fund holds the token it is for. When an account is bankrupt with a negative balance in
+/// the token, token_insurance_fund_cover can pay it off directly from the fund's vault.
+/// That should happen before token_liq_bankruptcy or perp_liq_negative_pnl_or_bankruptcy
+/// fall back to the group insurance fund or socialize the loss.
+///
+/// The fund is topped up by sending tokens to the insurance_vault.
+#[account(zero_copy)]
+#[derive(Debug)]
+pub struct TokenInsuranceFund {
+    // ABI: Clients rely on this being at offset 8
+    pub group: Pubkey,
+    // ABI: Clients rely on this being at offset 40
+    pub token_index: TokenIndex,
+
+    pub bump: u8,
+    pub padding: [u8; 5],
+
+    pub mint: Pubkey,
+
+    /// Token account holding the fund, owned by the group
+    pub insurance_vault: Pubkey,
+
+    /// Total native amount paid out to cover bankrupt accounts
+    pub total_covered: u64,
+
+    /// Total native amount withdrawn by the admin
+    pub total_withdrawn: u64,
+
+    pub reserved: [u8; 256],
+}
+const_assert_eq!(
+    size_of::<TokenInsuranceFund>(),
+    32 + 2 + 1 + 5 + 32 + 32 + 8 + 8 + 256
+);
+const_assert_eq!(size_of::<TokenInsuranceFund>(), 376);
+const_assert_eq!(size_of::<TokenInsuranceFund>() % 8, 0);
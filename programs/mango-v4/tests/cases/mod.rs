@@ -20,9 +20,11 @@ mod test_borrow_limits;
 mod test_collateral_fees;
 mod test_delegate;
 mod test_fees_buyback_with_mngo;
+mod test_fixed_term_loan;
 mod test_force_close;
 mod test_health_compute;
 mod test_health_region;
+mod test_isolated_pools;
 mod test_ix_gate_set;
 mod test_liq_perps_bankruptcy;
 mod test_liq_perps_base_and_bankruptcy;
@@ -30,9 +32,12 @@ mod test_liq_perps_force_cancel;
 mod test_liq_perps_positive_pnl;
 mod test_liq_tokens;
 mod test_margin_trade;
+mod test_median_oracle;
+mod test_openbook_v2;
 mod test_perp;
 mod test_perp_settle;
 mod test_perp_settle_fees;
+mod test_perp_trigger_order;
 mod test_position_lifetime;
 mod test_reduce_only;
 mod test_serum;
@@ -610,3 +610,230 @@ async fn test_bankrupt_tokens_insurance_fund() -> Result<(), TransportError> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_bankrupt_tokens_token_insurance_fund() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(85_000); // TokenLiqWithToken needs 84k
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..3];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..3];
+
+    //
+    // SETUP: Create a group and an account to fill the vaults
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let borrow_token = &tokens[1];
+    let collateral_token = &tokens[2];
+
+    let vault_amount = 100000;
+    let vault_account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        mints,
+        vault_amount,
+        1,
+    )
+    .await;
+
+    //
+    // SETUP: Create a dedicated insurance fund for the borrow token
+    //
+    send_tx(
+        solana,
+        TokenInsuranceFundCreateInstruction {
+            group,
+            admin,
+            mint: borrow_token.mint.pubkey,
+            token_index: borrow_token.index,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let insurance_fund = Pubkey::find_program_address(
+        &[
+            b"TokenInsuranceFund".as_ref(),
+            group.as_ref(),
+            &borrow_token.index.to_le_bytes(),
+        ],
+        &mango_v4::id(),
+    )
+    .0;
+    let insurance_vault = solana
+        .get_account::<TokenInsuranceFund>(insurance_fund)
+        .await
+        .insurance_vault;
+
+    // fund the insurance vault
+    {
+        let mut tx = ClientTransaction::new(solana);
+        tx.add_instruction_direct(
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &payer_mint_accounts[1],
+                &insurance_vault,
+                &payer.pubkey(),
+                &[&payer.pubkey()],
+                150,
+            )
+            .unwrap(),
+        );
+        tx.add_signer(payer);
+        tx.send().await.unwrap();
+    }
+
+    //
+    // TEST: the admin can withdraw from the fund
+    //
+    send_tx(
+        solana,
+        TokenInsuranceFundWithdrawInstruction {
+            group,
+            admin,
+            insurance_fund,
+            destination: payer_mint_accounts[1],
+            amount: 50,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(solana.token_account_balance(insurance_vault).await, 100);
+    let fund_data: TokenInsuranceFund = solana.get_account(insurance_fund).await;
+    assert_eq!(fund_data.total_withdrawn, 50);
+
+    //
+    // SETUP: Make an account with some collateral and some borrows
+    //
+    let account = send_tx(
+        solana,
+        AccountCreateInstruction {
+            account_num: 0,
+            group,
+            owner,
+            payer,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+    .account;
+
+    send_tx(
+        solana,
+        TokenDepositInstruction {
+            amount: 1000,
+            reduce_only: false,
+            account,
+            owner,
+            token_account: payer_mint_accounts[2],
+            token_authority: payer.clone(),
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 350,
+            allow_borrow: true,
+            account,
+            owner,
+            token_account: payer_mint_accounts[1],
+            bank_index: 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: the fund can't be used while the account isn't bankrupt
+    //
+    let cover_ix = TokenInsuranceFundCoverInstruction {
+        liqee: account,
+        insurance_fund,
+        bank: borrow_token.bank,
+        max_cover_amount: u64::MAX,
+    };
+    assert!(send_tx(solana, cover_ix.clone()).await.is_err());
+
+    //
+    // SETUP: Change the oracle to make health go very negative and liquidate all collateral
+    //
+    set_bank_stub_oracle_price(solana, group, borrow_token, admin, 20.0).await;
+
+    send_tx(
+        solana,
+        TokenLiqWithTokenInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            asset_token_index: collateral_token.index,
+            asset_bank_index: 1,
+            liab_token_index: borrow_token.index,
+            liab_bank_index: 1,
+            max_liab_transfer: I80F48::from_num(100000.0),
+        },
+    )
+    .await
+    .unwrap();
+    assert!(account_position_closed(solana, account, collateral_token.bank).await);
+    let liq_fee_factor = 1.02 * 1.02;
+    let borrow_after_liq = -350.0f64 + (1000.0 / 20.0 / liq_fee_factor);
+    assert_eq!(
+        account_position(solana, account, borrow_token.bank).await,
+        borrow_after_liq.round() as i64
+    );
+
+    //
+    // TEST: the token insurance fund pays off part of the bankrupt borrow
+    //
+    send_tx(solana, cover_ix).await.unwrap();
+    assert_eq!(solana.token_account_balance(insurance_vault).await, 0);
+    assert_eq!(
+        account_position(solana, account, borrow_token.bank).await,
+        (borrow_after_liq + 100.0).round() as i64
+    );
+    let fund_data: TokenInsuranceFund = solana.get_account(insurance_fund).await;
+    assert_eq!(fund_data.total_covered, 100);
+    let liqee = get_mango_account(solana, account).await;
+    assert!(liqee.being_liquidated());
+
+    //
+    // TEST: the remainder is socialized as usual
+    //
+    send_tx(
+        solana,
+        TokenLiqBankruptcyInstruction {
+            liqee: account,
+            liqor: vault_account,
+            liqor_owner: owner,
+            liab_mint_info: borrow_token.mint_info,
+            max_liab_transfer: I80F48::from_num(100000.0),
+        },
+    )
+    .await
+    .unwrap();
+    let liqee = get_mango_account(solana, account).await;
+    assert!(!liqee.being_liquidated());
+    assert!(account_position_closed(solana, account, borrow_token.bank).await);
+
+    Ok(())
+}
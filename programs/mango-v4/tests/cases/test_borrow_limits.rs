@@ -387,3 +387,115 @@ async fn test_bank_net_borrows_based_borrow_limit() -> Result<(), TransportError
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_bank_total_and_account_borrow_limit() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group, limit borrows of token 0 and prepare accounts
+    //
+
+    let GroupWithTokens { group, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[0].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                loan_origination_fee_rate_opt: Some(0.0),
+                borrow_limit_opt: Some(1500),
+                account_borrow_limit_quote_opt: Some(1000),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        &mints[0..1],
+        10_000,
+        0,
+    )
+    .await;
+    let account_1 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        &mints[1..2],
+        100_000,
+        0,
+    )
+    .await;
+    let account_2 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        &mints[1..2],
+        100_000,
+        0,
+    )
+    .await;
+
+    let borrow = |account: Pubkey, amount: u64| TokenWithdrawInstruction {
+        amount,
+        allow_borrow: true,
+        account,
+        owner,
+        token_account: payer_mint_accounts[0],
+        bank_index: 0,
+    };
+
+    //
+    // TEST: An account can borrow up to the account borrow limit
+    //
+    send_tx(solana, borrow(account_1, 1000)).await.unwrap();
+
+    let res = send_tx(solana, borrow(account_1, 1)).await;
+    assert_mango_error(
+        &res,
+        MangoError::AccountBorrowLimit.into(),
+        "account borrow limit".into(),
+    );
+
+    //
+    // TEST: All accounts together can borrow up to the total borrow limit
+    //
+    let res = send_tx(solana, borrow(account_2, 600)).await;
+    assert_mango_error(
+        &res,
+        MangoError::BankTotalBorrowLimit.into(),
+        "total borrow limit".into(),
+    );
+
+    send_tx(solana, borrow(account_2, 500)).await.unwrap();
+
+    Ok(())
+}
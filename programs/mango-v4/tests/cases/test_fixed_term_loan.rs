@@ -0,0 +1,490 @@
+use super::*;
+
+#[tokio::test]
+async fn test_fixed_term_loan() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let settler = TestKeypair::new();
+    let mints = &context.mints[0..2];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group, allow fixed term loans on token 0 and prepare accounts
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let bank = tokens[0].bank;
+
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[0].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                fixed_term_rate_premium_opt: Some(0.05),
+                fixed_term_max_duration_opt: Some(1000),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        &mints[0..1],
+        10_000,
+        0,
+    )
+    .await;
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        &mints[1..2],
+        100_000,
+        0,
+    )
+    .await;
+
+    expand_for_fixed_term_loans(solana, group, owner, payer, 1).await;
+
+    let create_ix = FixedTermLoanCreateInstruction {
+        account,
+        owner,
+        bank,
+        amount: 1000,
+        duration: 1000,
+        auto_roll: false,
+    };
+
+    //
+    // TEST: The duration is limited by the bank
+    //
+    let res = send_tx(
+        solana,
+        FixedTermLoanCreateInstruction {
+            duration: 1001,
+            ..create_ix.clone()
+        },
+    )
+    .await;
+    assert_mango_error(
+        &res,
+        MangoError::FixedTermLoanDurationNotAllowed.into(),
+        "duration".into(),
+    );
+
+    //
+    // TEST: Creating a loan credits the tokens and locks the rate, the loan
+    // origination fee is added to the principal
+    //
+    send_tx(solana, create_ix.clone()).await.unwrap();
+    assert_eq!(account_position(solana, account, bank).await, 1000);
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(account_data.active_fixed_term_loans().count(), 1);
+    let loan = account_data.fixed_term_loan_by_index(0).unwrap().clone();
+    assert_eq!(loan.id, 0);
+    assert_eq!(loan.principal, 1001);
+    assert!(loan.rate >= 0.05);
+
+    let bank_data = solana.get_account::<Bank>(bank).await;
+    assert_eq!(bank_data.fixed_term_borrows, I80F48::from(1001));
+    assert_eq!(bank_data.fixed_term_yearly_interest, loan.yearly_interest());
+
+    // the borrowed tokens can be withdrawn
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 1000,
+            allow_borrow: false,
+            account,
+            owner,
+            token_account: payer_mint_accounts[0],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Others can't settle a healthy account's loan before maturity
+    //
+    let settle_ix = FixedTermLoanSettleInstruction {
+        account,
+        settler,
+        bank,
+        index: 0,
+        id: 0,
+    };
+    let res = send_tx(solana, settle_ix.clone()).await;
+    assert_mango_error(
+        &res,
+        MangoError::FixedTermLoanNotSettleable.into(),
+        "not settleable".into(),
+    );
+
+    //
+    // TEST: At maturity anyone can force repayment; the shortfall becomes a
+    // variable borrow and is charged the liquidation fee
+    //
+    solana
+        .advance_clock_to(loan.maturity_timestamp as i64)
+        .await;
+    send_tx(solana, settle_ix.clone()).await.unwrap();
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(account_data.active_fixed_term_loans().count(), 0);
+
+    let now_ts = solana.clock_timestamp().await;
+    let owed = loan.owed(now_ts).to_num::<f64>();
+    let position = account_position_f64(solana, account, bank).await;
+    assert!(position <= -owed * 1.02 + 1.0);
+    assert!(position >= -owed * 1.02 - 1.0);
+
+    let bank_data = solana.get_account::<Bank>(bank).await;
+    assert_eq!(bank_data.fixed_term_borrows, I80F48::ZERO);
+    assert_eq!(bank_data.fixed_term_yearly_interest, I80F48::ZERO);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fixed_term_loan_owner_settle() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group, allow fixed term loans on token 0 and prepare an account
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let bank = tokens[0].bank;
+
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[0].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                fixed_term_rate_premium_opt: Some(0.05),
+                fixed_term_max_duration_opt: Some(1000),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    let account =
+        create_funded_account(&solana, group, owner, 0, &context.users[1], mints, 1000, 0).await;
+    expand_for_fixed_term_loans(solana, group, owner, payer, 0).await;
+
+    send_tx(
+        solana,
+        FixedTermLoanCreateInstruction {
+            account,
+            owner,
+            bank,
+            amount: 1000,
+            duration: 1000,
+            auto_roll: false,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(account_position(solana, account, bank).await, 2000);
+
+    //
+    // TEST: The owner can repay before maturity, which releases the token position
+    //
+    solana.advance_clock().await;
+    send_tx(
+        solana,
+        FixedTermLoanSettleInstruction {
+            account,
+            settler: owner,
+            bank,
+            index: 0,
+            id: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(account_data.active_fixed_term_loans().count(), 0);
+    let position = account_data.token_position(0).unwrap();
+    assert_eq!(position.in_use_count, 0);
+    assert_eq!(position.fixed_term_principal, 0);
+
+    let bank_data = solana.get_account::<Bank>(bank).await;
+    assert_eq!(bank_data.fixed_term_borrows, I80F48::ZERO);
+
+    let position = account_position(solana, account, bank).await;
+    assert!(position >= 998 && position <= 999);
+
+    //
+    // TEST: The account can be emptied and closed afterwards
+    //
+    for &token_account in payer_mint_accounts {
+        send_tx(
+            solana,
+            TokenWithdrawInstruction {
+                amount: u64::MAX,
+                allow_borrow: false,
+                account,
+                owner,
+                token_account,
+                bank_index: 0,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    send_tx(
+        solana,
+        AccountCloseInstruction {
+            group,
+            account,
+            owner,
+            sol_destination: payer.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fixed_term_loan_borrow_limits() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+    let payer_mint_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group, allow fixed term loans on token 0 and prepare accounts
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let bank = tokens[0].bank;
+
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[0].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                fixed_term_rate_premium_opt: Some(0.05),
+                fixed_term_max_duration_opt: Some(1000),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    let liquidity_account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        &mints[0..1],
+        10_000,
+        0,
+    )
+    .await;
+    let mut accounts = vec![];
+    for account_num in 1..3 {
+        accounts.push(
+            create_funded_account(
+                &solana,
+                group,
+                owner,
+                account_num,
+                &context.users[1],
+                &mints[1..2],
+                100_000,
+                0,
+            )
+            .await,
+        );
+        expand_for_fixed_term_loans(solana, group, owner, payer, account_num).await;
+    }
+    let (account_1, account_2) = (accounts[0], accounts[1]);
+
+    let create_loan = |account: Pubkey, amount: u64| FixedTermLoanCreateInstruction {
+        account,
+        owner,
+        bank,
+        amount,
+        duration: 1000,
+        auto_roll: false,
+    };
+    let withdraw = |account: Pubkey, amount: u64, allow_borrow: bool| TokenWithdrawInstruction {
+        amount,
+        allow_borrow,
+        account,
+        owner,
+        token_account: payer_mint_accounts[0],
+        bank_index: 0,
+    };
+
+    //
+    // TEST: Fixed term loans count towards the bank utilization
+    //
+    send_tx(solana, create_loan(account_1, 7000)).await.unwrap();
+    send_tx(solana, withdraw(account_1, 7000, false))
+        .await
+        .unwrap();
+
+    let res = send_tx(solana, withdraw(account_2, 1000, true)).await;
+    assert_mango_error(
+        &res,
+        MangoError::BankBorrowLimitReached.into(),
+        "variable borrow over utilization".into(),
+    );
+
+    let res = send_tx(solana, create_loan(account_2, 1000)).await;
+    assert_mango_error(
+        &res,
+        MangoError::BankBorrowLimitReached.into(),
+        "fixed term loan over utilization".into(),
+    );
+
+    //
+    // TEST: Fixed term loans count towards the account borrow limit
+    //
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[0].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                account_borrow_limit_quote_opt: Some(1500),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    // add liquidity so only the account limit applies
+    send_tx(
+        solana,
+        TokenDepositInstruction {
+            amount: 10_000,
+            reduce_only: false,
+            account: liquidity_account,
+            owner,
+            token_account: payer_mint_accounts[0],
+            token_authority: payer,
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    send_tx(solana, create_loan(account_2, 900)).await.unwrap();
+    send_tx(solana, withdraw(account_2, 900, false))
+        .await
+        .unwrap();
+
+    let res = send_tx(solana, withdraw(account_2, 700, true)).await;
+    assert_mango_error(
+        &res,
+        MangoError::AccountBorrowLimit.into(),
+        "variable borrow over account limit".into(),
+    );
+
+    let res = send_tx(solana, create_loan(account_2, 600)).await;
+    assert_mango_error(
+        &res,
+        MangoError::AccountBorrowLimit.into(),
+        "fixed term loan over account limit".into(),
+    );
+
+    Ok(())
+}
+
+async fn expand_for_fixed_term_loans(
+    solana: &SolanaCookie,
+    group: Pubkey,
+    owner: TestKeypair,
+    payer: TestKeypair,
+    account_num: u32,
+) {
+    send_tx(
+        solana,
+        AccountExpandInstruction {
+            account_num,
+            token_count: 8,
+            serum3_count: 4,
+            perp_count: 4,
+            perp_oo_count: 16,
+            token_conditional_swap_count: 1,
+            openbook_v2_count: 0,
+            perp_trigger_order_count: 0,
+            fixed_term_loan_count: 2,
+            group,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+}
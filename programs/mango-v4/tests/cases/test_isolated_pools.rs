@@ -0,0 +1,214 @@
+use super::*;
+
+#[tokio::test]
+async fn test_isolated_pools() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..3];
+    let payer_token_accounts = &context.users[1].token_accounts[0..3];
+
+    let mango_setup::GroupWithTokens { group, tokens, .. } = mango_setup::GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..mango_setup::GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    // fund the vaults to allow borrowing
+    create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        1_000_000,
+        0,
+    )
+    .await;
+
+    // token 2 is isolated and may only be combined with token 0
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[2].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                isolated_opt: Some(true),
+                isolated_quote_token_indexes_opt: Some(vec![tokens[0].index]),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        &mints[2..3],
+        1_000,
+        0,
+    )
+    .await;
+
+    //
+    // TEST: Borrowing the quote token against the isolated token works
+    //
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 100,
+            allow_borrow: true,
+            account,
+            owner,
+            token_account: payer_token_accounts[0],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Borrowing any other token fails
+    //
+    let err = send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 100,
+            allow_borrow: true,
+            account,
+            owner,
+            token_account: payer_token_accounts[1],
+            bank_index: 0,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &err,
+        MangoError::IsolatedTokenCombination.into(),
+        "can't borrow a non-quote token".into(),
+    );
+
+    //
+    // TEST: An account that got into a mixed state by depositing can only leave it
+    //
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        &mints[1..3],
+        1_000,
+        0,
+    )
+    .await;
+
+    let err = send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 100,
+            allow_borrow: false,
+            account,
+            owner,
+            token_account: payer_token_accounts[1],
+            bank_index: 0,
+        },
+    )
+    .await;
+    assert_mango_error(
+        &err,
+        MangoError::IsolatedTokenCombination.into(),
+        "partial withdraw keeps the mixed state".into(),
+    );
+
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: u64::MAX,
+            allow_borrow: false,
+            account,
+            owner,
+            token_account: payer_token_accounts[1],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: A liqor can't take on the isolated token next to a non-quote token
+    //
+    let liqee = create_funded_account(
+        &solana,
+        group,
+        owner,
+        3,
+        &context.users[1],
+        &mints[2..3],
+        1_000,
+        0,
+    )
+    .await;
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 500,
+            allow_borrow: true,
+            account: liqee,
+            owner,
+            token_account: payer_token_accounts[0],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    let liqor = create_funded_account(
+        &solana,
+        group,
+        owner,
+        4,
+        &context.users[1],
+        &mints[0..2],
+        10_000,
+        0,
+    )
+    .await;
+
+    set_bank_stub_oracle_price(solana, group, &tokens[0], admin, 10.0).await;
+
+    let err = send_tx(
+        solana,
+        TokenLiqWithTokenInstruction {
+            liqee,
+            liqor,
+            liqor_owner: owner,
+            asset_token_index: tokens[2].index,
+            asset_bank_index: 0,
+            liab_token_index: tokens[0].index,
+            liab_bank_index: 0,
+            max_liab_transfer: I80F48::from_num(10.0),
+        },
+    )
+    .await;
+    assert_mango_error(
+        &err,
+        MangoError::IsolatedTokenCombination.into(),
+        "liqor can't mix the isolated token".into(),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+#[tokio::test]
+async fn test_median_oracle_mode() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+    let payer_token_accounts = &context.users[1].token_accounts[0..2];
+
+    //
+    // SETUP: Create a group, a perp market and two more oracles for token 1
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: 0.0000,
+            taker_fee: 0.0000,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[1]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let mut median_oracles = vec![];
+    for _ in 0..2 {
+        let oracle_kp = TestKeypair::new();
+        send_tx(
+            solana,
+            StubOracleCreate {
+                oracle: oracle_kp,
+                group,
+                mint: mints[1].pubkey,
+                admin,
+                payer,
+            },
+        )
+        .await
+        .unwrap();
+        median_oracles.push(oracle_kp.pubkey());
+    }
+
+    //
+    // TEST: Enable the median oracle mode with a quorum of 2 on the bank and perp market
+    //
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[1].pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                median_oracles_opt: Some(median_oracles.clone()),
+                median_oracle_quorum_opt: Some(2),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+    send_tx(
+        solana,
+        PerpSetMedianOracles {
+            group,
+            admin,
+            perp_market,
+            median_oracles: median_oracles.clone(),
+            quorum: 2,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Deposits, withdraws and perp orders work with the median oracle accounts
+    //
+    let account = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        10_000,
+        0,
+    )
+    .await;
+    assert_eq!(
+        account_position(solana, account, tokens[1].bank).await,
+        10_000
+    );
+
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: 1_000,
+            allow_borrow: false,
+            account,
+            owner,
+            token_account: payer_token_accounts[1],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        account_position(solana, account, tokens[1].bank).await,
+        9_000
+    );
+
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots: perp_market_data.native_price_to_lot(I80F48::ONE),
+            max_base_lots: 1,
+            client_order_id: 0,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(account_data.perp_position(0).unwrap().bids_base_lots, 1);
+
+    send_tx(
+        solana,
+        TokenUpdateIndexAndRateInstruction {
+            mint_info: tokens[1].mint_info,
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,566 @@
+#![allow(dead_code)]
+use super::*;
+
+use mango_v4::openbook_v2_cpi::OpenOrdersSlim;
+use mango_v4::serum3_cpi::OpenOrdersAmounts;
+use openbook_v2::state::{PlaceOrderType, SelfTradeBehavior, Side};
+use std::sync::Arc;
+
+struct OpenbookV2OrderPlacer {
+    solana: Arc<SolanaCookie>,
+    openbook_v2: Arc<OpenbookV2Cookie>,
+    account: Pubkey,
+    owner: TestKeypair,
+    openbook_v2_market: Pubkey,
+    open_orders: Pubkey,
+    next_client_order_id: u64,
+}
+
+impl OpenbookV2OrderPlacer {
+    fn inc_client_order_id(&mut self) -> u64 {
+        let id = self.next_client_order_id;
+        self.next_client_order_id += 1;
+        id
+    }
+
+    async fn find_order_id_for_client_order_id(&self, client_order_id: u64) -> Option<u128> {
+        let open_orders = self.openbook_v2.load_open_orders(self.open_orders).await;
+        open_orders
+            .find_order_with_client_order_id(client_order_id)
+            .map(|o| o.id)
+    }
+
+    async fn try_place(
+        &mut self,
+        side: Side,
+        limit_price: f64,
+        max_base: u64,
+        taker: bool,
+    ) -> Result<mango_v4::accounts::OpenbookV2PlaceOrder, TransportError> {
+        let client_order_id = self.inc_client_order_id();
+        let fees = if taker { 0.0004 } else { 0.0 };
+        send_tx(
+            &self.solana,
+            OpenbookV2PlaceOrderInstruction {
+                side,
+                limit_price: (limit_price * 100.0 / 10.0) as u64, // in quote_lot (10) per base lot (100)
+                max_base_qty: max_base / 100,                     // in base lot (100)
+                // 4 bps taker fees added in
+                max_native_quote_qty_including_fees: (limit_price
+                    * (max_base as f64)
+                    * (1.0 + fees))
+                    .ceil() as u64,
+                self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+                order_type: if taker {
+                    PlaceOrderType::ImmediateOrCancel
+                } else {
+                    PlaceOrderType::Limit
+                },
+                client_order_id,
+                limit: 10,
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+            },
+        )
+        .await
+    }
+
+    async fn bid_maker(&mut self, limit_price: f64, max_base: u64) -> Option<u128> {
+        self.try_place(Side::Bid, limit_price, max_base, false)
+            .await
+            .unwrap();
+        self.find_order_id_for_client_order_id(self.next_client_order_id - 1)
+            .await
+    }
+
+    async fn bid_taker(&mut self, limit_price: f64, max_base: u64) {
+        self.try_place(Side::Bid, limit_price, max_base, true)
+            .await
+            .unwrap();
+    }
+
+    async fn ask(&mut self, limit_price: f64, max_base: u64) -> Option<u128> {
+        self.try_place(Side::Ask, limit_price, max_base, false)
+            .await
+            .unwrap();
+        self.find_order_id_for_client_order_id(self.next_client_order_id - 1)
+            .await
+    }
+
+    async fn cancel(&self, side: Side, order_id: u128) {
+        self.try_cancel(side, order_id).await.unwrap();
+    }
+
+    async fn try_cancel(&self, side: Side, order_id: u128) -> Result<(), TransportError> {
+        send_tx(
+            &self.solana,
+            OpenbookV2CancelOrderInstruction {
+                side,
+                order_id,
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+            },
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn cancel_all(&self) {
+        send_tx(
+            &self.solana,
+            OpenbookV2CancelAllOrdersInstruction {
+                limit: 10,
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn settle(&self) {
+        send_tx(
+            &self.solana,
+            OpenbookV2SettleFundsInstruction {
+                account: self.account,
+                owner: self.owner,
+                openbook_v2_market: self.openbook_v2_market,
+                fees_to_dao: true,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn mango_openbook_v2_orders(&self) -> OpenbookV2Orders {
+        let account_data = get_mango_account(&self.solana, self.account).await;
+        let orders = account_data
+            .all_openbook_v2_orders()
+            .find(|s| s.open_orders == self.open_orders)
+            .unwrap();
+        orders.clone()
+    }
+
+    async fn open_orders(&self) -> OpenOrdersSlim {
+        let orders = self.mango_openbook_v2_orders().await;
+        let open_orders = self.openbook_v2.load_open_orders(self.open_orders).await;
+        OpenOrdersSlim::from_oo(&open_orders, orders.base_lot_size, orders.quote_lot_size)
+    }
+}
+
+async fn create_openbook_v2_account(
+    solana: &SolanaCookie,
+    group: Pubkey,
+    owner: TestKeypair,
+    account_num: u32,
+    payer: &UserCookie,
+    mints: &[MintCookie],
+    amount: u64,
+) -> Pubkey {
+    let account = send_tx(
+        solana,
+        AccountCreateInstruction {
+            account_num,
+            serum3_count: 0,
+            openbook_v2_count: 2,
+            group,
+            owner,
+            payer: payer.key,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+    .account;
+
+    for mint in mints {
+        send_tx(
+            solana,
+            TokenDepositInstruction {
+                amount,
+                reduce_only: false,
+                account,
+                owner,
+                token_account: payer.token_accounts[mint.index],
+                token_authority: payer.key,
+                bank_index: 0,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    account
+}
+
+#[tokio::test]
+async fn test_openbook_v2_basics() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(150_000);
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group and an account
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let base_token = &tokens[0];
+    let quote_token = &tokens[1];
+
+    //
+    // SETUP: Create openbook v2 market
+    //
+    let openbook_v2_market_cookie = context
+        .openbook_v2
+        .list_spot_market(&base_token.mint, &quote_token.mint)
+        .await;
+
+    //
+    // TEST: Register an openbook v2 market
+    //
+    let openbook_v2_market = send_tx(
+        solana,
+        OpenbookV2RegisterMarketInstruction {
+            group,
+            admin,
+            openbook_v2_program: context.openbook_v2.program_id,
+            openbook_v2_market_external: openbook_v2_market_cookie.market,
+            market_index: 0,
+            base_bank: base_token.bank,
+            quote_bank: quote_token.bank,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .openbook_v2_market;
+
+    //
+    // SETUP: Create account
+    //
+    let account =
+        create_openbook_v2_account(solana, group, owner, 0, &context.users[1], mints, 1000).await;
+
+    //
+    // TEST: Create an open orders account
+    //
+    let open_orders = send_tx(
+        solana,
+        OpenbookV2CreateOpenOrdersInstruction {
+            account,
+            openbook_v2_market,
+            account_num: 0,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .open_orders;
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(
+        account_data
+            .active_openbook_v2_orders()
+            .map(|v| (v.open_orders, v.market_index))
+            .collect::<Vec<_>>(),
+        [(open_orders, 0)]
+    );
+
+    let mut order_placer = OpenbookV2OrderPlacer {
+        solana: solana.clone(),
+        openbook_v2: context.openbook_v2.clone(),
+        account,
+        owner,
+        openbook_v2_market,
+        open_orders,
+        next_client_order_id: 0,
+    };
+
+    //
+    // TEST: Place an order
+    //
+    let order_id = order_placer.bid_maker(0.9, 100).await.unwrap();
+    check_prev_instruction_post_health(solana, account).await;
+
+    let native0 = account_position(solana, account, base_token.bank).await;
+    let native1 = account_position(solana, account, quote_token.bank).await;
+    assert_eq!(native0, 1000);
+    assert_eq!(native1, 910);
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(0)
+            .unwrap()
+            .in_use_count,
+        1
+    );
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(1)
+            .unwrap()
+            .in_use_count,
+        1
+    );
+    let openbook_orders = order_placer.mango_openbook_v2_orders().await;
+    assert_eq!(openbook_orders.base_borrows_without_fee, 0);
+    assert_eq!(openbook_orders.quote_borrows_without_fee, 0);
+    assert_eq!(openbook_orders.potential_base_tokens, 100);
+    assert_eq!(openbook_orders.potential_quote_tokens, 90);
+
+    let base_bank = solana.get_account::<Bank>(base_token.bank).await;
+    assert_eq!(base_bank.potential_serum_tokens, 100);
+    let quote_bank = solana.get_account::<Bank>(quote_token.bank).await;
+    assert_eq!(quote_bank.potential_serum_tokens, 90);
+
+    assert_eq!(order_placer.open_orders().await.native_quote_reserved(), 90);
+
+    //
+    // TEST: Cancel the order, the side must match
+    //
+    assert!(order_placer.try_cancel(Side::Ask, order_id).await.is_err());
+    order_placer.cancel(Side::Bid, order_id).await;
+
+    //
+    // TEST: Cancel all orders
+    //
+    order_placer.bid_maker(0.8, 100).await.unwrap();
+    order_placer.bid_maker(0.7, 100).await.unwrap();
+    order_placer.cancel_all().await;
+    assert_eq!(order_placer.open_orders().await.native_quote_reserved(), 0);
+
+    //
+    // TEST: Settle, moving the freed up funds back
+    //
+    order_placer.settle().await;
+
+    let native0 = account_position(solana, account, base_token.bank).await;
+    let native1 = account_position(solana, account, quote_token.bank).await;
+    assert_eq!(native0, 1000);
+    assert_eq!(native1, 1000);
+
+    let openbook_orders = order_placer.mango_openbook_v2_orders().await;
+    assert_eq!(openbook_orders.potential_base_tokens, 0);
+    assert_eq!(openbook_orders.potential_quote_tokens, 0);
+
+    let base_bank = solana.get_account::<Bank>(base_token.bank).await;
+    assert_eq!(base_bank.potential_serum_tokens, 0);
+    let quote_bank = solana.get_account::<Bank>(quote_token.bank).await;
+    assert_eq!(quote_bank.potential_serum_tokens, 0);
+
+    //
+    // TEST: Close the open orders account
+    //
+    send_tx(
+        solana,
+        OpenbookV2CloseOpenOrdersInstruction {
+            account,
+            openbook_v2_market,
+            owner,
+            sol_destination: payer.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let account_data = get_mango_account(solana, account).await;
+    assert_eq!(account_data.active_openbook_v2_orders().count(), 0);
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(0)
+            .unwrap()
+            .in_use_count,
+        0
+    );
+    assert_eq!(
+        account_data
+            .token_position_by_raw_index(1)
+            .unwrap()
+            .in_use_count,
+        0
+    );
+
+    //
+    // TEST: Deregister the market
+    //
+    send_tx(
+        solana,
+        OpenbookV2DeregisterMarketInstruction {
+            group,
+            admin,
+            openbook_v2_market_external: openbook_v2_market_cookie.market,
+            sol_destination: payer.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_openbook_v2_trade() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(150_000);
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, market and two accounts
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+    let base_token = &tokens[0];
+    let quote_token = &tokens[1];
+
+    let openbook_v2_market_cookie = context
+        .openbook_v2
+        .list_spot_market(&base_token.mint, &quote_token.mint)
+        .await;
+
+    let openbook_v2_market = send_tx(
+        solana,
+        OpenbookV2RegisterMarketInstruction {
+            group,
+            admin,
+            openbook_v2_program: context.openbook_v2.program_id,
+            openbook_v2_market_external: openbook_v2_market_cookie.market,
+            market_index: 0,
+            base_bank: base_token.bank,
+            quote_bank: quote_token.bank,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .openbook_v2_market;
+
+    let deposit_amount = 10000;
+    let account = create_openbook_v2_account(
+        solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+    )
+    .await;
+    let account2 = create_openbook_v2_account(
+        solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+    )
+    .await;
+
+    let mut order_placers = vec![];
+    for (account_num, account) in [account, account2].into_iter().enumerate() {
+        let open_orders = send_tx(
+            solana,
+            OpenbookV2CreateOpenOrdersInstruction {
+                account,
+                openbook_v2_market,
+                account_num: account_num as u32,
+                owner,
+                payer,
+            },
+        )
+        .await
+        .unwrap()
+        .open_orders;
+        order_placers.push(OpenbookV2OrderPlacer {
+            solana: solana.clone(),
+            openbook_v2: context.openbook_v2.clone(),
+            account,
+            owner,
+            openbook_v2_market,
+            open_orders,
+            next_client_order_id: 0,
+        });
+    }
+    let mut order_placer2 = order_placers.pop().unwrap();
+    let mut order_placer = order_placers.pop().unwrap();
+
+    //
+    // TEST: Taker bid fills against a resting ask
+    //
+    order_placer2.ask(1.0, 1000).await.unwrap();
+    order_placer.bid_taker(1.0, 1000).await;
+    check_prev_instruction_post_health(solana, account).await;
+
+    // the taker's funds are on its open orders account, ready to be settled
+    let oo = order_placer.open_orders().await;
+    assert_eq!(oo.native_base_free(), 1000);
+    assert_eq!(oo.native_quote_reserved(), 0);
+
+    context
+        .openbook_v2
+        .consume_events(
+            &openbook_v2_market_cookie,
+            &[order_placer.open_orders, order_placer2.open_orders],
+        )
+        .await;
+
+    //
+    // TEST: Settle both sides
+    //
+    order_placer.settle().await;
+    order_placer2.settle().await;
+
+    // taker pays 4 bps fees on top of the quote amount
+    assert_eq!(
+        account_position(solana, account, base_token.bank).await,
+        (deposit_amount + 1000) as i64
+    );
+    assert_eq!(
+        account_position(solana, account, quote_token.bank).await,
+        (deposit_amount - 1000 - 1) as i64
+    );
+    assert_eq!(
+        account_position(solana, account2, base_token.bank).await,
+        (deposit_amount - 1000) as i64
+    );
+    assert_eq!(
+        account_position(solana, account2, quote_token.bank).await,
+        (deposit_amount + 1000) as i64
+    );
+
+    let openbook_orders = order_placer.mango_openbook_v2_orders().await;
+    assert_eq!(openbook_orders.potential_base_tokens, 0);
+    assert_eq!(openbook_orders.potential_quote_tokens, 0);
+
+    Ok(())
+}
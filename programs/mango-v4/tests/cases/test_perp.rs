@@ -1593,6 +1593,385 @@ async fn test_perp_cancel_with_in_flight_events() -> Result<(), TransportError>
     Ok(())
 }
 
+#[tokio::test]
+async fn test_perp_edit_order() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, an account and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let deposit_amount = 1000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+
+    let mango_v4::accounts::PerpCreateMarket {
+        perp_market, bids, ..
+    } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: 0.0000,
+            taker_fee: 0.0000,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[1]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    let price_lots = perp_market_data.native_price_to_lot(I80F48::from(1));
+
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 10,
+            client_order_id: 7,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+    let order_id = solana
+        .get_account::<MangoAccount>(account_0)
+        .await
+        .perp_open_orders[0]
+        .id;
+
+    //
+    // TEST: Reducing the quantity at the same price keeps the order id
+    //
+    send_tx(
+        solana,
+        PerpEditOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            client_order_id: 7,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 4,
+            ..PerpEditOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perp_open_orders[0].id, order_id);
+    assert_eq!(mango_account_0.perp_open_orders[0].quantity, 4);
+    assert_eq!(mango_account_0.perps[0].bids_base_lots, 4);
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 1);
+
+    //
+    // TEST: Changing the price replaces the order
+    //
+    send_tx(
+        solana,
+        PerpEditOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            client_order_id: 7,
+            side: Side::Bid,
+            price_lots: price_lots - 1,
+            max_base_lots: 6,
+            ..PerpEditOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    let oo = mango_account_0
+        .perp_open_orders
+        .iter()
+        .find(|oo| oo.is_active())
+        .unwrap();
+    assert_ne!(oo.id, order_id);
+    assert_eq!(oo.client_id, 7);
+    assert_eq!(oo.quantity, 6);
+    assert_eq!(mango_account_0.perps[0].bids_base_lots, 6);
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 1);
+
+    //
+    // TEST: Orders with an expiry slot in the past are skipped
+    //
+    let slot = solana.clock().await.slot;
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            client_order_id: 9,
+            expiry_slot: slot,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perps[0].bids_base_lots, 6);
+
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            client_order_id: 9,
+            expiry_slot: slot + 100,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perps[0].bids_base_lots, 7);
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 2);
+
+    //
+    // TEST: Editing an unknown order fails
+    //
+    let result = send_tx(
+        solana,
+        PerpEditOrderInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            client_order_id: 8,
+            side: Side::Bid,
+            price_lots,
+            max_base_lots: 1,
+            ..PerpEditOrderInstruction::default()
+        },
+    )
+    .await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpOrderIdNotFound.into(),
+        "unknown client order id".to_string(),
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_perp_place_orders_batch() -> Result<(), TransportError> {
+    use mango_v4::accounts_ix::PerpBatchOrderParams;
+
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group, an account and a perp market
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let deposit_amount = 1000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+
+    let mango_v4::accounts::PerpCreateMarket {
+        perp_market,
+        bids,
+        asks,
+        ..
+    } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: 0.0000,
+            taker_fee: 0.0000,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[1]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    let price_lots = perp_market_data.native_price_to_lot(I80F48::from(1));
+
+    let bid = |price_lots: i64, client_order_id: u64| PerpBatchOrderParams {
+        side: Side::Bid,
+        oracle_pegged: false,
+        price_lots,
+        peg_limit: -1,
+        max_base_lots: 1,
+        max_quote_lots: i64::MAX,
+        client_order_id,
+        order_type: PlaceOrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::DecrementTake,
+        reduce_only: false,
+        expiry_timestamp: 0,
+        expiry_slot: 0,
+        display_base_lots: 0,
+    };
+
+    //
+    // TEST: Place a ladder of fixed bids and a pegged ask in one instruction
+    //
+    send_tx(
+        solana,
+        PerpPlaceOrdersBatchInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            cancel_all: false,
+            orders: vec![
+                bid(price_lots, 1),
+                bid(price_lots - 1, 2),
+                bid(price_lots - 2, 3),
+                PerpBatchOrderParams {
+                    side: Side::Ask,
+                    oracle_pegged: true,
+                    price_lots: 1,
+                    client_order_id: 4,
+                    ..bid(0, 0)
+                },
+            ],
+            limit: 10,
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 3);
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[1].leaf_count, 1);
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perps[0].bids_base_lots, 3);
+    assert_eq!(mango_account_0.perps[0].asks_base_lots, 1);
+
+    //
+    // TEST: Requote after canceling all existing orders
+    //
+    send_tx(
+        solana,
+        PerpPlaceOrdersBatchInstruction {
+            account: account_0,
+            perp_market,
+            owner,
+            cancel_all: true,
+            orders: vec![bid(price_lots - 3, 5)],
+            limit: 10,
+        },
+    )
+    .await
+    .unwrap();
+    check_prev_instruction_post_health(&solana, account_0).await;
+
+    let bids_data = solana.get_account_boxed::<BookSide>(bids).await;
+    assert_eq!(bids_data.roots[0].leaf_count, 1);
+    let asks_data = solana.get_account_boxed::<BookSide>(asks).await;
+    assert_eq!(asks_data.roots[1].leaf_count, 0);
+    let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
+    assert_eq!(mango_account_0.perps[0].bids_base_lots, 1);
+    assert_eq!(mango_account_0.perps[0].asks_base_lots, 0);
+    assert_eq!(
+        mango_account_0
+            .perp_open_orders
+            .iter()
+            .filter(|oo| oo.is_active())
+            .map(|oo| oo.client_id)
+            .collect_vec(),
+        vec![5]
+    );
+
+    Ok(())
+}
+
 async fn assert_no_perp_orders(solana: &SolanaCookie, account_0: Pubkey) {
     let mango_account_0 = solana.get_account::<MangoAccount>(account_0).await;
 
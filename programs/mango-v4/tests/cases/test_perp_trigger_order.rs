@@ -0,0 +1,223 @@
+use super::*;
+
+#[tokio::test]
+async fn test_perp_trigger_order_basic() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let solana = &context.solana.clone();
+
+    let admin = TestKeypair::new();
+    let owner = context.users[0].key;
+    let payer = context.users[1].key;
+    let mints = &context.mints[0..2];
+
+    //
+    // SETUP: Create a group and accounts
+    //
+
+    let GroupWithTokens { group, tokens, .. } = GroupWithTokensConfig {
+        admin,
+        payer,
+        mints: mints.to_vec(),
+        ..GroupWithTokensConfig::default()
+    }
+    .create(solana)
+    .await;
+
+    let deposit_amount = 10000;
+    let account_0 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        0,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let account_1 = create_funded_account(
+        &solana,
+        group,
+        owner,
+        1,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+    let caller = create_funded_account(
+        &solana,
+        group,
+        owner,
+        2,
+        &context.users[1],
+        mints,
+        deposit_amount,
+        0,
+    )
+    .await;
+
+    let mango_v4::accounts::PerpCreateMarket { perp_market, .. } = send_tx(
+        solana,
+        PerpCreateMarketInstruction {
+            group,
+            admin,
+            payer,
+            perp_market_index: 0,
+            quote_lot_size: 10,
+            base_lot_size: 100,
+            maint_base_asset_weight: 0.975,
+            init_base_asset_weight: 0.95,
+            maint_base_liab_weight: 1.025,
+            init_base_liab_weight: 1.05,
+            base_liquidation_fee: 0.012,
+            maker_fee: 0.0000,
+            taker_fee: 0.0000,
+            settle_pnl_limit_factor: -1.0,
+            settle_pnl_limit_window_size_ts: 24 * 60 * 60,
+            ..PerpCreateMarketInstruction::with_new_book_and_queue(&solana, &tokens[1]).await
+        },
+    )
+    .await
+    .unwrap();
+
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 1000.0).await;
+
+    let trigger_ix = PerpTriggerOrderCreateInstruction {
+        account: account_0,
+        owner,
+        perp_market,
+        side: Side::Bid,
+        trigger_condition: PerpTriggerCondition::PriceAbove,
+        trigger_price: 1100.0,
+        max_base_lots: 1,
+        max_slippage: 0.05,
+        reduce_only: false,
+        expiry_timestamp: u64::MAX,
+    };
+
+    //
+    // TEST: Trying to add a trigger order on an account without space will fail
+    //
+    assert!(send_tx(solana, trigger_ix.clone()).await.is_err());
+
+    //
+    // TEST: Extending an account to have space for trigger orders works
+    //
+    send_tx(
+        solana,
+        AccountExpandInstruction {
+            account_num: 0,
+            token_count: 8,
+            serum3_count: 4,
+            perp_count: 4,
+            perp_oo_count: 16,
+            token_conditional_swap_count: 0,
+            openbook_v2_count: 0,
+            perp_trigger_order_count: 2,
+            fixed_term_loan_count: 0,
+            group,
+            owner,
+            payer,
+        },
+    )
+    .await
+    .unwrap();
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.header.perp_trigger_order_count, 2);
+
+    //
+    // TEST: Create and cancel a trigger order
+    //
+    send_tx(solana, trigger_ix.clone()).await.unwrap();
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_trigger_orders().count(), 1);
+    let order = account_data.perp_trigger_order_by_index(0).unwrap().clone();
+    assert_eq!(order.id, 0);
+    assert_eq!(order.trigger_price, 1100.0);
+
+    send_tx(
+        solana,
+        PerpTriggerOrderCancelInstruction {
+            account: account_0,
+            owner,
+            index: 0,
+            id: 0,
+        },
+    )
+    .await
+    .unwrap();
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_trigger_orders().count(), 0);
+
+    //
+    // TEST: Execution fails while the trigger price isn't reached
+    //
+    send_tx(solana, trigger_ix.clone()).await.unwrap();
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.perp_trigger_order_by_index(0).unwrap().id, 1);
+
+    let execute_ix = PerpTriggerOrderExecuteInstruction {
+        account: account_0,
+        caller,
+        caller_owner: owner,
+        index: 0,
+    };
+    let result = send_tx(solana, execute_ix.clone()).await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpTriggerOrderPriceNotReached.into(),
+        "not triggered".to_string(),
+    );
+
+    set_perp_stub_oracle_price(solana, group, perp_market, &tokens[1], admin, 1150.0).await;
+
+    //
+    // TEST: Without liquidity on the book, execution fails and keeps the trigger order
+    //
+    let result = send_tx(solana, execute_ix.clone()).await;
+    assert_mango_error(
+        &result,
+        MangoError::PerpTriggerOrderNotFilled.into(),
+        "not filled".to_string(),
+    );
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_trigger_orders().count(), 1);
+
+    //
+    // TEST: Once triggered, execution takes liquidity from the book and pays the caller
+    //
+    let perp_market_data = solana.get_account::<PerpMarket>(perp_market).await;
+    send_tx(
+        solana,
+        PerpPlaceOrderInstruction {
+            account: account_1,
+            perp_market,
+            owner,
+            side: Side::Ask,
+            price_lots: perp_market_data.native_price_to_lot(I80F48::from(1160)),
+            max_base_lots: 2,
+            client_order_id: 5,
+            ..PerpPlaceOrderInstruction::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let caller_quote_before = account_position(solana, caller, tokens[0].bank).await;
+    send_tx(solana, execute_ix.clone()).await.unwrap();
+
+    let account_data = get_mango_account(solana, account_0).await;
+    assert_eq!(account_data.active_perp_trigger_orders().count(), 0);
+    assert_eq!(
+        account_data.perp_position(0).unwrap().base_position_lots(),
+        1
+    );
+    assert_eq!(
+        account_position(solana, caller, tokens[0].bank).await,
+        caller_quote_before + PERP_TRIGGER_ORDER_EXECUTE_INCENTIVE as i64
+    );
+
+    Ok(())
+}
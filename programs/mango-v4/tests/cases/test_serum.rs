@@ -978,6 +978,124 @@ async fn test_serum_reduce_only_borrows() -> Result<(), TransportError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_serum_borrow_limits() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(150_000);
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    //
+    // SETUP: Create a group, accounts, market etc
+    //
+    let deposit_amount = 1000;
+    let CommonSetup {
+        group_with_tokens,
+        base_token,
+        mut order_placer,
+        ..
+    } = common_setup(&context, deposit_amount).await;
+
+    send_tx(
+        solana,
+        TokenEdit {
+            group: group_with_tokens.group,
+            admin: group_with_tokens.admin,
+            mint: base_token.mint.pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                account_borrow_limit_quote_opt: Some(100),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Borrows created by placing orders count towards the account borrow limit
+    //
+    order_placer.try_ask(1.0, 1050).await.unwrap();
+
+    let err = order_placer.try_ask(1.0, 100).await;
+    assert_mango_error(
+        &err,
+        MangoError::AccountBorrowLimit.into(),
+        "serum borrow over the account limit".into(),
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_serum_isolated_pools_borrow() -> Result<(), TransportError> {
+    let mut test_builder = TestContextBuilder::new();
+    test_builder.test().set_compute_max_units(150_000);
+    let context = test_builder.start_default().await;
+    let solana = &context.solana.clone();
+
+    //
+    // SETUP: Create a group, accounts, market etc
+    //
+    let deposit_amount = 1000;
+    let CommonSetup {
+        group_with_tokens,
+        quote_token,
+        base_token,
+        mut order_placer,
+        ..
+    } = common_setup(&context, deposit_amount).await;
+    let tokens = &group_with_tokens.tokens;
+
+    // token 2 is isolated and may only be combined with the market's quote token
+    send_tx(
+        solana,
+        TokenEdit {
+            group: group_with_tokens.group,
+            admin: group_with_tokens.admin,
+            mint: tokens[2].mint.pubkey,
+            fallback_oracle: Pubkey::default(),
+            options: mango_v4::instruction::TokenEdit {
+                isolated_opt: Some(true),
+                isolated_quote_token_indexes_opt: Some(vec![quote_token.index]),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    // leave only the isolated token and its quote token on the account
+    send_tx(
+        solana,
+        TokenWithdrawInstruction {
+            amount: u64::MAX,
+            allow_borrow: false,
+            account: order_placer.account,
+            owner: order_placer.owner,
+            token_account: context.users[1].token_accounts[base_token.mint.index],
+            bank_index: 0,
+        },
+    )
+    .await
+    .unwrap();
+
+    //
+    // TEST: Borrowing the non-quote base token through an ask fails
+    //
+    let err = order_placer.try_ask(1.0, 100).await;
+    assert_mango_error(
+        &err,
+        MangoError::IsolatedTokenCombination.into(),
+        "serum borrow of a non-quote token".into(),
+    );
+
+    // Paying with the quote token is fine, the order doesn't fill
+    order_placer.try_bid(0.5, 100, false).await.unwrap();
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_serum_reduce_only_deposits1() -> Result<(), TransportError> {
     let mut test_builder = TestContextBuilder::new();
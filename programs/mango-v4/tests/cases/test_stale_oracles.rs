@@ -494,12 +494,43 @@ async fn test_clmm_fallback_oracle() -> Result<(), TransportError> {
     .result
     .is_err());
 
-    // Finally send txn with a fallback oracle and pyth USD feed
+    // The fixture pool has little active liquidity, so its price fails the confidence check
     let pyth_usd_oracle_meta = AccountMeta {
         pubkey: pyth_usd_oracle,
         is_writable: false,
         is_signer: false,
     };
+    assert!(send_tx_with_extra_accounts(
+        solana,
+        token_withdraw_ix.clone(),
+        vec![fallback_oracle_meta.clone(), pyth_usd_oracle_meta.clone()]
+    )
+    .await
+    .unwrap()
+    .result
+    .is_err());
+
+    // Widen the conf filter enough to accept the thin pool, but not the stub oracle
+    send_tx(
+        solana,
+        TokenEdit {
+            group,
+            admin,
+            mint: mints[2].pubkey,
+            fallback_oracle,
+            options: mango_v4::instruction::TokenEdit {
+                oracle_config_opt: Some(mango_v4::state::OracleConfigParams {
+                    conf_filter: 1000.0,
+                    max_staleness_slots: None,
+                }),
+                ..token_edit_instruction_default()
+            },
+        },
+    )
+    .await
+    .unwrap();
+
+    // Finally send txn with a fallback oracle and pyth USD feed
     send_tx_with_extra_accounts(
         solana,
         token_withdraw_ix,
@@ -116,6 +116,9 @@ async fn test_token_conditional_swap_basic() -> Result<(), TransportError> {
             perp_count: 4,
             perp_oo_count: 16,
             token_conditional_swap_count: 2,
+            openbook_v2_count: 0,
+            perp_trigger_order_count: 0,
+            fixed_term_loan_count: 0,
             group,
             owner,
             payer,
@@ -412,6 +415,9 @@ async fn test_token_conditional_swap_linear_auction() -> Result<(), TransportErr
             perp_count: 4,
             perp_oo_count: 16,
             token_conditional_swap_count: 2,
+            openbook_v2_count: 0,
+            perp_trigger_order_count: 0,
+            fixed_term_loan_count: 0,
             group,
             owner,
             payer,
@@ -673,6 +679,9 @@ async fn test_token_conditional_swap_premium_auction() -> Result<(), TransportEr
             perp_count: 4,
             perp_oo_count: 16,
             token_conditional_swap_count: 2,
+            openbook_v2_count: 0,
+            perp_trigger_order_count: 0,
+            fixed_term_loan_count: 0,
             group,
             owner,
             payer,
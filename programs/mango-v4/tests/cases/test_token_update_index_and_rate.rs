@@ -107,7 +107,7 @@ async fn test_token_rates_migrate() -> Result<(), TransportError> {
     let payer = context.users[1].key;
     let mints = &context.mints[0..1];
 
-    let mango_setup::GroupWithTokens { tokens, .. } = mango_setup::GroupWithTokensConfig {
+    let mango_setup::GroupWithTokens { group, tokens, .. } = mango_setup::GroupWithTokensConfig {
         admin,
         payer,
         mints: mints.to_vec(),
@@ -117,6 +117,19 @@ async fn test_token_rates_migrate() -> Result<(), TransportError> {
     .create(solana)
     .await;
 
+    let rate_history = send_tx(
+        solana,
+        TokenRateHistoryCreateInstruction {
+            group,
+            mint_info: tokens[0].mint_info,
+            token_index: tokens[0].index,
+            payer,
+        },
+    )
+    .await
+    .unwrap()
+    .rate_history;
+
     let start_time = solana.clock_timestamp().await;
 
     // Change the bank to have the old on-chain state without curveScaling
@@ -154,5 +167,32 @@ async fn test_token_rates_migrate() -> Result<(), TransportError> {
         0.0001
     ));
 
+    // The hourly update writes a rate history entry
+    let history = solana
+        .get_account_boxed::<TokenRateHistory>(rate_history)
+        .await
+        .history;
+    assert_eq!(history.len(), 1);
+    let entry = history.latest().unwrap();
+    assert_eq!(entry.timestamp, start_time + 3601);
+    assert_eq!(entry.deposit_index, bank_after.deposit_index);
+    assert_eq!(entry.borrow_index, bank_after.borrow_index);
+
+    // Updates within the hour don't
+    solana.set_clock_timestamp(start_time + 3700).await;
+    send_tx(
+        solana,
+        TokenUpdateIndexAndRateInstruction {
+            mint_info: tokens[0].mint_info,
+        },
+    )
+    .await
+    .unwrap();
+    let history = solana
+        .get_account_boxed::<TokenRateHistory>(rate_history)
+        .await
+        .history;
+    assert_eq!(history.len(), 1);
+
     Ok(())
 }
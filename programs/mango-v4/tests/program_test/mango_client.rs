@@ -7,7 +7,7 @@ use anchor_spl::token::{Token, TokenAccount};
 use fixed::types::I80F48;
 use itertools::Itertools;
 use mango_v4::accounts_ix::{
-    InterestRateParams, Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side,
+    InterestRateParams, PerpBatchOrderParams, Serum3OrderType, Serum3SelfTradeBehavior, Serum3Side,
 };
 use mango_v4::state::{MangoAccount, MangoAccountValue};
 use solana_program::instruction::Instruction;
@@ -290,11 +290,14 @@ async fn derive_health_check_remaining_account_metas(
     // figure out all the banks/oracles that need to be passed for the health check
     let mut banks = vec![];
     let mut oracles = vec![];
+    let mut median_oracles = vec![];
     for position in adjusted_account.active_token_positions() {
         let mint_info =
             get_mint_info_by_token_index(account_loader, account, position.token_index).await;
         banks.push(mint_info.first_bank());
         oracles.push(mint_info.oracle);
+        let bank: Bank = account_loader.load(&mint_info.first_bank()).await.unwrap();
+        median_oracles.extend(bank.median_oracles);
     }
 
     let perp_markets = adjusted_account
@@ -306,10 +309,17 @@ async fn derive_health_check_remaining_account_metas(
         .active_perp_positions()
         .map(|perp| get_perp_market_address_by_index(account.fixed.group, perp.market_index))
     {
-        perp_oracles.push(get_oracle_address_from_perp_market_address(account_loader, &perp).await)
+        perp_oracles.push(get_oracle_address_from_perp_market_address(account_loader, &perp).await);
+        let perp_market: PerpMarket = account_loader.load(&perp).await.unwrap();
+        median_oracles.extend(perp_market.median_oracles);
     }
 
     let serum_oos = account.active_serum3_orders().map(|&s| s.open_orders);
+    let openbook_v2_oos = account.active_openbook_v2_orders().map(|&s| s.open_orders);
+    let median_oracles = median_oracles
+        .into_iter()
+        .filter(|key| *key != Pubkey::default())
+        .unique();
 
     let to_account_meta = |pubkey| AccountMeta {
         pubkey,
@@ -328,6 +338,8 @@ async fn derive_health_check_remaining_account_metas(
         .chain(perp_markets.map(to_account_meta))
         .chain(perp_oracles.into_iter().map(to_account_meta))
         .chain(serum_oos.map(to_account_meta))
+        .chain(openbook_v2_oos.map(to_account_meta))
+        .chain(median_oracles.map(to_account_meta))
         .collect()
 }
 
@@ -376,6 +388,10 @@ async fn derive_liquidation_remaining_account_metas(
         .active_serum3_orders()
         .chain(liqor.active_serum3_orders())
         .map(|&s| s.open_orders);
+    let openbook_v2_oos = liqee
+        .active_openbook_v2_orders()
+        .chain(liqor.active_openbook_v2_orders())
+        .map(|&s| s.open_orders);
 
     let to_account_meta = |pubkey| AccountMeta {
         pubkey,
@@ -394,6 +410,7 @@ async fn derive_liquidation_remaining_account_metas(
         .chain(perp_markets.into_iter().map(to_account_meta))
         .chain(perp_oracles.into_iter().map(to_account_meta))
         .chain(serum_oos.map(to_account_meta))
+        .chain(openbook_v2_oos.map(to_account_meta))
         .collect()
 }
 
@@ -1328,6 +1345,18 @@ pub fn token_edit_instruction_default() -> mango_v4::instruction::TokenEdit {
         platform_liquidation_fee_opt: None,
         disable_asset_liquidation_opt: None,
         collateral_fee_per_day_opt: None,
+        median_oracles_opt: None,
+        median_oracle_quorum_opt: None,
+        isolated_opt: None,
+        isolated_quote_token_indexes_opt: None,
+        borrow_limit_opt: None,
+        account_borrow_limit_quote_opt: None,
+        interest_curve_type_opt: None,
+        pid_kp_opt: None,
+        pid_ki_opt: None,
+        pid_kd_opt: None,
+        fixed_term_rate_premium_opt: None,
+        fixed_term_max_duration_opt: None,
     }
 }
 
@@ -1363,7 +1392,7 @@ impl ClientInstruction for TokenEdit {
             group: self.group,
             admin: self.admin.pubkey(),
             mint_info: mint_info_key,
-            oracle: mint_info.oracle,
+            oracle: self.options.oracle_opt.unwrap_or(mint_info.oracle),
             fallback_oracle: self.fallback_oracle,
         };
 
@@ -1375,6 +1404,18 @@ impl ClientInstruction for TokenEdit {
                 is_signer: false,
                 is_writable: true,
             }));
+        if let Some(median_oracles) = self.options.median_oracles_opt.as_ref() {
+            instruction.accounts.extend(
+                median_oracles
+                    .iter()
+                    .filter(|&&k| k != Pubkey::default())
+                    .map(|&k| AccountMeta {
+                        pubkey: k,
+                        is_signer: false,
+                        is_writable: false,
+                    }),
+            );
+        }
         (accounts, instruction)
     }
 
@@ -1996,6 +2037,7 @@ pub struct AccountCreateInstruction {
     pub perp_count: u8,
     pub perp_oo_count: u8,
     pub token_conditional_swap_count: u8,
+    pub openbook_v2_count: u8,
     pub group: Pubkey,
     pub owner: TestKeypair,
     pub payer: TestKeypair,
@@ -2009,6 +2051,7 @@ impl Default for AccountCreateInstruction {
             perp_count: 4,
             perp_oo_count: 16,
             token_conditional_swap_count: 1,
+            openbook_v2_count: 0,
             group: Default::default(),
             owner: Default::default(),
             payer: Default::default(),
@@ -2017,8 +2060,8 @@ impl Default for AccountCreateInstruction {
 }
 #[async_trait::async_trait(?Send)]
 impl ClientInstruction for AccountCreateInstruction {
-    type Accounts = mango_v4::accounts::AccountCreate;
-    type Instruction = mango_v4::instruction::AccountCreateV2;
+    type Accounts = mango_v4::accounts::AccountCreateV3;
+    type Instruction = mango_v4::instruction::AccountCreateV3;
     async fn to_instruction(
         &self,
         _account_loader: impl ClientAccountLoader + 'async_trait,
@@ -2031,6 +2074,7 @@ impl ClientInstruction for AccountCreateInstruction {
             perp_count: self.perp_count,
             perp_oo_count: self.perp_oo_count,
             token_conditional_swap_count: self.token_conditional_swap_count,
+            openbook_v2_count: self.openbook_v2_count,
             name: "my_mango_account".to_string(),
         };
 
@@ -2073,11 +2117,14 @@ pub struct AccountExpandInstruction {
     pub perp_count: u8,
     pub perp_oo_count: u8,
     pub token_conditional_swap_count: u8,
+    pub openbook_v2_count: u8,
+    pub perp_trigger_order_count: u8,
+    pub fixed_term_loan_count: u8,
 }
 #[async_trait::async_trait(?Send)]
 impl ClientInstruction for AccountExpandInstruction {
     type Accounts = mango_v4::accounts::AccountExpand;
-    type Instruction = mango_v4::instruction::AccountExpandV2;
+    type Instruction = mango_v4::instruction::AccountExpandV5;
     async fn to_instruction(
         &self,
         _account_loader: impl ClientAccountLoader + 'async_trait,
@@ -2089,6 +2136,9 @@ impl ClientInstruction for AccountExpandInstruction {
             perp_count: self.perp_count,
             perp_oo_count: self.perp_oo_count,
             token_conditional_swap_count: self.token_conditional_swap_count,
+            openbook_v2_count: self.openbook_v2_count,
+            perp_trigger_order_count: self.perp_trigger_order_count,
+            fixed_term_loan_count: self.fixed_term_loan_count,
         };
 
         let account = Pubkey::find_program_address(
@@ -3049,260 +3099,1087 @@ impl ClientInstruction for Serum3LiqForceCancelOrdersInstruction {
     }
 }
 
-pub struct TokenForceCloseBorrowsWithTokenInstruction {
-    pub liqee: Pubkey,
-    pub liqor: Pubkey,
-    pub liqor_owner: TestKeypair,
+pub struct OpenbookV2RegisterMarketInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub payer: TestKeypair,
 
-    pub asset_token_index: TokenIndex,
-    pub asset_bank_index: usize,
-    pub liab_token_index: TokenIndex,
-    pub liab_bank_index: usize,
-    pub max_liab_transfer: u64,
+    pub openbook_v2_program: Pubkey,
+    pub openbook_v2_market_external: Pubkey,
+
+    pub base_bank: Pubkey,
+    pub quote_bank: Pubkey,
+
+    pub market_index: OpenbookV2MarketIndex,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenForceCloseBorrowsWithTokenInstruction {
-    type Accounts = mango_v4::accounts::TokenForceCloseBorrowsWithToken;
-    type Instruction = mango_v4::instruction::TokenForceCloseBorrowsWithToken;
+impl ClientInstruction for OpenbookV2RegisterMarketInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2RegisterMarket;
+    type Instruction = mango_v4::instruction::OpenbookV2RegisterMarket;
     async fn to_instruction(
         &self,
-        account_loader: impl ClientAccountLoader + 'async_trait,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
         let instruction = Self::Instruction {
-            asset_token_index: self.asset_token_index,
-            liab_token_index: self.liab_token_index,
-            max_liab_transfer: self.max_liab_transfer,
+            market_index: self.market_index,
+            name: "UUU/usdc".to_string(),
+            oracle_price_band: f32::MAX,
         };
 
-        let liqee = account_loader
-            .load_mango_account(&self.liqee)
-            .await
-            .unwrap();
-        let liqor = account_loader
-            .load_mango_account(&self.liqor)
-            .await
-            .unwrap();
-        let health_check_metas = derive_liquidation_remaining_account_metas(
-            &account_loader,
-            &liqee,
-            &liqor,
-            self.asset_token_index,
-            self.asset_bank_index,
-            self.liab_token_index,
-            self.liab_bank_index,
+        let openbook_v2_market = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Market".as_ref(),
+                self.group.as_ref(),
+                self.openbook_v2_market_external.as_ref(),
+            ],
+            &program_id,
         )
-        .await;
+        .0;
+
+        let index_reservation = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Index".as_ref(),
+                self.group.as_ref(),
+                &self.market_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
 
         let accounts = Self::Accounts {
-            group: liqee.fixed.group,
-            liqee: self.liqee,
-            liqor: self.liqor,
-            liqor_owner: self.liqor_owner.pubkey(),
+            group: self.group,
+            admin: self.admin.pubkey(),
+            openbook_v2_program: self.openbook_v2_program,
+            openbook_v2_market_external: self.openbook_v2_market_external,
+            openbook_v2_market,
+            index_reservation,
+            base_bank: self.base_bank,
+            quote_bank: self.quote_bank,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
         };
 
-        let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        instruction.accounts.extend(health_check_metas.into_iter());
-
+        let instruction = make_instruction(program_id, &accounts, &instruction);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.liqor_owner]
+        vec![self.admin, self.payer]
     }
 }
 
-pub struct TokenLiqWithTokenInstruction {
-    pub liqee: Pubkey,
-    pub liqor: Pubkey,
-    pub liqor_owner: TestKeypair,
+pub fn openbook_v2_edit_market_instruction_default() -> mango_v4::instruction::OpenbookV2EditMarket
+{
+    mango_v4::instruction::OpenbookV2EditMarket {
+        reduce_only_opt: None,
+        force_close_opt: None,
+        name_opt: None,
+        oracle_price_band_opt: None,
+    }
+}
 
-    pub asset_token_index: TokenIndex,
-    pub asset_bank_index: usize,
-    pub liab_token_index: TokenIndex,
-    pub liab_bank_index: usize,
-    pub max_liab_transfer: I80F48,
+pub struct OpenbookV2EditMarketInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub market: Pubkey,
+    pub options: mango_v4::instruction::OpenbookV2EditMarket,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenLiqWithTokenInstruction {
-    type Accounts = mango_v4::accounts::TokenLiqWithToken;
-    type Instruction = mango_v4::instruction::TokenLiqWithToken;
+impl ClientInstruction for OpenbookV2EditMarketInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2EditMarket;
+    type Instruction = mango_v4::instruction::OpenbookV2EditMarket;
     async fn to_instruction(
         &self,
-        account_loader: impl ClientAccountLoader + 'async_trait,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = Self::Instruction {
-            asset_token_index: self.asset_token_index,
-            liab_token_index: self.liab_token_index,
-            max_liab_transfer: self.max_liab_transfer,
-        };
-
-        let liqee = account_loader
-            .load_mango_account(&self.liqee)
-            .await
-            .unwrap();
-        let liqor = account_loader
-            .load_mango_account(&self.liqor)
-            .await
-            .unwrap();
-        let health_check_metas = derive_liquidation_remaining_account_metas(
-            &account_loader,
-            &liqee,
-            &liqor,
-            self.asset_token_index,
-            self.asset_bank_index,
-            self.liab_token_index,
-            self.liab_bank_index,
-        )
-        .await;
 
         let accounts = Self::Accounts {
-            group: liqee.fixed.group,
-            liqee: self.liqee,
-            liqor: self.liqor,
-            liqor_owner: self.liqor_owner.pubkey(),
+            group: self.group,
+            admin: self.admin.pubkey(),
+            market: self.market,
         };
 
-        let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        instruction.accounts.extend(health_check_metas.into_iter());
-
+        let instruction = make_instruction(program_id, &accounts, &self.options);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.liqor_owner]
+        vec![self.admin]
     }
 }
 
-pub struct TokenLiqBankruptcyInstruction {
-    pub liqee: Pubkey,
-    pub liqor: Pubkey,
-    pub liqor_owner: TestKeypair,
-
-    pub max_liab_transfer: I80F48,
-    pub liab_mint_info: Pubkey,
+pub struct OpenbookV2DeregisterMarketInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub openbook_v2_market_external: Pubkey,
+    pub sol_destination: Pubkey,
 }
 #[async_trait::async_trait(?Send)]
-impl ClientInstruction for TokenLiqBankruptcyInstruction {
-    type Accounts = mango_v4::accounts::TokenLiqBankruptcy;
-    type Instruction = mango_v4::instruction::TokenLiqBankruptcy;
+impl ClientInstruction for OpenbookV2DeregisterMarketInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2DeregisterMarket;
+    type Instruction = mango_v4::instruction::OpenbookV2DeregisterMarket;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = Self::Instruction {
-            max_liab_transfer: self.max_liab_transfer,
-        };
-
-        let liab_mint_info: MintInfo = account_loader.load(&self.liab_mint_info).await.unwrap();
-        let liqee = account_loader
-            .load_mango_account(&self.liqee)
-            .await
-            .unwrap();
-        let liqor = account_loader
-            .load_mango_account(&self.liqor)
-            .await
-            .unwrap();
-        let health_check_metas = derive_liquidation_remaining_account_metas(
-            &account_loader,
-            &liqee,
-            &liqor,
-            QUOTE_TOKEN_INDEX,
-            0,
-            liab_mint_info.token_index,
-            0,
-        )
-        .await;
-
-        let group_key = liqee.fixed.group;
-        let group: Group = account_loader.load(&group_key).await.unwrap();
+        let instruction = Self::Instruction {};
 
-        let quote_mint_info = Pubkey::find_program_address(
+        let openbook_v2_market = Pubkey::find_program_address(
             &[
-                b"MintInfo".as_ref(),
-                liqee.fixed.group.as_ref(),
-                group.insurance_mint.as_ref(),
+                b"OpenbookV2Market".as_ref(),
+                self.group.as_ref(),
+                self.openbook_v2_market_external.as_ref(),
             ],
             &program_id,
         )
         .0;
-        let quote_mint_info: MintInfo = account_loader.load(&quote_mint_info).await.unwrap();
+        let openbook_v2_market_data: OpenbookV2Market =
+            account_loader.load(&openbook_v2_market).await.unwrap();
 
-        let insurance_vault = Pubkey::find_program_address(
-            &[b"InsuranceVault".as_ref(), group_key.as_ref()],
+        let index_reservation = Pubkey::find_program_address(
+            &[
+                b"OpenbookV2Index".as_ref(),
+                self.group.as_ref(),
+                &openbook_v2_market_data.market_index.to_le_bytes(),
+            ],
             &program_id,
         )
         .0;
 
         let accounts = Self::Accounts {
-            group: group_key,
-            liqee: self.liqee,
-            liqor: self.liqor,
-            liqor_owner: self.liqor_owner.pubkey(),
-            liab_mint_info: self.liab_mint_info,
-            quote_vault: quote_mint_info.first_vault(),
-            insurance_vault,
+            group: self.group,
+            admin: self.admin.pubkey(),
+            openbook_v2_market,
+            index_reservation,
+            sol_destination: self.sol_destination,
             token_program: Token::id(),
         };
 
-        let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        let mut bank_ams = liab_mint_info
-            .banks()
-            .iter()
-            .map(|bank| AccountMeta {
-                pubkey: *bank,
-                is_signer: false,
-                is_writable: true,
-            })
-            .collect::<Vec<_>>();
-        instruction.accounts.append(&mut bank_ams);
-        instruction.accounts.extend(health_check_metas.into_iter());
-
+        let instruction = make_instruction(program_id, &accounts, &instruction);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![self.liqor_owner]
+        vec![self.admin]
     }
 }
 
-#[derive(Default)]
-pub struct PerpCreateMarketInstruction {
-    pub group: Pubkey,
-    pub admin: TestKeypair,
-    pub oracle: Pubkey,
-    pub bids: Pubkey,
-    pub asks: Pubkey,
-    pub event_queue: Pubkey,
+pub struct OpenbookV2CreateOpenOrdersInstruction {
+    pub account: Pubkey,
+    pub openbook_v2_market: Pubkey,
+    pub account_num: u32,
+    pub owner: TestKeypair,
     pub payer: TestKeypair,
-    pub settle_token_index: TokenIndex,
-    pub perp_market_index: PerpMarketIndex,
-    pub base_decimals: u8,
-    pub quote_lot_size: i64,
-    pub base_lot_size: i64,
-    pub maint_base_asset_weight: f32,
-    pub init_base_asset_weight: f32,
-    pub maint_base_liab_weight: f32,
-    pub init_base_liab_weight: f32,
-    pub maint_overall_asset_weight: f32,
-    pub init_overall_asset_weight: f32,
-    pub base_liquidation_fee: f32,
-    pub positive_pnl_liquidation_fee: f32,
-    pub maker_fee: f32,
-    pub taker_fee: f32,
-    pub group_insurance_fund: bool,
-    pub fee_penalty: f32,
-    pub settle_fee_flat: f32,
-    pub settle_fee_amount_threshold: f32,
-    pub settle_fee_fraction_low_health: f32,
-    pub settle_pnl_limit_factor: f32,
-    pub settle_pnl_limit_window_size_ts: u64,
-    pub platform_liquidation_fee: f32,
 }
-impl PerpCreateMarketInstruction {
-    pub async fn with_new_book_and_queue(
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2CreateOpenOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CreateOpenOrders;
+    type Instruction = mango_v4::instruction::OpenbookV2CreateOpenOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            account_num: self.account_num,
+        };
+
+        let account: MangoAccount = account_loader.load(&self.account).await.unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = Pubkey::find_program_address(
+            &[
+                b"OpenOrders".as_ref(),
+                account.group.as_ref(),
+                openbook_v2_market.openbook_v2_market_external.as_ref(),
+                &self.account_num.to_le_bytes(),
+            ],
+            &openbook_v2_market.openbook_v2_program,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: account.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            open_orders,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
+            rent: sysvar::rent::Rent::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner, self.payer]
+    }
+}
+
+pub struct OpenbookV2CloseOpenOrdersInstruction {
+    pub account: Pubkey,
+    pub openbook_v2_market: Pubkey,
+    pub owner: TestKeypair,
+    pub sol_destination: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2CloseOpenOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CloseOpenOrders;
+    type Instruction = mango_v4::instruction::OpenbookV2CloseOpenOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {};
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            open_orders,
+            sol_destination: self.sol_destination,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2PlaceOrderInstruction {
+    pub side: openbook_v2::state::Side,
+    pub limit_price: u64,
+    pub max_base_qty: u64,
+    pub max_native_quote_qty_including_fees: u64,
+    pub self_trade_behavior: openbook_v2::state::SelfTradeBehavior,
+    pub order_type: openbook_v2::state::PlaceOrderType,
+    pub client_order_id: u64,
+    pub limit: u16,
+
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+
+    pub openbook_v2_market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2PlaceOrderInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2PlaceOrder;
+    type Instruction = mango_v4::instruction::OpenbookV2PlaceOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side as u8,
+            limit_price: self.limit_price,
+            max_base_qty: self.max_base_qty,
+            max_native_quote_qty_including_fees: self.max_native_quote_qty_including_fees,
+            self_trade_behavior: self.self_trade_behavior as u8,
+            order_type: self.order_type as u8,
+            client_order_id: self.client_order_id,
+            limit: self.limit,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let quote_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.quote_token_index,
+        )
+        .await;
+        let base_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.base_token_index,
+        )
+        .await;
+
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let mut health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let (payer_info, receiver_info) = &match self.side {
+            openbook_v2::state::Side::Bid => (&quote_info, &base_info),
+            openbook_v2::state::Side::Ask => (&base_info, &quote_info),
+        };
+
+        let receiver_active_index = account
+            .active_token_positions()
+            .position(|tp| tp.token_index == receiver_info.token_index)
+            .unwrap();
+        health_check_metas[receiver_active_index].is_writable = true;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            payer_bank: payer_info.first_bank(),
+            payer_vault: payer_info.first_vault(),
+            payer_oracle: payer_info.oracle,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+            event_heap: market_external.event_heap,
+            market_base_vault: market_external.market_base_vault,
+            market_quote_vault: market_external.market_quote_vault,
+            market_vault_signer: market_external.market_authority,
+            token_program: Token::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2CancelOrderInstruction {
+    pub side: openbook_v2::state::Side,
+    pub order_id: u128,
+
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+
+    pub openbook_v2_market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2CancelOrderInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CancelOrder;
+    type Instruction = mango_v4::instruction::OpenbookV2CancelOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side as u8,
+            order_id: self.order_id,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2CancelAllOrdersInstruction {
+    pub limit: u8,
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub openbook_v2_market: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2CancelAllOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2CancelOrder;
+    type Instruction = mango_v4::instruction::OpenbookV2CancelAllOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction { limit: self.limit };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2SettleFundsInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+
+    pub openbook_v2_market: Pubkey,
+    pub fees_to_dao: bool,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2SettleFundsInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2SettleFunds;
+    type Instruction = mango_v4::instruction::OpenbookV2SettleFunds;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            fees_to_dao: self.fees_to_dao,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let quote_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.quote_token_index,
+        )
+        .await;
+        let base_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.base_token_index,
+        )
+        .await;
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            market_base_vault: market_external.market_base_vault,
+            market_quote_vault: market_external.market_quote_vault,
+            market_vault_signer: market_external.market_authority,
+            quote_bank: quote_info.first_bank(),
+            quote_vault: quote_info.first_vault(),
+            base_bank: base_info.first_bank(),
+            base_vault: base_info.first_vault(),
+            quote_oracle: quote_info.oracle,
+            base_oracle: base_info.oracle,
+            token_program: Token::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct OpenbookV2LiqForceCancelOrdersInstruction {
+    pub account: Pubkey,
+    pub openbook_v2_market: Pubkey,
+    pub limit: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for OpenbookV2LiqForceCancelOrdersInstruction {
+    type Accounts = mango_v4::accounts::OpenbookV2LiqForceCancelOrders;
+    type Instruction = mango_v4::instruction::OpenbookV2LiqForceCancelOrders;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction { limit: self.limit };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let openbook_v2_market: OpenbookV2Market =
+            account_loader.load(&self.openbook_v2_market).await.unwrap();
+        let open_orders = account
+            .openbook_v2_orders(openbook_v2_market.market_index)
+            .unwrap()
+            .open_orders;
+        let quote_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.quote_token_index,
+        )
+        .await;
+        let base_info = get_mint_info_by_token_index(
+            &account_loader,
+            &account,
+            openbook_v2_market.base_token_index,
+        )
+        .await;
+        let market_external: openbook_v2::state::Market = account_loader
+            .load(&openbook_v2_market.openbook_v2_market_external)
+            .await
+            .unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            open_orders,
+            openbook_v2_market: self.openbook_v2_market,
+            openbook_v2_program: openbook_v2_market.openbook_v2_program,
+            openbook_v2_market_external: openbook_v2_market.openbook_v2_market_external,
+            bids: market_external.bids,
+            asks: market_external.asks,
+            event_heap: market_external.event_heap,
+            market_base_vault: market_external.market_base_vault,
+            market_quote_vault: market_external.market_quote_vault,
+            market_vault_signer: market_external.market_authority,
+            quote_bank: quote_info.first_bank(),
+            quote_vault: quote_info.first_vault(),
+            base_bank: base_info.first_bank(),
+            base_vault: base_info.first_vault(),
+            token_program: Token::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
+pub struct TokenForceCloseBorrowsWithTokenInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+
+    pub asset_token_index: TokenIndex,
+    pub asset_bank_index: usize,
+    pub liab_token_index: TokenIndex,
+    pub liab_bank_index: usize,
+    pub max_liab_transfer: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenForceCloseBorrowsWithTokenInstruction {
+    type Accounts = mango_v4::accounts::TokenForceCloseBorrowsWithToken;
+    type Instruction = mango_v4::instruction::TokenForceCloseBorrowsWithToken;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            asset_token_index: self.asset_token_index,
+            liab_token_index: self.liab_token_index,
+            max_liab_transfer: self.max_liab_transfer,
+        };
+
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            self.asset_token_index,
+            self.asset_bank_index,
+            self.liab_token_index,
+            self.liab_bank_index,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: liqee.fixed.group,
+            liqee: self.liqee,
+            liqor: self.liqor,
+            liqor_owner: self.liqor_owner.pubkey(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.liqor_owner]
+    }
+}
+
+pub struct TokenLiqWithTokenInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+
+    pub asset_token_index: TokenIndex,
+    pub asset_bank_index: usize,
+    pub liab_token_index: TokenIndex,
+    pub liab_bank_index: usize,
+    pub max_liab_transfer: I80F48,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenLiqWithTokenInstruction {
+    type Accounts = mango_v4::accounts::TokenLiqWithToken;
+    type Instruction = mango_v4::instruction::TokenLiqWithToken;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            asset_token_index: self.asset_token_index,
+            liab_token_index: self.liab_token_index,
+            max_liab_transfer: self.max_liab_transfer,
+        };
+
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            self.asset_token_index,
+            self.asset_bank_index,
+            self.liab_token_index,
+            self.liab_bank_index,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: liqee.fixed.group,
+            liqee: self.liqee,
+            liqor: self.liqor,
+            liqor_owner: self.liqor_owner.pubkey(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.liqor_owner]
+    }
+}
+
+pub struct TokenLiqBankruptcyInstruction {
+    pub liqee: Pubkey,
+    pub liqor: Pubkey,
+    pub liqor_owner: TestKeypair,
+
+    pub max_liab_transfer: I80F48,
+    pub liab_mint_info: Pubkey,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenLiqBankruptcyInstruction {
+    type Accounts = mango_v4::accounts::TokenLiqBankruptcy;
+    type Instruction = mango_v4::instruction::TokenLiqBankruptcy;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            max_liab_transfer: self.max_liab_transfer,
+        };
+
+        let liab_mint_info: MintInfo = account_loader.load(&self.liab_mint_info).await.unwrap();
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let liqor = account_loader
+            .load_mango_account(&self.liqor)
+            .await
+            .unwrap();
+        let health_check_metas = derive_liquidation_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            &liqor,
+            QUOTE_TOKEN_INDEX,
+            0,
+            liab_mint_info.token_index,
+            0,
+        )
+        .await;
+
+        let group_key = liqee.fixed.group;
+        let group: Group = account_loader.load(&group_key).await.unwrap();
+
+        let quote_mint_info = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                liqee.fixed.group.as_ref(),
+                group.insurance_mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let quote_mint_info: MintInfo = account_loader.load(&quote_mint_info).await.unwrap();
+
+        let insurance_vault = Pubkey::find_program_address(
+            &[b"InsuranceVault".as_ref(), group_key.as_ref()],
+            &program_id,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: group_key,
+            liqee: self.liqee,
+            liqor: self.liqor,
+            liqor_owner: self.liqor_owner.pubkey(),
+            liab_mint_info: self.liab_mint_info,
+            quote_vault: quote_mint_info.first_vault(),
+            insurance_vault,
+            token_program: Token::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        let mut bank_ams = liab_mint_info
+            .banks()
+            .iter()
+            .map(|bank| AccountMeta {
+                pubkey: *bank,
+                is_signer: false,
+                is_writable: true,
+            })
+            .collect::<Vec<_>>();
+        instruction.accounts.append(&mut bank_ams);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.liqor_owner]
+    }
+}
+
+pub struct TokenInsuranceFundCreateInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub mint: Pubkey,
+    pub token_index: TokenIndex,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenInsuranceFundCreateInstruction {
+    type Accounts = mango_v4::accounts::TokenInsuranceFundCreate;
+    type Instruction = mango_v4::instruction::TokenInsuranceFundCreate;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            token_index: self.token_index,
+        };
+
+        let mint_info = Pubkey::find_program_address(
+            &[
+                b"MintInfo".as_ref(),
+                self.group.as_ref(),
+                self.mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let insurance_fund = Pubkey::find_program_address(
+            &[
+                b"TokenInsuranceFund".as_ref(),
+                self.group.as_ref(),
+                &self.token_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+        let insurance_vault = Pubkey::find_program_address(
+            &[
+                b"TokenInsuranceVault".as_ref(),
+                self.group.as_ref(),
+                &self.token_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            mint_info,
+            mint: self.mint,
+            insurance_fund,
+            insurance_vault,
+            payer: self.payer.pubkey(),
+            token_program: Token::id(),
+            system_program: System::id(),
+            rent: sysvar::rent::Rent::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin, self.payer]
+    }
+}
+
+pub struct TokenInsuranceFundWithdrawInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub insurance_fund: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenInsuranceFundWithdrawInstruction {
+    type Accounts = mango_v4::accounts::TokenInsuranceFundWithdraw;
+    type Instruction = mango_v4::instruction::TokenInsuranceFundWithdraw;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            amount: self.amount,
+        };
+
+        let insurance_fund: TokenInsuranceFund =
+            account_loader.load(&self.insurance_fund).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            insurance_fund: self.insurance_fund,
+            insurance_vault: insurance_fund.insurance_vault,
+            destination: self.destination,
+            token_program: Token::id(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenInsuranceFundCoverInstruction {
+    pub liqee: Pubkey,
+    pub insurance_fund: Pubkey,
+    pub bank: Pubkey,
+    pub max_cover_amount: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenInsuranceFundCoverInstruction {
+    type Accounts = mango_v4::accounts::TokenInsuranceFundCover;
+    type Instruction = mango_v4::instruction::TokenInsuranceFundCover;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            max_cover_amount: self.max_cover_amount,
+        };
+
+        let liqee = account_loader
+            .load_mango_account(&self.liqee)
+            .await
+            .unwrap();
+        let insurance_fund: TokenInsuranceFund =
+            account_loader.load(&self.insurance_fund).await.unwrap();
+        let bank: Bank = account_loader.load(&self.bank).await.unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &liqee,
+            Some(self.bank),
+            true,
+            None,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: liqee.fixed.group,
+            liqee: self.liqee,
+            insurance_fund: self.insurance_fund,
+            insurance_vault: insurance_fund.insurance_vault,
+            bank: self.bank,
+            vault: bank.vault,
+            token_program: Token::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
+#[derive(Default)]
+pub struct PerpCreateMarketInstruction {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub oracle: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub payer: TestKeypair,
+    pub settle_token_index: TokenIndex,
+    pub perp_market_index: PerpMarketIndex,
+    pub base_decimals: u8,
+    pub quote_lot_size: i64,
+    pub base_lot_size: i64,
+    pub maint_base_asset_weight: f32,
+    pub init_base_asset_weight: f32,
+    pub maint_base_liab_weight: f32,
+    pub init_base_liab_weight: f32,
+    pub maint_overall_asset_weight: f32,
+    pub init_overall_asset_weight: f32,
+    pub base_liquidation_fee: f32,
+    pub positive_pnl_liquidation_fee: f32,
+    pub maker_fee: f32,
+    pub taker_fee: f32,
+    pub group_insurance_fund: bool,
+    pub fee_penalty: f32,
+    pub settle_fee_flat: f32,
+    pub settle_fee_amount_threshold: f32,
+    pub settle_fee_fraction_low_health: f32,
+    pub settle_pnl_limit_factor: f32,
+    pub settle_pnl_limit_window_size_ts: u64,
+    pub platform_liquidation_fee: f32,
+    pub funding_interest_rate: f32,
+    pub funding_dampener: f32,
+}
+impl PerpCreateMarketInstruction {
+    pub async fn with_new_book_and_queue(
         solana: &SolanaCookie,
         base: &super::mango_setup::Token,
     ) -> Self {
@@ -3363,6 +4240,8 @@ impl ClientInstruction for PerpCreateMarketInstruction {
             settle_pnl_limit_window_size_ts: self.settle_pnl_limit_window_size_ts,
             positive_pnl_liquidation_fee: self.positive_pnl_liquidation_fee,
             platform_liquidation_fee: self.platform_liquidation_fee,
+            funding_interest_rate: self.funding_interest_rate,
+            funding_dampener: self.funding_dampener,
         };
 
         let perp_market = Pubkey::find_program_address(
@@ -3429,6 +4308,11 @@ fn perp_edit_instruction_default() -> mango_v4::instruction::PerpEditMarket {
         name_opt: None,
         force_close_opt: None,
         platform_liquidation_fee_opt: None,
+        median_oracles_opt: None,
+        median_oracle_quorum_opt: None,
+        funding_sample_interval_opt: None,
+        funding_interest_rate_opt: None,
+        funding_dampener_opt: None,
     }
 }
 
@@ -3553,6 +4437,55 @@ impl ClientInstruction for PerpMakeReduceOnly {
     }
 }
 
+pub struct PerpSetMedianOracles {
+    pub group: Pubkey,
+    pub admin: TestKeypair,
+    pub perp_market: Pubkey,
+    pub median_oracles: Vec<Pubkey>,
+    pub quorum: u8,
+}
+
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpSetMedianOracles {
+    type Accounts = mango_v4::accounts::PerpEditMarket;
+    type Instruction = mango_v4::instruction::PerpEditMarket;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+
+        let instruction = Self::Instruction {
+            median_oracles_opt: Some(self.median_oracles.clone()),
+            median_oracle_quorum_opt: Some(self.quorum),
+            ..perp_edit_instruction_default()
+        };
+
+        let accounts = Self::Accounts {
+            group: self.group,
+            admin: self.admin.pubkey(),
+            perp_market: self.perp_market,
+            oracle: perp_market.oracle,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction
+            .accounts
+            .extend(self.median_oracles.iter().map(|&k| AccountMeta {
+                pubkey: k,
+                is_signer: false,
+                is_writable: false,
+            }));
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.admin]
+    }
+}
+
 pub struct PerpChangeWeights {
     pub group: Pubkey,
     pub admin: TestKeypair,
@@ -3676,6 +4609,7 @@ pub struct PerpPlaceOrderInstruction {
     pub reduce_only: bool,
     pub client_order_id: u64,
     pub self_trade_behavior: SelfTradeBehavior,
+    pub expiry_slot: u64,
     pub limit: u8,
 }
 impl Default for PerpPlaceOrderInstruction {
@@ -3691,6 +4625,7 @@ impl Default for PerpPlaceOrderInstruction {
             reduce_only: false,
             client_order_id: 0,
             self_trade_behavior: SelfTradeBehavior::DecrementTake,
+            expiry_slot: 0,
             limit: 10,
         }
     }
@@ -3698,7 +4633,7 @@ impl Default for PerpPlaceOrderInstruction {
 #[async_trait::async_trait(?Send)]
 impl ClientInstruction for PerpPlaceOrderInstruction {
     type Accounts = mango_v4::accounts::PerpPlaceOrder;
-    type Instruction = mango_v4::instruction::PerpPlaceOrderV2;
+    type Instruction = mango_v4::instruction::PerpPlaceOrderV3;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
@@ -3714,6 +4649,155 @@ impl ClientInstruction for PerpPlaceOrderInstruction {
             self_trade_behavior: self.self_trade_behavior,
             reduce_only: self.reduce_only,
             expiry_timestamp: 0,
+            expiry_slot: self.expiry_slot,
+            limit: self.limit,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(perp_market.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            perp_market: self.perp_market,
+            bids: perp_market.bids,
+            asks: perp_market.asks,
+            event_queue: perp_market.event_queue,
+            oracle: perp_market.oracle,
+            owner: self.owner.pubkey(),
+        };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct PerpEditOrderInstruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub owner: TestKeypair,
+    pub client_order_id: u64,
+    pub side: Side,
+    pub price_lots: i64,
+    pub max_base_lots: i64,
+    pub max_quote_lots: i64,
+    pub reduce_only: bool,
+    pub self_trade_behavior: SelfTradeBehavior,
+    pub expiry_slot: u64,
+    pub limit: u8,
+}
+impl Default for PerpEditOrderInstruction {
+    fn default() -> Self {
+        Self {
+            account: Pubkey::default(),
+            perp_market: Pubkey::default(),
+            owner: TestKeypair::default(),
+            client_order_id: 0,
+            side: Side::Bid,
+            price_lots: 0,
+            max_base_lots: i64::MAX,
+            max_quote_lots: i64::MAX,
+            reduce_only: false,
+            self_trade_behavior: SelfTradeBehavior::DecrementTake,
+            expiry_slot: 0,
+            limit: 10,
+        }
+    }
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpEditOrderInstruction {
+    type Accounts = mango_v4::accounts::PerpEditOrder;
+    type Instruction = mango_v4::instruction::PerpEditOrder;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            client_order_id: self.client_order_id,
+            side: self.side,
+            price_lots: self.price_lots,
+            max_base_lots: self.max_base_lots,
+            max_quote_lots: self.max_quote_lots,
+            order_type: PlaceOrderType::Limit,
+            self_trade_behavior: self.self_trade_behavior,
+            reduce_only: self.reduce_only,
+            expiry_timestamp: 0,
+            expiry_slot: self.expiry_slot,
+            limit: self.limit,
+        };
+
+        let perp_market: PerpMarket = account_loader.load(&self.perp_market).await.unwrap();
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            Some(perp_market.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            perp_market: self.perp_market,
+            bids: perp_market.bids,
+            asks: perp_market.asks,
+            event_queue: perp_market.event_queue,
+            oracle: perp_market.oracle,
+            owner: self.owner.pubkey(),
+        };
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas);
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+pub struct PerpPlaceOrdersBatchInstruction {
+    pub account: Pubkey,
+    pub perp_market: Pubkey,
+    pub owner: TestKeypair,
+    pub cancel_all: bool,
+    pub orders: Vec<PerpBatchOrderParams>,
+    pub limit: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpPlaceOrdersBatchInstruction {
+    type Accounts = mango_v4::accounts::PerpPlaceOrdersBatch;
+    type Instruction = mango_v4::instruction::PerpPlaceOrdersBatch;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            cancel_all: self.cancel_all,
+            orders: self.orders.clone(),
             limit: self.limit,
         };
 
@@ -3766,7 +4850,7 @@ pub struct PerpPlaceOrderPeggedInstruction {
 #[async_trait::async_trait(?Send)]
 impl ClientInstruction for PerpPlaceOrderPeggedInstruction {
     type Accounts = mango_v4::accounts::PerpPlaceOrder;
-    type Instruction = mango_v4::instruction::PerpPlaceOrderPeggedV2;
+    type Instruction = mango_v4::instruction::PerpPlaceOrderPeggedV3;
     async fn to_instruction(
         &self,
         account_loader: impl ClientAccountLoader + 'async_trait,
@@ -3782,6 +4866,7 @@ impl ClientInstruction for PerpPlaceOrderPeggedInstruction {
             order_type: PlaceOrderType::Limit,
             reduce_only: false,
             expiry_timestamp: 0,
+            expiry_slot: 0,
             self_trade_behavior: SelfTradeBehavior::DecrementTake,
             limit: 10,
             max_oracle_staleness_slots: -1,
@@ -4382,37 +5467,111 @@ impl ClientInstruction for TokenUpdateIndexAndRateInstruction {
     type Instruction = mango_v4::instruction::TokenUpdateIndexAndRate;
     async fn to_instruction(
         &self,
-        loader: impl ClientAccountLoader + 'async_trait,
+        loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {};
+
+        let mint_info: MintInfo = loader.load(&self.mint_info).await.unwrap();
+
+        let accounts = Self::Accounts {
+            group: mint_info.group,
+            mint_info: self.mint_info,
+            oracle: mint_info.oracle,
+            instructions: solana_program::sysvar::instructions::id(),
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        let mut bank_ams = mint_info
+            .banks()
+            .iter()
+            .map(|bank| AccountMeta {
+                pubkey: *bank,
+                is_signer: false,
+                is_writable: true,
+            })
+            .collect::<Vec<_>>();
+        instruction.accounts.append(&mut bank_ams);
+
+        let bank: Bank = loader.load(&mint_info.first_bank()).await.unwrap();
+        instruction.accounts.extend(
+            bank.median_oracles
+                .iter()
+                .filter(|key| **key != Pubkey::default())
+                .map(|&pubkey| AccountMeta {
+                    pubkey,
+                    is_signer: false,
+                    is_writable: false,
+                }),
+        );
+
+        // Skipped by the program if it wasn't created
+        let rate_history = Pubkey::find_program_address(
+            &[
+                b"TokenRateHistory".as_ref(),
+                mint_info.group.as_ref(),
+                &mint_info.token_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
+        instruction.accounts.push(AccountMeta {
+            pubkey: rate_history,
+            is_signer: false,
+            is_writable: true,
+        });
+
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![]
+    }
+}
+
+pub struct TokenRateHistoryCreateInstruction {
+    pub group: Pubkey,
+    pub mint_info: Pubkey,
+    pub token_index: TokenIndex,
+    pub payer: TestKeypair,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for TokenRateHistoryCreateInstruction {
+    type Accounts = mango_v4::accounts::TokenRateHistoryCreate;
+    type Instruction = mango_v4::instruction::TokenRateHistoryCreate;
+    async fn to_instruction(
+        &self,
+        _account_loader: impl ClientAccountLoader + 'async_trait,
     ) -> (Self::Accounts, instruction::Instruction) {
         let program_id = mango_v4::id();
-        let instruction = Self::Instruction {};
+        let instruction = Self::Instruction {
+            token_index: self.token_index,
+        };
 
-        let mint_info: MintInfo = loader.load(&self.mint_info).await.unwrap();
+        let rate_history = Pubkey::find_program_address(
+            &[
+                b"TokenRateHistory".as_ref(),
+                self.group.as_ref(),
+                &self.token_index.to_le_bytes(),
+            ],
+            &program_id,
+        )
+        .0;
 
         let accounts = Self::Accounts {
-            group: mint_info.group,
+            group: self.group,
             mint_info: self.mint_info,
-            oracle: mint_info.oracle,
-            instructions: solana_program::sysvar::instructions::id(),
+            rate_history,
+            payer: self.payer.pubkey(),
+            system_program: System::id(),
         };
 
-        let mut instruction = make_instruction(program_id, &accounts, &instruction);
-        let mut bank_ams = mint_info
-            .banks()
-            .iter()
-            .map(|bank| AccountMeta {
-                pubkey: *bank,
-                is_signer: false,
-                is_writable: true,
-            })
-            .collect::<Vec<_>>();
-        instruction.accounts.append(&mut bank_ams);
-
+        let instruction = make_instruction(program_id, &accounts, &instruction);
         (accounts, instruction)
     }
 
     fn signers(&self) -> Vec<TestKeypair> {
-        vec![]
+        vec![self.payer]
     }
 }
 
@@ -5042,6 +6201,177 @@ impl ClientInstruction for TokenConditionalSwapStartInstruction {
     }
 }
 
+#[derive(Clone)]
+pub struct PerpTriggerOrderCreateInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub perp_market: Pubkey,
+    pub side: Side,
+    pub trigger_condition: PerpTriggerCondition,
+    pub trigger_price: f64,
+    pub max_base_lots: i64,
+    pub max_slippage: f64,
+    pub reduce_only: bool,
+    pub expiry_timestamp: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpTriggerOrderCreateInstruction {
+    type Accounts = mango_v4::accounts::PerpTriggerOrderCreate;
+    type Instruction = mango_v4::instruction::PerpTriggerOrderCreate;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            side: self.side,
+            trigger_condition: self.trigger_condition,
+            trigger_price: self.trigger_price,
+            max_base_lots: self.max_base_lots,
+            max_slippage: self.max_slippage,
+            reduce_only: self.reduce_only,
+            expiry_timestamp: self.expiry_timestamp,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            perp_market: self.perp_market,
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct PerpTriggerOrderCancelInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub index: u8,
+    pub id: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpTriggerOrderCancelInstruction {
+    type Accounts = mango_v4::accounts::PerpTriggerOrderCancel;
+    type Instruction = mango_v4::instruction::PerpTriggerOrderCancel;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            perp_trigger_order_index: self.index,
+            perp_trigger_order_id: self.id,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+        };
+
+        let instruction = make_instruction(program_id, &accounts, &instruction);
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct PerpTriggerOrderExecuteInstruction {
+    pub account: Pubkey,
+    pub caller: Pubkey,
+    pub caller_owner: TestKeypair,
+    pub index: u8,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for PerpTriggerOrderExecuteInstruction {
+    type Accounts = mango_v4::accounts::PerpTriggerOrderExecute;
+    type Instruction = mango_v4::instruction::PerpTriggerOrderExecute;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let trigger_order = account
+            .perp_trigger_order_by_index(self.index.into())
+            .unwrap()
+            .clone();
+
+        let perp_market_pk =
+            get_perp_market_address_by_index(account.fixed.group, trigger_order.perp_market_index);
+        let perp_market: PerpMarket = account_loader.load(&perp_market_pk).await.unwrap();
+        let settle_mint_info =
+            get_mint_info_by_token_index(&account_loader, &account, perp_market.settle_token_index)
+                .await;
+        let settle_bank: Bank = account_loader
+            .load(&settle_mint_info.first_bank())
+            .await
+            .unwrap();
+
+        let instruction = Self::Instruction {
+            perp_trigger_order_index: self.index,
+            perp_trigger_order_id: trigger_order.id,
+            limit: 10,
+        };
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            Some(settle_mint_info.first_bank()),
+            false,
+            Some(trigger_order.perp_market_index),
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            caller: self.caller,
+            caller_authority: self.caller_owner.pubkey(),
+            perp_market: perp_market_pk,
+            bids: perp_market.bids,
+            asks: perp_market.asks,
+            event_queue: perp_market.event_queue,
+            oracle: perp_market.oracle,
+            settle_bank: settle_mint_info.first_bank(),
+            settle_oracle: settle_bank.oracle,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.caller_owner]
+    }
+}
+
 #[derive(Clone)]
 pub struct TokenChargeCollateralFeesInstruction {
     pub account: Pubkey,
@@ -5086,3 +6416,111 @@ impl ClientInstruction for TokenChargeCollateralFeesInstruction {
         vec![]
     }
 }
+
+#[derive(Clone)]
+pub struct FixedTermLoanCreateInstruction {
+    pub account: Pubkey,
+    pub owner: TestKeypair,
+    pub bank: Pubkey,
+    pub amount: u64,
+    pub duration: u64,
+    pub auto_roll: bool,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for FixedTermLoanCreateInstruction {
+    type Accounts = mango_v4::accounts::FixedTermLoanCreate;
+    type Instruction = mango_v4::instruction::FixedTermLoanCreate;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            amount: self.amount,
+            duration: self.duration,
+            auto_roll: self.auto_roll,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            Some(self.bank),
+            false,
+            None,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            authority: self.owner.pubkey(),
+            bank: self.bank,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.owner]
+    }
+}
+
+#[derive(Clone)]
+pub struct FixedTermLoanSettleInstruction {
+    pub account: Pubkey,
+    pub settler: TestKeypair,
+    pub bank: Pubkey,
+    pub index: u8,
+    pub id: u64,
+}
+#[async_trait::async_trait(?Send)]
+impl ClientInstruction for FixedTermLoanSettleInstruction {
+    type Accounts = mango_v4::accounts::FixedTermLoanSettle;
+    type Instruction = mango_v4::instruction::FixedTermLoanSettle;
+    async fn to_instruction(
+        &self,
+        account_loader: impl ClientAccountLoader + 'async_trait,
+    ) -> (Self::Accounts, instruction::Instruction) {
+        let program_id = mango_v4::id();
+        let instruction = Self::Instruction {
+            fixed_term_loan_index: self.index,
+            fixed_term_loan_id: self.id,
+        };
+
+        let account = account_loader
+            .load_mango_account(&self.account)
+            .await
+            .unwrap();
+
+        let health_check_metas = derive_health_check_remaining_account_metas(
+            &account_loader,
+            &account,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let accounts = Self::Accounts {
+            group: account.fixed.group,
+            account: self.account,
+            settler: self.settler.pubkey(),
+            bank: self.bank,
+        };
+
+        let mut instruction = make_instruction(program_id, &accounts, &instruction);
+        instruction.accounts.extend(health_check_metas.into_iter());
+        (accounts, instruction)
+    }
+
+    fn signers(&self) -> Vec<TestKeypair> {
+        vec![self.settler]
+    }
+}
@@ -11,6 +11,7 @@ use spl_token::{state::*, *};
 
 pub use cookies::*;
 pub use mango_client::*;
+pub use openbook::*;
 pub use serum::*;
 pub use solana::*;
 pub use utils::*;
@@ -18,6 +19,7 @@ pub use utils::*;
 pub mod cookies;
 pub mod mango_client;
 pub mod mango_setup;
+pub mod openbook;
 pub mod serum;
 pub mod solana;
 pub mod utils;
@@ -188,6 +190,16 @@ impl TestContextBuilder {
         serum_program_id
     }
 
+    pub fn add_openbook_v2_program(&mut self) -> Pubkey {
+        let openbook_v2_program_id = openbook_v2::id();
+        self.test.add_program(
+            "openbook_v2",
+            openbook_v2_program_id,
+            processor!(openbook_v2::entry),
+        );
+        openbook_v2_program_id
+    }
+
     pub fn add_margin_trade_program(&mut self) -> MarginTradeCookie {
         let program = Pubkey::from_str("J83w4HKfqxwcq3BEMMkPFSppX3gqekLyLJBexebFVkix").unwrap();
         let token_account = TestKeypair::new();
@@ -222,6 +234,7 @@ impl TestContextBuilder {
         let mints = self.create_mints();
         let users = self.create_users(&mints);
         let serum_program_id = self.add_serum_program();
+        let openbook_v2_program_id = self.add_openbook_v2_program();
 
         let solana = self.start().await;
 
@@ -229,12 +242,17 @@ impl TestContextBuilder {
             solana: solana.clone(),
             program_id: serum_program_id,
         });
+        let openbook_v2 = Arc::new(OpenbookV2Cookie {
+            solana: solana.clone(),
+            program_id: openbook_v2_program_id,
+        });
 
         TestContext {
             solana: solana.clone(),
             mints,
             users,
             serum,
+            openbook_v2,
         }
     }
 
@@ -257,6 +275,7 @@ pub struct TestContext {
     pub mints: Vec<MintCookie>,
     pub users: Vec<UserCookie>,
     pub serum: Arc<SerumCookie>,
+    pub openbook_v2: Arc<OpenbookV2Cookie>,
 }
 
 impl TestContext {
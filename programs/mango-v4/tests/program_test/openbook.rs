@@ -0,0 +1,163 @@
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use super::*;
+
+#[derive(Clone, Debug)]
+pub struct OpenbookV2MarketCookie {
+    pub market: Pubkey,
+    pub market_authority: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_heap: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub base_mint: MintCookie,
+    pub quote_mint: MintCookie,
+}
+
+pub struct OpenbookV2Cookie {
+    pub solana: Arc<solana::SolanaCookie>,
+    pub program_id: Pubkey,
+}
+
+impl OpenbookV2Cookie {
+    fn create_program_account(&self, len: usize) -> (TestKeypair, Instruction) {
+        let key = TestKeypair::new();
+        let rent = self.solana.rent.minimum_balance(len);
+        let create_account_instr = solana_sdk::system_instruction::create_account(
+            &self.solana.context.borrow().payer.pubkey(),
+            &key.pubkey(),
+            rent,
+            len as u64,
+            &self.program_id,
+        );
+        (key, create_account_instr)
+    }
+
+    pub async fn list_spot_market(
+        &self,
+        base_mint: &MintCookie,
+        quote_mint: &MintCookie,
+    ) -> OpenbookV2MarketCookie {
+        let market = TestKeypair::new();
+        let market_authority = Pubkey::find_program_address(
+            &[b"Market".as_ref(), market.pubkey().as_ref()],
+            &self.program_id,
+        )
+        .0;
+
+        let (bids, create_bids) =
+            self.create_program_account(8 + std::mem::size_of::<openbook_v2::state::BookSide>());
+        let (asks, create_asks) =
+            self.create_program_account(8 + std::mem::size_of::<openbook_v2::state::BookSide>());
+        let (event_heap, create_event_heap) =
+            self.create_program_account(8 + std::mem::size_of::<openbook_v2::state::EventHeap>());
+
+        let market_base_vault = spl_associated_token_account::get_associated_token_address(
+            &market_authority,
+            &base_mint.pubkey,
+        );
+        let market_quote_vault = spl_associated_token_account::get_associated_token_address(
+            &market_authority,
+            &quote_mint.pubkey,
+        );
+
+        let payer = self.solana.context.borrow().payer.pubkey();
+        let accounts = openbook_v2::accounts::CreateMarket {
+            market: market.pubkey(),
+            market_authority,
+            bids: bids.pubkey(),
+            asks: asks.pubkey(),
+            event_heap: event_heap.pubkey(),
+            payer,
+            market_base_vault,
+            market_quote_vault,
+            base_mint: base_mint.pubkey,
+            quote_mint: quote_mint.pubkey,
+            system_program: solana_sdk::system_program::id(),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            oracle_a: None,
+            oracle_b: None,
+            collect_fee_admin: payer,
+            open_orders_admin: None,
+            consume_events_admin: None,
+            close_market_admin: None,
+        };
+        let data = openbook_v2::instruction::CreateMarket {
+            name: "UUU/usdc".to_string(),
+            oracle_config: openbook_v2::state::OracleConfigParams {
+                conf_filter: 0.1,
+                max_staleness_slots: None,
+            },
+            quote_lot_size: base_mint.quote_lot as i64,
+            base_lot_size: base_mint.base_lot as i64,
+            maker_fee: 0,
+            taker_fee: 400, // 4 bps
+            time_expiry: 0,
+        };
+        let create_market = Instruction {
+            program_id: self.program_id,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        };
+
+        let instructions = vec![create_bids, create_asks, create_event_heap, create_market];
+        let signers = vec![market, bids, asks, event_heap];
+        self.solana
+            .process_transaction(&instructions, Some(&signers))
+            .await
+            .unwrap();
+
+        OpenbookV2MarketCookie {
+            market: market.pubkey(),
+            market_authority,
+            bids: bids.pubkey(),
+            asks: asks.pubkey(),
+            event_heap: event_heap.pubkey(),
+            market_base_vault,
+            market_quote_vault,
+            base_mint: base_mint.clone(),
+            quote_mint: quote_mint.clone(),
+        }
+    }
+
+    pub async fn consume_events(
+        &self,
+        market_cookie: &OpenbookV2MarketCookie,
+        open_orders: &[Pubkey],
+    ) {
+        let accounts = openbook_v2::accounts::ConsumeEvents {
+            consume_events_admin: None,
+            market: market_cookie.market,
+            event_heap: market_cookie.event_heap,
+        };
+        let mut metas = accounts.to_account_metas(None);
+        metas.extend(open_orders.iter().map(|oo| AccountMeta::new(*oo, false)));
+        let instruction = Instruction {
+            program_id: self.program_id,
+            accounts: metas,
+            data: openbook_v2::instruction::ConsumeEvents { limit: 10 }.data(),
+        };
+        self.solana
+            .process_transaction(&[instruction], None)
+            .await
+            .unwrap();
+    }
+
+    pub async fn load_open_orders(
+        &self,
+        open_orders: Pubkey,
+    ) -> openbook_v2::state::OpenOrdersAccount {
+        let data = self.solana.get_account_data(open_orders).await.unwrap();
+        let mut data_slice: &[u8] = &data;
+        openbook_v2::state::OpenOrdersAccount::try_deserialize(&mut data_slice).unwrap()
+    }
+}
@@ -11,6 +11,9 @@ const SBV1_DEVNET_PID = new PublicKey(
 const SBV1_MAINNET_PID = new PublicKey(
   'DtmE9D2CSB4L5D6A15mraeEjrGMm6auWVzgaD8hK2tZM',
 );
+const SB_ON_DEMAND_MAINNET_PID = new PublicKey(
+  'SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv',
+);
 let sbv2DevnetProgram;
 let sbv2MainnetProgram;
 
@@ -74,7 +77,33 @@ export function parseSwitchboardOracleV1(accountInfo: AccountInfo<Buffer>): {
   const maxResponse = accountInfo.data.readDoubleLE(
     1 + 32 + 4 + 4 + 8 + 8 + 8 + 8,
   );
-  return { price, lastUpdatedSlot, uiDeviation: maxResponse - minResponse };
+  // v1 doesn't store the std-dev, use the largest one possible for the response range
+  return {
+    price,
+    lastUpdatedSlot,
+    uiDeviation: (maxResponse - minResponse) / 2,
+  };
+}
+
+export function parseSwitchboardOnDemandOracle(
+  accountInfo: AccountInfo<Buffer>,
+): { price: number; lastUpdatedSlot: number; uiDeviation: number } {
+  // offset of PullFeedAccountData.result, values are i128 with 18 decimals
+  const resultOffset = 2264;
+  const readI128 = (offset: number): number =>
+    new Big(
+      new BN(accountInfo.data.subarray(offset, offset + 16), 'le')
+        .fromTwos(128)
+        .toString(),
+    )
+      .div(new Big(10).pow(18))
+      .toNumber();
+  const price = readI128(resultOffset);
+  const uiDeviation = readI128(resultOffset + 16);
+  const lastUpdatedSlot = parseInt(
+    accountInfo.data.readBigUInt64LE(resultOffset + 104).toString(),
+  );
+  return { price, lastUpdatedSlot, uiDeviation };
 }
 
 export function switchboardDecimalToBig(sbDecimal: {
@@ -145,6 +174,10 @@ export async function parseSwitchboardOracle(
     return parseSwitchboardOracleV1(accountInfo);
   }
 
+  if (accountInfo.owner.equals(SB_ON_DEMAND_MAINNET_PID)) {
+    return parseSwitchboardOnDemandOracle(accountInfo);
+  }
+
   throw new Error(`Should not be reached!`);
 }
 
@@ -152,6 +185,7 @@ export function isSwitchboardOracle(accountInfo: AccountInfo<Buffer>): boolean {
   if (
     accountInfo.owner.equals(SBV1_DEVNET_PID) ||
     accountInfo.owner.equals(SBV1_MAINNET_PID) ||
+    accountInfo.owner.equals(SB_ON_DEMAND_MAINNET_PID) ||
     accountInfo.owner.equals(SwitchboardProgram.devnetPid) ||
     accountInfo.owner.equals(SwitchboardProgram.mainnetPid)
   ) {
@@ -747,9 +747,7 @@ export class BookSide {
         stack.push(innerNode.children[right], innerNode.children[left]);
       } else if (node.tag === BookSide.LEAF_NODE_TAG) {
         const leafNode = BookSide.toLeafNode(this.client, node.data);
-        const expiryTimestamp = leafNode.timeInForce
-          ? leafNode.timestamp.add(new BN(leafNode.timeInForce))
-          : U64_MAX_BN;
+        const expiryTimestamp = leafNode.expiry();
 
         yield PerpOrder.from(
           this.perpMarket,
@@ -777,9 +775,7 @@ export class BookSide {
         stack.push(innerNode.children[right], innerNode.children[left]);
       } else if (node.tag === BookSide.LEAF_NODE_TAG) {
         const leafNode = BookSide.toLeafNode(this.client, node.data);
-        const expiryTimestamp = leafNode.timeInForce
-          ? leafNode.timestamp.add(new BN(leafNode.timeInForce))
-          : U64_MAX_BN;
+        const expiryTimestamp = leafNode.expiry();
 
         yield PerpOrder.from(
           this.perpMarket,
@@ -873,6 +869,8 @@ export class LeafNode {
     quantity: BN;
     timestamp: BN;
     pegLimit: BN;
+    expiryTimestamp: BN;
+    expirySlot: BN;
   }): LeafNode {
     return new LeafNode(
       obj.ownerSlot,
@@ -883,6 +881,8 @@ export class LeafNode {
       obj.quantity,
       obj.timestamp,
       obj.pegLimit,
+      obj.expiryTimestamp,
+      obj.expirySlot,
     );
   }
 
@@ -895,7 +895,23 @@ export class LeafNode {
     public quantity: BN,
     public timestamp: BN,
     public pegLimit: BN,
+    public expiryTimestamp: BN,
+    public expirySlot: BN,
   ) {}
+
+  /**
+   * Time at which the order expires, U64_MAX_BN if never.
+   * Does not take expirySlot into account.
+   */
+  public expiry(): BN {
+    if (this.timeInForce) {
+      return this.timestamp.add(new BN(this.timeInForce));
+    }
+    if (!this.expiryTimestamp.isZero()) {
+      return this.expiryTimestamp;
+    }
+    return U64_MAX_BN;
+  }
 }
 export class InnerNode {
   static from(obj: { children: [number] }): InnerNode {
@@ -970,9 +986,7 @@ export class PerpOrder {
     } else {
       priceLots = BookSide.getPriceFromKey(leafNode.key);
     }
-    const expiryTimestamp = leafNode.timeInForce
-      ? leafNode.timestamp.add(new BN(leafNode.timeInForce))
-      : U64_MAX_BN;
+    const expiryTimestamp = leafNode.expiry();
 
     return new PerpOrder(
       type === BookSideType.bids
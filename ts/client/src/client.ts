@@ -559,6 +559,18 @@ export class MangoClient {
         params.platformLiquidationFee,
         params.disableAssetLiquidation,
         params.collateralFeePerDay,
+        params.medianOracles,
+        params.medianOracleQuorum,
+        params.isolated,
+        params.isolatedQuoteTokenIndexes,
+        params.borrowLimit,
+        params.accountBorrowLimitQuote,
+        params.interestCurveType,
+        params.pidKp,
+        params.pidKi,
+        params.pidKd,
+        params.fixedTermRatePremium,
+        params.fixedTermMaxDuration,
       )
       .accounts({
         group: group.publicKey,
@@ -573,6 +585,17 @@ export class MangoClient {
           isWritable: true,
           isSigner: false,
         } as AccountMeta,
+        // new median oracles are checked on chain and must be passed after the bank
+        ...(params.medianOracles ?? [])
+          .filter((pk) => !pk.equals(PublicKey.default))
+          .map(
+            (pk) =>
+              ({
+                pubkey: pk,
+                isWritable: false,
+                isSigner: false,
+              } as AccountMeta),
+          ),
       ])
       .instruction();
     return await this.sendAndConfirmTransactionForGroup(group, [ix]);
@@ -2537,6 +2560,8 @@ export class MangoClient {
     settlePnlLimitWindowSize: number,
     positivePnlLiquidationFee: number,
     platformLiquidationFee: number,
+    fundingInterestRate = 0,
+    fundingDampener = 0,
   ): Promise<MangoSignatureStatus> {
     const bids = new Keypair();
     const asks = new Keypair();
@@ -2579,6 +2604,8 @@ export class MangoClient {
         new BN(settlePnlLimitWindowSize),
         positivePnlLiquidationFee,
         platformLiquidationFee,
+        fundingInterestRate,
+        fundingDampener,
       )
       .accounts({
         group: group.publicKey,
@@ -2675,6 +2702,13 @@ export class MangoClient {
         params.name,
         params.forceClose,
         params.platformLiquidationFee,
+        params.medianOracles,
+        params.medianOracleQuorum,
+        params.fundingSampleInterval !== null
+          ? new BN(params.fundingSampleInterval)
+          : null,
+        params.fundingInterestRate,
+        params.fundingDampener,
       )
       .accounts({
         group: group.publicKey,
@@ -2682,6 +2716,18 @@ export class MangoClient {
         admin: (this.program.provider as AnchorProvider).wallet.publicKey,
         perpMarket: perpMarket.publicKey,
       })
+      .remainingAccounts(
+        (params.medianOracles ?? [])
+          .filter((pk) => !pk.equals(PublicKey.default))
+          .map(
+            (pk) =>
+              ({
+                pubkey: pk,
+                isWritable: false,
+                isSigner: false,
+              } as AccountMeta),
+          ),
+      )
       .instruction();
     return await this.sendAndConfirmTransactionForGroup(group, [ix]);
   }
@@ -117,6 +117,18 @@ export interface TokenEditParams {
   platformLiquidationFee: number | null;
   disableAssetLiquidation: boolean | null;
   collateralFeePerDay: number | null;
+  medianOracles: PublicKey[] | null;
+  medianOracleQuorum: number | null;
+  isolated: boolean | null;
+  isolatedQuoteTokenIndexes: number[] | null;
+  borrowLimit: BN | null;
+  accountBorrowLimitQuote: BN | null;
+  interestCurveType: number | null;
+  pidKp: number | null;
+  pidKi: number | null;
+  pidKd: number | null;
+  fixedTermRatePremium: number | null;
+  fixedTermMaxDuration: BN | null;
 }
 
 export const NullTokenEditParams: TokenEditParams = {
@@ -160,6 +172,18 @@ export const NullTokenEditParams: TokenEditParams = {
   platformLiquidationFee: null,
   disableAssetLiquidation: null,
   collateralFeePerDay: null,
+  medianOracles: null,
+  medianOracleQuorum: null,
+  isolated: null,
+  isolatedQuoteTokenIndexes: null,
+  borrowLimit: null,
+  accountBorrowLimitQuote: null,
+  interestCurveType: null,
+  pidKp: null,
+  pidKi: null,
+  pidKd: null,
+  fixedTermRatePremium: null,
+  fixedTermMaxDuration: null,
 };
 
 export interface PerpEditParams {
@@ -194,6 +218,11 @@ export interface PerpEditParams {
   name: string | null;
   forceClose: boolean | null;
   platformLiquidationFee: number | null;
+  medianOracles: PublicKey[] | null;
+  medianOracleQuorum: number | null;
+  fundingSampleInterval: number | null;
+  fundingInterestRate: number | null;
+  fundingDampener: number | null;
 }
 
 export const NullPerpEditParams: PerpEditParams = {
@@ -228,6 +257,11 @@ export const NullPerpEditParams: PerpEditParams = {
   name: null,
   forceClose: null,
   platformLiquidationFee: null,
+  medianOracles: null,
+  medianOracleQuorum: null,
+  fundingSampleInterval: null,
+  fundingInterestRate: null,
+  fundingDampener: null,
 };
 
 // Use with TrueIxGateParams and buildIxGate
@@ -297,7 +331,6 @@ export interface IxGateParams {
   OpenbookV2EditMarket: boolean;
   OpenbookV2LiqForceCancelOrders: boolean;
   OpenbookV2PlaceOrder: boolean;
-  OpenbookV2PlaceTakeOrder: boolean;
   OpenbookV2RegisterMarket: boolean;
   OpenbookV2SettleFunds: boolean;
   AdminTokenWithdrawFees: boolean;
@@ -307,6 +340,17 @@ export interface IxGateParams {
   TokenConditionalSwapCreatePremiumAuction: boolean;
   TokenConditionalSwapCreateLinearAuction: boolean;
   Serum3PlaceOrderV2: boolean;
+  PerpTriggerOrderCreate: boolean;
+  PerpTriggerOrderCancel: boolean;
+  PerpTriggerOrderExecute: boolean;
+  PerpEditOrder: boolean;
+  PerpPlaceOrdersBatch: boolean;
+  TokenInsuranceFundCreate: boolean;
+  TokenInsuranceFundWithdraw: boolean;
+  TokenInsuranceFundCover: boolean;
+  FixedTermLoanCreate: boolean;
+  FixedTermLoanSettle: boolean;
+  TokenRateHistoryCreate: boolean;
 }
 
 // Default with all ixs enabled, use with buildIxGate
@@ -376,7 +420,6 @@ export const TrueIxGateParams: IxGateParams = {
   OpenbookV2EditMarket: true,
   OpenbookV2LiqForceCancelOrders: true,
   OpenbookV2PlaceOrder: true,
-  OpenbookV2PlaceTakeOrder: true,
   OpenbookV2RegisterMarket: true,
   OpenbookV2SettleFunds: true,
   AdminTokenWithdrawFees: true,
@@ -386,6 +429,17 @@ export const TrueIxGateParams: IxGateParams = {
   TokenConditionalSwapCreatePremiumAuction: true,
   TokenConditionalSwapCreateLinearAuction: true,
   Serum3PlaceOrderV2: true,
+  PerpTriggerOrderCreate: true,
+  PerpTriggerOrderCancel: true,
+  PerpTriggerOrderExecute: true,
+  PerpEditOrder: true,
+  PerpPlaceOrdersBatch: true,
+  TokenInsuranceFundCreate: true,
+  TokenInsuranceFundWithdraw: true,
+  TokenInsuranceFundCover: true,
+  FixedTermLoanCreate: true,
+  FixedTermLoanSettle: true,
+  TokenRateHistoryCreate: true,
 };
 
 // build ix gate e.g. buildIxGate(Builder(TrueIxGateParams).TokenDeposit(false).build()).toNumber(),
@@ -465,7 +519,6 @@ export function buildIxGate(p: IxGateParams): BN {
   toggleIx(ixGate, p, 'OpenbookV2EditMarket', 59);
   toggleIx(ixGate, p, 'OpenbookV2LiqForceCancelOrders', 60);
   toggleIx(ixGate, p, 'OpenbookV2PlaceOrder', 61);
-  toggleIx(ixGate, p, 'OpenbookV2PlaceTakeOrder', 62);
   toggleIx(ixGate, p, 'OpenbookV2RegisterMarket', 63);
   toggleIx(ixGate, p, 'OpenbookV2SettleFunds', 63);
   toggleIx(ixGate, p, 'AdminTokenWithdrawFees', 65);
@@ -475,6 +528,17 @@ export function buildIxGate(p: IxGateParams): BN {
   toggleIx(ixGate, p, 'TokenConditionalSwapCreatePremiumAuction', 69);
   toggleIx(ixGate, p, 'TokenConditionalSwapCreateLinearAuction', 70);
   toggleIx(ixGate, p, 'Serum3PlaceOrderV2', 71);
+  toggleIx(ixGate, p, 'PerpTriggerOrderCreate', 72);
+  toggleIx(ixGate, p, 'PerpTriggerOrderCancel', 73);
+  toggleIx(ixGate, p, 'PerpTriggerOrderExecute', 74);
+  toggleIx(ixGate, p, 'PerpEditOrder', 75);
+  toggleIx(ixGate, p, 'PerpPlaceOrdersBatch', 76);
+  toggleIx(ixGate, p, 'TokenInsuranceFundCreate', 77);
+  toggleIx(ixGate, p, 'TokenInsuranceFundWithdraw', 78);
+  toggleIx(ixGate, p, 'TokenInsuranceFundCover', 79);
+  toggleIx(ixGate, p, 'FixedTermLoanCreate', 80);
+  toggleIx(ixGate, p, 'FixedTermLoanSettle', 81);
+  toggleIx(ixGate, p, 'TokenRateHistoryCreate', 82);
 
   return ixGate;
 }
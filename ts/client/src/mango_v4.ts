@@ -1067,6 +1067,82 @@ export type MangoV4 = {
           "type": {
             "option": "f32"
           }
+        },
+        {
+          "name": "medianOraclesOpt",
+          "type": {
+            "option": {
+              "vec": "publicKey"
+            }
+          }
+        },
+        {
+          "name": "medianOracleQuorumOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "isolatedOpt",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "isolatedQuoteTokenIndexesOpt",
+          "type": {
+            "option": {
+              "vec": "u16"
+            }
+          }
+        },
+        {
+          "name": "borrowLimitOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "accountBorrowLimitQuoteOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "interestCurveTypeOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "pidKpOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "pidKiOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "pidKdOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "fixedTermRatePremiumOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "fixedTermMaxDurationOpt",
+          "type": {
+            "option": "u64"
+          }
         }
       ]
     },
@@ -3983,6 +4059,14 @@ export type MangoV4 = {
         {
           "name": "platformLiquidationFee",
           "type": "f32"
+        },
+        {
+          "name": "fundingInterestRate",
+          "type": "f32"
+        },
+        {
+          "name": "fundingDampener",
+          "type": "f32"
         }
       ]
     },
@@ -4203,6 +4287,38 @@ export type MangoV4 = {
           "type": {
             "option": "f32"
           }
+        },
+        {
+          "name": "medianOraclesOpt",
+          "type": {
+            "option": {
+              "vec": "publicKey"
+            }
+          }
+        },
+        {
+          "name": "medianOracleQuorumOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "fundingSampleIntervalOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "fundingInterestRateOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "fundingDampenerOpt",
+          "type": {
+            "option": "f32"
+          }
         }
       ]
     },
@@ -6203,6 +6319,10 @@ export type MangoV4 = {
         {
           "name": "name",
           "type": "string"
+        },
+        {
+          "name": "oraclePriceBand",
+          "type": "f32"
         }
       ]
     },
@@ -6240,6 +6360,18 @@ export type MangoV4 = {
           "type": {
             "option": "bool"
           }
+        },
+        {
+          "name": "nameOpt",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "oraclePriceBandOpt",
+          "type": {
+            "option": "f32"
+          }
         }
       ]
     },
@@ -6581,148 +6713,6 @@ export type MangoV4 = {
         }
       ]
     },
-    {
-      "name": "openbookV2PlaceTakerOrder",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "authority",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "openbookV2Market",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group",
-            "openbook_v2_program",
-            "openbook_v2_market_external"
-          ]
-        },
-        {
-          "name": "openbookV2Program",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2MarketExternal",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "bids",
-            "asks",
-            "event_heap"
-          ]
-        },
-        {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "eventHeap",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketRequestQueue",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketBaseVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketQuoteVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketVaultSigner",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "payerBank",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank that pays for the order, if necessary"
-          ],
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "payerVault",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank vault that pays for the order, if necessary"
-          ]
-        },
-        {
-          "name": "payerOracle",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "side",
-          "type": "u8"
-        },
-        {
-          "name": "limitPrice",
-          "type": "u64"
-        },
-        {
-          "name": "maxBaseQty",
-          "type": "u64"
-        },
-        {
-          "name": "maxNativeQuoteQtyIncludingFees",
-          "type": "u64"
-        },
-        {
-          "name": "selfTradeBehavior",
-          "type": "u8"
-        },
-        {
-          "name": "clientOrderId",
-          "type": "u64"
-        },
-        {
-          "name": "limit",
-          "type": "u16"
-        }
-      ]
-    },
     {
       "name": "openbookV2CancelOrder",
       "accounts": [
@@ -10906,9 +10896,6 @@ export type MangoV4 = {
           {
             "name": "OpenbookV2PlaceOrder"
           },
-          {
-            "name": "OpenbookV2PlaceTakeOrder"
-          },
           {
             "name": "OpenbookV2RegisterMarket"
           },
@@ -15233,6 +15220,82 @@ export const IDL: MangoV4 = {
           "type": {
             "option": "f32"
           }
+        },
+        {
+          "name": "medianOraclesOpt",
+          "type": {
+            "option": {
+              "vec": "publicKey"
+            }
+          }
+        },
+        {
+          "name": "medianOracleQuorumOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "isolatedOpt",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "isolatedQuoteTokenIndexesOpt",
+          "type": {
+            "option": {
+              "vec": "u16"
+            }
+          }
+        },
+        {
+          "name": "borrowLimitOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "accountBorrowLimitQuoteOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "interestCurveTypeOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "pidKpOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "pidKiOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "pidKdOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "fixedTermRatePremiumOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "fixedTermMaxDurationOpt",
+          "type": {
+            "option": "u64"
+          }
         }
       ]
     },
@@ -18149,6 +18212,14 @@ export const IDL: MangoV4 = {
         {
           "name": "platformLiquidationFee",
           "type": "f32"
+        },
+        {
+          "name": "fundingInterestRate",
+          "type": "f32"
+        },
+        {
+          "name": "fundingDampener",
+          "type": "f32"
         }
       ]
     },
@@ -18369,6 +18440,38 @@ export const IDL: MangoV4 = {
           "type": {
             "option": "f32"
           }
+        },
+        {
+          "name": "medianOraclesOpt",
+          "type": {
+            "option": {
+              "vec": "publicKey"
+            }
+          }
+        },
+        {
+          "name": "medianOracleQuorumOpt",
+          "type": {
+            "option": "u8"
+          }
+        },
+        {
+          "name": "fundingSampleIntervalOpt",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "fundingInterestRateOpt",
+          "type": {
+            "option": "f32"
+          }
+        },
+        {
+          "name": "fundingDampenerOpt",
+          "type": {
+            "option": "f32"
+          }
         }
       ]
     },
@@ -20369,6 +20472,10 @@ export const IDL: MangoV4 = {
         {
           "name": "name",
           "type": "string"
+        },
+        {
+          "name": "oraclePriceBand",
+          "type": "f32"
         }
       ]
     },
@@ -20406,6 +20513,18 @@ export const IDL: MangoV4 = {
           "type": {
             "option": "bool"
           }
+        },
+        {
+          "name": "nameOpt",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "oraclePriceBandOpt",
+          "type": {
+            "option": "f32"
+          }
         }
       ]
     },
@@ -20747,148 +20866,6 @@ export const IDL: MangoV4 = {
         }
       ]
     },
-    {
-      "name": "openbookV2PlaceTakerOrder",
-      "accounts": [
-        {
-          "name": "group",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "account",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "authority",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "openbookV2Market",
-          "isMut": false,
-          "isSigner": false,
-          "relations": [
-            "group",
-            "openbook_v2_program",
-            "openbook_v2_market_external"
-          ]
-        },
-        {
-          "name": "openbookV2Program",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "openbookV2MarketExternal",
-          "isMut": true,
-          "isSigner": false,
-          "relations": [
-            "bids",
-            "asks",
-            "event_heap"
-          ]
-        },
-        {
-          "name": "bids",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "asks",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "eventHeap",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketRequestQueue",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketBaseVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketQuoteVault",
-          "isMut": true,
-          "isSigner": false
-        },
-        {
-          "name": "marketVaultSigner",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "payerBank",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank that pays for the order, if necessary"
-          ],
-          "relations": [
-            "group"
-          ]
-        },
-        {
-          "name": "payerVault",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The bank vault that pays for the order, if necessary"
-          ]
-        },
-        {
-          "name": "payerOracle",
-          "isMut": false,
-          "isSigner": false
-        },
-        {
-          "name": "tokenProgram",
-          "isMut": false,
-          "isSigner": false
-        }
-      ],
-      "args": [
-        {
-          "name": "side",
-          "type": "u8"
-        },
-        {
-          "name": "limitPrice",
-          "type": "u64"
-        },
-        {
-          "name": "maxBaseQty",
-          "type": "u64"
-        },
-        {
-          "name": "maxNativeQuoteQtyIncludingFees",
-          "type": "u64"
-        },
-        {
-          "name": "selfTradeBehavior",
-          "type": "u8"
-        },
-        {
-          "name": "clientOrderId",
-          "type": "u64"
-        },
-        {
-          "name": "limit",
-          "type": "u16"
-        }
-      ]
-    },
     {
       "name": "openbookV2CancelOrder",
       "accounts": [
@@ -25072,9 +25049,6 @@ export const IDL: MangoV4 = {
           {
             "name": "OpenbookV2PlaceOrder"
           },
-          {
-            "name": "OpenbookV2PlaceTakeOrder"
-          },
           {
             "name": "OpenbookV2RegisterMarket"
           },